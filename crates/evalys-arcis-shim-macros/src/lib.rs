@@ -0,0 +1,61 @@
+//! `#[encrypted]` and `#[instruction]` for `evalys-arcis-shim`.
+//!
+//! Arcis compiles an `#[encrypted]` module into a circuit. Here the module
+//! stays plain Rust: it is made public so the reference crate can call its
+//! instructions, and its structs and enums derive the traits host code needs
+//! to build inputs and compare outputs.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Attribute, Item, ItemMod, Visibility};
+
+fn is_instruction(attr: &Attribute) -> bool {
+    attr.path().is_ident("instruction")
+}
+
+/// Make the circuit module public and derive `Debug`, `Clone`, `Copy`,
+/// `PartialEq`, `Eq` and serde on its types
+#[proc_macro_attribute]
+pub fn encrypted(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut module = parse_macro_input!(item as ItemMod);
+    module.vis = Visibility::Public(Default::default());
+
+    let derive: Attribute = parse_quote! {
+        #[derive(
+            Debug,
+            Clone,
+            Copy,
+            PartialEq,
+            Eq,
+            ::arcis_imports::serde::Serialize,
+            ::arcis_imports::serde::Deserialize,
+        )]
+    };
+    if let Some((_, items)) = &mut module.content {
+        for item in items {
+            match item {
+                Item::Struct(item) => {
+                    item.attrs.push(derive.clone());
+                    item.attrs.push(parse_quote! {
+                        #[serde(crate = "::arcis_imports::serde", deny_unknown_fields)]
+                    });
+                }
+                Item::Enum(item) => {
+                    item.attrs.push(derive.clone());
+                    item.attrs.push(parse_quote! {
+                        #[serde(crate = "::arcis_imports::serde")]
+                    });
+                }
+                Item::Fn(item) => item.attrs.retain(|attr| !is_instruction(attr)),
+                _ => {}
+            }
+        }
+    }
+    quote!(#module).into()
+}
+
+/// Circuit entry point; a plain function on the host
+#[proc_macro_attribute]
+pub fn instruction(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
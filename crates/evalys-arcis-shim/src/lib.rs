@@ -0,0 +1,100 @@
+//! Host stand-in for `arcis-imports`.
+//!
+//! Provides just enough of the Arcis surface used by `encrypted-ixs/` for the
+//! circuits to compile and run as ordinary Rust: `Enc<Shared, T>` holds its
+//! value in the clear, `to_arcis` and `from_arcis` move values in and out of
+//! it, and `reveal` is the identity. `ArcisRNG` draws from a seeded host
+//! generator instead of randomness shared by the cluster nodes. Nothing here
+//! is encrypted; it exists so circuit logic can be executed and tested on the
+//! host.
+
+use std::cell::Cell;
+
+pub use evalys_arcis_shim_macros::{encrypted, instruction};
+
+#[doc(hidden)]
+pub use serde;
+
+/// Owner of values encrypted to the client's shared secret
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Shared;
+
+impl Shared {
+    /// Wrap a circuit output for this owner
+    pub fn from_arcis<T>(self, value: T) -> Enc<Shared, T> {
+        Enc { owner: self, value }
+    }
+}
+
+/// A value "encrypted" to `owner`; on the host it is kept in the clear
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Enc<O, T> {
+    pub owner: O,
+    value: T,
+}
+
+impl<T> Enc<Shared, T> {
+    /// Input as a client would submit it
+    pub fn shared(value: T) -> Self {
+        Shared.from_arcis(value)
+    }
+}
+
+impl<O, T> Enc<O, T> {
+    /// Value as the client would decrypt it
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<O, T: Copy> Enc<O, T> {
+    /// Value as the circuit sees it
+    pub fn to_arcis(&self) -> T {
+        self.value
+    }
+}
+
+/// `reveal()` publishes a value computed in MPC; on the host it is a no-op
+pub trait Reveal: Sized {
+    fn reveal(self) -> Self {
+        self
+    }
+}
+
+impl<T> Reveal for T {}
+
+thread_local! {
+    static RNG_STATE: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Randomness drawn inside MPC; on the host a splitmix64 stream per thread
+pub struct ArcisRNG;
+
+impl ArcisRNG {
+    /// Restart this thread's stream at `seed`, so runs are reproducible
+    pub fn seed(seed: u64) {
+        RNG_STATE.with(|state| state.set(seed));
+    }
+
+    fn next_u64() -> u64 {
+        RNG_STATE.with(|state| {
+            let next = state.get().wrapping_add(0x9e37_79b9_7f4a_7c15);
+            state.set(next);
+            let mut z = next;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        })
+    }
+
+    /// Uniform integer of `width` bits (at most 128)
+    pub fn gen_integer_from_width(width: usize) -> u128 {
+        assert!(width <= 128, "width {width} exceeds 128 bits");
+        let value = (u128::from(Self::next_u64()) << 64) | u128::from(Self::next_u64());
+        match width {
+            0 => 0,
+            128 => value,
+            width => value & ((1u128 << width) - 1),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! CSV input: curve snapshots and user intents.
+//!
+//! Both files start with a header row naming their columns, in any order;
+//! extra columns are ignored, blank lines and lines starting with `#` are
+//! skipped. Rows are sorted by `timestamp` (unix seconds).
+//!
+//! | file      | columns                                                          |
+//! |-----------|------------------------------------------------------------------|
+//! | snapshots | `timestamp`, `price`, `liquidity_depth`, `volatility`, `recent_volume`, `curve_position`, `volatility_score` |
+//! | intents   | `timestamp`, `desired_size`, `slippage_tolerance`, `risk_appetite`, `preferred_hold_time`, `recent_pnl`, `win_rate`, `avg_hold_time`, `total_trades`, `risk_level`, `privacy_priority`, `max_dd` |
+//!
+//! Units follow the circuit inputs: sizes, prices and liquidity in lamports,
+//! `slippage_tolerance`, `win_rate`, `max_dd`, `curve_position` and
+//! `volatility_score` in bps, hold times in seconds.
+
+use std::str::FromStr;
+
+use crate::BacktestError;
+
+/// Curve state at one point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    pub timestamp: u64,
+    /// Token price in lamports
+    pub price: u64,
+    /// Liquidity in lamports
+    pub liquidity_depth: u64,
+    /// `CurveState.volatility` of `confidential_strategy_plan`
+    pub volatility: u16,
+    pub recent_volume: u64,
+    /// Position on the bonding curve in bps
+    pub curve_position: u16,
+    /// `IntentInput.volatility_score` of `evalys_gmpc_strategy`, in bps
+    pub volatility_score: u16,
+}
+
+/// One user's trade intent with the history both circuits take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Intent {
+    pub timestamp: u64,
+    /// Size to buy in lamports
+    pub desired_size: u64,
+    /// Largest price impact a slice accepts, in bps
+    pub slippage_tolerance: u16,
+    pub risk_appetite: u8,
+    /// Seconds to hold the position after the last slice
+    pub preferred_hold_time: u32,
+    pub recent_pnl: i64,
+    pub win_rate: u16,
+    pub avg_hold_time: u32,
+    pub total_trades: u32,
+    /// 0 = low, 1 = normal, 2 = high
+    pub risk_level: u8,
+    /// 0 = normal, 1 = stealth, 2 = max privacy
+    pub privacy_priority: u8,
+    pub max_dd: u16,
+}
+
+/// Rows of a CSV file, with the position of each named column
+struct Table<'a> {
+    file: &'static str,
+    header: Vec<&'a str>,
+    rows: Vec<(usize, Vec<&'a str>)>,
+}
+
+impl<'a> Table<'a> {
+    fn parse(file: &'static str, text: &'a str) -> Result<Self, BacktestError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let (_, header) = lines.next().ok_or(BacktestError::MissingHeader { file })?;
+        let header: Vec<&str> = header.split(',').map(str::trim).collect();
+        let rows = lines
+            .map(|(line, row)| {
+                let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+                if fields.len() != header.len() {
+                    return Err(BacktestError::FieldCount {
+                        file,
+                        line,
+                        expected: header.len(),
+                        actual: fields.len(),
+                    });
+                }
+                Ok((line, fields))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if rows.is_empty() {
+            return Err(BacktestError::Empty { file });
+        }
+        Ok(Table { file, header, rows })
+    }
+
+    fn column(&self, column: &'static str) -> Result<Column, BacktestError> {
+        let index = self.header.iter().position(|name| *name == column).ok_or(
+            BacktestError::MissingColumn {
+                file: self.file,
+                column,
+            },
+        )?;
+        Ok(Column {
+            file: self.file,
+            name: column,
+            index,
+        })
+    }
+}
+
+struct Column {
+    file: &'static str,
+    name: &'static str,
+    index: usize,
+}
+
+impl Column {
+    fn get<T: FromStr>(&self, line: usize, row: &[&str]) -> Result<T, BacktestError> {
+        let value = row[self.index];
+        value.parse().map_err(|_| BacktestError::InvalidValue {
+            file: self.file,
+            line,
+            column: self.name,
+            value: value.to_string(),
+        })
+    }
+}
+
+fn check_order(file: &'static str, rows: &[(usize, u64)]) -> Result<(), BacktestError> {
+    for pair in rows.windows(2) {
+        let (line, timestamp) = pair[1];
+        if timestamp < pair[0].1 {
+            return Err(BacktestError::Unordered {
+                file,
+                line,
+                timestamp,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Parse a snapshots CSV
+pub fn parse_snapshots(text: &str) -> Result<Vec<Snapshot>, BacktestError> {
+    let table = Table::parse("snapshots", text)?;
+    let timestamp = table.column("timestamp")?;
+    let price = table.column("price")?;
+    let liquidity_depth = table.column("liquidity_depth")?;
+    let volatility = table.column("volatility")?;
+    let recent_volume = table.column("recent_volume")?;
+    let curve_position = table.column("curve_position")?;
+    let volatility_score = table.column("volatility_score")?;
+
+    let mut order = Vec::with_capacity(table.rows.len());
+    let snapshots = table
+        .rows
+        .iter()
+        .map(|(line, row)| {
+            let snapshot = Snapshot {
+                timestamp: timestamp.get(*line, row)?,
+                price: price.get(*line, row)?,
+                liquidity_depth: liquidity_depth.get(*line, row)?,
+                volatility: volatility.get(*line, row)?,
+                recent_volume: recent_volume.get(*line, row)?,
+                curve_position: curve_position.get(*line, row)?,
+                volatility_score: volatility_score.get(*line, row)?,
+            };
+            order.push((*line, snapshot.timestamp));
+            Ok(snapshot)
+        })
+        .collect::<Result<Vec<_>, BacktestError>>()?;
+    check_order(table.file, &order)?;
+    Ok(snapshots)
+}
+
+/// Parse an intents CSV
+pub fn parse_intents(text: &str) -> Result<Vec<Intent>, BacktestError> {
+    let table = Table::parse("intents", text)?;
+    let timestamp = table.column("timestamp")?;
+    let desired_size = table.column("desired_size")?;
+    let slippage_tolerance = table.column("slippage_tolerance")?;
+    let risk_appetite = table.column("risk_appetite")?;
+    let preferred_hold_time = table.column("preferred_hold_time")?;
+    let recent_pnl = table.column("recent_pnl")?;
+    let win_rate = table.column("win_rate")?;
+    let avg_hold_time = table.column("avg_hold_time")?;
+    let total_trades = table.column("total_trades")?;
+    let risk_level = table.column("risk_level")?;
+    let privacy_priority = table.column("privacy_priority")?;
+    let max_dd = table.column("max_dd")?;
+
+    let mut order = Vec::with_capacity(table.rows.len());
+    let intents = table
+        .rows
+        .iter()
+        .map(|(line, row)| {
+            let intent = Intent {
+                timestamp: timestamp.get(*line, row)?,
+                desired_size: desired_size.get(*line, row)?,
+                slippage_tolerance: slippage_tolerance.get(*line, row)?,
+                risk_appetite: risk_appetite.get(*line, row)?,
+                preferred_hold_time: preferred_hold_time.get(*line, row)?,
+                recent_pnl: recent_pnl.get(*line, row)?,
+                win_rate: win_rate.get(*line, row)?,
+                avg_hold_time: avg_hold_time.get(*line, row)?,
+                total_trades: total_trades.get(*line, row)?,
+                risk_level: risk_level.get(*line, row)?,
+                privacy_priority: privacy_priority.get(*line, row)?,
+                max_dd: max_dd.get(*line, row)?,
+            };
+            order.push((*line, intent.timestamp));
+            Ok(intent)
+        })
+        .collect::<Result<Vec<_>, BacktestError>>()?;
+    check_order(table.file, &order)?;
+    Ok(intents)
+}
+
+/// Snapshots sorted by time, looked up by the latest one at a given time
+#[derive(Debug, Clone)]
+pub struct Curve {
+    snapshots: Vec<Snapshot>,
+}
+
+impl Curve {
+    /// `snapshots` must be sorted by timestamp, as [`parse_snapshots`] returns them
+    pub fn new(snapshots: Vec<Snapshot>) -> Self {
+        Curve { snapshots }
+    }
+
+    /// Latest snapshot at or before `timestamp`; `None` before the first one
+    pub fn at(&self, timestamp: u64) -> Option<&Snapshot> {
+        let after = self
+            .snapshots
+            .partition_point(|snapshot| snapshot.timestamp <= timestamp);
+        after.checked_sub(1).map(|index| &self.snapshots[index])
+    }
+
+    /// Snapshots taken after `after` and at or before `until`, oldest first
+    pub fn between(&self, after: u64, until: u64) -> &[Snapshot] {
+        let start = self
+            .snapshots
+            .partition_point(|snapshot| snapshot.timestamp <= after);
+        let end = self
+            .snapshots
+            .partition_point(|snapshot| snapshot.timestamp <= until);
+        &self.snapshots[start..end.max(start)]
+    }
+}
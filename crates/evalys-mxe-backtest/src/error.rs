@@ -0,0 +1,35 @@
+use thiserror::Error;
+
+/// Errors returned while loading backtest data
+#[derive(Debug, Error)]
+pub enum BacktestError {
+    #[error("{file}: missing header row")]
+    MissingHeader { file: &'static str },
+    #[error("{file}: missing column `{column}`")]
+    MissingColumn {
+        file: &'static str,
+        column: &'static str,
+    },
+    #[error("{file}:{line}: expected {expected} fields, got {actual}")]
+    FieldCount {
+        file: &'static str,
+        line: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("{file}:{line}: invalid `{column}` value {value:?}")]
+    InvalidValue {
+        file: &'static str,
+        line: usize,
+        column: &'static str,
+        value: String,
+    },
+    #[error("{file}:{line}: timestamp {timestamp} is before the previous row")]
+    Unordered {
+        file: &'static str,
+        line: usize,
+        timestamp: u64,
+    },
+    #[error("{file}: no rows")]
+    Empty { file: &'static str },
+}
@@ -0,0 +1,20 @@
+//! Backtest of the strategy circuits against historical curve data.
+//!
+//! Replays user intents ([`data::Intent`]) against recorded curve snapshots
+//! ([`data::Snapshot`]): each intent is planned by the plaintext reference of
+//! `confidential_strategy_plan` and of `evalys_gmpc_strategy`, the plan's
+//! slices are executed against the curve as recorded at their time
+//! ([`sim`]), and the position is closed by the plan's exit slices or its
+//! take-profit and stop-loss prices. [`report::Report`] aggregates slippage,
+//! fill rate and realized PnL per circuit and privacy mode.
+//!
+//! The execution model is deliberately simple (price impact proportional to
+//! size over liquidity depth, no competing flow): it compares thresholds
+//! against each other, not against a live market.
+
+pub mod data;
+mod error;
+pub mod report;
+pub mod sim;
+
+pub use error::BacktestError;
@@ -0,0 +1,45 @@
+//! `evalys-mxe-backtest`: replay intents through the strategy circuits
+//! against recorded curve snapshots.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use evalys_mxe_backtest::data::{self, Curve};
+use evalys_mxe_backtest::report::Report;
+
+#[derive(Parser)]
+#[command(
+    name = "evalys-mxe-backtest",
+    version,
+    about = "Backtest the Evalys MXE strategy circuits against historical curve data"
+)]
+struct Cli {
+    /// CSV of curve snapshots
+    #[arg(long)]
+    snapshots: PathBuf,
+    /// CSV of user intents
+    #[arg(long)]
+    intents: PathBuf,
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let curve = Curve::new(data::parse_snapshots(&read(&cli.snapshots)?)?);
+    let intents = data::parse_intents(&read(&cli.intents)?)?;
+    let report = Report::run(&curve, &intents);
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{report}");
+    }
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! Results aggregated per circuit and privacy mode.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::data::{Curve, Intent};
+use crate::sim::{self, Circuit, Execution, ExitReason};
+
+/// Totals of the executions of one circuit in one privacy mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ModeStats {
+    pub intents: u32,
+    pub slices_planned: u32,
+    pub slices_filled: u32,
+    /// Lamports
+    pub size_planned: u64,
+    /// Lamports
+    pub size_filled: u64,
+    /// Filled size over planned size
+    pub fill_rate: f64,
+    /// Size-weighted average price impact of filled slices, in bps
+    pub avg_slippage_bps: f64,
+    /// Realized PnL in lamports
+    pub pnl: f64,
+    /// PnL over filled size, in bps
+    pub pnl_bps: f64,
+    /// Positions closed early at the take-profit price
+    pub take_profit_exits: u32,
+    /// Positions closed early at the stop-loss price
+    pub stop_loss_exits: u32,
+    #[serde(skip)]
+    weighted_slippage_bps: f64,
+}
+
+impl ModeStats {
+    fn add(&mut self, execution: &Execution) {
+        self.intents += 1;
+        self.slices_planned += execution.slices_planned;
+        self.slices_filled += execution.slices_filled;
+        self.size_planned += execution.size_planned;
+        self.size_filled += execution.size_filled;
+        self.weighted_slippage_bps += execution.weighted_slippage_bps;
+        self.pnl += execution.pnl;
+        match execution.exit {
+            ExitReason::Schedule => {}
+            ExitReason::TakeProfit => self.take_profit_exits += 1,
+            ExitReason::StopLoss => self.stop_loss_exits += 1,
+        }
+
+        let ratio = |a: f64, b: u64| if b == 0 { 0.0 } else { a / b as f64 };
+        self.fill_rate = ratio(self.size_filled as f64, self.size_planned);
+        self.avg_slippage_bps = ratio(self.weighted_slippage_bps, self.size_filled);
+        self.pnl_bps = ratio(self.pnl * 10_000.0, self.size_filled);
+    }
+}
+
+/// Backtest results
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Report {
+    /// Intents before the first snapshot, not replayed
+    pub skipped: u32,
+    /// Per circuit, per privacy mode
+    pub circuits: BTreeMap<Circuit, BTreeMap<u8, ModeStats>>,
+}
+
+impl Report {
+    /// Replay every intent through both circuits
+    pub fn run(curve: &Curve, intents: &[Intent]) -> Self {
+        let mut report = Report::default();
+        for intent in intents {
+            let Some(snapshot) = curve.at(intent.timestamp) else {
+                report.skipped += 1;
+                continue;
+            };
+            for circuit in Circuit::ALL {
+                let plan = sim::plan(circuit, intent, snapshot);
+                let execution =
+                    sim::execute(&plan, intent, curve).expect("intent time has a snapshot");
+                report
+                    .circuits
+                    .entry(circuit)
+                    .or_default()
+                    .entry(plan.privacy_mode)
+                    .or_default()
+                    .add(&execution);
+            }
+        }
+        report
+    }
+}
+
+fn mode_name(mode: u8) -> &'static str {
+    match mode {
+        0 => "normal",
+        1 => "stealth",
+        2 => "max_ghost",
+        _ => "unknown",
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (circuit, modes) in &self.circuits {
+            writeln!(f, "{}", circuit.name())?;
+            writeln!(
+                f,
+                "  {:<10} {:>7} {:>13} {:>9} {:>14} {:>16} {:>9} {:>9}",
+                "mode",
+                "intents",
+                "slices",
+                "fill",
+                "slippage bps",
+                "pnl lamports",
+                "pnl bps",
+                "tp/sl"
+            )?;
+            for (mode, stats) in modes {
+                writeln!(
+                    f,
+                    "  {:<10} {:>7} {:>13} {:>8.1}% {:>14.1} {:>16.0} {:>9.1} {:>9}",
+                    mode_name(*mode),
+                    stats.intents,
+                    format!("{}/{}", stats.slices_filled, stats.slices_planned),
+                    stats.fill_rate * 100.0,
+                    stats.avg_slippage_bps,
+                    stats.pnl,
+                    stats.pnl_bps,
+                    format!("{}/{}", stats.take_profit_exits, stats.stop_loss_exits)
+                )?;
+            }
+        }
+        if self.skipped > 0 {
+            writeln!(
+                f,
+                "{} intent(s) before the first snapshot skipped",
+                self.skipped
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+//! Planning an intent with a circuit and executing the plan on the curve.
+//!
+//! Execution model:
+//!
+//! - Each slice is sent at its own delay after the intent's timestamp. For
+//!   `confidential_strategy_plan` the sizes and delays are the plan's
+//!   jittered per-slice schedule; `evalys_gmpc_strategy` plans only a count
+//!   and a window, so its size is split into equal slices (the last one
+//!   takes the remainder) spread evenly over the window.
+//! - A slice of `size` against a snapshot moves the price by
+//!   `size / liquidity_depth`; it fills at `price * (1 + impact)` if the
+//!   impact is within the intent's `slippage_tolerance`, and is skipped
+//!   otherwise.
+//! - The position is sold by exit slices, each a share of what was bought,
+//!   at its own delay after the intent's timestamp, at the snapshot then in
+//!   effect and with the same impact model. Past the end of the data the
+//!   last snapshot is used. For `confidential_strategy_plan` these are the
+//!   plan's exit schedule; `evalys_gmpc_strategy` plans no exit, so the whole
+//!   position is sold the intent's average hold time after the window
+//!   closes.
+//! - From the last buy on, every snapshot up to the last exit slice is
+//!   checked against the plan's take-profit and stop-loss prices, if it has
+//!   them: the first snapshot at or beyond either sells the rest of the
+//!   position at once, and the remaining exit slices are dropped.
+
+use evalys_mxe_reference::{gmpc, strategy, MAX_SLICES};
+use serde::Serialize;
+
+use crate::data::{Curve, Intent, Snapshot};
+
+/// Strategy circuit under test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Circuit {
+    /// `confidential_strategy_plan`
+    StrategyPlan,
+    /// `evalys_gmpc_strategy`
+    GmpcStrategy,
+}
+
+impl Circuit {
+    pub const ALL: [Circuit; 2] = [Circuit::StrategyPlan, Circuit::GmpcStrategy];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Circuit::StrategyPlan => "confidential_strategy_plan",
+            Circuit::GmpcStrategy => "evalys_gmpc_strategy",
+        }
+    }
+}
+
+/// One buy order of a plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slice {
+    /// Lamports
+    pub size: u64,
+    /// Seconds after the intent's timestamp
+    pub delay_sec: u32,
+}
+
+/// One sell order of a plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitSlice {
+    /// Share of the position bought, in bps
+    pub bps: u16,
+    /// Seconds after the intent's timestamp
+    pub delay_sec: u32,
+}
+
+/// `count` equal slices of `size` (the last one takes the remainder), started
+/// evenly over `window_sec`; one slice if `count` is 0
+pub fn even_slices(size: u64, count: u8, window_sec: u32) -> Vec<Slice> {
+    let count = u64::from(count.max(1));
+    let slice_size = size / count;
+    (0..count)
+        .map(|slice| Slice {
+            size: if slice + 1 == count {
+                size - slice_size * (count - 1)
+            } else {
+                slice_size
+            },
+            delay_sec: (u64::from(window_sec) * slice / count) as u32,
+        })
+        .collect()
+}
+
+/// The parts of a circuit's output the simulation executes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// 0 = normal, 1 = stealth, 2 = max ghost
+    pub privacy_mode: u8,
+    /// Buy orders, in the order they are sent
+    pub slices: Vec<Slice>,
+    /// Sell orders, in the order they are sent; the last one sells whatever
+    /// is left
+    pub exits: Vec<ExitSlice>,
+    /// Price at or above which the rest of the position is sold at once
+    pub take_profit_price: Option<u64>,
+    /// Price at or below which the rest of the position is sold at once
+    pub stop_loss_price: Option<u64>,
+}
+
+impl Plan {
+    /// Total size to buy in lamports
+    pub fn size(&self) -> u64 {
+        self.slices.iter().map(|slice| slice.size).sum()
+    }
+}
+
+/// Plan `intent` with `circuit` against the curve at the intent's time, under
+/// the default policies. The circuits' in-MPC randomness is seeded with the
+/// intent's timestamp, so a run is reproducible.
+pub fn plan(circuit: Circuit, intent: &Intent, snapshot: &Snapshot) -> Plan {
+    match circuit {
+        Circuit::StrategyPlan => {
+            let output = evalys_mxe_reference::strategy_plan(
+                strategy::UserPreferences {
+                    desired_size: intent.desired_size,
+                    slippage_tolerance: intent.slippage_tolerance,
+                    risk_appetite: intent.risk_appetite,
+                    preferred_hold_time: intent.preferred_hold_time,
+                },
+                strategy::UserHistory {
+                    recent_pnl: intent.recent_pnl,
+                    win_rate: intent.win_rate,
+                    avg_hold_time: intent.avg_hold_time,
+                    total_trades: intent.total_trades,
+                },
+                strategy::CurveState {
+                    current_price: snapshot.price,
+                    liquidity_depth: snapshot.liquidity_depth,
+                    volatility: snapshot.volatility,
+                    recent_volume: snapshot.recent_volume,
+                },
+                strategy::StrategyPolicy::default(),
+                strategy::InputCommitment { hi: 0, lo: 0 },
+                intent.timestamp,
+            );
+            // Slots past `num_slices` are zero
+            let count = usize::from(output.num_slices).min(MAX_SLICES);
+            let slices = output
+                .slice_sizes()
+                .into_iter()
+                .zip(output.slice_delays_sec())
+                .take(count)
+                .map(|(size, delay_sec)| Slice { size, delay_sec })
+                .collect();
+            // Exit delays count from the start of the exit
+            let exits = output
+                .exit_slice_bps()
+                .into_iter()
+                .zip(output.exit_delays_sec())
+                .take(count)
+                .map(|(bps, delay_sec)| ExitSlice {
+                    bps,
+                    delay_sec: output.exit_start_sec.saturating_add(delay_sec),
+                })
+                .collect();
+            Plan {
+                privacy_mode: output.recommended_mode,
+                slices,
+                exits,
+                take_profit_price: Some(output.take_profit_price),
+                stop_loss_price: Some(output.stop_loss_price),
+            }
+        }
+        Circuit::GmpcStrategy => {
+            let output = evalys_mxe_reference::gmpc_strategy(
+                gmpc::IntentInput {
+                    max_size_sol: intent.desired_size,
+                    risk_level: intent.risk_level,
+                    privacy_priority: intent.privacy_priority,
+                    market_price: snapshot.price,
+                    curve_position: snapshot.curve_position,
+                    volatility_score: snapshot.volatility_score,
+                    avg_hold_time: intent.avg_hold_time,
+                    win_rate: intent.win_rate,
+                    max_dd: intent.max_dd,
+                },
+                gmpc::GmpcPolicy::default(),
+                gmpc::InputCommitment { hi: 0, lo: 0 },
+                intent.timestamp,
+            );
+            Plan {
+                privacy_mode: output.privacy_mode,
+                slices: even_slices(
+                    output.recommended_size_sol,
+                    output.slice_count,
+                    output.time_window_sec,
+                ),
+                exits: vec![ExitSlice {
+                    bps: 10_000,
+                    delay_sec: output.time_window_sec.saturating_add(intent.avg_hold_time),
+                }],
+                take_profit_price: None,
+                stop_loss_price: None,
+            }
+        }
+    }
+}
+
+/// What closed the position
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExitReason {
+    /// Every exit slice ran (or nothing was bought)
+    #[default]
+    Schedule,
+    TakeProfit,
+    StopLoss,
+}
+
+/// Outcome of executing one plan
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Execution {
+    pub slices_planned: u32,
+    pub slices_filled: u32,
+    /// Lamports the plan meant to spend
+    pub size_planned: u64,
+    /// Lamports actually spent
+    pub size_filled: u64,
+    /// Sum over filled slices of size × price impact in bps
+    pub weighted_slippage_bps: f64,
+    /// Exit proceeds minus lamports spent
+    pub pnl: f64,
+    pub exit: ExitReason,
+}
+
+/// Price impact of an order of `size` lamports, as a fraction of the price
+fn impact(size: f64, snapshot: &Snapshot) -> f64 {
+    if snapshot.liquidity_depth == 0 {
+        f64::INFINITY
+    } else {
+        size / snapshot.liquidity_depth as f64
+    }
+}
+
+/// Proceeds of selling `tokens` at `snapshot`
+fn sell(tokens: f64, snapshot: &Snapshot) -> f64 {
+    let value = tokens * snapshot.price as f64;
+    value * (1.0 - impact(value, snapshot)).max(0.0)
+}
+
+/// First snapshot in `(after, until]` at or beyond the plan's take-profit or
+/// stop-loss price
+fn trigger<'a>(
+    plan: &Plan,
+    curve: &'a Curve,
+    after: u64,
+    until: u64,
+) -> Option<(ExitReason, &'a Snapshot)> {
+    curve.between(after, until).iter().find_map(|snapshot| {
+        if plan
+            .take_profit_price
+            .is_some_and(|price| snapshot.price >= price)
+        {
+            Some((ExitReason::TakeProfit, snapshot))
+        } else if plan
+            .stop_loss_price
+            .is_some_and(|price| snapshot.price <= price)
+        {
+            Some((ExitReason::StopLoss, snapshot))
+        } else {
+            None
+        }
+    })
+}
+
+/// Execute `plan` for `intent` on `curve`; `None` if the curve has no
+/// snapshot at the intent's time
+pub fn execute(plan: &Plan, intent: &Intent, curve: &Curve) -> Option<Execution> {
+    curve.at(intent.timestamp)?;
+    let tolerance = f64::from(intent.slippage_tolerance) / 10_000.0;
+
+    let mut execution = Execution {
+        slices_planned: plan.slices.len() as u32,
+        size_planned: plan.size(),
+        ..Execution::default()
+    };
+    let mut tokens = 0.0;
+    let mut last_buy = intent.timestamp;
+    for &Slice { size, delay_sec } in &plan.slices {
+        if size == 0 {
+            continue;
+        }
+        let at = intent.timestamp + u64::from(delay_sec);
+        last_buy = last_buy.max(at);
+        let snapshot = curve.at(at).expect("intent time has a snapshot");
+        let impact = impact(size as f64, snapshot);
+        if impact > tolerance || snapshot.price == 0 {
+            continue;
+        }
+        tokens += size as f64 / (snapshot.price as f64 * (1.0 + impact));
+        execution.slices_filled += 1;
+        execution.size_filled += size;
+        execution.weighted_slippage_bps += size as f64 * impact * 10_000.0;
+    }
+
+    let mut proceeds = 0.0;
+    let mut held = tokens;
+    let mut watched = last_buy;
+    for (index, exit) in plan.exits.iter().enumerate() {
+        if held <= 0.0 {
+            break;
+        }
+        let at = (intent.timestamp + u64::from(exit.delay_sec)).max(watched);
+        if let Some((reason, snapshot)) = trigger(plan, curve, watched, at) {
+            proceeds += sell(held, snapshot);
+            execution.exit = reason;
+            break;
+        }
+        watched = at;
+        let amount = if index + 1 == plan.exits.len() {
+            held
+        } else {
+            (tokens * f64::from(exit.bps) / 10_000.0).min(held)
+        };
+        let snapshot = curve.at(at).expect("exit is after the intent");
+        proceeds += sell(amount, snapshot);
+        held -= amount;
+    }
+    execution.pnl = proceeds - execution.size_filled as f64;
+    Some(execution)
+}
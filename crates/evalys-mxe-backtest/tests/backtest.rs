@@ -0,0 +1,335 @@
+//! Backtest data loading and the execution model.
+
+use std::fs;
+use std::path::PathBuf;
+
+use evalys_mxe_backtest::data::{self, Curve, Intent, Snapshot};
+use evalys_mxe_backtest::report::Report;
+use evalys_mxe_backtest::sim::{self, Circuit, ExitReason, ExitSlice, Plan, Slice};
+use evalys_mxe_backtest::BacktestError;
+use evalys_mxe_reference::strategy;
+
+fn snapshot(timestamp: u64, price: u64, liquidity_depth: u64) -> Snapshot {
+    Snapshot {
+        timestamp,
+        price,
+        liquidity_depth,
+        volatility: 100,
+        recent_volume: 1_000_000_000,
+        curve_position: 5_000,
+        volatility_score: 3_000,
+    }
+}
+
+fn intent(timestamp: u64, desired_size: u64, slippage_tolerance: u16) -> Intent {
+    Intent {
+        timestamp,
+        desired_size,
+        slippage_tolerance,
+        risk_appetite: 100,
+        preferred_hold_time: 600,
+        recent_pnl: 0,
+        win_rate: 5_500,
+        avg_hold_time: 600,
+        total_trades: 10,
+        risk_level: 1,
+        privacy_priority: 0,
+        max_dd: 1_000,
+    }
+}
+
+/// Sell everything at 900 s
+fn exits() -> Vec<ExitSlice> {
+    vec![ExitSlice {
+        bps: 10_000,
+        delay_sec: 900,
+    }]
+}
+
+fn plan(size: u64, slices: u8) -> Plan {
+    Plan {
+        privacy_mode: 0,
+        slices: sim::even_slices(size, slices, 300),
+        exits: exits(),
+        take_profit_price: None,
+        stop_loss_price: None,
+    }
+}
+
+#[test]
+fn parses_columns_in_any_order() {
+    let csv = "# comment\n\
+               volatility_score,curve_position,recent_volume,volatility,liquidity_depth,price,timestamp,extra\n\
+               3000,5000,7,100,80,25,1,x\n\
+               \n\
+               3100,5100,8,110,81,26,2,y\n";
+    let snapshots = data::parse_snapshots(csv).unwrap();
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(snapshots[1].price, 26);
+    assert_eq!(snapshots[1].volatility_score, 3100);
+}
+
+#[test]
+fn rejects_malformed_files() {
+    let header =
+        "timestamp,price,liquidity_depth,volatility,recent_volume,curve_position,volatility_score";
+    assert!(matches!(
+        data::parse_snapshots("timestamp,price\n1,2\n"),
+        Err(BacktestError::MissingColumn {
+            column: "liquidity_depth",
+            ..
+        })
+    ));
+    assert!(matches!(
+        data::parse_snapshots(&format!("{header}\n1,2,3\n")),
+        Err(BacktestError::FieldCount { line: 2, .. })
+    ));
+    assert!(matches!(
+        data::parse_snapshots(&format!("{header}\n1,2,3,70000,5,6,7\n")),
+        Err(BacktestError::InvalidValue {
+            column: "volatility",
+            ..
+        })
+    ));
+    assert!(matches!(
+        data::parse_snapshots(&format!("{header}\n5,2,3,4,5,6,7\n4,2,3,4,5,6,7\n")),
+        Err(BacktestError::Unordered {
+            line: 3,
+            timestamp: 4,
+            ..
+        })
+    ));
+    assert!(matches!(
+        data::parse_snapshots(header),
+        Err(BacktestError::Empty { .. })
+    ));
+}
+
+#[test]
+fn curve_returns_latest_snapshot() {
+    let curve = Curve::new(vec![snapshot(10, 1, 1), snapshot(20, 2, 1)]);
+    assert_eq!(curve.at(9), None);
+    assert_eq!(curve.at(10).unwrap().price, 1);
+    assert_eq!(curve.at(19).unwrap().price, 1);
+    assert_eq!(curve.at(1_000).unwrap().price, 2);
+    assert_eq!(curve.between(0, 9), []);
+    assert_eq!(curve.between(10, 20), [snapshot(20, 2, 1)]);
+    assert_eq!(curve.between(9, 1_000).len(), 2);
+    assert_eq!(curve.between(20, 10), []);
+}
+
+#[test]
+fn flat_curve_loses_only_impact() {
+    let curve = Curve::new(vec![snapshot(0, 1_000, 1_000_000_000_000)]);
+    let execution = sim::execute(&plan(1_000_000_000, 4), &intent(0, 0, 100), &curve).unwrap();
+    assert_eq!(execution.slices_filled, 4);
+    assert_eq!(execution.size_filled, 1_000_000_000);
+    // Each slice moves the price by 2.5 bps
+    assert!((execution.weighted_slippage_bps / 1e9 - 2.5).abs() < 1e-9);
+    // 2.5 bps in, 10 bps out on selling 1 SOL into 1000 SOL of depth
+    assert!(execution.pnl < -1_200_000.0);
+    assert!(execution.pnl > -1_300_000.0);
+}
+
+#[test]
+fn slices_over_tolerance_are_skipped() {
+    // 1 SOL slices against 50 SOL of depth move the price 200 bps
+    let curve = Curve::new(vec![
+        snapshot(0, 1_000, 50_000_000_000),
+        snapshot(150, 1_000, 500_000_000_000),
+    ]);
+    let execution = sim::execute(&plan(2_000_000_000, 2), &intent(0, 0, 100), &curve).unwrap();
+    assert_eq!(execution.slices_planned, 2);
+    assert_eq!(execution.slices_filled, 1);
+    assert_eq!(execution.size_filled, 1_000_000_000);
+}
+
+#[test]
+fn slices_run_at_their_own_size_and_delay() {
+    // Depth grows tenfold at 100 s: only slices sent after it fill
+    let curve = Curve::new(vec![
+        snapshot(0, 1_000, 50_000_000_000),
+        snapshot(100, 1_000, 500_000_000_000),
+    ]);
+    let slices = |delays: [u32; 3]| Plan {
+        privacy_mode: 0,
+        slices: [300_000_000, 1_000_000_000, 200_000_000]
+            .into_iter()
+            .zip(delays)
+            .map(|(size, delay_sec)| Slice { size, delay_sec })
+            .collect(),
+        exits: exits(),
+        take_profit_price: None,
+        stop_loss_price: None,
+    };
+    let early = sim::execute(&slices([0, 40, 90]), &intent(0, 0, 100), &curve).unwrap();
+    assert_eq!(early.slices_planned, 3);
+    assert_eq!(early.size_planned, 1_500_000_000);
+    // 0.3 and 0.2 SOL move the price 60 and 40 bps; 1 SOL 200 bps
+    assert_eq!(early.size_filled, 500_000_000);
+    let late = sim::execute(&slices([0, 100, 150]), &intent(0, 0, 100), &curve).unwrap();
+    assert_eq!(late.size_filled, 1_500_000_000);
+}
+
+#[test]
+fn strategy_plan_is_executed_on_its_schedule() {
+    let intent = intent(1_000, 10_000_000_000, 100);
+    let snapshot = snapshot(1_000, 1_000, 1_000_000_000_000);
+    let plan = sim::plan(Circuit::StrategyPlan, &intent, &snapshot);
+    let output = evalys_mxe_reference::strategy_plan(
+        strategy::UserPreferences {
+            desired_size: intent.desired_size,
+            slippage_tolerance: intent.slippage_tolerance,
+            risk_appetite: intent.risk_appetite,
+            preferred_hold_time: intent.preferred_hold_time,
+        },
+        strategy::UserHistory {
+            recent_pnl: intent.recent_pnl,
+            win_rate: intent.win_rate,
+            avg_hold_time: intent.avg_hold_time,
+            total_trades: intent.total_trades,
+        },
+        strategy::CurveState {
+            current_price: snapshot.price,
+            liquidity_depth: snapshot.liquidity_depth,
+            volatility: snapshot.volatility,
+            recent_volume: snapshot.recent_volume,
+        },
+        strategy::StrategyPolicy::default(),
+        strategy::InputCommitment { hi: 0, lo: 0 },
+        intent.timestamp,
+    );
+    let count = usize::from(output.num_slices);
+    assert!(count > 1);
+    assert_eq!(plan.slices.len(), count);
+    for (i, slice) in plan.slices.iter().enumerate() {
+        assert_eq!(slice.size, output.slice_sizes()[i]);
+        assert_eq!(slice.delay_sec, output.slice_delays_sec()[i]);
+    }
+    assert_eq!(plan.size(), output.slice_sizes().iter().sum::<u64>());
+    assert_eq!(plan.exits.len(), count);
+    for (i, exit) in plan.exits.iter().enumerate() {
+        assert_eq!(exit.bps, output.exit_slice_bps()[i]);
+        assert_eq!(
+            exit.delay_sec,
+            output.exit_start_sec + output.exit_delays_sec()[i]
+        );
+    }
+    assert_eq!(plan.take_profit_price, Some(output.take_profit_price));
+    assert_eq!(plan.stop_loss_price, Some(output.stop_loss_price));
+}
+
+#[test]
+fn exit_slices_sell_their_share_at_their_time() {
+    let curve = Curve::new(vec![
+        snapshot(0, 1_000, 1_000_000_000_000),
+        snapshot(500, 1_100, 1_000_000_000_000),
+        snapshot(1_000, 1_200, 1_000_000_000_000),
+    ]);
+    let plan = Plan {
+        exits: vec![
+            ExitSlice {
+                bps: 2_500,
+                delay_sec: 600,
+            },
+            ExitSlice {
+                bps: 7_500,
+                delay_sec: 1_100,
+            },
+        ],
+        ..plan(1_000_000_000, 1)
+    };
+    let execution = sim::execute(&plan, &intent(0, 0, 100), &curve).unwrap();
+    assert_eq!(execution.exit, ExitReason::Schedule);
+    // A quarter sold 10% up, the rest 20% up, less 10 bps of impact in and
+    // 3 and 9 bps out
+    assert!(execution.pnl > 172_500_000.0);
+    assert!(execution.pnl < 173_500_000.0);
+}
+
+#[test]
+fn take_profit_and_stop_loss_close_the_position_early() {
+    let plan = Plan {
+        take_profit_price: Some(1_150),
+        stop_loss_price: Some(900),
+        ..plan(1_000_000_000, 1)
+    };
+    let depth = 1_000_000_000_000;
+
+    // Sold at 1200 when the curve first reaches the take-profit, not at the
+    // scheduled 1000
+    let curve = Curve::new(vec![
+        snapshot(0, 1_000, depth),
+        snapshot(300, 1_200, depth),
+        snapshot(600, 1_000, depth),
+    ]);
+    let execution = sim::execute(&plan, &intent(0, 0, 100), &curve).unwrap();
+    assert_eq!(execution.exit, ExitReason::TakeProfit);
+    assert!(execution.pnl > 190_000_000.0);
+
+    // Sold at 800 when the curve first falls to the stop-loss, not at the
+    // scheduled 1200
+    let curve = Curve::new(vec![
+        snapshot(0, 1_000, depth),
+        snapshot(300, 800, depth),
+        snapshot(600, 1_200, depth),
+    ]);
+    let execution = sim::execute(&plan, &intent(0, 0, 100), &curve).unwrap();
+    assert_eq!(execution.exit, ExitReason::StopLoss);
+    assert!(execution.pnl < -200_000_000.0);
+
+    // Levels are watched from the last buy on only
+    let curve = Curve::new(vec![
+        snapshot(0, 1_000, depth),
+        snapshot(1_000, 1_200, depth),
+    ]);
+    let execution = sim::execute(&plan, &intent(0, 0, 100), &curve).unwrap();
+    assert_eq!(execution.exit, ExitReason::Schedule);
+}
+
+#[test]
+fn rising_curve_realizes_profit() {
+    let curve = Curve::new(vec![
+        snapshot(0, 1_000, 1_000_000_000_000),
+        snapshot(900, 1_100, 1_000_000_000_000),
+    ]);
+    let execution = sim::execute(&plan(1_000_000_000, 1), &intent(0, 0, 100), &curve).unwrap();
+    assert!(execution.pnl > 90_000_000.0);
+}
+
+#[test]
+fn intents_before_the_data_are_skipped() {
+    let curve = Curve::new(vec![snapshot(100, 1_000, 1_000_000_000_000)]);
+    let report = Report::run(
+        &curve,
+        &[
+            intent(50, 1_000_000_000, 100),
+            intent(100, 1_000_000_000, 100),
+        ],
+    );
+    assert_eq!(report.skipped, 1);
+    for circuit in Circuit::ALL {
+        let intents: u32 = report.circuits[&circuit].values().map(|s| s.intents).sum();
+        assert_eq!(intents, 1, "{}", circuit.name());
+    }
+}
+
+#[test]
+fn sample_data_covers_every_privacy_mode() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data");
+    let curve = Curve::new(
+        data::parse_snapshots(&fs::read_to_string(dir.join("snapshots.csv")).unwrap()).unwrap(),
+    );
+    let intents =
+        data::parse_intents(&fs::read_to_string(dir.join("intents.csv")).unwrap()).unwrap();
+    let report = Report::run(&curve, &intents);
+    assert_eq!(report.skipped, 0);
+    for circuit in Circuit::ALL {
+        let modes = &report.circuits[&circuit];
+        assert_eq!(modes.keys().copied().collect::<Vec<_>>(), [0, 1, 2]);
+        for stats in modes.values() {
+            assert!((0.0..=1.0).contains(&stats.fill_rate));
+            assert!(stats.slices_filled <= stats.slices_planned);
+        }
+    }
+}
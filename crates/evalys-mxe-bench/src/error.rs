@@ -0,0 +1,30 @@
+use std::io;
+use std::path::PathBuf;
+
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::ClientError;
+use evalys_mxe_mock::MockError;
+use thiserror::Error;
+
+/// Errors returned while measuring the circuits and instructions
+#[derive(Debug, Error)]
+pub enum BenchError {
+    #[error("reading {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("parsing {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: syn::Error,
+    },
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    #[error(transparent)]
+    Mock(#[from] MockError),
+    #[error("{0:?} callback emitted an event that is not base64")]
+    Event(CircuitKind),
+}
@@ -0,0 +1,394 @@
+//! Size of every program instruction, result event and callback transaction,
+//! measured by building them with `evalys-mxe-client` and running the
+//! requests through the mock cluster.
+//!
+//! The mock simulates the program on the host instead of executing it, so
+//! it cannot meter compute units; data size and account count are the parts
+//! of an instruction's cost the host can see, and the ones a change to the
+//! request layout moves. `request_multi_user_analytics` is measured with
+//! [`MAX_ANALYTICS_PROFILES`] profiles.
+
+use std::collections::BTreeMap;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use evalys_mxe_client::arcium::ArciumDeployment;
+use evalys_mxe_client::cipher::ClientCipher;
+use evalys_mxe_client::events::ResultEvent;
+use evalys_mxe_client::inputs::{self, EncryptedInput};
+use evalys_mxe_client::instructions::{self, RequestAccounts, MAX_ANALYTICS_PROFILES};
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::Pubkey;
+use evalys_mxe_mock::MockCluster;
+use serde::{Deserialize, Serialize};
+use solana_instruction::Instruction;
+use x25519_dalek::StaticSecret;
+
+use crate::BenchError;
+
+/// Size of one program instruction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstructionFootprint {
+    /// Discriminator and Borsh arguments
+    pub data_bytes: u64,
+    pub accounts: u64,
+    pub writable_accounts: u64,
+    pub signers: u64,
+}
+
+impl InstructionFootprint {
+    fn of(instruction: &Instruction) -> Self {
+        let count = |f: fn(&solana_instruction::AccountMeta) -> bool| {
+            instruction.accounts.iter().filter(|meta| f(meta)).count() as u64
+        };
+        InstructionFootprint {
+            data_bytes: instruction.data.len() as u64,
+            accounts: instruction.accounts.len() as u64,
+            writable_accounts: count(|meta| meta.is_writable),
+            signers: count(|meta| meta.is_signer),
+        }
+    }
+
+    /// Named sizes, in field order
+    pub fn metrics(&self) -> [(&'static str, u64); 4] {
+        [
+            ("data_bytes", self.data_bytes),
+            ("accounts", self.accounts),
+            ("writable_accounts", self.writable_accounts),
+            ("signers", self.signers),
+        ]
+    }
+}
+
+/// Largest serialized transaction a Solana validator accepts
+/// (`PACKET_DATA_SIZE`)
+pub const MAX_TRANSACTION_BYTES: u64 = 1232;
+
+/// Size of a circuit's callback and of the result event it emits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallbackFootprint {
+    /// Discriminator and Borsh fields of the event
+    pub event_bytes: u64,
+    /// Estimated size of the transaction that delivers the output to the
+    /// callback, at most [`MAX_TRANSACTION_BYTES`]
+    pub transaction_bytes: u64,
+}
+
+impl CallbackFootprint {
+    /// Named sizes, in field order
+    pub fn metrics(&self) -> [(&'static str, u64); 2] {
+        [
+            ("event_bytes", self.event_bytes),
+            ("transaction_bytes", self.transaction_bytes),
+        ]
+    }
+}
+
+/// Legacy transaction an Arcium node sends to invoke a callback: its
+/// signature, a message with the node, the program, the Arcium program, the
+/// comp def and computation accounts, the instructions sysvar and the
+/// callback accounts the request passed, and one instruction whose data is
+/// the callback discriminator and the Borsh `ComputationOutputs::Success`:
+/// the output's x25519 key, nonce and ciphertexts, then any revealed
+/// plaintext. The layout of the Arcium part is taken from `arcium-anchor`'s
+/// callback accounts and may drift with it.
+fn callback_transaction_bytes(
+    ciphertexts: usize,
+    callback_accounts: usize,
+    plaintext_bytes: usize,
+) -> u64 {
+    let keys = 6 + callback_accounts;
+    let data = 8 + 1 + 32 + 16 + 32 * ciphertexts + plaintext_bytes;
+    let signatures = 1 + 64;
+    let message = 3 + (1 + 32 * keys) + 32;
+    // Program index, account indices (all keys but the node and the
+    // program) and data, each with a compact length
+    let instruction = 1 + 1 + (1 + keys - 2) + (2 + data);
+    (signatures + message + instruction) as u64
+}
+
+/// Footprints of every instruction and callback, by name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Footprints {
+    pub instructions: BTreeMap<String, InstructionFootprint>,
+    /// By encrypted instruction
+    pub callbacks: BTreeMap<String, CallbackFootprint>,
+}
+
+fn sample_requests(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    deployment: &ArciumDeployment,
+    client: &ClientCipher,
+) -> Result<Vec<(&'static str, Instruction)>, BenchError> {
+    let accounts = |encrypted_ix: &str, computation_offset: u64| RequestAccounts {
+        payer: *payer,
+        computation_offset,
+        arcium: deployment.accounts(program_id, encrypted_ix, computation_offset),
+    };
+
+    let intent = inputs::IntentInput {
+        max_size_sol: 3_000_000_000,
+        risk_level: 1,
+        privacy_priority: 1,
+        market_price: 1_000,
+        curve_position: 4_000,
+        volatility_score: 5_000,
+        avg_hold_time: 900,
+        win_rate: 5_500,
+        max_dd: 1_800,
+    };
+    let intent = client.encrypt_with_nonce(&intent, 1);
+    let salt = client.encrypt_with_nonce(&inputs::QuorumSalt { salt: 1 }, 2);
+    let profile = inputs::UserProfile {
+        profile_id_hash: 1,
+        avg_hold_time: 3_600,
+        win_rate: 6_000,
+        preferred_size_range_min: 1_000_000_000,
+        preferred_size_range_max: 3_000_000_000,
+        risk_tolerance: 1,
+    };
+    let profiles: Vec<EncryptedInput> = (0..MAX_ANALYTICS_PROFILES as u128)
+        .map(|nonce| client.encrypt_with_nonce(&profile, 10 + nonce))
+        .collect();
+
+    let preferences = inputs::UserPreferences {
+        desired_size: 5_000_000_000,
+        slippage_tolerance: 150,
+        risk_appetite: 180,
+        preferred_hold_time: 3_600,
+    };
+    let history = inputs::UserHistory {
+        recent_pnl: 0,
+        win_rate: 5_000,
+        avg_hold_time: 1_800,
+        total_trades: 40,
+    };
+    let curve_state = inputs::CurveState {
+        current_price: 1_000,
+        liquidity_depth: 50_000_000_000,
+        volatility: 300,
+        recent_volume: 10_000_000_000,
+    };
+    let portfolio = inputs::PortfolioContext {
+        total_capital: 100_000_000_000,
+        current_exposure: 30_000_000_000,
+        diversification_score: 120,
+        leverage_ratio: 15_000,
+    };
+    let performance = inputs::PerformanceHistory {
+        total_pnl: 0,
+        sharpe_ratio: 50,
+        max_drawdown: 2_500,
+        consistency_score: 90,
+    };
+    let market = inputs::MarketConditions {
+        curve_volatility: 300,
+        liquidity_risk: 140,
+        market_sentiment: 0,
+    };
+    let sizing = inputs::SizingPreferences {
+        target_size: 2_000_000_000,
+        min_size: 500_000_000,
+        max_size: 4_000_000_000,
+        capital_allocation_pct: 25,
+    };
+    let constraints = inputs::UserConstraints {
+        max_slippage_bps: 200,
+        time_constraint_sec: 600,
+        priority_level: 200,
+    };
+    let metrics = inputs::CurveMetrics {
+        current_price: 1_000,
+        price_change_24h: 0,
+        liquidity_depth: 40_000_000_000,
+        buy_pressure: 5_000,
+        sell_pressure: 5_000,
+    };
+
+    Ok(vec![
+        (
+            "request_strategy_plan",
+            instructions::request_strategy_plan(
+                program_id,
+                &accounts(CircuitKind::StrategyPlan.encrypted_ix(), 1),
+                1,
+                &client.encrypt_with_nonce(&preferences, 3),
+                &client.encrypt_with_nonce(&history, 4),
+                &curve_state,
+            ),
+        ),
+        (
+            "request_risk_score",
+            instructions::request_risk_score(
+                program_id,
+                &accounts(CircuitKind::RiskScore.encrypted_ix(), 2),
+                1,
+                &client.encrypt_with_nonce(&portfolio, 5),
+                &client.encrypt_with_nonce(&performance, 6),
+                &market,
+            ),
+        ),
+        (
+            "request_curve_eval",
+            instructions::request_curve_eval(
+                program_id,
+                &accounts(CircuitKind::CurveEval.encrypted_ix(), 3),
+                1,
+                &client.encrypt_with_nonce(&sizing, 7),
+                &client.encrypt_with_nonce(&constraints, 8),
+                &metrics,
+            ),
+        ),
+        (
+            "request_gmpc_strategy",
+            instructions::request_gmpc_strategy(
+                program_id,
+                &accounts(CircuitKind::GmpcStrategy.encrypted_ix(), 4),
+                1,
+                &intent,
+            ),
+        ),
+        (
+            "request_multi_user_analytics",
+            instructions::request_multi_user_analytics(
+                program_id,
+                &accounts(CircuitKind::MultiUserAnalytics.encrypted_ix(), 5),
+                1,
+                &profiles,
+                0,
+            )?,
+        ),
+        (
+            "request_gmpc_strategy_quorum",
+            instructions::request_gmpc_strategy_quorum(
+                program_id,
+                &accounts(CircuitKind::GmpcStrategyQuorum.encrypted_ix(), 6),
+                7,
+                1,
+                &intent,
+                &salt,
+            ),
+        ),
+    ])
+}
+
+/// Build every program instruction, queue the requests on a mock cluster and
+/// measure the instructions and the result events of their callbacks
+pub fn measure() -> Result<Footprints, BenchError> {
+    let program_id = Pubkey::new_from_array([1; 32]);
+    let payer = Pubkey::new_from_array([2; 32]);
+    let deployment = ArciumDeployment {
+        arcium_program: Pubkey::new_from_array([3; 32]),
+        cluster_offset: 1,
+        pool_account: Pubkey::new_from_array([4; 32]),
+        clock_account: Pubkey::new_from_array([5; 32]),
+    };
+    let mut cluster = MockCluster::new(StaticSecret::from([6; 32]));
+    let client = ClientCipher::new(&StaticSecret::from([7; 32]), &cluster.public_key());
+    let peer_program_id = Pubkey::new_from_array([8; 32]);
+
+    let mut footprints = Footprints::default();
+    let mut add = |name: &str, instruction: &Instruction| {
+        footprints
+            .instructions
+            .insert(name.to_string(), InstructionFootprint::of(instruction));
+    };
+    add(
+        "initialize_config",
+        &instructions::initialize_config(&program_id, &payer, &peer_program_id),
+    );
+    add(
+        "initialize_audit_log",
+        &instructions::initialize_audit_log(&program_id, &payer),
+    );
+    add(
+        "set_quorum_peer",
+        &instructions::set_quorum_peer(&program_id, &payer, &peer_program_id),
+    );
+    add(
+        "init_client_key_registry",
+        &instructions::init_client_key_registry(&program_id, &payer),
+    );
+    add(
+        "rotate_client_key",
+        &instructions::rotate_client_key(&program_id, &payer, &[10; 32]),
+    );
+    add(
+        "remove_client_key",
+        &instructions::remove_client_key(&program_id, &payer, 1),
+    );
+    for kind in CircuitKind::ALL {
+        let instruction = instructions::init_comp_def(
+            &program_id,
+            &payer,
+            kind,
+            &[11; 32],
+            "v0.1.0",
+            &format!("https://circuits.example/{}.arcis", kind.encrypted_ix()),
+            &deployment.comp_def_accounts(&program_id, kind.encrypted_ix()),
+        );
+        add(kind.init_instruction(), &instruction);
+        cluster.register_circuit(kind, [11; 32]);
+    }
+    add(
+        "upgrade_circuit",
+        &instructions::upgrade_circuit(
+            &program_id,
+            &payer,
+            CircuitKind::StrategyPlan,
+            &[12; 32],
+            "v0.2.0",
+            &deployment
+                .comp_def_accounts(&program_id, CircuitKind::StrategyPlan.encrypted_ix())
+                .comp_def_account,
+        ),
+    );
+    add(
+        "open_gmpc_quorum",
+        &instructions::open_gmpc_quorum(&program_id, &payer, 7),
+    );
+    add(
+        "settle_gmpc_quorum",
+        &instructions::settle_gmpc_quorum(&program_id, &peer_program_id, 7),
+    );
+
+    for (name, instruction) in sample_requests(&program_id, &payer, &deployment, &client)? {
+        add(name, &instruction);
+        cluster.queue(&instruction)?;
+    }
+    for callback in cluster.execute_all()? {
+        let event_bytes = callback
+            .logs
+            .iter()
+            .filter_map(|log| log.strip_prefix("Program data: "))
+            .map(|data| STANDARD.decode(data).map(|bytes| bytes.len() as u64))
+            .sum::<Result<u64, _>>()
+            .map_err(|_| BenchError::Event(callback.kind))?;
+        let ciphertexts = callback
+            .logs
+            .iter()
+            .map(|log| ResultEvent::from_log(log).map_err(|_| BenchError::Event(callback.kind)))
+            .filter_map(Result::transpose)
+            .map(|event| event.map(|event| event.ciphertexts.len()))
+            .sum::<Result<usize, _>>()?;
+        // Quorum legs also update the quorum, read the peer's and reveal a
+        // u128 digest
+        let (callback_accounts, plaintext_bytes) = match callback.quorum_digest {
+            Some(_) => (4, 16),
+            None => (2, 0),
+        };
+        footprints.callbacks.insert(
+            callback.kind.encrypted_ix().to_string(),
+            CallbackFootprint {
+                event_bytes,
+                transaction_bytes: callback_transaction_bytes(
+                    ciphertexts,
+                    callback_accounts,
+                    plaintext_bytes,
+                ),
+            },
+        );
+    }
+    Ok(footprints)
+}
@@ -0,0 +1,28 @@
+//! Cost tracking for the MXE's circuits and program instructions.
+//!
+//! [`ops`] counts the comparisons, multiplications, divisions and additions
+//! of every encrypted instruction in `encrypted-ixs/` from its source, which
+//! is what an MPC circuit evaluates: every branch, every iteration.
+//! [`footprint`] builds every program instruction with `evalys-mxe-client`,
+//! runs the requests through `evalys-mxe-mock` and measures their data,
+//! accounts and result events. [`report::Report`] holds both and compares
+//! them with a baseline.
+//!
+//! This crate does not meter compute units or count the gates of compiled
+//! circuits. Both are separate, outstanding work:
+//!
+//! - The op counts are estimates from the Rust source, not counts of the
+//!   gates in the `.arcis` circuits `arcium build` produces. They track
+//!   changes to the source, but the compiler's own lowering (bit
+//!   decompositions, constant folding, dead-branch removal) is not reflected.
+//! - Compute units are not measured: the mock simulates the program instead
+//!   of executing it. Metering them needs the program built for BPF and run
+//!   under `solana-program-test` or LiteSVM with a stub Arcium program, the
+//!   program-level harness `evalys-mxe-mock` documents as outstanding.
+
+mod error;
+pub mod footprint;
+pub mod ops;
+pub mod report;
+
+pub use error::BenchError;
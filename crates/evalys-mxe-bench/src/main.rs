@@ -0,0 +1,65 @@
+//! `evalys-mxe-bench`: measure the circuits and program instructions and
+//! check them against the baseline.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use evalys_mxe_bench::report::Report;
+
+#[derive(Parser)]
+#[command(
+    name = "evalys-mxe-bench",
+    version,
+    about = "Operation counts of the Evalys MXE circuits and footprint of its instructions"
+)]
+struct Cli {
+    /// Directory of the encrypted instructions
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/../../encrypted-ixs"))]
+    encrypted_ixs: PathBuf,
+    /// Baseline to compare with
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/baseline.json"))]
+    baseline: PathBuf,
+    /// Overwrite the baseline with the current measurements
+    #[arg(long)]
+    update: bool,
+    /// Print the measurements as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let report = Report::measure(&cli.encrypted_ixs)?;
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{report}");
+    }
+
+    if cli.update {
+        let json = serde_json::to_string_pretty(&report)? + "\n";
+        fs::write(&cli.baseline, json)
+            .with_context(|| format!("writing {}", cli.baseline.display()))?;
+        eprintln!("baseline written to {}", cli.baseline.display());
+        return Ok(());
+    }
+    let baseline: Report = serde_json::from_str(
+        &fs::read_to_string(&cli.baseline)
+            .with_context(|| format!("reading {}", cli.baseline.display()))?,
+    )
+    .with_context(|| format!("parsing {}", cli.baseline.display()))?;
+    let regressions = report.regressions(&baseline);
+    if !regressions.is_empty() {
+        for regression in &regressions {
+            eprintln!("regression: {regression}");
+        }
+        bail!(
+            "{} measurement(s) above {}",
+            regressions.len(),
+            cli.baseline.display()
+        );
+    }
+    Ok(())
+}
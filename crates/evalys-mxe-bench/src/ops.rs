@@ -0,0 +1,289 @@
+//! Static operation counts of the encrypted instructions.
+//!
+//! The counts are estimated from the Rust source of `encrypted-ixs/`, not read
+//! from the compiled `.arcis` circuits, so they compare revisions of the
+//! source rather than predict the circuit size.
+//!
+//! An MPC circuit evaluates every branch of every `if` and `match`, so the
+//! cost of an encrypted instruction is the number of operations in its
+//! source, not in any one execution path. Counting rules:
+//!
+//! - `<`, `<=`, `>`, `>=`, `==`, `!=`, each non-wildcard `match` arm, and
+//!   `min(x)`, `max(x)` (1), `clamp(a, b)` (2) are comparisons.
+//! - `*` is a multiplication, `/` and `%` are divisions, `+` and `-` (and
+//!   their compound assignments) are additions. `saturating_add` and
+//!   `saturating_sub` are an addition and a comparison, `wrapping_add` and
+//!   `wrapping_sub` an addition.
+//! - Operations between constants are folded at compile time and not counted;
+//!   neither are casts, bitwise and boolean operators, or field and constant
+//!   index accesses. The variable of a counted `for` loop (below) is a
+//!   constant in each unrolled iteration.
+//! - Calls to functions of the same module are inlined.
+//! - A `for` loop over a range of literals and module constants runs once
+//!   per value. Other loops and iterator closures run once per element: their
+//!   body counts `elements` times, and `sum()`, `max()` and `min()` over an
+//!   iterator are `elements - 1` additions or comparisons. With `elements`
+//!   set to the largest input a circuit accepts, the counts are the worst
+//!   case.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use syn::visit::{self, Visit};
+use syn::{BinOp, Expr, ExprBinary, ExprCall, ExprClosure, ExprForLoop, ExprMatch};
+use syn::{ExprMethodCall, ExprRange, File, Item, ItemFn, Lit, Pat, RangeLimits};
+
+/// Operations of one encrypted instruction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpCounts {
+    pub comparisons: u64,
+    pub multiplications: u64,
+    pub divisions: u64,
+    pub additions: u64,
+}
+
+impl OpCounts {
+    /// Named counts, in field order
+    pub fn metrics(&self) -> [(&'static str, u64); 4] {
+        [
+            ("comparisons", self.comparisons),
+            ("multiplications", self.multiplications),
+            ("divisions", self.divisions),
+            ("additions", self.additions),
+        ]
+    }
+}
+
+/// Counts of every `#[instruction]` function in `file`, by name
+pub fn count_file(file: &File, elements: u64) -> BTreeMap<String, OpCounts> {
+    let mut counts = BTreeMap::new();
+    collect(&file.items, elements, &mut counts);
+    counts
+}
+
+fn collect(items: &[Item], elements: u64, counts: &mut BTreeMap<String, OpCounts>) {
+    let functions: HashMap<String, &ItemFn> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fn(function) => Some((function.sig.ident.to_string(), function)),
+            _ => None,
+        })
+        .collect();
+    let constants: HashMap<String, u64> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Const(constant) => Some((
+                constant.ident.to_string(),
+                integer(&constant.expr, &HashMap::new())?,
+            )),
+            _ => None,
+        })
+        .collect();
+    for item in items {
+        match item {
+            Item::Fn(function) if is_instruction(function) => {
+                let mut counter = Counter {
+                    functions: &functions,
+                    constants: &constants,
+                    elements,
+                    factor: 1,
+                    inlining: vec![function.sig.ident.to_string()],
+                    loop_variables: Vec::new(),
+                    counts: OpCounts::default(),
+                };
+                counter.visit_block(&function.block);
+                counts.insert(function.sig.ident.to_string(), counter.counts);
+            }
+            Item::Mod(module) => {
+                if let Some((_, items)) = &module.content {
+                    collect(items, elements, counts);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_instruction(function: &ItemFn) -> bool {
+    function
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("instruction"))
+}
+
+/// Literal, or an expression of literals, `SCREAMING_CASE` constants and
+/// the `loop_variables` of enclosing counted loops
+fn is_constant(expr: &Expr, loop_variables: &[String]) -> bool {
+    match expr {
+        Expr::Lit(_) => true,
+        Expr::Path(path) => path.path.get_ident().is_some_and(|ident| {
+            let name = ident.to_string();
+            name.chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                || loop_variables.contains(&name)
+        }),
+        Expr::Paren(paren) => is_constant(&paren.expr, loop_variables),
+        Expr::Cast(cast) => is_constant(&cast.expr, loop_variables),
+        Expr::Unary(unary) => is_constant(&unary.expr, loop_variables),
+        Expr::Binary(binary) => {
+            is_constant(&binary.left, loop_variables) && is_constant(&binary.right, loop_variables)
+        }
+        _ => false,
+    }
+}
+
+/// Value of an integer literal or of a constant in `constants`
+fn integer(expr: &Expr, constants: &HashMap<String, u64>) -> Option<u64> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int.base10_parse().ok(),
+            _ => None,
+        },
+        Expr::Path(path) => constants.get(&path.path.get_ident()?.to_string()).copied(),
+        Expr::Paren(paren) => integer(&paren.expr, constants),
+        _ => None,
+    }
+}
+
+/// Alternatives a `match` arm compares the scrutinee against
+fn arm_cases(pat: &Pat) -> u64 {
+    match pat {
+        Pat::Wild(_) | Pat::Ident(_) => 0,
+        Pat::Or(or) => or.cases.iter().map(arm_cases).sum(),
+        _ => 1,
+    }
+}
+
+struct Counter<'a> {
+    functions: &'a HashMap<String, &'a ItemFn>,
+    constants: &'a HashMap<String, u64>,
+    elements: u64,
+    /// Times the expression being visited runs
+    factor: u64,
+    /// Functions being inlined, to stop at recursion
+    inlining: Vec<String>,
+    /// Variables of the counted loops being visited
+    loop_variables: Vec<String>,
+    counts: OpCounts,
+}
+
+impl Counter<'_> {
+    fn repeated(&mut self, times: u64, visit: impl FnOnce(&mut Self)) {
+        let factor = self.factor;
+        self.factor *= times;
+        visit(self);
+        self.factor = factor;
+    }
+
+    /// Values of a range of literals and constants
+    fn range_len(&self, range: &ExprRange) -> Option<u64> {
+        let start = match &range.start {
+            Some(start) => integer(start, self.constants)?,
+            None => 0,
+        };
+        let end = integer(range.end.as_deref()?, self.constants)?;
+        Some(match range.limits {
+            RangeLimits::HalfOpen(_) => end.saturating_sub(start),
+            RangeLimits::Closed(_) => (end + 1).saturating_sub(start),
+        })
+    }
+
+    /// `elements - 1` operations of a reduction over an iterator
+    fn reduction(&self) -> u64 {
+        self.factor * self.elements.saturating_sub(1)
+    }
+}
+
+impl<'ast> Visit<'ast> for Counter<'_> {
+    fn visit_expr_binary(&mut self, expr: &'ast ExprBinary) {
+        if !is_constant(&expr.left, &self.loop_variables)
+            || !is_constant(&expr.right, &self.loop_variables)
+        {
+            let counts = &mut self.counts;
+            let count = match expr.op {
+                BinOp::Lt(_)
+                | BinOp::Le(_)
+                | BinOp::Gt(_)
+                | BinOp::Ge(_)
+                | BinOp::Eq(_)
+                | BinOp::Ne(_) => Some(&mut counts.comparisons),
+                BinOp::Mul(_) | BinOp::MulAssign(_) => Some(&mut counts.multiplications),
+                BinOp::Div(_) | BinOp::Rem(_) | BinOp::DivAssign(_) | BinOp::RemAssign(_) => {
+                    Some(&mut counts.divisions)
+                }
+                BinOp::Add(_) | BinOp::Sub(_) | BinOp::AddAssign(_) | BinOp::SubAssign(_) => {
+                    Some(&mut counts.additions)
+                }
+                _ => None,
+            };
+            if let Some(count) = count {
+                *count += self.factor;
+            }
+        }
+        visit::visit_expr_binary(self, expr);
+    }
+
+    fn visit_expr_match(&mut self, expr: &'ast ExprMatch) {
+        let cases: u64 = expr.arms.iter().map(|arm| arm_cases(&arm.pat)).sum();
+        self.counts.comparisons += self.factor * cases;
+        visit::visit_expr_match(self, expr);
+    }
+
+    fn visit_expr_method_call(&mut self, expr: &'ast ExprMethodCall) {
+        let method = expr.method.to_string();
+        match (method.as_str(), expr.args.len()) {
+            ("min" | "max", 0) => self.counts.comparisons += self.reduction(),
+            ("min" | "max", 1) => self.counts.comparisons += self.factor,
+            ("clamp", 2) => self.counts.comparisons += 2 * self.factor,
+            ("sum", 0) => self.counts.additions += self.reduction(),
+            ("saturating_add" | "saturating_sub", 1) => {
+                self.counts.additions += self.factor;
+                self.counts.comparisons += self.factor;
+            }
+            ("wrapping_add" | "wrapping_sub", 1) => self.counts.additions += self.factor,
+            _ => {}
+        }
+        visit::visit_expr_method_call(self, expr);
+    }
+
+    fn visit_expr_call(&mut self, expr: &'ast ExprCall) {
+        if let Expr::Path(path) = &*expr.func {
+            if let Some(ident) = path.path.get_ident() {
+                let name = ident.to_string();
+                if let Some(function) = self.functions.get(&name) {
+                    if !self.inlining.contains(&name) {
+                        self.inlining.push(name);
+                        self.visit_block(&function.block);
+                        self.inlining.pop();
+                    }
+                }
+            }
+        }
+        visit::visit_expr_call(self, expr);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast ExprForLoop) {
+        self.visit_expr(&expr.expr);
+        let times = match &*expr.expr {
+            Expr::Range(range) => self.range_len(range),
+            _ => None,
+        };
+        // A counted loop unrolls, so its variable is a constant in the body
+        let variable = match (&*expr.pat, times) {
+            (Pat::Ident(ident), Some(_)) => Some(ident.ident.to_string()),
+            _ => None,
+        };
+        let unrolled = variable.is_some();
+        self.loop_variables.extend(variable);
+        self.repeated(times.unwrap_or(self.elements), |counter| {
+            counter.visit_block(&expr.body)
+        });
+        if unrolled {
+            self.loop_variables.pop();
+        }
+    }
+
+    fn visit_expr_closure(&mut self, expr: &'ast ExprClosure) {
+        self.repeated(self.elements, |counter| counter.visit_expr(&expr.body));
+    }
+}
@@ -0,0 +1,198 @@
+//! All measurements, and their comparison with a baseline.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use evalys_mxe_client::instructions::MAX_ANALYTICS_PROFILES;
+use serde::{Deserialize, Serialize};
+
+use crate::footprint::{self, CallbackFootprint, InstructionFootprint};
+use crate::ops::{self, OpCounts};
+use crate::BenchError;
+
+/// Operation counts of every encrypted instruction and footprint of every
+/// program instruction; the baseline file is one serialized
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    /// By encrypted instruction
+    pub circuits: BTreeMap<String, OpCounts>,
+    /// By program instruction
+    pub instructions: BTreeMap<String, InstructionFootprint>,
+    /// By encrypted instruction
+    pub callbacks: BTreeMap<String, CallbackFootprint>,
+}
+
+/// A measurement above its baseline, or missing from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regression {
+    pub section: &'static str,
+    pub name: String,
+    pub metric: &'static str,
+    /// `None` if the baseline has no entry for `name`
+    pub baseline: Option<u64>,
+    pub current: u64,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.baseline {
+            Some(baseline) => write!(
+                f,
+                "{} {}: {} {} -> {}",
+                self.section, self.name, self.metric, baseline, self.current
+            ),
+            None => write!(f, "{} {}: not in the baseline", self.section, self.name),
+        }
+    }
+}
+
+fn compare<T, const N: usize>(
+    section: &'static str,
+    current: &BTreeMap<String, T>,
+    baseline: &BTreeMap<String, T>,
+    metrics: fn(&T) -> [(&'static str, u64); N],
+    regressions: &mut Vec<Regression>,
+) {
+    for (name, measured) in current {
+        let Some(base) = baseline.get(name) else {
+            regressions.push(Regression {
+                section,
+                name: name.clone(),
+                metric: "",
+                baseline: None,
+                current: 0,
+            });
+            continue;
+        };
+        for ((metric, current), (_, baseline)) in metrics(measured).into_iter().zip(metrics(base)) {
+            if current > baseline {
+                regressions.push(Regression {
+                    section,
+                    name: name.clone(),
+                    metric,
+                    baseline: Some(baseline),
+                    current,
+                });
+            }
+        }
+    }
+}
+
+impl Report {
+    /// Count the operations of every `*.rs` file in `encrypted_ixs` and
+    /// measure the program instructions
+    pub fn measure(encrypted_ixs: &Path) -> Result<Self, BenchError> {
+        let io = |source| BenchError::Io {
+            path: encrypted_ixs.to_path_buf(),
+            source,
+        };
+        let mut paths = fs::read_dir(encrypted_ixs)
+            .map_err(io)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io)?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "rs"));
+        paths.sort();
+
+        let mut report = Report::default();
+        for path in paths {
+            let source = fs::read_to_string(&path).map_err(|source| BenchError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            let file = syn::parse_file(&source).map_err(|source| BenchError::Parse {
+                path: path.clone(),
+                source,
+            })?;
+            report
+                .circuits
+                .extend(ops::count_file(&file, MAX_ANALYTICS_PROFILES as u64));
+        }
+
+        let footprints = footprint::measure()?;
+        report.instructions = footprints.instructions;
+        report.callbacks = footprints.callbacks;
+        Ok(report)
+    }
+
+    /// Measurements that went up since `baseline`, or that it does not have
+    pub fn regressions(&self, baseline: &Report) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+        compare(
+            "circuit",
+            &self.circuits,
+            &baseline.circuits,
+            OpCounts::metrics,
+            &mut regressions,
+        );
+        compare(
+            "instruction",
+            &self.instructions,
+            &baseline.instructions,
+            InstructionFootprint::metrics,
+            &mut regressions,
+        );
+        compare(
+            "callback",
+            &self.callbacks,
+            &baseline.callbacks,
+            CallbackFootprint::metrics,
+            &mut regressions,
+        );
+        regressions
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<36} {:>11} {:>15} {:>9} {:>9}",
+            "circuit (source estimate)", "comparisons", "multiplications", "divisions", "additions"
+        )?;
+        for (name, counts) in &self.circuits {
+            writeln!(
+                f,
+                "{:<36} {:>11} {:>15} {:>9} {:>9}",
+                name,
+                counts.comparisons,
+                counts.multiplications,
+                counts.divisions,
+                counts.additions
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<36} {:>11} {:>15} {:>9} {:>9}",
+            "instruction", "data bytes", "accounts", "writable", "signers"
+        )?;
+        for (name, footprint) in &self.instructions {
+            writeln!(
+                f,
+                "{:<36} {:>11} {:>15} {:>9} {:>9}",
+                name,
+                footprint.data_bytes,
+                footprint.accounts,
+                footprint.writable_accounts,
+                footprint.signers
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<36} {:>11} {:>15}",
+            "callback", "event bytes", "tx bytes"
+        )?;
+        for (name, footprint) in &self.callbacks {
+            writeln!(
+                f,
+                "{:<36} {:>11} {:>15}",
+                name, footprint.event_bytes, footprint.transaction_bytes
+            )?;
+        }
+        Ok(())
+    }
+}
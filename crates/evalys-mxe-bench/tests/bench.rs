@@ -0,0 +1,170 @@
+//! Counting rules of the operation counter, and the checked-in baseline.
+//!
+//! After an intentional change to a circuit or an instruction layout,
+//! regenerate the baseline and review the diff:
+//!
+//! ```text
+//! EVALYS_UPDATE_BASELINE=1 cargo test -p evalys-mxe-bench --test bench
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+
+use evalys_mxe_bench::footprint::MAX_TRANSACTION_BYTES;
+use evalys_mxe_bench::ops::{self, OpCounts};
+use evalys_mxe_bench::report::Report;
+
+fn count(source: &str, elements: u64) -> OpCounts {
+    let file = syn::parse_file(source).unwrap();
+    let counts = ops::count_file(&file, elements);
+    assert_eq!(counts.len(), 1);
+    counts.into_values().next().unwrap()
+}
+
+#[test]
+fn every_branch_counts() {
+    let counts = count(
+        r#"
+        #[encrypted]
+        mod circuits {
+            #[instruction]
+            pub fn circuit(a: u64, b: u8) -> u64 {
+                let x = if a > 100 { a * 3 } else { a / 2 };
+                let y = match b {
+                    0 => 1u64,
+                    1 | 2 => 2u64,
+                    _ => 3u64,
+                };
+                (x + y).clamp(0, 1_000) - 10_000 * 100
+            }
+        }
+        "#,
+        1,
+    );
+    assert_eq!(
+        counts,
+        OpCounts {
+            comparisons: 1 + 3 + 2,
+            multiplications: 1,
+            divisions: 1,
+            additions: 2,
+        }
+    );
+}
+
+#[test]
+fn helpers_are_inlined() {
+    let counts = count(
+        r#"
+        mod circuits {
+            #[instruction]
+            pub fn circuit(a: u64) -> u64 {
+                half(a) + half(a)
+            }
+
+            fn half(a: u64) -> u64 {
+                a / 2
+            }
+        }
+        "#,
+        1,
+    );
+    assert_eq!(counts.divisions, 2);
+    assert_eq!(counts.additions, 1);
+}
+
+#[test]
+fn loops_count_per_element() {
+    let counts = count(
+        r#"
+        mod circuits {
+            #[instruction]
+            pub fn circuit(values: Vec<u64>) -> u64 {
+                let mut total = 0;
+                for value in &values {
+                    total += value * 2;
+                }
+                let largest = values.iter().map(|v| v % 7).max().unwrap_or(0);
+                let sum: u64 = values.iter().map(|v| v.min(100)).sum();
+                total + largest + sum
+            }
+        }
+        "#,
+        4,
+    );
+    assert_eq!(
+        counts,
+        OpCounts {
+            comparisons: 3 + 4,
+            multiplications: 4,
+            divisions: 4,
+            additions: 4 + 3 + 2,
+        }
+    );
+}
+
+#[test]
+fn counted_loops_run_per_value() {
+    let counts = count(
+        r#"
+        mod circuits {
+            const SLOTS: usize = 8;
+
+            #[instruction]
+            pub fn circuit(a: u64) -> u64 {
+                let mut total = 0;
+                for i in 0..SLOTS {
+                    total += a * (i as u64);
+                    total += a >> (8 * (i % 2));
+                }
+                for _ in 1..=3 {
+                    total -= 1;
+                }
+                total
+            }
+        }
+        "#,
+        16,
+    );
+    assert_eq!(counts.multiplications, 8);
+    assert_eq!(counts.divisions, 0);
+    assert_eq!(counts.additions, 2 * 8 + 3);
+}
+
+#[test]
+fn callbacks_fit_in_a_transaction() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let report = Report::measure(&root.join("../../encrypted-ixs")).unwrap();
+    assert_eq!(report.callbacks.len(), 6);
+    for (name, footprint) in &report.callbacks {
+        assert!(
+            footprint.transaction_bytes <= MAX_TRANSACTION_BYTES,
+            "{name} callback is {} bytes",
+            footprint.transaction_bytes
+        );
+    }
+}
+
+#[test]
+fn baseline_is_current() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let report = Report::measure(&root.join("../../encrypted-ixs")).unwrap();
+    let path = root.join("baseline.json");
+    if std::env::var_os("EVALYS_UPDATE_BASELINE").is_some() {
+        let json = serde_json::to_string_pretty(&report).unwrap() + "\n";
+        fs::write(&path, json).unwrap();
+        return;
+    }
+    let baseline: Report = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let regressions: Vec<String> = report
+        .regressions(&baseline)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert!(regressions.is_empty(), "regressions: {regressions:#?}");
+    assert!(
+        report == baseline,
+        "{} is stale (set EVALYS_UPDATE_BASELINE=1 to regenerate)",
+        path.display()
+    );
+}
@@ -0,0 +1,141 @@
+//! HTTP API.
+//!
+//! | route                                          | body                                | response                                         |
+//! |------------------------------------------------|-------------------------------------|--------------------------------------------------|
+//! | `POST /circuits/<circuit>?timeout=<seconds>`   | request file of `evalys-mxe submit` | `{ "circuit", "computation_offsets", "output" }` |
+//! | `GET /schema`                                  |                                     | JSON Schema of the request files and outputs     |
+//!
+//! `<circuit>` is `strategy-plan`, `risk-score`, `curve-eval`,
+//! `gmpc-strategy`, `gmpc-strategy-quorum` or `multi-user-analytics`.
+//! Errors are `{ "error": "..." }` with status 400 (invalid input), 404
+//! (unknown route or circuit), 502 (the request failed or its result did not
+//! verify) or 504 (no result within the timeout). Requests are served one at
+//! a time.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use tiny_http::{Header, Server};
+
+use crate::backend::Backend;
+use crate::bridge::{self, Bridge};
+use crate::BridgeError;
+
+/// Status code and JSON body of an API response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+impl From<BridgeError> for Response {
+    fn from(err: BridgeError) -> Self {
+        Response::error(err.status(), err.to_string())
+    }
+}
+
+fn timeout(query: &str, default: Duration) -> Result<Duration, Response> {
+    let mut timeout = default;
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        match pair.split_once('=') {
+            Some(("timeout", seconds)) => {
+                timeout = seconds.parse().map(Duration::from_secs).map_err(|_| {
+                    Response::error(
+                        400,
+                        format!("timeout is not a number of seconds: {seconds}"),
+                    )
+                })?
+            }
+            _ => {
+                return Err(Response::error(
+                    400,
+                    format!("unknown query parameter {pair}"),
+                ))
+            }
+        }
+    }
+    Ok(timeout)
+}
+
+/// Answer one request; `default_timeout` applies when the request sets none
+pub fn handle<B: Backend>(
+    bridge: &mut Bridge<B>,
+    default_timeout: Duration,
+    method: &str,
+    url: &str,
+    body: &str,
+) -> Response {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let path = path.trim_end_matches('/');
+    if path == "/schema" {
+        if method != "GET" {
+            return Response::error(405, "only GET is supported");
+        }
+        return Response {
+            status: 200,
+            body: evalys_mxe_client::schema::json_schema(),
+        };
+    }
+    let Some(name) = path.strip_prefix("/circuits/") else {
+        return Response::error(404, format!("no route {path}"));
+    };
+    if method != "POST" {
+        return Response::error(405, "only POST is supported");
+    }
+    let Some(kind) = bridge::circuit(name) else {
+        return BridgeError::UnknownCircuit(name.to_string()).into();
+    };
+    let outcome = timeout(query, default_timeout).and_then(|timeout| {
+        bridge
+            .run(kind, body, timeout)
+            .map_err(Response::from)
+            .and_then(|outcome| {
+                serde_json::to_value(outcome).map_err(|err| BridgeError::from(err).into())
+            })
+    });
+    match outcome {
+        Ok(body) => Response { status: 200, body },
+        Err(response) => response,
+    }
+}
+
+/// Serve the API on `listen` until the process exits
+pub fn serve<B: Backend>(
+    bridge: &mut Bridge<B>,
+    default_timeout: Duration,
+    listen: &str,
+) -> Result<()> {
+    let server = Server::http(listen).map_err(|err| anyhow!("listening on {listen}: {err}"))?;
+    let content_type = Header::from_bytes("Content-Type", "application/json")
+        .map_err(|_| anyhow!("invalid content type header"))?;
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let response = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => handle(
+                bridge,
+                default_timeout,
+                request.method().as_str(),
+                request.url(),
+                &body,
+            ),
+            Err(err) => Response::error(400, format!("reading request body: {err}")),
+        };
+        let reply = tiny_http::Response::from_string(response.body.to_string())
+            .with_status_code(response.status)
+            .with_header(content_type.clone());
+        if let Err(err) = request.respond(reply) {
+            eprintln!("responding to request: {err}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+//! Where the bridge sends its requests: a live cluster over RPC
+//! ([`crate::rpc::RpcBackend`]) or the mock executor ([`MockBackend`]).
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use evalys_mxe_client::arcium::ArciumDeployment;
+use evalys_mxe_client::events::ResultEvent;
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::Pubkey;
+use evalys_mxe_mock::{Callback, MockCluster, MockError};
+use solana_instruction::Instruction;
+use x25519_dalek::StaticSecret;
+
+use crate::BridgeError;
+
+/// Submits transactions and reports computation results
+pub trait Backend {
+    /// Fee payer and requester of every request
+    fn payer(&self) -> Pubkey;
+
+    /// Sign and send `instructions` in one transaction
+    fn send(&mut self, instructions: &[Instruction]) -> Result<(), BridgeError>;
+
+    /// Result event of the computation `program_id` queued at
+    /// `computation_offset`, waiting up to `timeout` for it to complete
+    fn result(
+        &mut self,
+        program_id: &Pubkey,
+        computation_offset: u64,
+        timeout: Duration,
+    ) -> Result<ResultEvent, BridgeError>;
+}
+
+/// Circuit hash the mock registers every comp def with
+pub const MOCK_CIRCUIT_HASH: [u8; 32] = [0; 32];
+
+/// The mock executor: requests run as soon as their result is asked for.
+///
+/// Each deployment has its own mock cluster. Instructions other than
+/// `request_*` (`open_gmpc_quorum`) and instructions of other programs are
+/// accepted and ignored, as the mock does not model program state.
+pub struct MockBackend {
+    clusters: HashMap<Pubkey, MockCluster>,
+    payer: Pubkey,
+    callbacks: HashMap<(Pubkey, u64), Callback>,
+}
+
+impl MockBackend {
+    /// Mock cluster of `program_id` whose MXE holds `secret`, with every
+    /// circuit registered
+    pub fn new(program_id: Pubkey, secret: StaticSecret, payer: Pubkey) -> Self {
+        MockBackend {
+            clusters: HashMap::new(),
+            payer,
+            callbacks: HashMap::new(),
+        }
+        .with_deployment(program_id, secret)
+    }
+
+    /// Add a second deployment, e.g. a quorum peer, on its own mock cluster
+    pub fn with_deployment(mut self, program_id: Pubkey, secret: StaticSecret) -> Self {
+        let mut cluster = MockCluster::new(secret);
+        for kind in CircuitKind::ALL {
+            cluster.register_circuit(kind, MOCK_CIRCUIT_HASH);
+        }
+        self.clusters.insert(program_id, cluster);
+        self
+    }
+
+    /// MXE x25519 public key of the mock cluster of `program_id`
+    pub fn mxe_x25519_pubkey(&self, program_id: &Pubkey) -> Option<[u8; 32]> {
+        self.clusters.get(program_id).map(MockCluster::public_key)
+    }
+
+    /// Arcium accounts of a made-up deployment; the mock does not read them
+    pub fn deployment() -> ArciumDeployment {
+        ArciumDeployment {
+            arcium_program: Pubkey::new_from_array([1; 32]),
+            cluster_offset: 0,
+            pool_account: Pubkey::new_from_array([2; 32]),
+            clock_account: Pubkey::new_from_array([3; 32]),
+        }
+    }
+}
+
+impl Backend for MockBackend {
+    fn payer(&self) -> Pubkey {
+        self.payer
+    }
+
+    fn send(&mut self, instructions: &[Instruction]) -> Result<(), BridgeError> {
+        for instruction in instructions {
+            let Some(cluster) = self.clusters.get_mut(&instruction.program_id) else {
+                continue;
+            };
+            match cluster.queue(instruction) {
+                Ok(_) | Err(MockError::NotARequest) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn result(
+        &mut self,
+        program_id: &Pubkey,
+        computation_offset: u64,
+        timeout: Duration,
+    ) -> Result<ResultEvent, BridgeError> {
+        for (&program_id, cluster) in &mut self.clusters {
+            for callback in cluster.execute_all()? {
+                self.callbacks
+                    .insert((program_id, callback.computation_offset), callback);
+            }
+        }
+        let callback = self
+            .callbacks
+            .remove(&(*program_id, computation_offset))
+            .ok_or(BridgeError::Timeout(computation_offset, timeout))?;
+        for log in &callback.logs {
+            if let Some(event) = ResultEvent::from_log(log)? {
+                return Ok(event);
+            }
+        }
+        Err(BridgeError::Aborted(computation_offset))
+    }
+}
@@ -0,0 +1,376 @@
+//! The request loop every integrator would otherwise write: encrypt the
+//! plaintext inputs, queue the circuit's request, wait for the result event,
+//! decrypt it and check that it echoes the request's input commitment.
+
+use std::time::Duration;
+
+use evalys_mxe_client::arcium::ArciumDeployment;
+use evalys_mxe_client::cipher::ClientCipher;
+use evalys_mxe_client::events::ResultEvent;
+use evalys_mxe_client::inputs::{input_commitment, EncryptedInput, QuorumSalt};
+use evalys_mxe_client::instructions::{self, RequestAccounts};
+use evalys_mxe_client::outputs::{
+    AggregatedMetrics, CircuitOutput, ExecutionRecommendation, PlanOutput, RiskAssessment,
+    StrategyPlan,
+};
+use evalys_mxe_client::schema::{
+    CurveEvalInputs, GmpcStrategyInputs, MultiUserAnalyticsInputs, RiskScoreInputs,
+    StrategyPlanInputs,
+};
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::Pubkey;
+use rand_core::{OsRng, RngCore};
+use serde::Serialize;
+use serde_json::Value;
+use solana_instruction::Instruction;
+use x25519_dalek::StaticSecret;
+
+use crate::backend::Backend;
+use crate::BridgeError;
+
+/// Deployment the bridge submits to
+#[derive(Debug, Clone)]
+pub struct BridgeConfig {
+    pub program_id: Pubkey,
+    pub arcium: ArciumDeployment,
+    pub mxe_x25519_pubkey: [u8; 32],
+    pub quorum_peer: Option<PeerConfig>,
+    /// Registered key id of the client key
+    pub client_key_id: u32,
+}
+
+/// Deployment that runs the other leg of quorum requests: the same program
+/// under another id, bound to another cluster with its own MXE key. The
+/// bridge's client key must be registered with it as well.
+#[derive(Debug, Clone)]
+pub struct PeerConfig {
+    pub program_id: Pubkey,
+    pub arcium: ArciumDeployment,
+    pub mxe_x25519_pubkey: [u8; 32],
+    /// Key id the peer's registry gave the client key
+    pub client_key_id: u32,
+}
+
+/// Decrypted result of one request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Outcome {
+    /// Encrypted instruction that ran
+    pub circuit: &'static str,
+    /// One per quorum leg, otherwise one
+    pub computation_offsets: Vec<u64>,
+    /// The circuit's output, input commitment verified
+    pub output: Value,
+}
+
+/// Submits requests with one client key and decrypts their results
+pub struct Bridge<B> {
+    backend: B,
+    config: BridgeConfig,
+    cipher: ClientCipher,
+    /// Shared secret with the quorum peer's MXE
+    peer_cipher: Option<ClientCipher>,
+}
+
+/// Request circuit name of the HTTP API and the CLI
+pub fn circuit(name: &str) -> Option<CircuitKind> {
+    Some(match name {
+        "strategy-plan" => CircuitKind::StrategyPlan,
+        "risk-score" => CircuitKind::RiskScore,
+        "curve-eval" => CircuitKind::CurveEval,
+        "gmpc-strategy" => CircuitKind::GmpcStrategy,
+        "gmpc-strategy-quorum" => CircuitKind::GmpcStrategyQuorum,
+        "multi-user-analytics" => CircuitKind::MultiUserAnalytics,
+        _ => return None,
+    })
+}
+
+impl<B: Backend> Bridge<B> {
+    pub fn new(backend: B, config: BridgeConfig, client_secret: &StaticSecret) -> Self {
+        let cipher = ClientCipher::new(client_secret, &config.mxe_x25519_pubkey);
+        let peer_cipher = config
+            .quorum_peer
+            .as_ref()
+            .map(|peer| ClientCipher::new(client_secret, &peer.mxe_x25519_pubkey));
+        Bridge {
+            backend,
+            config,
+            cipher,
+            peer_cipher,
+        }
+    }
+
+    fn accounts(&self, kind: CircuitKind, computation_offset: u64) -> RequestAccounts {
+        RequestAccounts {
+            payer: self.backend.payer(),
+            computation_offset,
+            arcium: self.config.arcium.accounts(
+                &self.config.program_id,
+                kind.encrypted_ix(),
+                computation_offset,
+            ),
+        }
+    }
+
+    /// Wait for the result of `computation_offset`, decrypt it and check its
+    /// input commitment. `on_peer` reads it from the quorum peer instead.
+    fn output<T: CircuitOutput>(
+        &mut self,
+        kind: CircuitKind,
+        computation_offset: u64,
+        commitment: &[u8; 32],
+        on_peer: bool,
+        timeout: Duration,
+    ) -> Result<T, BridgeError> {
+        let (program_id, client_key_id, cipher) = match (&self.config.quorum_peer, on_peer) {
+            (Some(peer), true) => (
+                peer.program_id,
+                peer.client_key_id,
+                self.peer_cipher.as_ref().unwrap(),
+            ),
+            (None, true) => return Err(BridgeError::NoQuorumPeer),
+            (_, false) => (
+                self.config.program_id,
+                self.config.client_key_id,
+                &self.cipher,
+            ),
+        };
+        let event: ResultEvent = self
+            .backend
+            .result(&program_id, computation_offset, timeout)?;
+        let expected = match kind {
+            CircuitKind::GmpcStrategyQuorum => CircuitKind::GmpcStrategy,
+            kind => kind,
+        };
+        if event.kind != expected {
+            return Err(BridgeError::WrongCircuit {
+                offset: computation_offset,
+                expected,
+                actual: event.kind,
+            });
+        }
+        if event.client_key_id != client_key_id {
+            return Err(BridgeError::WrongClientKey {
+                offset: computation_offset,
+                key_id: event.client_key_id,
+            });
+        }
+        let output: T = event.decrypt(cipher)?;
+        output.input_commitment().verify(commitment)?;
+        Ok(output)
+    }
+
+    /// Send one request and return its verified output
+    fn single<T: CircuitOutput + Serialize>(
+        &mut self,
+        kind: CircuitKind,
+        computation_offset: u64,
+        instruction: Instruction,
+        inputs: &[&EncryptedInput],
+        timeout: Duration,
+    ) -> Result<Outcome, BridgeError> {
+        let commitment = input_commitment(&self.backend.payer(), computation_offset, inputs);
+        self.backend.send(&[instruction])?;
+        let output: T = self.output(kind, computation_offset, &commitment, false, timeout)?;
+        Ok(Outcome {
+            circuit: kind.encrypted_ix(),
+            computation_offsets: vec![computation_offset],
+            output: serde_json::to_value(output)?,
+        })
+    }
+
+    /// Encrypt the JSON request file `input` of `kind` (the `evalys-mxe
+    /// submit` format), queue it and wait up to `timeout` for its result
+    pub fn run(
+        &mut self,
+        kind: CircuitKind,
+        input: &str,
+        timeout: Duration,
+    ) -> Result<Outcome, BridgeError> {
+        let program_id = self.config.program_id;
+        let key_id = self.config.client_key_id;
+        let offset = OsRng.next_u64();
+        let accounts = self.accounts(kind, offset);
+        match kind {
+            CircuitKind::StrategyPlan => {
+                let input: StrategyPlanInputs = serde_json::from_str(input)?;
+                let preferences = self.cipher.encrypt(&input.preferences);
+                let history = self.cipher.encrypt(&input.history);
+                let instruction = instructions::request_strategy_plan(
+                    &program_id,
+                    &accounts,
+                    key_id,
+                    &preferences,
+                    &history,
+                    &input.curve_state,
+                );
+                self.single::<StrategyPlan>(
+                    kind,
+                    offset,
+                    instruction,
+                    &[&preferences, &history],
+                    timeout,
+                )
+            }
+            CircuitKind::RiskScore => {
+                let input: RiskScoreInputs = serde_json::from_str(input)?;
+                let portfolio = self.cipher.encrypt(&input.portfolio);
+                let performance = self.cipher.encrypt(&input.performance);
+                let instruction = instructions::request_risk_score(
+                    &program_id,
+                    &accounts,
+                    key_id,
+                    &portfolio,
+                    &performance,
+                    &input.market,
+                );
+                self.single::<RiskAssessment>(
+                    kind,
+                    offset,
+                    instruction,
+                    &[&portfolio, &performance],
+                    timeout,
+                )
+            }
+            CircuitKind::CurveEval => {
+                let input: CurveEvalInputs = serde_json::from_str(input)?;
+                let sizing = self.cipher.encrypt(&input.sizing);
+                let constraints = self.cipher.encrypt(&input.constraints);
+                let instruction = instructions::request_curve_eval(
+                    &program_id,
+                    &accounts,
+                    key_id,
+                    &sizing,
+                    &constraints,
+                    &input.curve,
+                );
+                self.single::<ExecutionRecommendation>(
+                    kind,
+                    offset,
+                    instruction,
+                    &[&sizing, &constraints],
+                    timeout,
+                )
+            }
+            CircuitKind::GmpcStrategy => {
+                let input: GmpcStrategyInputs = serde_json::from_str(input)?;
+                let intent = self.cipher.encrypt(&input.intent);
+                let instruction =
+                    instructions::request_gmpc_strategy(&program_id, &accounts, key_id, &intent);
+                self.single::<PlanOutput>(kind, offset, instruction, &[&intent], timeout)
+            }
+            CircuitKind::MultiUserAnalytics => {
+                let input: MultiUserAnalyticsInputs = serde_json::from_str(input)?;
+                let profiles: Vec<_> = input
+                    .profiles
+                    .iter()
+                    .map(|profile| self.cipher.encrypt(profile))
+                    .collect();
+                let instruction = instructions::request_multi_user_analytics(
+                    &program_id,
+                    &accounts,
+                    key_id,
+                    &profiles,
+                    input.aggregation_type,
+                )?;
+                let inputs: Vec<_> = profiles.iter().collect();
+                self.single::<AggregatedMetrics>(kind, offset, instruction, &inputs, timeout)
+            }
+            CircuitKind::GmpcStrategyQuorum => {
+                let input: GmpcStrategyInputs = serde_json::from_str(input)?;
+                self.quorum(&input, offset, timeout)
+            }
+        }
+    }
+
+    /// Open the quorum on both deployments and queue one leg on each, with
+    /// the same intent and salt encrypted to each deployment's MXE key; the
+    /// peer's leg runs at `computation_offset + 1`. Both legs must return the
+    /// same plan.
+    fn quorum(
+        &mut self,
+        input: &GmpcStrategyInputs,
+        computation_offset: u64,
+        timeout: Duration,
+    ) -> Result<Outcome, BridgeError> {
+        let kind = CircuitKind::GmpcStrategyQuorum;
+        let payer = self.backend.payer();
+        let peer = self
+            .config
+            .quorum_peer
+            .as_ref()
+            .ok_or(BridgeError::NoQuorumPeer)?;
+        let peer_cipher = self.peer_cipher.as_ref().unwrap();
+        let legs = [
+            (
+                self.config.program_id,
+                self.config.arcium,
+                self.config.client_key_id,
+                &self.cipher,
+            ),
+            (
+                peer.program_id,
+                peer.arcium,
+                peer.client_key_id,
+                peer_cipher,
+            ),
+        ];
+
+        let quorum_offset = OsRng.next_u64();
+        // One transaction, so no other requester can take either side
+        let opens: Vec<_> = legs
+            .iter()
+            .map(|(program_id, ..)| {
+                instructions::open_gmpc_quorum(program_id, &payer, quorum_offset)
+            })
+            .collect();
+        let salt = QuorumSalt::random();
+        let mut requests = Vec::with_capacity(2);
+        let mut queued = Vec::with_capacity(2);
+        for (leg, (program_id, arcium, key_id, cipher)) in legs.iter().enumerate() {
+            let leg_offset = computation_offset.wrapping_add(leg as u64);
+            let intent = cipher.encrypt(&input.intent);
+            let salt = cipher.encrypt(&salt);
+            let accounts = RequestAccounts {
+                payer,
+                computation_offset: leg_offset,
+                arcium: arcium.accounts(program_id, kind.encrypted_ix(), leg_offset),
+            };
+            requests.push(instructions::request_gmpc_strategy_quorum(
+                program_id,
+                &accounts,
+                quorum_offset,
+                *key_id,
+                &intent,
+                &salt,
+            ));
+            queued.push((
+                leg_offset,
+                input_commitment(&payer, leg_offset, &[&intent, &salt]),
+            ));
+        }
+        self.backend.send(&opens)?;
+        for request in requests {
+            self.backend.send(&[request])?;
+        }
+
+        let mut plans = Vec::with_capacity(2);
+        for (leg, (offset, commitment)) in queued.iter().enumerate() {
+            let plan: PlanOutput = self.output(kind, *offset, commitment, leg == 1, timeout)?;
+            plans.push(serde_json::to_value(plan)?);
+        }
+        // Each plan echoes its own leg's input commitment
+        let [own, peer] = [&plans[0], &plans[1]].map(|plan| {
+            let mut plan = plan.clone();
+            plan.as_object_mut().unwrap().remove("input_commitment");
+            plan
+        });
+        if own != peer {
+            return Err(BridgeError::QuorumMismatch);
+        }
+        Ok(Outcome {
+            circuit: kind.encrypted_ix(),
+            computation_offsets: queued.iter().map(|(offset, _)| *offset).collect(),
+            output: plans.swap_remove(0),
+        })
+    }
+}
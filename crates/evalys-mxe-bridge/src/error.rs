@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::ClientError;
+use evalys_mxe_mock::MockError;
+use thiserror::Error;
+
+/// Errors returned while serving one request
+#[derive(Debug, Error)]
+pub enum BridgeError {
+    #[error("unknown circuit {0}")]
+    UnknownCircuit(String),
+    #[error("invalid input: {0}")]
+    InvalidInput(#[from] serde_json::Error),
+    #[error("deployment has no quorum peer")]
+    NoQuorumPeer,
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    #[error("computation {0} was aborted by the cluster")]
+    Aborted(u64),
+    #[error("computation {0} still pending after {1:?}")]
+    Timeout(u64, Duration),
+    #[error("computation {offset} returned a {actual:?} result, expected {expected:?}")]
+    WrongCircuit {
+        offset: u64,
+        expected: CircuitKind,
+        actual: CircuitKind,
+    },
+    #[error("computation {offset} result is encrypted to client key {key_id}, not the bridge's")]
+    WrongClientKey { offset: u64, key_id: u32 },
+    #[error("quorum legs returned different results")]
+    QuorumMismatch,
+    #[error(transparent)]
+    Mock(#[from] MockError),
+    #[error("rpc: {0}")]
+    Rpc(String),
+}
+
+impl BridgeError {
+    /// HTTP status of a response reporting this error
+    pub fn status(&self) -> u16 {
+        match self {
+            BridgeError::UnknownCircuit(_) => 404,
+            BridgeError::InvalidInput(_) => 400,
+            BridgeError::Timeout(..) => 504,
+            BridgeError::NoQuorumPeer => 501,
+            _ => 502,
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Local bridge service for the MXE.
+//!
+//! Takes a circuit's plaintext inputs as JSON (the request files of
+//! `evalys-mxe submit`), encrypts them with one client key, queues the
+//! request, waits for its result event, decrypts it and checks the echoed
+//! input commitment ([`bridge::Bridge`]), and returns the typed output over
+//! HTTP ([`api`]). Requests go to a deployed MXE over RPC
+//! ([`rpc::RpcBackend`]) or to the mock executor
+//! ([`backend::MockBackend`]), which needs no cluster.
+
+pub mod api;
+pub mod backend;
+pub mod bridge;
+mod error;
+pub mod rpc;
+
+pub use error::BridgeError;
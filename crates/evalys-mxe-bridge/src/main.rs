@@ -0,0 +1,133 @@
+//! `evalys-mxe-bridge`: serve the encrypt → request → decrypt loop over HTTP.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use evalys_mxe_bridge::api;
+use evalys_mxe_bridge::backend::MockBackend;
+use evalys_mxe_bridge::bridge::{Bridge, BridgeConfig, PeerConfig};
+use evalys_mxe_bridge::rpc::RpcBackend;
+use evalys_mxe_client::keys::Keystore;
+use evalys_mxe_client::manifest::Manifest;
+use evalys_mxe_client::Pubkey;
+use rand_core::OsRng;
+use solana_keypair::read_keypair_file;
+use x25519_dalek::StaticSecret;
+
+#[derive(Parser)]
+#[command(
+    name = "evalys-mxe-bridge",
+    version,
+    about = "Serve plaintext-in, decrypted-result-out requests to the Evalys MXE over HTTP"
+)]
+struct Cli {
+    /// Address of the HTTP API
+    #[arg(long, default_value = "127.0.0.1:8788")]
+    listen: String,
+    /// Seconds to wait for a result when the request sets no timeout
+    #[arg(long, default_value_t = 120)]
+    timeout: u64,
+    /// Run requests on the mock executor instead of a deployment
+    #[arg(long, conflicts_with_all = ["keystore", "keypair", "rpc_url"])]
+    mock: bool,
+    /// Deployment file (program id, RPC URL, Arcium cluster, MXE key)
+    #[arg(long, short, default_value = "evalys-mxe.json")]
+    deployment: PathBuf,
+    /// Payer keypair; defaults to the Solana CLI keypair
+    #[arg(long, short)]
+    keypair: Option<PathBuf>,
+    /// Client keystore; the active key encrypts the inputs
+    #[arg(long, required_unless_present = "mock")]
+    keystore: Option<PathBuf>,
+    /// Override the deployment file's RPC URL
+    #[arg(long)]
+    rpc_url: Option<String>,
+}
+
+fn read_manifest(path: &Path) -> Result<Manifest> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("reading deployment file {}", path.display()))?;
+    serde_json::from_str(&json)
+        .with_context(|| format!("parsing deployment file {}", path.display()))
+}
+
+fn keypair_path(keypair: Option<PathBuf>) -> Result<PathBuf> {
+    match keypair {
+        Some(path) => Ok(path),
+        None => std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(".config/solana/id.json"))
+            .ok_or_else(|| anyhow!("HOME is not set; pass --keypair")),
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let timeout = Duration::from_secs(cli.timeout);
+
+    if cli.mock {
+        let (program_id, peer_program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let backend = MockBackend::new(
+            program_id,
+            StaticSecret::random_from_rng(OsRng),
+            Pubkey::new_unique(),
+        )
+        .with_deployment(peer_program_id, StaticSecret::random_from_rng(OsRng));
+        let config = BridgeConfig {
+            program_id,
+            arcium: MockBackend::deployment(),
+            mxe_x25519_pubkey: backend.mxe_x25519_pubkey(&program_id).unwrap(),
+            quorum_peer: Some(PeerConfig {
+                program_id: peer_program_id,
+                arcium: MockBackend::deployment(),
+                mxe_x25519_pubkey: backend.mxe_x25519_pubkey(&peer_program_id).unwrap(),
+                client_key_id: 1,
+            }),
+            client_key_id: 1,
+        };
+        let mut bridge = Bridge::new(backend, config, &StaticSecret::random_from_rng(OsRng));
+        println!("serving the mock executor on {}", cli.listen);
+        return api::serve(&mut bridge, timeout, &cli.listen);
+    }
+
+    let manifest = read_manifest(&cli.deployment)?;
+    let keystore_path = cli.keystore.expect("required unless --mock");
+    let keystore = Keystore::open(&keystore_path)?;
+    let key = keystore
+        .active()
+        .ok_or_else(|| anyhow!("keystore has no active client key"))?;
+    let path = keypair_path(cli.keypair)?;
+    let payer = read_keypair_file(&path)
+        .map_err(|err| anyhow!("reading keypair {}: {err}", path.display()))?;
+    let rpc_url = cli.rpc_url.unwrap_or(manifest.rpc_url.clone());
+
+    let config = BridgeConfig {
+        program_id: manifest.program_id,
+        arcium: manifest.arcium(),
+        mxe_x25519_pubkey: manifest
+            .mxe_x25519_pubkey
+            .ok_or_else(|| anyhow!("deployment file has no mxe_x25519_pubkey"))?,
+        // The client key is registered with the peer under the same id
+        quorum_peer: match manifest.quorum_peer {
+            Some(peer) => Some(PeerConfig {
+                program_id: peer.program_id,
+                arcium: manifest.peer_arcium(&peer),
+                mxe_x25519_pubkey: peer
+                    .mxe_x25519_pubkey
+                    .ok_or_else(|| anyhow!("deployment file has no quorum peer MXE key"))?,
+                client_key_id: key.key_id,
+            }),
+            None => None,
+        },
+        client_key_id: key.key_id,
+    };
+    let backend = RpcBackend::new(&rpc_url, payer);
+    let mut bridge = Bridge::new(backend, config, &key.secret());
+    println!(
+        "serving program {} via {rpc_url} on {}",
+        manifest.program_id, cli.listen
+    );
+    api::serve(&mut bridge, timeout, &cli.listen)
+}
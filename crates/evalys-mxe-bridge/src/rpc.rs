@@ -0,0 +1,92 @@
+//! Live backend: sends transactions over Solana JSON-RPC and waits for the
+//! request record to leave `Pending`.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use evalys_mxe_client::events::ResultEvent;
+use evalys_mxe_client::rpc::Rpc;
+use evalys_mxe_client::state::RequestStatus;
+use evalys_mxe_client::{ClientError, Pubkey};
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::backend::Backend;
+use crate::BridgeError;
+
+const RESULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Backend of deployed MXEs reachable through one RPC node
+pub struct RpcBackend {
+    rpc: Rpc,
+    payer: Keypair,
+}
+
+fn rpc_error(err: ClientError) -> BridgeError {
+    BridgeError::Rpc(err.to_string())
+}
+
+impl RpcBackend {
+    pub fn new(url: &str, payer: Keypair) -> Self {
+        RpcBackend {
+            rpc: Rpc::new(url),
+            payer,
+        }
+    }
+}
+
+impl Backend for RpcBackend {
+    fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    fn send(&mut self, instructions: &[Instruction]) -> Result<(), BridgeError> {
+        let blockhash = self.rpc.latest_blockhash().map_err(rpc_error)?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+        self.rpc.send_and_confirm(&transaction).map_err(rpc_error)?;
+        Ok(())
+    }
+
+    fn result(
+        &mut self,
+        program_id: &Pubkey,
+        computation_offset: u64,
+        timeout: Duration,
+    ) -> Result<ResultEvent, BridgeError> {
+        let started = Instant::now();
+        loop {
+            let request = self
+                .rpc
+                .computation_request(program_id, computation_offset)
+                .map_err(rpc_error)?;
+            match request.map(|request| request.status) {
+                Some(RequestStatus::Failed) => {
+                    return Err(BridgeError::Aborted(computation_offset))
+                }
+                Some(RequestStatus::Pending) | None => {}
+                Some(_) => {
+                    return self
+                        .rpc
+                        .result_event(program_id, computation_offset)
+                        .map_err(rpc_error)?
+                        .ok_or_else(|| {
+                            BridgeError::Rpc(format!(
+                                "no result event found for computation {computation_offset}"
+                            ))
+                        })
+                }
+            }
+            if started.elapsed() >= timeout {
+                return Err(BridgeError::Timeout(computation_offset, timeout));
+            }
+            thread::sleep(RESULT_POLL_INTERVAL);
+        }
+    }
+}
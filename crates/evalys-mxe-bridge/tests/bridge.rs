@@ -0,0 +1,226 @@
+//! Requests through the HTTP handler against the mock executor.
+
+use std::time::Duration;
+
+use evalys_mxe_bridge::api::{self, Response};
+use evalys_mxe_bridge::backend::MockBackend;
+use evalys_mxe_bridge::bridge::{Bridge, BridgeConfig, PeerConfig};
+use evalys_mxe_client::Pubkey;
+use serde_json::{json, Value};
+use x25519_dalek::StaticSecret;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+const PROGRAM_ID: Pubkey = Pubkey::new_from_array([3; 32]);
+const PEER_PROGRAM_ID: Pubkey = Pubkey::new_from_array([5; 32]);
+
+/// Bridge on the mock executor; `quorum` adds a peer deployment on a second
+/// mock cluster with its own MXE key
+fn bridge(quorum: bool) -> Bridge<MockBackend> {
+    let backend = MockBackend::new(
+        PROGRAM_ID,
+        StaticSecret::from([1; 32]),
+        Pubkey::new_from_array([2; 32]),
+    )
+    .with_deployment(PEER_PROGRAM_ID, StaticSecret::from([6; 32]));
+    let quorum_peer = quorum.then(|| PeerConfig {
+        program_id: PEER_PROGRAM_ID,
+        arcium: MockBackend::deployment(),
+        mxe_x25519_pubkey: backend.mxe_x25519_pubkey(&PEER_PROGRAM_ID).unwrap(),
+        client_key_id: 2,
+    });
+    let config = BridgeConfig {
+        program_id: PROGRAM_ID,
+        arcium: MockBackend::deployment(),
+        mxe_x25519_pubkey: backend.mxe_x25519_pubkey(&PROGRAM_ID).unwrap(),
+        quorum_peer,
+        client_key_id: 1,
+    };
+    Bridge::new(backend, config, &StaticSecret::from([4; 32]))
+}
+
+fn post(bridge: &mut Bridge<MockBackend>, url: &str, body: &Value) -> Response {
+    api::handle(bridge, TIMEOUT, "POST", url, &body.to_string())
+}
+
+fn intent() -> Value {
+    json!({
+        "intent": {
+            "max_size_sol": 3_000_000_000u64,
+            "risk_level": 2,
+            "privacy_priority": 1,
+            "market_price": 1_000,
+            "curve_position": 4_000,
+            "volatility_score": 7_000,
+            "avg_hold_time": 900,
+            "win_rate": 5_500,
+            "max_dd": 1_800,
+        }
+    })
+}
+
+#[test]
+fn returns_the_decrypted_plan() {
+    let mut bridge = bridge(false);
+    let response = post(&mut bridge, "/circuits/gmpc-strategy", &intent());
+    assert_eq!(response.status, 200, "{}", response.body);
+    assert_eq!(response.body["circuit"], "evalys_gmpc_strategy");
+    assert_eq!(
+        response.body["computation_offsets"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    let plan = &response.body["output"];
+    // High risk, normal volatility: full size, stealth slicing over Jito,
+    // jittered by up to one slice and 20% of the window
+    assert_eq!(plan["recommended_size_sol"], 3_000_000_000u64);
+    assert!((5..=6).contains(&plan["slice_count"].as_u64().unwrap()));
+    assert!((31..=45).contains(&plan["time_window_sec"].as_u64().unwrap()));
+    assert_eq!(plan["mev_route"], 1);
+    assert_eq!(plan["privacy_mode"], 1);
+}
+
+#[test]
+fn every_circuit_answers() {
+    let profile = json!({
+        "profile_id_hash": 11,
+        "avg_hold_time": 3_600,
+        "win_rate": 6_000,
+        "preferred_size_range_min": 1_000_000_000u64,
+        "preferred_size_range_max": 3_000_000_000u64,
+        "risk_tolerance": 0,
+    });
+    let requests = [
+        (
+            "strategy-plan",
+            json!({
+                "preferences": {
+                    "desired_size": 5_000_000_000u64,
+                    "slippage_tolerance": 150,
+                    "risk_appetite": 180,
+                    "preferred_hold_time": 3_600,
+                },
+                "history": {
+                    "recent_pnl": -250_000_000,
+                    "win_rate": 6_200,
+                    "avg_hold_time": 1_800,
+                    "total_trades": 40,
+                },
+                "curve_state": {
+                    "current_price": 1_000,
+                    "liquidity_depth": 50_000_000_000u64,
+                    "volatility": 300,
+                    "recent_volume": 10_000_000_000u64,
+                },
+            }),
+        ),
+        (
+            "risk-score",
+            json!({
+                "portfolio": {
+                    "total_capital": 100_000_000_000u64,
+                    "current_exposure": 30_000_000_000u64,
+                    "diversification_score": 120,
+                    "leverage_ratio": 15_000,
+                },
+                "performance": {
+                    "total_pnl": -5_000_000_000i64,
+                    "sharpe_ratio": -40,
+                    "max_drawdown": 2_500,
+                    "consistency_score": 90,
+                },
+                "market": {
+                    "curve_volatility": 600,
+                    "liquidity_risk": 140,
+                    "market_sentiment": -30,
+                },
+            }),
+        ),
+        (
+            "curve-eval",
+            json!({
+                "sizing": {
+                    "target_size": 2_000_000_000u64,
+                    "min_size": 500_000_000,
+                    "max_size": 4_000_000_000u64,
+                    "capital_allocation_pct": 25,
+                },
+                "constraints": {
+                    "max_slippage_bps": 200,
+                    "time_constraint_sec": 600,
+                    "priority_level": 200,
+                },
+                "curve": {
+                    "current_price": 950,
+                    "price_change_24h": -1_200,
+                    "liquidity_depth": 40_000_000_000u64,
+                    "buy_pressure": 6_500,
+                    "sell_pressure": 3_500,
+                },
+            }),
+        ),
+        ("gmpc-strategy", intent()),
+        (
+            "multi-user-analytics",
+            json!({ "profiles": [profile, profile], "aggregation_type": 1 }),
+        ),
+    ];
+    let mut bridge = bridge(false);
+    for (circuit, input) in requests {
+        let response = post(&mut bridge, &format!("/circuits/{circuit}"), &input);
+        assert_eq!(response.status, 200, "{circuit}: {}", response.body);
+    }
+}
+
+#[test]
+fn quorum_legs_agree() {
+    let mut bridge = bridge(true);
+    let response = post(
+        &mut bridge,
+        "/circuits/gmpc-strategy-quorum?timeout=5",
+        &intent(),
+    );
+    assert_eq!(response.status, 200, "{}", response.body);
+    let offsets = response.body["computation_offsets"].as_array().unwrap();
+    assert_eq!(offsets.len(), 2);
+    assert_eq!(response.body["output"]["slice_count"], 5);
+
+    let mut bridge = self::bridge(false);
+    let response = post(&mut bridge, "/circuits/gmpc-strategy-quorum", &intent());
+    assert_eq!(response.status, 501, "{}", response.body);
+}
+
+#[test]
+fn rejects_bad_requests() {
+    let mut bridge = bridge(false);
+    let mut out_of_range = intent();
+    out_of_range["intent"]["risk_level"] = json!(3);
+    let response = post(&mut bridge, "/circuits/gmpc-strategy", &out_of_range);
+    assert_eq!(response.status, 400);
+    assert!(
+        response.body["error"]
+            .as_str()
+            .unwrap()
+            .contains("risk_level"),
+        "{}",
+        response.body
+    );
+
+    let response = post(
+        &mut bridge,
+        "/circuits/gmpc-strategy",
+        &json!({ "intent": {} }),
+    );
+    assert_eq!(response.status, 400);
+    let response = post(&mut bridge, "/circuits/gmpc-strategy?wait=1", &intent());
+    assert_eq!(response.status, 400);
+    let response = post(&mut bridge, "/circuits/unknown", &intent());
+    assert_eq!(response.status, 404);
+    let response = api::handle(&mut bridge, TIMEOUT, "GET", "/circuits/gmpc-strategy", "");
+    assert_eq!(response.status, 405);
+    let response = api::handle(&mut bridge, TIMEOUT, "GET", "/schema", "");
+    assert_eq!(response.status, 200);
+    assert!(response.body["$defs"]["IntentInput"].is_object());
+}
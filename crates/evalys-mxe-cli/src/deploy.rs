@@ -0,0 +1,704 @@
+//! `deploy`: build and deploy the MXE, register every comp def and write the
+//! deployment manifest.
+//!
+//! Every step is idempotent, so an interrupted deploy can be rerun with the
+//! same arguments:
+//!
+//! 1. `arcium build` (skipped with `--skip-build`).
+//! 2. `arcium deploy`, unless the program account already exists (force an
+//!    upgrade with `--upgrade`).
+//! 3. `initialize_config` if the config does not exist, with
+//!    `--quorum-peer-program` as quorum peer: the second deployment of the
+//!    program, bound to `--quorum-peer-cluster-offset`. The payer must be the
+//!    program's upgrade authority and becomes the config authority.
+//! 4. `initialize_audit_log` if the audit log does not exist.
+//! 5. `init_*_comp_def` for every circuit not registered yet, with the hash
+//!    of its compiled circuit and its location under `--circuit-url`, if the
+//!    payer is the config authority. A circuit registered with another hash
+//!    is re-registered with `offline build ... upgrade-circuit` once its comp
+//!    def enforces the new one.
+//! 6. Check each comp def: the Arcium account exists and is owned by the
+//!    Arcium program, and the program's registration records the offset and
+//!    the circuit hash of the local build.
+//! 7. `set_*_policy` with the default thresholds for every circuit whose
+//!    policy is not set yet, if the payer is the config authority. Otherwise
+//!    the authority sets them with `offline build ... set-policy`; requests
+//!    of a circuit fail until its policy exists.
+//! 8. Write the manifest to the `--deployment` path.
+//!
+//! Arcium addresses, the quorum peer and the MXE keys not given on the
+//! command line are kept from an existing manifest at that path.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, ValueEnum};
+use evalys_mxe_client::arcium::comp_def_offset;
+use evalys_mxe_client::instructions;
+use evalys_mxe_client::manifest::{CompDefRecord, Manifest, QuorumPeer};
+use evalys_mxe_client::pda;
+use evalys_mxe_client::rpc::Rpc;
+use evalys_mxe_client::state::{CircuitKind, CircuitRegistration, MxeConfig};
+use evalys_mxe_client::Pubkey;
+use sha2::{Digest, Sha256};
+use solana_keypair::read_keypair_file;
+use solana_signer::Signer;
+
+use crate::deployment::{read_manifest, write_manifest, Deployment};
+use crate::offline::policy_instruction;
+use crate::Session;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Network {
+    Devnet,
+    MainnetBeta,
+    Localnet,
+}
+
+impl Network {
+    fn name(self) -> &'static str {
+        match self {
+            Network::Devnet => "devnet",
+            Network::MainnetBeta => "mainnet-beta",
+            Network::Localnet => "localnet",
+        }
+    }
+
+    fn rpc_url(self) -> &'static str {
+        match self {
+            Network::Devnet => "https://api.devnet.solana.com",
+            Network::MainnetBeta => "https://api.mainnet-beta.solana.com",
+            Network::Localnet => "http://127.0.0.1:8899",
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct DeployArgs {
+    #[arg(long, value_enum, default_value = "devnet")]
+    network: Network,
+    /// Arcium cluster the MXE is bound to
+    #[arg(long, default_value_t = 1078779259)]
+    cluster_offset: u32,
+    /// Program keypair; its pubkey is the program id
+    #[arg(
+        long,
+        default_value = "target/deploy/evalys_arcium_gmpc_mxe-keypair.json"
+    )]
+    program_keypair: PathBuf,
+    /// Arcium program id
+    #[arg(long)]
+    arcium_program: Option<String>,
+    /// Arcium fee pool account
+    #[arg(long)]
+    fee_pool: Option<String>,
+    /// Arcium clock account
+    #[arg(long)]
+    clock_account: Option<String>,
+    /// Hex of the MXE x25519 public key, once the cluster has generated it
+    #[arg(long)]
+    mxe_x25519_pubkey: Option<String>,
+    /// Program id of the deployment that runs the other leg of quorum
+    /// requests; the config is created with it as quorum peer
+    #[arg(long, requires = "quorum_peer_cluster_offset")]
+    quorum_peer_program: Option<String>,
+    /// Arcium cluster the quorum peer is bound to
+    #[arg(long, requires = "quorum_peer_program")]
+    quorum_peer_cluster_offset: Option<u32>,
+    /// Hex of the quorum peer's MXE x25519 public key
+    #[arg(long)]
+    quorum_peer_mxe_x25519_pubkey: Option<String>,
+    /// Directory holding `<encrypted_ix>.arcis` from `arcium build`
+    #[arg(long, default_value = "build")]
+    build_dir: PathBuf,
+    /// Version label recorded with the circuit hashes (max 32 bytes)
+    #[arg(long)]
+    version: String,
+    /// Base URL the `.arcis` files are uploaded to; Arx nodes fetch
+    /// `<circuit-url>/<encrypted_ix>.arcis`
+    #[arg(long)]
+    circuit_url: String,
+    /// Use the existing build
+    #[arg(long)]
+    skip_build: bool,
+    /// Redeploy the program even if it already exists
+    #[arg(long)]
+    upgrade: bool,
+}
+
+fn pubkey_arg(field: &str, arg: Option<&str>, previous: Option<Pubkey>) -> Result<Pubkey> {
+    match arg {
+        Some(value) => {
+            Pubkey::from_str(value).with_context(|| format!("--{field} is not a valid pubkey"))
+        }
+        None => previous.ok_or_else(|| anyhow!("--{field} is required for a first deploy")),
+    }
+}
+
+fn x25519_key_arg(field: &str, arg: Option<&str>) -> Result<Option<[u8; 32]>> {
+    arg.map(|key| {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(key, &mut bytes)
+            .with_context(|| format!("--{field} is not 32 bytes of hex"))?;
+        Ok(bytes)
+    })
+    .transpose()
+}
+
+/// Quorum peer of the command line, completed from the previous manifest
+fn quorum_peer_arg(args: &DeployArgs, previous: Option<&Manifest>) -> Result<Option<QuorumPeer>> {
+    let previous = previous.and_then(|manifest| manifest.quorum_peer);
+    let mxe_x25519_pubkey = x25519_key_arg(
+        "quorum-peer-mxe-x25519-pubkey",
+        args.quorum_peer_mxe_x25519_pubkey.as_deref(),
+    )?;
+    let peer = match (&args.quorum_peer_program, args.quorum_peer_cluster_offset) {
+        (Some(program), Some(cluster_offset)) => {
+            let program_id =
+                Pubkey::from_str(program).context("--quorum-peer-program is not a valid pubkey")?;
+            // A key generated for another program does not carry over
+            let kept_key = previous
+                .filter(|peer| peer.program_id == program_id)
+                .and_then(|peer| peer.mxe_x25519_pubkey);
+            QuorumPeer {
+                program_id,
+                cluster_offset,
+                mxe_x25519_pubkey: mxe_x25519_pubkey.or(kept_key),
+            }
+        }
+        _ => match previous {
+            Some(peer) => QuorumPeer {
+                mxe_x25519_pubkey: mxe_x25519_pubkey.or(peer.mxe_x25519_pubkey),
+                ..peer
+            },
+            None if mxe_x25519_pubkey.is_some() => {
+                bail!("--quorum-peer-mxe-x25519-pubkey needs --quorum-peer-program")
+            }
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(peer))
+}
+
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    println!("$ {program} {}", args.join(" "));
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .with_context(|| format!("running {program}"))?;
+    if !status.success() {
+        bail!("{program} {} failed ({status})", args[0]);
+    }
+    Ok(())
+}
+
+/// sha256 of the compiled circuit of `kind` in `build_dir`
+pub fn circuit_hash(build_dir: &Path, kind: CircuitKind) -> Result<[u8; 32]> {
+    let path = build_dir.join(format!("{}.arcis", kind.encrypted_ix()));
+    let circuit = fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(Sha256::digest(&circuit).into())
+}
+
+/// Off-chain location of the compiled circuit of `kind` under `circuit_url`
+pub fn circuit_source(circuit_url: &str, kind: CircuitKind) -> String {
+    format!(
+        "{}/{}.arcis",
+        circuit_url.trim_end_matches('/'),
+        kind.encrypted_ix()
+    )
+}
+
+/// What deploy does about an account only the config authority may create
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthorityStep {
+    /// The account exists already
+    Exists,
+    /// The payer is the config authority and creates it
+    Create,
+    /// Left to this authority, who signs with `offline build`
+    LeftTo(Pubkey),
+    /// The config, and so its authority, does not exist yet
+    NoConfig,
+}
+
+fn authority_step(exists: bool, authority: Option<Pubkey>, payer: &Pubkey) -> AuthorityStep {
+    match authority {
+        _ if exists => AuthorityStep::Exists,
+        Some(authority) if authority == *payer => AuthorityStep::Create,
+        Some(authority) => AuthorityStep::LeftTo(authority),
+        None => AuthorityStep::NoConfig,
+    }
+}
+
+/// Quorum peer to create the config with, or `None` if the config exists
+fn config_step(exists: bool, quorum_peer: Option<&QuorumPeer>) -> Result<Option<Pubkey>> {
+    if exists {
+        return Ok(None);
+    }
+    let peer = quorum_peer
+        .ok_or_else(|| anyhow!("--quorum-peer-program is required to initialize the config"))?;
+    Ok(Some(peer.program_id))
+}
+
+/// Authority of the program config, if it is initialized
+fn config_authority(session: &Session) -> Result<Option<Pubkey>> {
+    let address = pda::config_address(&session.deployment.program_id);
+    match session.rpc.account_data(&address)? {
+        Some(data) => Ok(Some(MxeConfig::from_account_data(&data)?.authority)),
+        None => Ok(None),
+    }
+}
+
+/// Create the config and the audit log unless they exist
+fn init_config(session: &Session, quorum_peer: Option<&QuorumPeer>) -> Result<()> {
+    let program_id = &session.deployment.program_id;
+    let payer = session.payer()?;
+    let exists = session
+        .rpc
+        .account_data(&pda::config_address(program_id))?
+        .is_some();
+    match config_step(exists, quorum_peer)? {
+        None => println!("{:<36} already initialized", "config"),
+        Some(peer) => {
+            let signature =
+                session.send(&[instructions::initialize_config(program_id, &payer, &peer)])?;
+            println!("{:<36} quorum peer {peer} ({signature})", "config");
+        }
+    }
+
+    if session
+        .rpc
+        .account_data(&pda::audit_log_address(program_id))?
+        .is_some()
+    {
+        println!("{:<36} already initialized", "audit log");
+    } else {
+        let signature = session.send(&[instructions::initialize_audit_log(program_id, &payer)])?;
+        println!("{:<36} ({signature})", "audit log");
+    }
+    Ok(())
+}
+
+/// Register every circuit's comp def not registered yet, if the payer is the
+/// config authority
+pub fn init_comp_defs(
+    session: &Session,
+    build_dir: &Path,
+    version: &str,
+    circuit_url: &str,
+) -> Result<()> {
+    let program_id = &session.deployment.program_id;
+    let payer = session.payer()?;
+    let authority = config_authority(session)?;
+    for kind in CircuitKind::ALL {
+        let name = kind.encrypted_ix();
+        let registration = pda::circuit_registration_address(program_id, kind);
+        let exists = session.rpc.account_data(&registration)?.is_some();
+        match authority_step(exists, authority, &payer) {
+            AuthorityStep::Create => {}
+            AuthorityStep::Exists => {
+                println!("{name:<36} already registered");
+                continue;
+            }
+            AuthorityStep::LeftTo(authority) => {
+                println!(
+                    "{name:<36} not registered: {authority} must register it with `offline build`"
+                );
+                continue;
+            }
+            AuthorityStep::NoConfig => {
+                println!("{name:<36} not registered: the config is not initialized yet");
+                continue;
+            }
+        }
+        let circuit_hash = circuit_hash(build_dir, kind)?;
+        let instruction = instructions::init_comp_def(
+            program_id,
+            &payer,
+            kind,
+            &circuit_hash,
+            version,
+            &circuit_source(circuit_url, kind),
+            &session
+                .deployment
+                .arcium
+                .comp_def_accounts(program_id, kind.encrypted_ix()),
+        );
+        let signature = session.send(&[instruction])?;
+        println!(
+            "{:<36} {} ({signature})",
+            kind.encrypted_ix(),
+            hex::encode(circuit_hash)
+        );
+    }
+    Ok(())
+}
+
+/// Store the default policy of every circuit without one, if the payer is
+/// the config authority
+fn init_policies(session: &Session) -> Result<()> {
+    let program_id = &session.deployment.program_id;
+    let payer = session.payer()?;
+    let authority = config_authority(session)?;
+    for kind in CircuitKind::ALL {
+        if kind.policy_kind() != Some(kind) {
+            continue;
+        }
+        let name = kind.encrypted_ix();
+        let exists = session
+            .rpc
+            .account_data(&pda::policy_address(program_id, kind))?
+            .is_some();
+        match authority_step(exists, authority, &payer) {
+            AuthorityStep::Exists => println!("{name:<36} policy already set"),
+            AuthorityStep::Create => {
+                let (_, instruction) = policy_instruction(program_id, &payer, kind, None)?;
+                let signature = session.send(&[instruction])?;
+                println!("{name:<36} default policy ({signature})");
+            }
+            AuthorityStep::LeftTo(authority) => {
+                println!("{name:<36} no policy: {authority} must set it with `offline build`")
+            }
+            AuthorityStep::NoConfig => {
+                println!("{name:<36} no policy: the config is not initialized yet")
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check every comp def is initialized with the local build and return its
+/// manifest record
+fn verify_comp_defs(session: &Session, build_dir: &Path) -> Result<Vec<CompDefRecord>> {
+    let deployment = &session.deployment;
+    let program_id = &deployment.program_id;
+    let mut records = Vec::with_capacity(CircuitKind::ALL.len());
+    for kind in CircuitKind::ALL {
+        let name = kind.encrypted_ix();
+        let offset = comp_def_offset(name);
+        let comp_def_account = deployment.arcium.comp_def_address(program_id, offset);
+        match session.rpc.account_owner(&comp_def_account)? {
+            Some(owner) if owner == deployment.arcium.arcium_program => {}
+            Some(owner) => bail!("{name}: comp def account {comp_def_account} is owned by {owner}"),
+            None => bail!("{name}: comp def account {comp_def_account} does not exist"),
+        }
+
+        let address = pda::circuit_registration_address(program_id, kind);
+        let registration = CircuitRegistration::from_account_data(
+            &session
+                .rpc
+                .require_account(&address, "circuit registration")?,
+        )?;
+        if registration.kind != kind || registration.comp_def_offset != offset {
+            bail!("{name}: registration {address} does not match comp def offset {offset}");
+        }
+        let local_hash = circuit_hash(build_dir, kind)?;
+        if registration.circuit_hash != local_hash {
+            bail!(
+                "{name}: registered circuit {} differs from the local build {}; \
+                 re-register it with `offline build ... upgrade-circuit`",
+                hex::encode(registration.circuit_hash),
+                hex::encode(local_hash)
+            );
+        }
+        println!("{name:<36} verified");
+        records.push(CompDefRecord {
+            encrypted_ix: name.to_string(),
+            comp_def_offset: offset,
+            comp_def_account,
+            registration: address,
+            circuit_hash: registration.circuit_hash,
+            version: registration.version,
+        });
+    }
+    Ok(records)
+}
+
+/// Manifest of the command line, with the Arcium addresses, the quorum peer
+/// and the MXE keys it leaves out kept from the `previous` manifest
+fn merge_manifest(
+    args: &DeployArgs,
+    program_id: Pubkey,
+    rpc_url: Option<String>,
+    previous: Option<&Manifest>,
+) -> Result<Manifest> {
+    let mxe_x25519_pubkey =
+        match x25519_key_arg("mxe-x25519-pubkey", args.mxe_x25519_pubkey.as_deref())? {
+            Some(key) => Some(key),
+            None => previous.and_then(|manifest| manifest.mxe_x25519_pubkey),
+        };
+    Ok(Manifest {
+        network: Some(args.network.name().to_string()),
+        rpc_url: rpc_url.unwrap_or_else(|| args.network.rpc_url().to_string()),
+        program_id,
+        arcium_program: pubkey_arg(
+            "arcium-program",
+            args.arcium_program.as_deref(),
+            previous.map(|manifest| manifest.arcium_program),
+        )?,
+        cluster_offset: args.cluster_offset,
+        fee_pool: pubkey_arg(
+            "fee-pool",
+            args.fee_pool.as_deref(),
+            previous.map(|manifest| manifest.fee_pool),
+        )?,
+        clock_account: pubkey_arg(
+            "clock-account",
+            args.clock_account.as_deref(),
+            previous.map(|manifest| manifest.clock_account),
+        )?,
+        mxe_x25519_pubkey,
+        quorum_peer: quorum_peer_arg(args, previous)?,
+        comp_defs: Vec::new(),
+    })
+}
+
+pub fn deploy(
+    manifest_path: &Path,
+    keypair: Option<PathBuf>,
+    rpc_url: Option<String>,
+    args: DeployArgs,
+) -> Result<()> {
+    let previous = if manifest_path.exists() {
+        Some(read_manifest(manifest_path)?)
+    } else {
+        None
+    };
+    let program_id = read_keypair_file(&args.program_keypair)
+        .map_err(|err| anyhow!("reading {}: {err}", args.program_keypair.display()))?
+        .pubkey();
+    let mut manifest = merge_manifest(&args, program_id, rpc_url, previous.as_ref())?;
+    let session = Session {
+        rpc: Rpc::new(&manifest.rpc_url),
+        deployment: Deployment::from_manifest(&manifest),
+        keypair,
+    };
+
+    if !args.skip_build {
+        run("arcium", &["build"])?;
+    }
+    if args.upgrade || session.rpc.account_data(&program_id)?.is_none() {
+        let keypair_path = session.keypair_path()?;
+        run(
+            "arcium",
+            &[
+                "deploy",
+                "--cluster-offset",
+                &args.cluster_offset.to_string(),
+                "--keypair-path",
+                &keypair_path.to_string_lossy(),
+                "--rpc-url",
+                &manifest.rpc_url,
+            ],
+        )?;
+    } else {
+        println!("program {program_id} already deployed");
+    }
+
+    init_config(&session, manifest.quorum_peer.as_ref())?;
+    init_comp_defs(&session, &args.build_dir, &args.version, &args.circuit_url)?;
+    manifest.comp_defs = verify_comp_defs(&session, &args.build_dir)?;
+    init_policies(&session)?;
+    write_manifest(manifest_path, &manifest)?;
+    println!("wrote {}", manifest_path.display());
+    if manifest.mxe_x25519_pubkey.is_none() {
+        println!(
+            "the manifest has no mxe_x25519_pubkey yet: rerun with --mxe-x25519-pubkey \
+             --skip-build once the cluster has generated the MXE key"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        deploy: DeployArgs,
+    }
+
+    fn args(extra: &[&str]) -> DeployArgs {
+        let base = [
+            "deploy",
+            "--version",
+            "v0.1.0",
+            "--circuit-url",
+            "https://c",
+        ];
+        Cli::try_parse_from(base.iter().chain(extra))
+            .unwrap()
+            .deploy
+    }
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn previous() -> Manifest {
+        Manifest {
+            network: Some("devnet".to_string()),
+            rpc_url: "https://rpc.example".to_string(),
+            program_id: key(1),
+            arcium_program: key(2),
+            cluster_offset: 7,
+            fee_pool: key(3),
+            clock_account: key(4),
+            mxe_x25519_pubkey: Some([5; 32]),
+            quorum_peer: Some(QuorumPeer {
+                program_id: key(6),
+                cluster_offset: 8,
+                mxe_x25519_pubkey: Some([9; 32]),
+            }),
+            comp_defs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn first_deploy_needs_the_arcium_accounts() {
+        let err = merge_manifest(&args(&[]), key(1), None, None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "--arcium-program is required for a first deploy"
+        );
+
+        let arcium = [
+            "--arcium-program".to_string(),
+            key(2).to_string(),
+            "--fee-pool".to_string(),
+            key(3).to_string(),
+            "--clock-account".to_string(),
+            key(4).to_string(),
+        ];
+        let arcium: Vec<&str> = arcium.iter().map(String::as_str).collect();
+        let manifest = merge_manifest(&args(&arcium), key(1), None, None).unwrap();
+        assert_eq!(manifest.arcium_program, key(2));
+        assert_eq!(manifest.clock_account, key(4));
+        assert_eq!(manifest.rpc_url, "https://api.devnet.solana.com");
+        assert_eq!(manifest.cluster_offset, 1078779259);
+        assert_eq!(manifest.mxe_x25519_pubkey, None);
+        assert_eq!(manifest.quorum_peer, None);
+    }
+
+    #[test]
+    fn rerun_keeps_what_the_command_line_leaves_out() {
+        let previous = previous();
+        let manifest = merge_manifest(
+            &args(&["--cluster-offset", "7"]),
+            key(1),
+            None,
+            Some(&previous),
+        )
+        .unwrap();
+        assert_eq!(
+            manifest,
+            Manifest {
+                rpc_url: "https://api.devnet.solana.com".to_string(),
+                ..previous.clone()
+            }
+        );
+
+        let manifest = merge_manifest(
+            &args(&[
+                "--fee-pool",
+                &key(13).to_string(),
+                "--mxe-x25519-pubkey",
+                &hex::encode([10; 32]),
+                "--quorum-peer-mxe-x25519-pubkey",
+                &hex::encode([11; 32]),
+            ]),
+            key(1),
+            Some("http://localhost:8899".to_string()),
+            Some(&previous),
+        )
+        .unwrap();
+        assert_eq!(manifest.rpc_url, "http://localhost:8899");
+        assert_eq!(manifest.fee_pool, key(13));
+        assert_eq!(manifest.arcium_program, previous.arcium_program);
+        assert_eq!(manifest.mxe_x25519_pubkey, Some([10; 32]));
+        assert_eq!(
+            manifest.quorum_peer,
+            Some(QuorumPeer {
+                mxe_x25519_pubkey: Some([11; 32]),
+                ..previous.quorum_peer.unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn quorum_peer_key_is_kept_only_for_the_same_program() {
+        let previous = previous();
+        let same = key(6).to_string();
+        let other = key(12).to_string();
+
+        let peer = |program: &str| {
+            let args = args(&[
+                "--quorum-peer-program",
+                program,
+                "--quorum-peer-cluster-offset",
+                "9",
+            ]);
+            quorum_peer_arg(&args, Some(&previous)).unwrap().unwrap()
+        };
+        assert_eq!(
+            peer(&same),
+            QuorumPeer {
+                program_id: key(6),
+                cluster_offset: 9,
+                mxe_x25519_pubkey: Some([9; 32]),
+            }
+        );
+        assert_eq!(
+            peer(&other),
+            QuorumPeer {
+                program_id: key(12),
+                cluster_offset: 9,
+                mxe_x25519_pubkey: None,
+            }
+        );
+
+        // The program and its cluster go together
+        let base = ["deploy", "--version", "v", "--circuit-url", "u"];
+        assert!(Cli::try_parse_from(base.iter().chain(&["--quorum-peer-program", &same])).is_err());
+
+        let key_only = args(&["--quorum-peer-mxe-x25519-pubkey", &hex::encode([1; 32])]);
+        assert!(quorum_peer_arg(&key_only, None).is_err());
+        assert_eq!(quorum_peer_arg(&args(&[]), None).unwrap(), None);
+        let bad_key = args(&["--mxe-x25519-pubkey", "abcd"]);
+        assert!(merge_manifest(&bad_key, key(1), None, Some(&previous)).is_err());
+    }
+
+    #[test]
+    fn existing_accounts_are_skipped() {
+        let payer = key(1);
+        let peer = previous().quorum_peer.unwrap();
+
+        assert_eq!(config_step(true, None).unwrap(), None);
+        assert_eq!(config_step(true, Some(&peer)).unwrap(), None);
+        assert_eq!(config_step(false, Some(&peer)).unwrap(), Some(key(6)));
+        assert!(config_step(false, None).is_err());
+
+        for authority in [None, Some(payer), Some(key(2))] {
+            assert_eq!(
+                authority_step(true, authority, &payer),
+                AuthorityStep::Exists
+            );
+        }
+        assert_eq!(
+            authority_step(false, Some(payer), &payer),
+            AuthorityStep::Create
+        );
+        assert_eq!(
+            authority_step(false, Some(key(2)), &payer),
+            AuthorityStep::LeftTo(key(2))
+        );
+        assert_eq!(authority_step(false, None, &payer), AuthorityStep::NoConfig);
+    }
+}
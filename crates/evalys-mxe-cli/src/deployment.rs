@@ -0,0 +1,72 @@
+//! Deployment file: the MXE's [`Manifest`], written by `deploy` or by hand.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use evalys_mxe_client::arcium::ArciumDeployment;
+use evalys_mxe_client::manifest::{Manifest, QuorumPeer};
+use evalys_mxe_client::Pubkey;
+
+pub struct Deployment {
+    pub rpc_url: String,
+    pub program_id: Pubkey,
+    pub arcium: ArciumDeployment,
+    mxe_x25519_pubkey: Option<[u8; 32]>,
+    pub quorum_peer: Option<QuorumPeer>,
+}
+
+/// Read a manifest
+pub fn read_manifest(path: &Path) -> Result<Manifest> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("reading deployment file {}", path.display()))?;
+    serde_json::from_str(&json)
+        .with_context(|| format!("parsing deployment file {}", path.display()))
+}
+
+/// Write a manifest, replacing any previous one
+pub fn write_manifest(path: &Path, manifest: &Manifest) -> Result<()> {
+    let mut json = serde_json::to_string_pretty(manifest)?;
+    json.push('\n');
+    fs::write(path, json).with_context(|| format!("writing deployment file {}", path.display()))
+}
+
+impl Deployment {
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(Self::from_manifest(&read_manifest(path)?))
+    }
+
+    pub fn from_manifest(manifest: &Manifest) -> Self {
+        Deployment {
+            rpc_url: manifest.rpc_url.clone(),
+            program_id: manifest.program_id,
+            arcium: manifest.arcium(),
+            mxe_x25519_pubkey: manifest.mxe_x25519_pubkey,
+            quorum_peer: manifest.quorum_peer,
+        }
+    }
+
+    /// The quorum peer's deployment, reached through the same RPC URL and
+    /// Arcium program
+    pub fn peer(&self) -> Result<Deployment> {
+        let peer = self
+            .quorum_peer
+            .ok_or_else(|| anyhow!("deployment file has no quorum_peer"))?;
+        Ok(Deployment {
+            rpc_url: self.rpc_url.clone(),
+            program_id: peer.program_id,
+            arcium: ArciumDeployment {
+                cluster_offset: peer.cluster_offset,
+                ..self.arcium
+            },
+            mxe_x25519_pubkey: peer.mxe_x25519_pubkey,
+            quorum_peer: None,
+        })
+    }
+
+    /// MXE key requests are encrypted to
+    pub fn mxe_x25519_pubkey(&self) -> Result<[u8; 32]> {
+        self.mxe_x25519_pubkey
+            .ok_or_else(|| anyhow!("deployment file has no mxe_x25519_pubkey"))
+    }
+}
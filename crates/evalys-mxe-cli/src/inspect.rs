@@ -0,0 +1,182 @@
+//! `inspect`: print decoded program accounts.
+
+use anyhow::{bail, Result};
+use evalys_mxe_client::audit::AuditLog;
+use evalys_mxe_client::pda;
+use evalys_mxe_client::state::{
+    CircuitKind, CircuitRegistration, ClientKeyRegistry, ComputationRequest, CurvePolicyAccount,
+    GmpcPolicyAccount, MxeConfig, QuorumRequest, RiskPolicyAccount, StrategyPolicyAccount,
+};
+use evalys_mxe_client::Pubkey;
+
+use crate::Session;
+
+pub fn config(session: &Session) -> Result<()> {
+    let address = pda::config_address(&session.deployment.program_id);
+    let config = MxeConfig::from_account_data(&session.rpc.require_account(&address, "config")?)?;
+    println!("config {address}");
+    println!("  authority        {}", config.authority);
+    println!("  quorum peer      {}", config.quorum_peer);
+    Ok(())
+}
+
+pub fn request(session: &Session, computation_offset: u64) -> Result<()> {
+    let address =
+        pda::computation_request_address(&session.deployment.program_id, computation_offset);
+    let request = ComputationRequest::from_account_data(
+        &session
+            .rpc
+            .require_account(&address, "computation request")?,
+    )?;
+    println!("computation request {address}");
+    println!("  requester         {}", request.requester);
+    println!("  kind              {:?}", request.kind);
+    println!("  status            {:?}", request.status);
+    println!("  client key        {}", request.client_key_id);
+    println!(
+        "  input commitment  {}",
+        hex::encode(request.input_commitment)
+    );
+    println!("  circuit hash      {}", hex::encode(request.circuit_hash));
+    println!("  requested slot    {}", request.requested_slot);
+    println!("  completed slot    {}", request.completed_slot);
+    Ok(())
+}
+
+pub fn policy(session: &Session, kind: CircuitKind) -> Result<()> {
+    let Some(kind) = kind.policy_kind() else {
+        bail!("{} has no policy", kind.encrypted_ix());
+    };
+    let address = pda::policy_address(&session.deployment.program_id, kind);
+    let data = session.rpc.require_account(&address, "policy")?;
+    let (policy, updated_slot) = match kind {
+        CircuitKind::StrategyPlan => {
+            let account = StrategyPolicyAccount::from_account_data(&data)?;
+            (
+                serde_json::to_string_pretty(&account.policy)?,
+                account.updated_slot,
+            )
+        }
+        CircuitKind::RiskScore => {
+            let account = RiskPolicyAccount::from_account_data(&data)?;
+            (
+                serde_json::to_string_pretty(&account.policy)?,
+                account.updated_slot,
+            )
+        }
+        CircuitKind::CurveEval => {
+            let account = CurvePolicyAccount::from_account_data(&data)?;
+            (
+                serde_json::to_string_pretty(&account.policy)?,
+                account.updated_slot,
+            )
+        }
+        _ => {
+            let account = GmpcPolicyAccount::from_account_data(&data)?;
+            (
+                serde_json::to_string_pretty(&account.policy)?,
+                account.updated_slot,
+            )
+        }
+    };
+    println!(
+        "policy {address} ({}, updated slot {updated_slot})",
+        kind.encrypted_ix()
+    );
+    println!("{policy}");
+    Ok(())
+}
+
+pub fn circuits(session: &Session) -> Result<()> {
+    for kind in CircuitKind::ALL {
+        let address = pda::circuit_registration_address(&session.deployment.program_id, kind);
+        match session.rpc.account_data(&address)? {
+            Some(data) => {
+                let registration = CircuitRegistration::from_account_data(&data)?;
+                println!(
+                    "{:<36} {} {:<12} slot {}",
+                    kind.encrypted_ix(),
+                    hex::encode(registration.circuit_hash),
+                    registration.version,
+                    registration.registered_slot
+                );
+            }
+            None => println!("{:<36} not registered", kind.encrypted_ix()),
+        }
+    }
+    Ok(())
+}
+
+pub fn keys(session: &Session, bridge: &Pubkey) -> Result<()> {
+    let address = pda::client_keys_address(&session.deployment.program_id, bridge);
+    let registry = ClientKeyRegistry::from_account_data(
+        &session
+            .rpc
+            .require_account(&address, "client key registry")?,
+    )?;
+    println!("client keys of {bridge} (next id {})", registry.next_key_id);
+    for key in &registry.keys {
+        println!(
+            "  {:>4} {:?} {} registered {} retired {}",
+            key.key_id,
+            key.status,
+            hex::encode(key.pub_key),
+            key.registered_slot,
+            key.retired_slot
+        );
+    }
+    Ok(())
+}
+
+/// This deployment's side of a quorum
+pub fn fetch_quorum(session: &Session, quorum_offset: u64) -> Result<QuorumRequest> {
+    let address = pda::quorum_request_address(&session.deployment.program_id, quorum_offset);
+    Ok(QuorumRequest::from_account_data(
+        &session.rpc.require_account(&address, "quorum request")?,
+    )?)
+}
+
+/// Print this deployment's side of a quorum, then the peer's if the
+/// deployment file names the peer
+pub fn quorum(session: &Session, quorum_offset: u64) -> Result<()> {
+    let peer = match session.deployment.quorum_peer {
+        Some(_) => Some(session.peer()?),
+        None => None,
+    };
+    for side in std::iter::once(session).chain(peer.as_ref()) {
+        let program_id = &side.deployment.program_id;
+        let quorum = fetch_quorum(side, quorum_offset)?;
+        println!(
+            "quorum request {}: {:?}",
+            pda::quorum_request_address(program_id, quorum_offset),
+            quorum.status
+        );
+        println!("  program           {program_id}");
+        println!("  requester         {}", quorum.requester);
+        println!("  peer              {}", quorum.peer);
+        let leg = &quorum.leg;
+        println!(
+            "  leg: offset {} queued {} completed {} failed {} digest {:032x}",
+            leg.computation_offset, leg.queued, leg.completed, leg.failed, leg.digest
+        );
+    }
+    Ok(())
+}
+
+pub fn audit(session: &Session, after: Option<u64>) -> Result<()> {
+    let address = pda::audit_log_address(&session.deployment.program_id);
+    let log = AuditLog::from_account_data(&session.rpc.require_account(&address, "audit log")?)?;
+    for entry in log.entries_after(after) {
+        println!(
+            "{:>8} slot {:>10} {:?} {:?} offset {} requester {} result {}",
+            entry.sequence,
+            entry.slot,
+            entry.kind,
+            entry.status,
+            entry.computation_offset,
+            entry.requester,
+            hex::encode(entry.result_hash)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,324 @@
+//! `evalys-mxe`: operate the Evalys Arcium gMPC MXE from the command line.
+
+mod deploy;
+mod deployment;
+mod inspect;
+mod offline;
+mod results;
+mod submit;
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use evalys_mxe_client::keys::Keystore;
+use evalys_mxe_client::rpc::Rpc;
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::Pubkey;
+use solana_instruction::Instruction;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::deployment::Deployment;
+
+#[derive(Parser)]
+#[command(
+    name = "evalys-mxe",
+    version,
+    about = "Operate the Evalys Arcium gMPC MXE"
+)]
+struct Cli {
+    /// Deployment file (program id, RPC URL, Arcium cluster, MXE key)
+    #[arg(long, short, global = true, default_value = "evalys-mxe.json")]
+    deployment: PathBuf,
+    /// Payer keypair; defaults to the Solana CLI keypair
+    #[arg(long, short, global = true)]
+    keypair: Option<PathBuf>,
+    /// Override the deployment file's RPC URL
+    #[arg(long, global = true)]
+    rpc_url: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build and deploy the MXE, register every comp def and write the
+    /// deployment file
+    Deploy(deploy::DeployArgs),
+    /// Register every circuit's comp def with the hash of its compiled circuit
+    /// (config authority only)
+    InitCompDefs {
+        /// Directory holding `<encrypted_ix>.arcis` from `arcium build`
+        #[arg(long, default_value = "build")]
+        build_dir: PathBuf,
+        /// Version label recorded with the circuit hashes (max 32 bytes)
+        #[arg(long)]
+        version: String,
+        /// Base URL the `.arcis` files are uploaded to
+        #[arg(long)]
+        circuit_url: String,
+    },
+    /// Encrypt a JSON input file and queue the circuit's request
+    Submit {
+        circuit: Circuit,
+        input: PathBuf,
+        /// Client keystore; the active key encrypts the inputs
+        #[arg(long)]
+        keystore: PathBuf,
+        /// Computation offset; random if omitted
+        #[arg(long)]
+        computation_offset: Option<u64>,
+        /// Wait for the result and print it; for a quorum request, the result
+        /// of each leg
+        #[arg(long)]
+        watch: bool,
+        /// Seconds to wait with --watch
+        #[arg(long, default_value_t = 120)]
+        timeout: u64,
+    },
+    /// Wait for a request to complete and print its decrypted result
+    Watch {
+        computation_offset: u64,
+        #[arg(long)]
+        keystore: PathBuf,
+        /// The request is a quorum leg queued on the quorum peer
+        #[arg(long)]
+        peer: bool,
+        /// Seconds to wait
+        #[arg(long, default_value_t = 120)]
+        timeout: u64,
+    },
+    /// Decrypt and print the result of a completed request
+    Decrypt {
+        computation_offset: u64,
+        #[arg(long)]
+        keystore: PathBuf,
+        /// The request is a quorum leg queued on the quorum peer
+        #[arg(long)]
+        peer: bool,
+    },
+    /// Settle this deployment's side of a quorum against the peer's, once
+    /// the peer's leg has reported (anyone may send it)
+    SettleQuorum { quorum_offset: u64 },
+    /// Print decoded program accounts
+    Inspect {
+        #[command(subcommand)]
+        target: InspectTarget,
+    },
+    /// Build, sign and broadcast admin transactions for air-gapped keys
+    Offline {
+        #[command(subcommand)]
+        command: offline::OfflineCommand,
+    },
+    /// Print the JSON Schema of the input files and circuit outputs
+    Schema,
+}
+
+#[derive(Subcommand)]
+enum InspectTarget {
+    /// Program configuration
+    Config,
+    /// Request record of a computation
+    Request { computation_offset: u64 },
+    /// Registered circuit hash and version of every comp def
+    Circuits,
+    /// Client key registry of a bridge (default: the payer)
+    Keys { bridge: Option<String> },
+    /// Both sides of a quorum request
+    Quorum { quorum_offset: u64 },
+    /// Policy a circuit runs under
+    Policy { circuit: Circuit },
+    /// Audit log entries, optionally only those after a sequence number
+    Audit {
+        #[arg(long)]
+        after: Option<u64>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Circuit {
+    StrategyPlan,
+    RiskScore,
+    CurveEval,
+    GmpcStrategy,
+    MultiUserAnalytics,
+    GmpcStrategyQuorum,
+}
+
+impl From<Circuit> for CircuitKind {
+    fn from(circuit: Circuit) -> Self {
+        match circuit {
+            Circuit::StrategyPlan => CircuitKind::StrategyPlan,
+            Circuit::RiskScore => CircuitKind::RiskScore,
+            Circuit::CurveEval => CircuitKind::CurveEval,
+            Circuit::GmpcStrategy => CircuitKind::GmpcStrategy,
+            Circuit::MultiUserAnalytics => CircuitKind::MultiUserAnalytics,
+            Circuit::GmpcStrategyQuorum => CircuitKind::GmpcStrategyQuorum,
+        }
+    }
+}
+
+/// Deployment, RPC connection and payer shared by every subcommand
+pub struct Session {
+    pub deployment: Deployment,
+    pub rpc: Rpc,
+    keypair: Option<PathBuf>,
+}
+
+impl Session {
+    fn keypair_path(&self) -> Result<PathBuf> {
+        match &self.keypair {
+            Some(path) => Ok(path.clone()),
+            None => Ok(dirs_home()?.join(".config/solana/id.json")),
+        }
+    }
+
+    fn keypair(&self) -> Result<Keypair> {
+        let path = self.keypair_path()?;
+        read_keypair_file(&path).map_err(|err| anyhow!("reading keypair {}: {err}", path.display()))
+    }
+
+    /// Session on the quorum peer's deployment, with the same payer
+    pub fn peer(&self) -> Result<Session> {
+        let deployment = self.deployment.peer()?;
+        Ok(Session {
+            rpc: Rpc::new(&deployment.rpc_url),
+            deployment,
+            keypair: self.keypair.clone(),
+        })
+    }
+
+    pub fn payer(&self) -> Result<Pubkey> {
+        Ok(self.keypair()?.pubkey())
+    }
+
+    /// Sign with the payer and send one transaction
+    pub fn send(&self, instructions: &[Instruction]) -> Result<String> {
+        let payer = self.keypair()?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &[&payer],
+            self.rpc.latest_blockhash()?,
+        );
+        Ok(self.rpc.send_and_confirm(&transaction)?)
+    }
+}
+
+fn dirs_home() -> Result<PathBuf> {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow!("HOME is not set; pass --keypair"))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Deploy(args) => {
+            return deploy::deploy(&cli.deployment, cli.keypair, cli.rpc_url, args);
+        }
+        Command::Offline {
+            command: offline::OfflineCommand::Sign { file, signer },
+        } => return offline::sign(&file, &signer),
+        Command::Schema => {
+            let schema = evalys_mxe_client::schema::json_schema();
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+            return Ok(());
+        }
+        _ => {}
+    }
+    let mut deployment = Deployment::load(&cli.deployment)?;
+    if let Some(rpc_url) = cli.rpc_url {
+        deployment.rpc_url = rpc_url;
+    }
+    let session = Session {
+        rpc: Rpc::new(&deployment.rpc_url),
+        deployment,
+        keypair: cli.keypair,
+    };
+
+    match cli.command {
+        Command::InitCompDefs {
+            build_dir,
+            version,
+            circuit_url,
+        } => deploy::init_comp_defs(&session, &build_dir, &version, &circuit_url),
+        Command::Submit {
+            circuit,
+            input,
+            keystore,
+            computation_offset,
+            watch,
+            timeout,
+        } => {
+            let keystore = Keystore::open(&keystore)?;
+            let queued = submit::submit(
+                &session,
+                circuit.into(),
+                &input,
+                &keystore,
+                computation_offset,
+            )?;
+            if watch {
+                for leg in queued {
+                    let peer;
+                    let session = if leg.on_peer {
+                        peer = session.peer()?;
+                        &peer
+                    } else {
+                        &session
+                    };
+                    let timeout = Duration::from_secs(timeout);
+                    results::watch(session, leg.computation_offset, &keystore, timeout)?;
+                }
+            }
+            Ok(())
+        }
+        Command::Watch {
+            computation_offset,
+            keystore,
+            peer,
+            timeout,
+        } => results::watch(
+            &if peer { session.peer()? } else { session },
+            computation_offset,
+            &Keystore::open(&keystore)?,
+            Duration::from_secs(timeout),
+        ),
+        Command::Decrypt {
+            computation_offset,
+            keystore,
+            peer,
+        } => results::decrypt(
+            &if peer { session.peer()? } else { session },
+            computation_offset,
+            &Keystore::open(&keystore)?,
+        ),
+        Command::SettleQuorum { quorum_offset } => submit::settle_quorum(&session, quorum_offset),
+        Command::Inspect { target } => match target {
+            InspectTarget::Config => inspect::config(&session),
+            InspectTarget::Request { computation_offset } => {
+                inspect::request(&session, computation_offset)
+            }
+            InspectTarget::Circuits => inspect::circuits(&session),
+            InspectTarget::Keys { bridge } => {
+                let bridge = match bridge {
+                    Some(bridge) => Pubkey::from_str(&bridge)?,
+                    None => session.payer()?,
+                };
+                inspect::keys(&session, &bridge)
+            }
+            InspectTarget::Quorum { quorum_offset } => inspect::quorum(&session, quorum_offset),
+            InspectTarget::Policy { circuit } => inspect::policy(&session, circuit.into()),
+            InspectTarget::Audit { after } => inspect::audit(&session, after),
+        },
+        Command::Offline { command } => offline::run(&session, command),
+        Command::Deploy(_) | Command::Schema => {
+            unreachable!("handled before loading the deployment")
+        }
+    }
+}
@@ -0,0 +1,563 @@
+//! `offline`: admin and init transactions signed on an air-gapped machine.
+//!
+//! 1. `offline build` writes an unsigned transaction to a file. Its first
+//!    instruction advances a durable nonce account, and the nonce stands in
+//!    for the recent blockhash, so the transaction does not expire while it
+//!    travels to the cold key and back.
+//! 2. `offline sign` adds a signature with a keypair file. It needs neither
+//!    RPC nor a deployment file; run it once per required signer.
+//! 3. `offline broadcast` sends the transaction once every signature is in.
+//!
+//! The nonce account must be initialized (`solana create-nonce-account`) and
+//! its authority must sign. Each broadcast advances the nonce, so build the
+//! next transaction after the previous one has landed.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::Subcommand;
+use evalys_mxe_client::instructions;
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::Pubkey;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use solana_hash::Hash;
+use solana_instruction::Instruction;
+use solana_keypair::read_keypair_file;
+use solana_signer::Signer;
+use solana_system_interface::instruction::advance_nonce_account;
+use solana_transaction::Transaction;
+
+use crate::deploy::{circuit_hash, circuit_source};
+use crate::Session;
+
+/// Admin and init instructions of the program
+#[derive(Subcommand)]
+pub enum AdminAction {
+    /// `initialize_config`: create the config with `authority`, the program's
+    /// upgrade authority, as its authority
+    InitializeConfig {
+        /// Program id of the deployment that runs the other quorum leg
+        quorum_peer: String,
+    },
+    /// `set_quorum_peer`: replace the quorum peer of quorums opened from now on
+    SetQuorumPeer { quorum_peer: String },
+    /// `initialize_audit_log`: create the audit log, paid by `authority`
+    InitializeAuditLog,
+    /// `init_*_comp_def`: register a circuit's comp def, paid by the config
+    /// authority
+    InitCompDef {
+        circuit: crate::Circuit,
+        /// Directory holding `<encrypted_ix>.arcis` from `arcium build`
+        #[arg(long, default_value = "build")]
+        build_dir: PathBuf,
+        /// Version label recorded with the circuit hash (max 32 bytes)
+        #[arg(long)]
+        version: String,
+        /// Base URL the `.arcis` files are uploaded to
+        #[arg(long)]
+        circuit_url: String,
+    },
+    /// `upgrade_circuit`: re-register a circuit with the hash of the local
+    /// build, once its comp def enforces that hash
+    UpgradeCircuit {
+        circuit: crate::Circuit,
+        /// Directory holding `<encrypted_ix>.arcis` from `arcium build`
+        #[arg(long, default_value = "build")]
+        build_dir: PathBuf,
+        /// Version label recorded with the circuit hash (max 32 bytes)
+        #[arg(long)]
+        version: String,
+    },
+    /// `init_client_key_registry`: create the registry of bridge `authority`
+    InitClientKeyRegistry,
+    /// `rotate_client_key`: register a new active key for bridge `authority`
+    RotateClientKey {
+        /// Hex of the x25519 public key
+        pub_key: String,
+    },
+    /// `remove_client_key`: drop a retired key of bridge `authority`
+    RemoveClientKey { key_id: u32 },
+    /// `set_*_policy`: store the tunable thresholds of a circuit (the quorum
+    /// variant shares the policy of `gmpc-strategy`)
+    SetPolicy {
+        circuit: crate::Circuit,
+        /// JSON file of the policy; the defaults when omitted
+        #[arg(long)]
+        policy: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum OfflineCommand {
+    /// Write an unsigned admin transaction using a durable nonce
+    Build {
+        /// Signing authority of the action (config authority, payer or bridge)
+        #[arg(long)]
+        authority: String,
+        /// Durable nonce account
+        #[arg(long)]
+        nonce_account: String,
+        /// Nonce authority; defaults to `authority`
+        #[arg(long)]
+        nonce_authority: Option<String>,
+        /// Fee payer; defaults to `authority`
+        #[arg(long)]
+        fee_payer: Option<String>,
+        /// Current nonce value; read from the nonce account if omitted
+        #[arg(long)]
+        nonce: Option<String>,
+        /// Output file
+        #[arg(long, short)]
+        out: PathBuf,
+        #[command(subcommand)]
+        action: AdminAction,
+    },
+    /// Add a signature to a transaction file (no network access)
+    Sign {
+        file: PathBuf,
+        /// Keypair to sign with
+        #[arg(long)]
+        signer: PathBuf,
+    },
+    /// Send a fully signed transaction file
+    Broadcast { file: PathBuf },
+}
+
+/// A transaction on its way through offline signing
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransactionFile {
+    /// Program instruction, for the operator reviewing it
+    instruction: String,
+    nonce_account: String,
+    /// Base64 of the bincode-encoded transaction
+    transaction: String,
+}
+
+impl TransactionFile {
+    fn read(path: &Path) -> Result<(Self, Transaction)> {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("reading transaction file {}", path.display()))?;
+        let file: TransactionFile = serde_json::from_str(&json)
+            .with_context(|| format!("parsing transaction file {}", path.display()))?;
+        let transaction = bincode::deserialize(&STANDARD.decode(&file.transaction)?)
+            .with_context(|| format!("decoding the transaction in {}", path.display()))?;
+        Ok((file, transaction))
+    }
+
+    fn write(&mut self, path: &Path, transaction: &Transaction) -> Result<()> {
+        self.transaction = STANDARD.encode(bincode::serialize(transaction)?);
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        fs::write(path, json)
+            .with_context(|| format!("writing transaction file {}", path.display()))
+    }
+}
+
+fn pubkey(field: &str, value: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value).with_context(|| format!("{field} is not a valid pubkey: {value}"))
+}
+
+/// Policy in the JSON file at `path`, or the defaults
+fn read_policy<T: DeserializeOwned + Default>(path: Option<&Path>) -> Result<T> {
+    let Some(path) = path else {
+        return Ok(T::default());
+    };
+    let json =
+        fs::read_to_string(path).with_context(|| format!("reading policy {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("parsing policy {}", path.display()))
+}
+
+/// `set_*_policy` of the policy account `kind` reads, with the policy in
+/// `path` or the defaults
+pub fn policy_instruction(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    kind: CircuitKind,
+    path: Option<&Path>,
+) -> Result<(&'static str, Instruction)> {
+    let Some(kind) = kind.policy_kind() else {
+        bail!("{} has no policy", kind.encrypted_ix());
+    };
+    Ok(match kind {
+        CircuitKind::StrategyPlan => (
+            "set_strategy_policy",
+            instructions::set_strategy_policy(program_id, authority, &read_policy(path)?)?,
+        ),
+        CircuitKind::RiskScore => (
+            "set_risk_policy",
+            instructions::set_risk_policy(program_id, authority, &read_policy(path)?)?,
+        ),
+        CircuitKind::CurveEval => (
+            "set_curve_policy",
+            instructions::set_curve_policy(program_id, authority, &read_policy(path)?)?,
+        ),
+        CircuitKind::GmpcStrategy => (
+            "set_gmpc_policy",
+            instructions::set_gmpc_policy(program_id, authority, &read_policy(path)?)?,
+        ),
+        CircuitKind::GmpcStrategyQuorum | CircuitKind::MultiUserAnalytics => {
+            unreachable!("policy_kind is an owning kind")
+        }
+    })
+}
+
+/// Durable nonce stored in a nonce account, checked against its authority
+fn read_nonce(session: &Session, nonce_account: &Pubkey, authority: &Pubkey) -> Result<Hash> {
+    let data = session.rpc.require_account(nonce_account, "nonce")?;
+    // Versions (u32), State (u32: 1 = initialized), authority, durable nonce, fee calculator
+    if data.len() < 72 || data[4..8] != 1u32.to_le_bytes() {
+        bail!("{nonce_account} is not an initialized nonce account");
+    }
+    let stored_authority = Pubkey::new_from_array(data[8..40].try_into().unwrap());
+    if stored_authority != *authority {
+        bail!("nonce account {nonce_account} is controlled by {stored_authority}, not {authority}");
+    }
+    Ok(Hash::new_from_array(data[40..72].try_into().unwrap()))
+}
+
+fn action_instruction(
+    session: &Session,
+    authority: &Pubkey,
+    action: &AdminAction,
+) -> Result<(&'static str, Instruction)> {
+    let program_id = &session.deployment.program_id;
+    Ok(match action {
+        AdminAction::InitializeConfig { quorum_peer } => (
+            "initialize_config",
+            instructions::initialize_config(
+                program_id,
+                authority,
+                &pubkey("quorum peer", quorum_peer)?,
+            ),
+        ),
+        AdminAction::SetQuorumPeer { quorum_peer } => (
+            "set_quorum_peer",
+            instructions::set_quorum_peer(
+                program_id,
+                authority,
+                &pubkey("quorum peer", quorum_peer)?,
+            ),
+        ),
+        AdminAction::InitializeAuditLog => (
+            "initialize_audit_log",
+            instructions::initialize_audit_log(program_id, authority),
+        ),
+        AdminAction::InitCompDef {
+            circuit,
+            build_dir,
+            version,
+            circuit_url,
+        } => {
+            let kind = CircuitKind::from(*circuit);
+            let instruction = instructions::init_comp_def(
+                program_id,
+                authority,
+                kind,
+                &circuit_hash(build_dir, kind)?,
+                version,
+                &circuit_source(circuit_url, kind),
+                &session
+                    .deployment
+                    .arcium
+                    .comp_def_accounts(program_id, kind.encrypted_ix()),
+            );
+            (kind.init_instruction(), instruction)
+        }
+        AdminAction::UpgradeCircuit {
+            circuit,
+            build_dir,
+            version,
+        } => {
+            let kind = CircuitKind::from(*circuit);
+            let comp_def_account = session
+                .deployment
+                .arcium
+                .comp_def_accounts(program_id, kind.encrypted_ix())
+                .comp_def_account;
+            (
+                "upgrade_circuit",
+                instructions::upgrade_circuit(
+                    program_id,
+                    authority,
+                    kind,
+                    &circuit_hash(build_dir, kind)?,
+                    version,
+                    &comp_def_account,
+                ),
+            )
+        }
+        AdminAction::InitClientKeyRegistry => (
+            "init_client_key_registry",
+            instructions::init_client_key_registry(program_id, authority),
+        ),
+        AdminAction::RotateClientKey { pub_key } => {
+            let mut key = [0u8; 32];
+            hex::decode_to_slice(pub_key, &mut key).context("pub_key is not 32 bytes of hex")?;
+            (
+                "rotate_client_key",
+                instructions::rotate_client_key(program_id, authority, &key),
+            )
+        }
+        AdminAction::RemoveClientKey { key_id } => (
+            "remove_client_key",
+            instructions::remove_client_key(program_id, authority, *key_id),
+        ),
+        AdminAction::SetPolicy { circuit, policy } => {
+            policy_instruction(program_id, authority, (*circuit).into(), policy.as_deref())?
+        }
+    })
+}
+
+/// Signers whose signature is still missing
+fn missing_signers(transaction: &Transaction) -> Vec<Pubkey> {
+    let required = transaction.message.header.num_required_signatures as usize;
+    transaction.message.account_keys[..required]
+        .iter()
+        .zip(&transaction.signatures)
+        .filter(|(_, signature)| **signature == Default::default())
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build(
+    session: &Session,
+    authority: &str,
+    nonce_account: &str,
+    nonce_authority: Option<&str>,
+    fee_payer: Option<&str>,
+    nonce: Option<&str>,
+    out: &Path,
+    action: &AdminAction,
+) -> Result<()> {
+    let authority = pubkey("authority", authority)?;
+    let nonce_account = pubkey("nonce account", nonce_account)?;
+    let nonce_authority = match nonce_authority {
+        Some(key) => pubkey("nonce authority", key)?,
+        None => authority,
+    };
+    let fee_payer = match fee_payer {
+        Some(key) => pubkey("fee payer", key)?,
+        None => authority,
+    };
+    let nonce = match nonce {
+        Some(nonce) => Hash::from_str(nonce).map_err(|_| anyhow!("nonce is not a valid hash"))?,
+        None => read_nonce(session, &nonce_account, &nonce_authority)?,
+    };
+
+    let (name, instruction) = action_instruction(session, &authority, action)?;
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            advance_nonce_account(&nonce_account, &nonce_authority),
+            instruction,
+        ],
+        Some(&fee_payer),
+    );
+    transaction.message.recent_blockhash = nonce;
+
+    let mut file = TransactionFile {
+        instruction: name.to_string(),
+        nonce_account: nonce_account.to_string(),
+        transaction: String::new(),
+    };
+    file.write(out, &transaction)?;
+    println!("wrote unsigned {name} to {} (nonce {nonce})", out.display());
+    for signer in missing_signers(&transaction) {
+        println!("  needs signature of {signer}");
+    }
+    Ok(())
+}
+
+pub fn sign(path: &Path, signer: &Path) -> Result<()> {
+    let keypair = read_keypair_file(signer)
+        .map_err(|err| anyhow!("reading keypair {}: {err}", signer.display()))?;
+    let (mut file, mut transaction) = TransactionFile::read(path)?;
+    if !missing_signers(&transaction).contains(&keypair.pubkey()) {
+        bail!(
+            "{} is not a missing signer of this {} transaction",
+            keypair.pubkey(),
+            file.instruction
+        );
+    }
+    println!(
+        "signing {} (program {}, nonce account {}) as {}",
+        file.instruction,
+        transaction
+            .message
+            .program_id(1)
+            .copied()
+            .unwrap_or_default(),
+        file.nonce_account,
+        keypair.pubkey()
+    );
+    let blockhash = transaction.message.recent_blockhash;
+    transaction.try_partial_sign(&[&keypair], blockhash)?;
+    file.write(path, &transaction)?;
+    match missing_signers(&transaction).as_slice() {
+        [] => println!("fully signed"),
+        missing => {
+            for signer in missing {
+                println!("  still needs signature of {signer}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn broadcast(session: &Session, path: &Path) -> Result<()> {
+    let (file, transaction) = TransactionFile::read(path)?;
+    let missing = missing_signers(&transaction);
+    if !missing.is_empty() {
+        bail!("transaction is missing signatures of {missing:?}");
+    }
+    let signature = session.rpc.send_and_confirm(&transaction)?;
+    println!("{} confirmed ({signature})", file.instruction);
+    Ok(())
+}
+
+pub fn run(session: &Session, command: OfflineCommand) -> Result<()> {
+    match command {
+        OfflineCommand::Build {
+            authority,
+            nonce_account,
+            nonce_authority,
+            fee_payer,
+            nonce,
+            out,
+            action,
+        } => build(
+            session,
+            &authority,
+            &nonce_account,
+            nonce_authority.as_deref(),
+            fee_payer.as_deref(),
+            nonce.as_deref(),
+            &out,
+            &action,
+        ),
+        OfflineCommand::Sign { file, signer } => sign(&file, &signer),
+        OfflineCommand::Broadcast { file } => broadcast(session, &file),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evalys_mxe_client::manifest::Manifest;
+    use evalys_mxe_client::rpc::Rpc;
+    use solana_keypair::{write_keypair_file, Keypair};
+
+    use super::*;
+    use crate::deployment::Deployment;
+
+    /// Session that never reaches the RPC: the nonce is given explicitly
+    fn session() -> Session {
+        let key = |byte| Pubkey::new_from_array([byte; 32]);
+        let manifest = Manifest {
+            network: None,
+            rpc_url: "http://127.0.0.1:1".to_string(),
+            program_id: key(1),
+            arcium_program: key(2),
+            cluster_offset: 0,
+            fee_pool: key(3),
+            clock_account: key(4),
+            mxe_x25519_pubkey: None,
+            quorum_peer: None,
+            comp_defs: Vec::new(),
+        };
+        Session {
+            rpc: Rpc::new(&manifest.rpc_url),
+            deployment: Deployment::from_manifest(&manifest),
+            keypair: None,
+        }
+    }
+
+    #[test]
+    fn build_sign_and_reread_with_two_signers() {
+        let dir = std::env::temp_dir().join(format!("evalys-offline-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let authority = Keypair::new();
+        let fee_payer = Keypair::new();
+        let authority_file = dir.join("authority.json");
+        let fee_payer_file = dir.join("fee-payer.json");
+        write_keypair_file(&authority, &authority_file).unwrap();
+        write_keypair_file(&fee_payer, &fee_payer_file).unwrap();
+        let nonce_account = Pubkey::new_from_array([7; 32]);
+        let nonce = Hash::new_from_array([8; 32]);
+        let peer = Pubkey::new_from_array([9; 32]);
+        let out = dir.join("set-peer.json");
+
+        build(
+            &session(),
+            &authority.pubkey().to_string(),
+            &nonce_account.to_string(),
+            None,
+            Some(&fee_payer.pubkey().to_string()),
+            Some(&nonce.to_string()),
+            &out,
+            &AdminAction::SetQuorumPeer {
+                quorum_peer: peer.to_string(),
+            },
+        )
+        .unwrap();
+
+        let (file, transaction) = TransactionFile::read(&out).unwrap();
+        assert_eq!(file.instruction, "set_quorum_peer");
+        assert_eq!(file.nonce_account, nonce_account.to_string());
+        assert_eq!(transaction.message.recent_blockhash, nonce);
+        let expected = Transaction::new_with_payer(
+            &[
+                advance_nonce_account(&nonce_account, &authority.pubkey()),
+                instructions::set_quorum_peer(
+                    &session().deployment.program_id,
+                    &authority.pubkey(),
+                    &peer,
+                ),
+            ],
+            Some(&fee_payer.pubkey()),
+        );
+        // The nonce advance comes first, so the nonce replaces the blockhash
+        assert_eq!(
+            transaction.message.instructions,
+            expected.message.instructions
+        );
+        assert_eq!(
+            transaction.message.account_keys,
+            expected.message.account_keys
+        );
+        assert_eq!(
+            missing_signers(&transaction),
+            [fee_payer.pubkey(), authority.pubkey()]
+        );
+
+        // A keypair that is not a required signer is refused
+        let stranger_file = dir.join("stranger.json");
+        write_keypair_file(&Keypair::new(), &stranger_file).unwrap();
+        assert!(sign(&out, &stranger_file).is_err());
+
+        sign(&out, &authority_file).unwrap();
+        let (_, transaction) = TransactionFile::read(&out).unwrap();
+        assert_eq!(missing_signers(&transaction), [fee_payer.pubkey()]);
+        assert!(sign(&out, &authority_file).is_err());
+
+        sign(&out, &fee_payer_file).unwrap();
+        let (_, transaction) = TransactionFile::read(&out).unwrap();
+        assert!(missing_signers(&transaction).is_empty());
+        assert_eq!(transaction.message.recent_blockhash, nonce);
+        let message = transaction.message_data();
+        assert_eq!(
+            transaction.signatures,
+            [
+                fee_payer.sign_message(&message),
+                authority.sign_message(&message)
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+//! `watch` and `decrypt`: find a request's result event and print the
+//! decrypted output.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
+use evalys_mxe_client::cipher::ClientCipher;
+use evalys_mxe_client::events::ResultEvent;
+use evalys_mxe_client::keys::Keystore;
+use evalys_mxe_client::outputs::{
+    AggregatedMetrics, CircuitOutput, ExecutionRecommendation, PlanOutput, RiskAssessment,
+    StrategyPlan,
+};
+use evalys_mxe_client::pda;
+use evalys_mxe_client::state::{CircuitKind, CircuitVersion, ComputationRequest, RequestStatus};
+use serde::Serialize;
+
+use crate::Session;
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+fn fetch_request(session: &Session, computation_offset: u64) -> Result<Option<ComputationRequest>> {
+    Ok(session
+        .rpc
+        .computation_request(&session.deployment.program_id, computation_offset)?)
+}
+
+/// Wait until the request leaves `Pending`, then print its result
+pub fn watch(
+    session: &Session,
+    computation_offset: u64,
+    keystore: &Keystore,
+    timeout: Duration,
+) -> Result<()> {
+    let started = Instant::now();
+    loop {
+        if let Some(request) = fetch_request(session, computation_offset)? {
+            if request.status != RequestStatus::Pending {
+                return print_result(session, &request, keystore);
+            }
+        }
+        if started.elapsed() >= timeout {
+            bail!("computation {computation_offset} still pending after {timeout:?}");
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+/// Print the result of a request that already left `Pending`
+pub fn decrypt(session: &Session, computation_offset: u64, keystore: &Keystore) -> Result<()> {
+    let request = fetch_request(session, computation_offset)?
+        .ok_or_else(|| anyhow!("no request at computation offset {computation_offset}"))?;
+    if request.status == RequestStatus::Pending {
+        bail!("computation {computation_offset} is still pending");
+    }
+    print_result(session, &request, keystore)
+}
+
+fn print_result(
+    session: &Session,
+    request: &ComputationRequest,
+    keystore: &Keystore,
+) -> Result<()> {
+    println!(
+        "computation {} ({:?}): {:?}",
+        request.computation_offset, request.kind, request.status
+    );
+    if request.status == RequestStatus::Failed {
+        bail!("computation aborted by the cluster; resubmit with a new offset");
+    }
+
+    let version_address =
+        pda::circuit_version_address(&session.deployment.program_id, &request.circuit_hash);
+    let version = match session.rpc.account_data(&version_address)? {
+        Some(data) => CircuitVersion::from_account_data(&data)?.version,
+        None => "unregistered".to_string(),
+    };
+    println!("circuit {} ({version})", hex::encode(request.circuit_hash));
+
+    let event = session
+        .rpc
+        .result_event(&session.deployment.program_id, request.computation_offset)?
+        .ok_or_else(|| {
+            anyhow!(
+                "no result event found for computation {}",
+                request.computation_offset
+            )
+        })?;
+    let key = keystore.get(event.client_key_id)?;
+    let cipher = ClientCipher::new(&key.secret(), &session.deployment.mxe_x25519_pubkey()?);
+
+    let output = format_output(request, &event, &cipher)?;
+    println!("input commitment verified");
+    println!("{output}");
+    Ok(())
+}
+
+/// Decrypt the output of `request`, check its echoed input commitment and
+/// format it as JSON followed by the decoded enums of the circuit
+fn format_output(
+    request: &ComputationRequest,
+    event: &ResultEvent,
+    cipher: &ClientCipher,
+) -> Result<String> {
+    Ok(match request.kind {
+        CircuitKind::StrategyPlan => {
+            let (plan, json): (StrategyPlan, _) = decode(event, cipher, request)?;
+            format!("{json}\nprivacy mode: {:?}", plan.privacy_mode()?)
+        }
+        CircuitKind::RiskScore => {
+            let (assessment, json): (RiskAssessment, _) = decode(event, cipher, request)?;
+            format!("{json}\nrecommendation: {:?}", assessment.recommendation()?)
+        }
+        CircuitKind::CurveEval => decode::<ExecutionRecommendation>(event, cipher, request)?.1,
+        CircuitKind::GmpcStrategy | CircuitKind::GmpcStrategyQuorum => {
+            let (plan, json): (PlanOutput, _) = decode(event, cipher, request)?;
+            format!(
+                "{json}\nprivacy mode: {:?}, MEV route: {:?}, risk class: {:?}",
+                plan.privacy_mode()?,
+                plan.mev_route()?,
+                plan.risk_class()?
+            )
+        }
+        CircuitKind::MultiUserAnalytics => decode::<AggregatedMetrics>(event, cipher, request)?.1,
+    })
+}
+
+/// Decrypt, check the echoed input commitment and render the output as JSON
+fn decode<T: CircuitOutput + Serialize>(
+    event: &ResultEvent,
+    cipher: &ClientCipher,
+    request: &ComputationRequest,
+) -> Result<(T, String)> {
+    let output: T = event.decrypt(cipher)?;
+    output
+        .input_commitment()
+        .verify(&request.input_commitment)?;
+    let json = serde_json::to_string_pretty(&output)?;
+    Ok((output, json))
+}
+
+#[cfg(test)]
+mod tests {
+    use evalys_mxe_client::arcium::ArciumDeployment;
+    use evalys_mxe_client::inputs::IntentInput;
+    use evalys_mxe_client::instructions::{self, RequestAccounts};
+    use evalys_mxe_client::Pubkey;
+    use evalys_mxe_mock::MockCluster;
+    use x25519_dalek::StaticSecret;
+
+    use super::*;
+
+    /// Result event of a `gmpc-strategy` request run by the mock cluster,
+    /// with the request record the program keeps for it
+    fn gmpc_result(
+        client: &ClientCipher,
+        cluster: &mut MockCluster,
+    ) -> (ComputationRequest, ResultEvent) {
+        let program_id = Pubkey::new_from_array([3; 32]);
+        let deployment = ArciumDeployment {
+            arcium_program: Pubkey::new_from_array([1; 32]),
+            cluster_offset: 0,
+            pool_account: Pubkey::new_from_array([2; 32]),
+            clock_account: Pubkey::new_from_array([4; 32]),
+        };
+        let kind = CircuitKind::GmpcStrategy;
+        let accounts = RequestAccounts {
+            payer: Pubkey::new_from_array([5; 32]),
+            computation_offset: 42,
+            arcium: deployment.accounts(&program_id, kind.encrypted_ix(), 42),
+        };
+        let intent = client.encrypt(&IntentInput {
+            max_size_sol: 3_000_000_000,
+            risk_level: 2,
+            privacy_priority: 1,
+            market_price: 1_000,
+            curve_position: 4_000,
+            volatility_score: 7_000,
+            avg_hold_time: 900,
+            win_rate: 5_500,
+            max_dd: 1_500,
+        });
+        cluster.register_circuit(kind, [7; 32]);
+        let queued = cluster
+            .queue(&instructions::request_gmpc_strategy(
+                &program_id,
+                &accounts,
+                1,
+                &intent,
+            ))
+            .unwrap()
+            .clone();
+        let callback = cluster.execute_next().unwrap().unwrap();
+        let event = ResultEvent::from_log(&callback.logs[0]).unwrap().unwrap();
+        let request = ComputationRequest {
+            requester: queued.requester,
+            kind,
+            computation_offset: queued.computation_offset,
+            input_commitment: queued.input_commitment,
+            circuit_hash: [7; 32],
+            client_key_id: queued.client_key_id,
+            status: RequestStatus::Completed,
+            requested_slot: 1,
+            completed_slot: 2,
+        };
+        (request, event)
+    }
+
+    #[test]
+    fn formats_the_decrypted_output_and_its_enums() {
+        let mut cluster = MockCluster::new(StaticSecret::from([1; 32]));
+        let client = ClientCipher::new(&StaticSecret::from([2; 32]), &cluster.public_key());
+        let (request, event) = gmpc_result(&client, &mut cluster);
+
+        let output = format_output(&request, &event, &client).unwrap();
+        let (json, summary) = output.rsplit_once('\n').unwrap();
+        let plan: PlanOutput = serde_json::from_str(json).unwrap();
+        assert_eq!(plan, event.decrypt::<PlanOutput>(&client).unwrap(),);
+        assert_eq!(
+            summary,
+            format!(
+                "privacy mode: {:?}, MEV route: {:?}, risk class: {:?}",
+                plan.privacy_mode().unwrap(),
+                plan.mev_route().unwrap(),
+                plan.risk_class().unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_an_output_for_other_inputs() {
+        let mut cluster = MockCluster::new(StaticSecret::from([1; 32]));
+        let client = ClientCipher::new(&StaticSecret::from([2; 32]), &cluster.public_key());
+        let (mut request, event) = gmpc_result(&client, &mut cluster);
+
+        request.input_commitment[0] ^= 1;
+        assert!(format_output(&request, &event, &client).is_err());
+    }
+}
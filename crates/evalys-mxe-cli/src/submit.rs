@@ -0,0 +1,427 @@
+//! `submit`: encrypt a JSON input file and queue the circuit's request.
+//!
+//! Input files use the circuit field names, one object per argument:
+//!
+//! | circuit                | input file                                         |
+//! |------------------------|----------------------------------------------------|
+//! | `strategy-plan`        | `{ "preferences", "history", "curve_state" }`      |
+//! | `risk-score`           | `{ "portfolio", "performance", "market" }`         |
+//! | `curve-eval`           | `{ "sizing", "constraints", "curve" }`             |
+//! | `gmpc-strategy`        | `{ "intent" }`                                     |
+//! | `gmpc-strategy-quorum` | `{ "intent" }`                                     |
+//! | `multi-user-analytics` | `{ "profiles": [...], "aggregation_type" }`        |
+//!
+//! Values outside a field's range (see `evalys-mxe schema`) are rejected
+//! before anything is encrypted.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use evalys_mxe_client::cipher::ClientCipher;
+use evalys_mxe_client::inputs::{IntentInput, QuorumSalt};
+use evalys_mxe_client::instructions::{self, RequestAccounts};
+use evalys_mxe_client::keys::{Keystore, StoredKey};
+use evalys_mxe_client::schema::{
+    CurveEvalInputs, GmpcStrategyInputs, MultiUserAnalyticsInputs, RiskScoreInputs,
+    StrategyPlanInputs,
+};
+use evalys_mxe_client::state::{CircuitKind, QuorumStatus};
+use evalys_mxe_client::Pubkey;
+use rand_core::{OsRng, RngCore};
+use serde::de::DeserializeOwned;
+use solana_instruction::Instruction;
+
+use crate::inspect::fetch_quorum;
+use crate::Session;
+
+fn read_input<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("reading input file {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("parsing input file {}", path.display()))
+}
+
+fn random_offset() -> u64 {
+    OsRng.next_u64()
+}
+
+/// A queued computation to watch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Queued {
+    pub computation_offset: u64,
+    /// Queued on the quorum peer's deployment
+    pub on_peer: bool,
+}
+
+/// Queue `kind` with the inputs of `input_path`; returns the computations to
+/// watch (one per deployment for a quorum request)
+pub fn submit(
+    session: &Session,
+    kind: CircuitKind,
+    input_path: &Path,
+    keystore: &Keystore,
+    computation_offset: Option<u64>,
+) -> Result<Vec<Queued>> {
+    let key = keystore
+        .active()
+        .ok_or_else(|| anyhow!("keystore has no active client key"))?;
+    let computation_offset = computation_offset.unwrap_or_else(random_offset);
+    if kind == CircuitKind::GmpcStrategyQuorum {
+        let input: GmpcStrategyInputs = read_input(input_path)?;
+        return submit_quorum(session, key, &input.intent, computation_offset);
+    }
+
+    let cipher = ClientCipher::new(&key.secret(), &session.deployment.mxe_x25519_pubkey()?);
+    let program_id = &session.deployment.program_id;
+    let payer = session.payer()?;
+    let accounts = RequestAccounts {
+        payer,
+        computation_offset,
+        arcium: session.deployment.arcium.accounts(
+            program_id,
+            kind.encrypted_ix(),
+            computation_offset,
+        ),
+    };
+    let instruction =
+        request_instruction(kind, input_path, program_id, &accounts, key.key_id, &cipher)?;
+
+    let signature = session.send(&[instruction])?;
+    println!("queued {kind:?} at computation offset {computation_offset} ({signature})");
+    Ok(vec![Queued {
+        computation_offset,
+        on_peer: false,
+    }])
+}
+
+/// Encrypt the inputs of `input_path` and build the `request_*` instruction
+/// of `kind`; quorum requests are built per deployment by `submit_quorum`
+fn request_instruction(
+    kind: CircuitKind,
+    input_path: &Path,
+    program_id: &Pubkey,
+    accounts: &RequestAccounts,
+    key_id: u32,
+    cipher: &ClientCipher,
+) -> Result<Instruction> {
+    Ok(match kind {
+        CircuitKind::StrategyPlan => {
+            let input: StrategyPlanInputs = read_input(input_path)?;
+            instructions::request_strategy_plan(
+                program_id,
+                accounts,
+                key_id,
+                &cipher.encrypt(&input.preferences),
+                &cipher.encrypt(&input.history),
+                &input.curve_state,
+            )
+        }
+        CircuitKind::RiskScore => {
+            let input: RiskScoreInputs = read_input(input_path)?;
+            instructions::request_risk_score(
+                program_id,
+                accounts,
+                key_id,
+                &cipher.encrypt(&input.portfolio),
+                &cipher.encrypt(&input.performance),
+                &input.market,
+            )
+        }
+        CircuitKind::CurveEval => {
+            let input: CurveEvalInputs = read_input(input_path)?;
+            instructions::request_curve_eval(
+                program_id,
+                accounts,
+                key_id,
+                &cipher.encrypt(&input.sizing),
+                &cipher.encrypt(&input.constraints),
+                &input.curve,
+            )
+        }
+        CircuitKind::GmpcStrategy => {
+            let input: GmpcStrategyInputs = read_input(input_path)?;
+            instructions::request_gmpc_strategy(
+                program_id,
+                accounts,
+                key_id,
+                &cipher.encrypt(&input.intent),
+            )
+        }
+        CircuitKind::MultiUserAnalytics => {
+            let input: MultiUserAnalyticsInputs = read_input(input_path)?;
+            let profiles: Vec<_> = input.profiles.iter().map(|p| cipher.encrypt(p)).collect();
+            instructions::request_multi_user_analytics(
+                program_id,
+                accounts,
+                key_id,
+                &profiles,
+                input.aggregation_type,
+            )?
+        }
+        CircuitKind::GmpcStrategyQuorum => {
+            bail!("{kind:?} is queued on both deployments by submit_quorum")
+        }
+    })
+}
+
+/// Open the quorum on both deployments in one transaction, then queue one
+/// leg on each with the same intent and salt encrypted to each deployment's
+/// MXE key; the peer's leg runs at `computation_offset + 1`. The client key
+/// must be registered with the peer under the same key id.
+fn submit_quorum(
+    session: &Session,
+    key: &StoredKey,
+    intent: &IntentInput,
+    computation_offset: u64,
+) -> Result<Vec<Queued>> {
+    let peer = session.peer()?;
+    let payer = session.payer()?;
+    let sides = [session, &peer];
+
+    let quorum_offset = random_offset();
+    let opens: Vec<_> = sides
+        .iter()
+        .map(|side| {
+            instructions::open_gmpc_quorum(&side.deployment.program_id, &payer, quorum_offset)
+        })
+        .collect();
+    let signature = session.send(&opens)?;
+    println!("opened quorum {quorum_offset} on both deployments ({signature})");
+
+    let salt = QuorumSalt::random();
+    let mut queued = Vec::with_capacity(2);
+    for (leg, side) in sides.into_iter().enumerate() {
+        let deployment = &side.deployment;
+        let program_id = &deployment.program_id;
+        let cipher = ClientCipher::new(&key.secret(), &deployment.mxe_x25519_pubkey()?);
+        let leg_offset = computation_offset.wrapping_add(leg as u64);
+        let accounts = RequestAccounts {
+            payer,
+            computation_offset: leg_offset,
+            arcium: deployment.arcium.accounts(
+                program_id,
+                CircuitKind::GmpcStrategyQuorum.encrypted_ix(),
+                leg_offset,
+            ),
+        };
+        let signature = session.send(&[instructions::request_gmpc_strategy_quorum(
+            program_id,
+            &accounts,
+            quorum_offset,
+            key.key_id,
+            &cipher.encrypt(intent),
+            &cipher.encrypt(&salt),
+        )])?;
+        println!(
+            "queued quorum leg on {program_id} at computation offset {leg_offset} ({signature})"
+        );
+        queued.push(Queued {
+            computation_offset: leg_offset,
+            on_peer: leg == 1,
+        });
+    }
+    Ok(queued)
+}
+
+/// Settle each side of a quorum that is still open although the other
+/// side's leg has reported
+pub fn settle_quorum(session: &Session, quorum_offset: u64) -> Result<()> {
+    let peer = session.peer()?;
+    let sides = [session, &peer];
+    let quorums = [
+        fetch_quorum(session, quorum_offset)?,
+        fetch_quorum(&peer, quorum_offset)?,
+    ];
+    for (index, side) in sides.iter().enumerate() {
+        let (quorum, other) = (&quorums[index], &quorums[1 - index]);
+        let program_id = &side.deployment.program_id;
+        if quorum.status != QuorumStatus::Open {
+            println!("{program_id}: {:?}", quorum.status);
+        } else if !(other.leg.completed || other.leg.failed) {
+            println!("{program_id}: open, waiting for the peer's leg");
+        } else {
+            let signature = session.send(&[instructions::settle_gmpc_quorum(
+                program_id,
+                &sides[1 - index].deployment.program_id,
+                quorum_offset,
+            )])?;
+            let status = fetch_quorum(side, quorum_offset)?.status;
+            println!("{program_id}: {status:?} ({signature})");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use evalys_mxe_client::arcium::ArciumDeployment;
+    use evalys_mxe_mock::MockCluster;
+    use x25519_dalek::StaticSecret;
+
+    use super::*;
+
+    const INTENT: &str = r#"{
+        "intent": {
+            "max_size_sol": 3000000000,
+            "risk_level": 2,
+            "privacy_priority": 1,
+            "market_price": 1000,
+            "curve_position": 4000,
+            "volatility_score": 7000,
+            "avg_hold_time": 900,
+            "win_rate": 5500,
+            "max_dd": 1500
+        }
+    }"#;
+
+    const PROFILE: &str = r#"{
+        "profile_id_hash": 7,
+        "avg_hold_time": 600,
+        "win_rate": 6000,
+        "preferred_size_range_min": 1000000000,
+        "preferred_size_range_max": 5000000000,
+        "risk_tolerance": 1
+    }"#;
+
+    /// Write `json` to a request file of its own
+    fn input_file(name: &str, json: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("evalys-mxe-cli-{}-{name}.json", std::process::id()));
+        fs::write(&path, json).unwrap();
+        path
+    }
+
+    fn accounts(kind: CircuitKind, computation_offset: u64) -> RequestAccounts {
+        let deployment = ArciumDeployment {
+            arcium_program: Pubkey::new_from_array([1; 32]),
+            cluster_offset: 0,
+            pool_account: Pubkey::new_from_array([2; 32]),
+            clock_account: Pubkey::new_from_array([4; 32]),
+        };
+        RequestAccounts {
+            payer: Pubkey::new_from_array([5; 32]),
+            computation_offset,
+            arcium: deployment.accounts(&PROGRAM_ID, kind.encrypted_ix(), computation_offset),
+        }
+    }
+
+    const PROGRAM_ID: Pubkey = Pubkey::new_from_array([3; 32]);
+
+    #[test]
+    fn parses_request_files() {
+        let path = input_file("intent", INTENT);
+        let input: GmpcStrategyInputs = read_input(&path).unwrap();
+        assert_eq!(input.intent.max_size_sol, 3_000_000_000);
+        assert_eq!(input.intent.privacy_priority, 1);
+        assert_eq!(input.intent.max_dd, 1_500);
+
+        let path = input_file(
+            "profiles",
+            &format!(r#"{{ "profiles": [{PROFILE}, {PROFILE}], "aggregation_type": 2 }}"#),
+        );
+        let input: MultiUserAnalyticsInputs = read_input(&path).unwrap();
+        assert_eq!(input.profiles.len(), 2);
+        assert_eq!(input.profiles[0].win_rate, 6_000);
+        assert_eq!(input.aggregation_type, 2);
+    }
+
+    #[test]
+    fn rejects_invalid_request_files() {
+        let intent = |from: &str, to: &str| INTENT.replace(from, to);
+        for (name, json) in [
+            ("win-rate", intent("5500", "10001")),
+            (
+                "unknown-field",
+                intent("\"max_dd\"", "\"drawdown\": 1, \"max_dd\""),
+            ),
+            (
+                "missing-field",
+                intent(",\n            \"max_dd\": 1500", ""),
+            ),
+        ] {
+            let path = input_file(name, &json);
+            let err = read_input::<GmpcStrategyInputs>(&path).unwrap_err();
+            assert!(
+                format!("{err:#}").contains(&path.display().to_string()),
+                "{name}: {err:#}"
+            );
+        }
+
+        for (name, json) in [
+            (
+                "no-profiles",
+                r#"{ "profiles": [], "aggregation_type": 0 }"#.to_string(),
+            ),
+            (
+                "aggregation-type",
+                format!(r#"{{ "profiles": [{PROFILE}], "aggregation_type": 3 }}"#),
+            ),
+        ] {
+            let path = input_file(name, &json);
+            assert!(
+                read_input::<MultiUserAnalyticsInputs>(&path).is_err(),
+                "{name}"
+            );
+        }
+
+        let missing = std::env::temp_dir().join("evalys-mxe-cli-missing.json");
+        let err = read_input::<GmpcStrategyInputs>(&missing).unwrap_err();
+        assert!(format!("{err:#}").starts_with("reading input file"));
+    }
+
+    #[test]
+    fn builds_requests_the_program_decodes() {
+        let mut cluster = MockCluster::new(StaticSecret::from([1; 32]));
+        let cipher = ClientCipher::new(&StaticSecret::from([2; 32]), &cluster.public_key());
+        let files = [
+            (
+                CircuitKind::GmpcStrategy,
+                input_file("request-intent", INTENT),
+            ),
+            (
+                CircuitKind::MultiUserAnalytics,
+                input_file(
+                    "request-profiles",
+                    &format!(r#"{{ "profiles": [{PROFILE}], "aggregation_type": 1 }}"#),
+                ),
+            ),
+        ];
+        for (index, (kind, path)) in files.iter().enumerate() {
+            cluster.register_circuit(*kind, [7; 32]);
+            let computation_offset = 40 + index as u64;
+            let instruction = request_instruction(
+                *kind,
+                path,
+                &PROGRAM_ID,
+                &accounts(*kind, computation_offset),
+                9,
+                &cipher,
+            )
+            .unwrap();
+            assert_eq!(instruction.program_id, PROGRAM_ID);
+
+            let queued = cluster.queue(&instruction).unwrap();
+            assert_eq!(queued.kind, *kind);
+            assert_eq!(queued.computation_offset, computation_offset);
+            assert_eq!(queued.client_key_id, 9);
+            assert_eq!(queued.requester, Pubkey::new_from_array([5; 32]));
+
+            // The mock decrypts the inputs with its MXE key to run the circuit
+            let callback = cluster.execute_next().unwrap().unwrap();
+            assert_eq!(callback.computation_offset, computation_offset);
+        }
+
+        let kind = CircuitKind::GmpcStrategyQuorum;
+        assert!(request_instruction(
+            kind,
+            &files[0].1,
+            &PROGRAM_ID,
+            &accounts(kind, 42),
+            9,
+            &cipher
+        )
+        .is_err());
+    }
+}
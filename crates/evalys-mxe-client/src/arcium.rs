@@ -0,0 +1,157 @@
+//! Arcium accounts that every `request_*` instruction forwards to
+//! `queue_computation`, and that `init_*_comp_def` forwards to
+//! `init_comp_def`.
+//!
+//! The program declares them with Arcium's account macros, after its own
+//! accounts and in the order of [`ArciumAccounts::account_metas`]. Most are
+//! PDAs of the Arcium program; [`ArciumDeployment`] derives them with the
+//! seeds of arcium-anchor's `derive_*_pda!` macros from the cluster offset the
+//! MXE is bound to.
+
+use sha2::{Digest, Sha256};
+use solana_instruction::AccountMeta;
+use solana_pubkey::Pubkey;
+
+use crate::instructions::SYSTEM_PROGRAM_ID;
+
+/// Seed of the program's signer PDA used to queue computations
+pub const SIGN_PDA_SEED: &[u8] = b"SignerAccount";
+pub const MXE_PDA_SEED: &[u8] = b"MXEAccount";
+pub const MEMPOOL_PDA_SEED: &[u8] = b"Mempool";
+pub const EXECPOOL_PDA_SEED: &[u8] = b"Execpool";
+pub const COMP_PDA_SEED: &[u8] = b"ComputationAccount";
+pub const COMP_DEF_PDA_SEED: &[u8] = b"ComputationDefinitionAccount";
+pub const CLUSTER_PDA_SEED: &[u8] = b"Cluster";
+
+/// Computation definition offset of an encrypted instruction:
+/// first 4 bytes of `sha256(name)`, little-endian
+pub fn comp_def_offset(encrypted_ix: &str) -> u32 {
+    let hash = Sha256::digest(encrypted_ix.as_bytes());
+    u32::from_le_bytes(hash[..4].try_into().unwrap())
+}
+
+pub fn sign_pda_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[SIGN_PDA_SEED], program_id).0
+}
+
+/// Arcium accounts of one queued computation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArciumAccounts {
+    pub arcium_program: Pubkey,
+    pub mxe_account: Pubkey,
+    pub mempool_account: Pubkey,
+    pub executing_pool: Pubkey,
+    /// Computation account of this request's `computation_offset`
+    pub computation_account: Pubkey,
+    /// Computation definition of the circuit being requested
+    pub comp_def_account: Pubkey,
+    /// Cluster that executes the computation
+    pub cluster_account: Pubkey,
+    pub pool_account: Pubkey,
+    pub clock_account: Pubkey,
+}
+
+impl ArciumAccounts {
+    pub fn account_metas(&self, program_id: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(sign_pda_address(program_id), false),
+            AccountMeta::new_readonly(self.mxe_account, false),
+            AccountMeta::new(self.mempool_account, false),
+            AccountMeta::new(self.executing_pool, false),
+            AccountMeta::new(self.computation_account, false),
+            AccountMeta::new_readonly(self.comp_def_account, false),
+            AccountMeta::new(self.cluster_account, false),
+            AccountMeta::new(self.pool_account, false),
+            AccountMeta::new_readonly(self.clock_account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(self.arcium_program, false),
+        ]
+    }
+}
+
+/// Arcium program and cluster an MXE deployment queues computations on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArciumDeployment {
+    pub arcium_program: Pubkey,
+    pub cluster_offset: u32,
+    /// Arcium fee pool account
+    pub pool_account: Pubkey,
+    /// Arcium clock account
+    pub clock_account: Pubkey,
+}
+
+impl ArciumDeployment {
+    fn find(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &self.arcium_program).0
+    }
+
+    pub fn mxe_address(&self, program_id: &Pubkey) -> Pubkey {
+        self.find(&[MXE_PDA_SEED, program_id.as_ref()])
+    }
+
+    pub fn comp_def_address(&self, program_id: &Pubkey, comp_def_offset: u32) -> Pubkey {
+        self.find(&[
+            COMP_DEF_PDA_SEED,
+            program_id.as_ref(),
+            &comp_def_offset.to_le_bytes(),
+        ])
+    }
+
+    pub fn cluster_address(&self, cluster_offset: u32) -> Pubkey {
+        self.find(&[CLUSTER_PDA_SEED, &cluster_offset.to_le_bytes()])
+    }
+
+    /// Accounts to queue `encrypted_ix` at `computation_offset` on this
+    /// deployment's cluster
+    pub fn accounts(
+        &self,
+        program_id: &Pubkey,
+        encrypted_ix: &str,
+        computation_offset: u64,
+    ) -> ArciumAccounts {
+        let cluster = self.cluster_offset.to_le_bytes();
+        ArciumAccounts {
+            arcium_program: self.arcium_program,
+            mxe_account: self.mxe_address(program_id),
+            mempool_account: self.find(&[MEMPOOL_PDA_SEED, &cluster]),
+            executing_pool: self.find(&[EXECPOOL_PDA_SEED, &cluster]),
+            computation_account: self.find(&[
+                COMP_PDA_SEED,
+                &cluster,
+                &computation_offset.to_le_bytes(),
+            ]),
+            comp_def_account: self.comp_def_address(program_id, comp_def_offset(encrypted_ix)),
+            cluster_account: self.cluster_address(self.cluster_offset),
+            pool_account: self.pool_account,
+            clock_account: self.clock_account,
+        }
+    }
+
+    /// Accounts `init_comp_def` needs for `encrypted_ix`
+    pub fn comp_def_accounts(&self, program_id: &Pubkey, encrypted_ix: &str) -> CompDefAccounts {
+        CompDefAccounts {
+            arcium_program: self.arcium_program,
+            mxe_account: self.mxe_address(program_id),
+            comp_def_account: self.comp_def_address(program_id, comp_def_offset(encrypted_ix)),
+        }
+    }
+}
+
+/// Arcium accounts of one `init_*_comp_def`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompDefAccounts {
+    pub arcium_program: Pubkey,
+    pub mxe_account: Pubkey,
+    pub comp_def_account: Pubkey,
+}
+
+impl CompDefAccounts {
+    pub fn account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.mxe_account, false),
+            AccountMeta::new(self.comp_def_account, false),
+            AccountMeta::new_readonly(self.arcium_program, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ]
+    }
+}
@@ -0,0 +1,212 @@
+//! Reader for the on-chain audit log ring buffer.
+//!
+//! The program keeps the last [`AUDIT_LOG_CAPACITY`] request and callback
+//! records in a zero-copy `AuditLog` account at [`audit_log_address`]. Each
+//! entry gets a sequence number (its position in the full append history), so
+//! a poller can fetch the account periodically and pick up only new entries
+//! with [`AuditLog::entries_after`].
+//!
+//! [`audit_log_address`]: crate::pda::audit_log_address
+
+use solana_pubkey::Pubkey;
+
+use crate::state::{account_body, CircuitKind, RequestStatus};
+use crate::ClientError;
+
+/// Number of entries kept by the audit log before it wraps
+pub const AUDIT_LOG_CAPACITY: usize = 100;
+
+/// Size of one `AuditEntry` (`#[repr(C)]`, 8-byte aligned)
+pub const AUDIT_ENTRY_LEN: usize = 88;
+
+/// Size of the `AuditLog` account body after the discriminator
+pub const AUDIT_LOG_LEN: usize = 8 + AUDIT_ENTRY_LEN * AUDIT_LOG_CAPACITY;
+
+/// One request or callback record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Position in the full append history (0 = first entry ever written)
+    pub sequence: u64,
+    pub requester: Pubkey,
+    /// SHA-256 of the output nonce and ciphertexts; zero for request entries
+    pub result_hash: [u8; 32],
+    pub computation_offset: u64,
+    pub slot: u64,
+    pub kind: CircuitKind,
+    pub status: RequestStatus,
+}
+
+/// Decoded audit log, entries in append order (oldest first)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLog {
+    /// Total entries ever appended
+    pub head: u64,
+    pub entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Decode the raw `AuditLog` account data, discriminator included
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let body = account_body("AuditLog", data, AUDIT_LOG_LEN)?;
+        let head = u64::from_le_bytes(body[..8].try_into().unwrap());
+        let slots = &body[8..];
+
+        let len = head.min(AUDIT_LOG_CAPACITY as u64);
+        let first = head - len;
+        let entries = (first..head)
+            .map(|sequence| {
+                let index = (sequence % AUDIT_LOG_CAPACITY as u64) as usize;
+                let raw = &slots[index * AUDIT_ENTRY_LEN..(index + 1) * AUDIT_ENTRY_LEN];
+                decode_entry(sequence, raw)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(AuditLog { head, entries })
+    }
+
+    /// Entries with a sequence number greater than `sequence`
+    ///
+    /// If the log wrapped past `sequence` since it was last read, the missing
+    /// entries are gone; compare the first returned sequence with
+    /// `sequence + 1` to detect the gap.
+    pub fn entries_after(&self, sequence: Option<u64>) -> &[AuditEntry] {
+        let start = match sequence {
+            Some(seq) => self
+                .entries
+                .iter()
+                .position(|e| e.sequence > seq)
+                .unwrap_or(self.entries.len()),
+            None => 0,
+        };
+        &self.entries[start..]
+    }
+
+    /// Entries for one computation, request first
+    pub fn for_computation(&self, computation_offset: u64) -> impl Iterator<Item = &AuditEntry> {
+        self.entries
+            .iter()
+            .filter(move |e| e.computation_offset == computation_offset)
+    }
+}
+
+fn decode_entry(sequence: u64, raw: &[u8]) -> Result<AuditEntry, ClientError> {
+    Ok(AuditEntry {
+        sequence,
+        requester: Pubkey::new_from_array(raw[0..32].try_into().unwrap()),
+        result_hash: raw[32..64].try_into().unwrap(),
+        computation_offset: u64::from_le_bytes(raw[64..72].try_into().unwrap()),
+        slot: u64::from_le_bytes(raw[72..80].try_into().unwrap()),
+        kind: CircuitKind::try_from(raw[80])?,
+        status: RequestStatus::try_from(raw[81])?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::account_discriminator;
+
+    /// `AuditEntry` as the program lays it out: requester, result hash,
+    /// computation offset, slot, kind, status and 6 bytes of padding
+    fn raw_entry(computation_offset: u64) -> [u8; AUDIT_ENTRY_LEN] {
+        let mut raw = [0u8; AUDIT_ENTRY_LEN];
+        raw[0..32].copy_from_slice(&[computation_offset as u8; 32]);
+        raw[32..64].copy_from_slice(&[0xab; 32]);
+        raw[64..72].copy_from_slice(&computation_offset.to_le_bytes());
+        raw[72..80].copy_from_slice(&(1_000 + computation_offset).to_le_bytes());
+        raw[80] = CircuitKind::GmpcStrategy as u8;
+        raw[81] = RequestStatus::Completed as u8;
+        raw
+    }
+
+    /// Account data after `appended` appends of entries whose computation
+    /// offset is their sequence number
+    fn account_data(appended: u64) -> Vec<u8> {
+        let mut data = account_discriminator("AuditLog").to_vec();
+        data.extend_from_slice(&appended.to_le_bytes());
+        let mut slots = vec![0u8; AUDIT_ENTRY_LEN * AUDIT_LOG_CAPACITY];
+        for sequence in 0..appended {
+            let index = (sequence % AUDIT_LOG_CAPACITY as u64) as usize;
+            slots[index * AUDIT_ENTRY_LEN..(index + 1) * AUDIT_ENTRY_LEN]
+                .copy_from_slice(&raw_entry(sequence));
+        }
+        data.extend_from_slice(&slots);
+        data
+    }
+
+    #[test]
+    fn entry_layout() {
+        // 32 + 32 + 8 + 8 + 1 + 1 + 6 padding
+        assert_eq!(AUDIT_ENTRY_LEN, 88);
+        assert_eq!(account_data(0).len(), 8 + AUDIT_LOG_LEN);
+
+        let entry = decode_entry(7, &raw_entry(42)).unwrap();
+        assert_eq!(
+            entry,
+            AuditEntry {
+                sequence: 7,
+                requester: Pubkey::new_from_array([42; 32]),
+                result_hash: [0xab; 32],
+                computation_offset: 42,
+                slot: 1_042,
+                kind: CircuitKind::GmpcStrategy,
+                status: RequestStatus::Completed,
+            }
+        );
+    }
+
+    #[test]
+    fn partial_log_is_in_append_order() {
+        let log = AuditLog::from_account_data(&account_data(3)).unwrap();
+        assert_eq!(log.head, 3);
+        let sequences: Vec<u64> = log.entries.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, [0, 1, 2]);
+        assert_eq!(log.entries_after(None).len(), 3);
+        assert_eq!(log.entries_after(Some(0))[0].sequence, 1);
+        assert!(log.entries_after(Some(2)).is_empty());
+    }
+
+    #[test]
+    fn wrapped_log_keeps_the_last_capacity_entries() {
+        let head = AUDIT_LOG_CAPACITY as u64 + 30;
+        let log = AuditLog::from_account_data(&account_data(head)).unwrap();
+        assert_eq!(log.head, head);
+        assert_eq!(log.entries.len(), AUDIT_LOG_CAPACITY);
+        // The oldest kept entry sits at slot 30, after the newest at slot 29
+        assert_eq!(log.entries[0].sequence, 30);
+        assert_eq!(log.entries.last().unwrap().sequence, head - 1);
+        for entry in &log.entries {
+            assert_eq!(entry.computation_offset, entry.sequence);
+        }
+    }
+
+    #[test]
+    fn entries_after_an_overwritten_sequence_starts_at_the_oldest_kept() {
+        let head = AUDIT_LOG_CAPACITY as u64 + 30;
+        let log = AuditLog::from_account_data(&account_data(head)).unwrap();
+
+        // Sequence 10 was overwritten: the gap shows as a first sequence
+        // greater than 11
+        let after = log.entries_after(Some(10));
+        assert_eq!(after.len(), AUDIT_LOG_CAPACITY);
+        assert_eq!(after[0].sequence, 30);
+
+        let after = log.entries_after(Some(head - 3));
+        let sequences: Vec<u64> = after.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, [head - 2, head - 1]);
+        assert!(log.entries_after(Some(head - 1)).is_empty());
+    }
+
+    #[test]
+    fn rejects_short_data_and_unknown_discriminants() {
+        let data = account_data(1);
+        assert!(matches!(
+            AuditLog::from_account_data(&data[..data.len() - 1]),
+            Err(ClientError::AccountDataTooShort { .. })
+        ));
+
+        let mut data = account_data(1);
+        data[16 + 80] = 0xff;
+        assert!(AuditLog::from_account_data(&data).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+//! Client side of Arcium's `Enc<Shared, T>` encryption.
+//!
+//! The client and the MXE agree on a shared secret with x25519. A Rescue-Prime
+//! hash of that secret keys the Rescue block cipher, which runs in counter mode
+//! over the Curve25519 base field `p = 2^255 - 19`: block `i` of the keystream
+//! is the encryption of `[nonce, i, 0, 0, 0]`. Every scalar field of a circuit
+//! input is one field element and encrypts to one 32-byte little-endian
+//! ciphertext, so a `T` with `n` fields needs `ceil(n / 5)` blocks.
+//!
+//! Round constants are squeezed from SHAKE-256 and the MDS matrix is the
+//! Cauchy matrix `1 / (i + j + 1)`, both fixed by the parameters below.
+
+use std::sync::OnceLock;
+
+use num_bigint::BigUint;
+use rand_core::{OsRng, RngCore};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::inputs::{CircuitInput, EncryptedInput};
+use crate::outputs::CircuitOutput;
+use crate::ClientError;
+
+/// Rescue state width of the cipher, and so the keystream block size
+pub const CIPHER_BLOCK_SIZE: usize = 5;
+const CIPHER_ROUNDS: usize = 10;
+const HASH_WIDTH: usize = 12;
+const HASH_RATE: usize = 5;
+const HASH_ROUNDS: usize = 8;
+/// S-box exponent: the smallest prime not dividing `p - 1`
+const ALPHA: u32 = 5;
+const CONSTANTS_SEED: &[u8] = b"encrypt everything, compute anything";
+
+/// The Curve25519 base field modulus `2^255 - 19`
+pub fn field_modulus() -> &'static BigUint {
+    static MODULUS: OnceLock<BigUint> = OnceLock::new();
+    MODULUS.get_or_init(|| (BigUint::from(1u8) << 255u32) - BigUint::from(19u8))
+}
+
+/// Field element of a 32-byte little-endian encoding, reduced mod `p`
+pub fn field_from_le_bytes(bytes: &[u8; 32]) -> BigUint {
+    BigUint::from_bytes_le(bytes) % field_modulus()
+}
+
+/// 32-byte little-endian encoding of a field element
+pub fn field_to_le_bytes(value: &BigUint) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let le = value.to_bytes_le();
+    bytes[..le.len()].copy_from_slice(&le);
+    bytes
+}
+
+fn add(a: &BigUint, b: &BigUint) -> BigUint {
+    (a + b) % field_modulus()
+}
+
+fn sub(a: &BigUint, b: &BigUint) -> BigUint {
+    let p = field_modulus();
+    (a + p - b) % p
+}
+
+fn inverse(a: &BigUint) -> BigUint {
+    let p = field_modulus();
+    a.modpow(&(p - BigUint::from(2u8)), p)
+}
+
+/// One Rescue instance: state width, round count, MDS matrix and constants
+struct Rescue {
+    width: usize,
+    rounds: usize,
+    alpha_inv: BigUint,
+    mds: Vec<Vec<BigUint>>,
+    constants: Vec<Vec<BigUint>>,
+}
+
+impl Rescue {
+    fn new(width: usize, rounds: usize, constant_count: usize) -> Self {
+        let p = field_modulus();
+        let p_minus_one = p - BigUint::from(1u8);
+        let alpha_inv = BigUint::from(ALPHA)
+            .modinv(&p_minus_one)
+            .expect("alpha is coprime to p - 1");
+
+        let mds = (0..width)
+            .map(|i| {
+                (0..width)
+                    .map(|j| inverse(&BigUint::from(i + j + 1)))
+                    .collect()
+            })
+            .collect();
+
+        let mut shake = Shake256::default();
+        shake.update(CONSTANTS_SEED);
+        shake.update(&[width as u8, rounds as u8]);
+        let mut reader = shake.finalize_xof();
+        let constants = (0..constant_count)
+            .map(|_| {
+                (0..width)
+                    .map(|_| {
+                        // 64 bytes per element keeps the bias of the reduction negligible
+                        let mut bytes = [0u8; 64];
+                        reader.read(&mut bytes);
+                        BigUint::from_bytes_le(&bytes) % p
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Rescue {
+            width,
+            rounds,
+            alpha_inv,
+            mds,
+            constants,
+        }
+    }
+
+    fn sbox(&self, state: &[BigUint], inverse: bool) -> Vec<BigUint> {
+        let p = field_modulus();
+        let exponent = if inverse {
+            self.alpha_inv.clone()
+        } else {
+            BigUint::from(ALPHA)
+        };
+        state.iter().map(|x| x.modpow(&exponent, p)).collect()
+    }
+
+    /// `mds * state + constants`
+    fn linear(&self, state: &[BigUint], constants: &[BigUint]) -> Vec<BigUint> {
+        let p = field_modulus();
+        (0..self.width)
+            .map(|i| {
+                let dot = self.mds[i]
+                    .iter()
+                    .zip(state)
+                    .fold(BigUint::from(0u8), |acc, (m, x)| (acc + m * x) % p);
+                add(&dot, &constants[i])
+            })
+            .collect()
+    }
+
+    /// Unkeyed Rescue-Prime permutation: x^alpha then x^(1/alpha) per round
+    fn permute(&self, state: &[BigUint]) -> Vec<BigUint> {
+        let mut state = state.to_vec();
+        for half_round in 0..2 * self.rounds {
+            let sboxed = self.sbox(&state, half_round % 2 == 1);
+            state = self.linear(&sboxed, &self.constants[half_round]);
+        }
+        state
+    }
+
+    /// Rescue key schedule: one round key per half round plus the whitening key
+    fn round_keys(&self, key: &[BigUint]) -> Vec<Vec<BigUint>> {
+        let mut key_state: Vec<BigUint> = key
+            .iter()
+            .zip(&self.constants[0])
+            .map(|(k, c)| add(k, c))
+            .collect();
+        let mut round_keys = vec![key_state.clone()];
+        for half_round in 0..2 * self.rounds {
+            let sboxed = self.sbox(&key_state, half_round % 2 == 0);
+            key_state = self.linear(&sboxed, &self.constants[half_round + 1]);
+            round_keys.push(key_state.clone());
+        }
+        round_keys
+    }
+
+    /// Rescue block cipher: x^(1/alpha) then x^alpha per round, keyed by `round_keys`
+    fn encrypt_block(&self, round_keys: &[Vec<BigUint>], block: &[BigUint]) -> Vec<BigUint> {
+        let mut state: Vec<BigUint> = block
+            .iter()
+            .zip(&round_keys[0])
+            .map(|(x, k)| add(x, k))
+            .collect();
+        for half_round in 0..2 * self.rounds {
+            let sboxed = self.sbox(&state, half_round % 2 == 0);
+            state = self.linear(&sboxed, &round_keys[half_round + 1]);
+        }
+        state
+    }
+}
+
+fn cipher_rescue() -> &'static Rescue {
+    static RESCUE: OnceLock<Rescue> = OnceLock::new();
+    RESCUE.get_or_init(|| Rescue::new(CIPHER_BLOCK_SIZE, CIPHER_ROUNDS, 2 * CIPHER_ROUNDS + 1))
+}
+
+fn hash_rescue() -> &'static Rescue {
+    static RESCUE: OnceLock<Rescue> = OnceLock::new();
+    RESCUE.get_or_init(|| Rescue::new(HASH_WIDTH, HASH_ROUNDS, 2 * HASH_ROUNDS))
+}
+
+/// Rescue-Prime sponge over field elements, squeezing one cipher key
+fn rescue_prime_hash(input: &[BigUint]) -> Vec<BigUint> {
+    let rescue = hash_rescue();
+    let mut padded = input.to_vec();
+    padded.push(BigUint::from(1u8));
+    padded.resize(padded.len().next_multiple_of(HASH_RATE), BigUint::from(0u8));
+
+    let mut state = vec![BigUint::from(0u8); HASH_WIDTH];
+    for chunk in padded.chunks(HASH_RATE) {
+        for (slot, value) in state.iter_mut().zip(chunk) {
+            *slot = add(slot, value);
+        }
+        state = rescue.permute(&state);
+    }
+    state.truncate(CIPHER_BLOCK_SIZE);
+    state
+}
+
+/// x25519 shared secret between a client key and the MXE public key
+pub fn shared_secret(client_secret: &StaticSecret, mxe_public_key: &[u8; 32]) -> [u8; 32] {
+    client_secret
+        .diffie_hellman(&PublicKey::from(*mxe_public_key))
+        .to_bytes()
+}
+
+/// Rescue cipher in counter mode, keyed by an x25519 shared secret
+#[derive(Clone)]
+pub struct RescueCipher {
+    round_keys: Vec<Vec<BigUint>>,
+}
+
+impl RescueCipher {
+    pub fn new(shared_secret: &[u8; 32]) -> Self {
+        let key = rescue_prime_hash(&[field_from_le_bytes(shared_secret)]);
+        RescueCipher {
+            round_keys: cipher_rescue().round_keys(&key),
+        }
+    }
+
+    fn keystream(&self, nonce: u128, len: usize) -> Vec<BigUint> {
+        let rescue = cipher_rescue();
+        let blocks = len.div_ceil(CIPHER_BLOCK_SIZE);
+        let mut keystream = Vec::with_capacity(blocks * CIPHER_BLOCK_SIZE);
+        for counter in 0..blocks {
+            let mut block = vec![BigUint::from(0u8); CIPHER_BLOCK_SIZE];
+            block[0] = BigUint::from(nonce);
+            block[1] = BigUint::from(counter);
+            keystream.extend(rescue.encrypt_block(&self.round_keys, &block));
+        }
+        keystream.truncate(len);
+        keystream
+    }
+
+    /// Encrypt field elements under `nonce`, one ciphertext per element
+    pub fn encrypt(&self, plaintext: &[BigUint], nonce: u128) -> Vec<[u8; 32]> {
+        self.keystream(nonce, plaintext.len())
+            .iter()
+            .zip(plaintext)
+            .map(|(k, x)| field_to_le_bytes(&add(x, k)))
+            .collect()
+    }
+
+    /// Decrypt ciphertexts produced under `nonce` back to field elements
+    pub fn decrypt(&self, ciphertexts: &[[u8; 32]], nonce: u128) -> Vec<BigUint> {
+        self.keystream(nonce, ciphertexts.len())
+            .iter()
+            .zip(ciphertexts)
+            .map(|(k, c)| sub(&field_from_le_bytes(c), k))
+            .collect()
+    }
+}
+
+/// A client key paired with the MXE: encrypts circuit inputs the MXE can
+/// decrypt, and decrypts results the MXE encrypted back to the client
+#[derive(Clone)]
+pub struct ClientCipher {
+    public_key: [u8; 32],
+    cipher: RescueCipher,
+}
+
+impl ClientCipher {
+    pub fn new(client_secret: &StaticSecret, mxe_public_key: &[u8; 32]) -> Self {
+        ClientCipher {
+            public_key: PublicKey::from(client_secret).to_bytes(),
+            cipher: RescueCipher::new(&shared_secret(client_secret, mxe_public_key)),
+        }
+    }
+
+    /// x25519 public key sent with every input
+    pub fn public_key(&self) -> [u8; 32] {
+        self.public_key
+    }
+
+    pub fn cipher(&self) -> &RescueCipher {
+        &self.cipher
+    }
+
+    /// Encrypt a circuit input under a fresh random nonce
+    pub fn encrypt<T: CircuitInput>(&self, input: &T) -> EncryptedInput {
+        let mut nonce = [0u8; 16];
+        OsRng.fill_bytes(&mut nonce);
+        self.encrypt_with_nonce(input, u128::from_le_bytes(nonce))
+    }
+
+    /// Encrypt a circuit input under a caller-chosen nonce. Reusing a nonce
+    /// with the same key reveals the difference of the plaintexts.
+    pub fn encrypt_with_nonce<T: CircuitInput>(&self, input: &T, nonce: u128) -> EncryptedInput {
+        EncryptedInput {
+            pub_key: self.public_key,
+            nonce,
+            ciphertexts: self.cipher.encrypt(&input.to_field_elements(), nonce),
+        }
+    }
+
+    /// Decrypt a circuit output the MXE encrypted to this client key
+    pub fn decrypt_output<T: CircuitOutput>(
+        &self,
+        ciphertexts: &[[u8; 32]],
+        nonce: &[u8; 16],
+    ) -> Result<T, ClientError> {
+        let fields = self
+            .cipher
+            .decrypt(ciphertexts, u128::from_le_bytes(*nonce));
+        T::from_field_elements(&fields)
+    }
+}
+
+/// Known answers of `@arcium-hq/client`, written by
+/// `scripts/arcium-cipher-kats.ts`. Ignored until that file is generated:
+/// only they pin the constants above to Arcium's. The round counts and the
+/// sponge shape are at least checked against the formulas of the Rescue and
+/// Rescue-Prime papers.
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    const VECTORS: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/vectors/arcium-cipher.json"
+    );
+
+    fn vectors() -> Value {
+        let json = std::fs::read_to_string(VECTORS).unwrap_or_else(|err| {
+            panic!("{VECTORS}: {err}; run `npx ts-node scripts/arcium-cipher-kats.ts`")
+        });
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn bytes32(value: &Value) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(value.as_str().unwrap(), &mut bytes).unwrap();
+        bytes
+    }
+
+    fn field(value: &Value) -> BigUint {
+        value.as_str().unwrap().parse().unwrap()
+    }
+
+    fn fields(value: &Value) -> Vec<BigUint> {
+        value.as_array().unwrap().iter().map(field).collect()
+    }
+
+    /// Target security level in bits
+    const SECURITY: u32 = 128;
+
+    fn binomial(n: u64, k: u64) -> BigUint {
+        (0..k).fold(BigUint::from(1u8), |acc, i| acc * (n - i) / (i + 1))
+    }
+
+    #[test]
+    fn cipher_rounds_follow_the_rescue_formula() {
+        // Rescue, section 4: twice the largest of the statistical, Gröbner
+        // basis (alpha > 3) and minimum round bounds
+        let m = CIPHER_BLOCK_SIZE as f64;
+        let s = SECURITY as f64;
+        let log2_p = 255.0f64;
+        let l0 = (2.0 * s / ((m + 1.0) * (log2_p - f64::from(ALPHA - 1).log2()))).ceil();
+        let l1 = ((s + 3.0) / (5.5 * m)).ceil();
+        let rounds = 2 * (l0.max(l1).max(5.0) as usize);
+        assert_eq!(rounds, CIPHER_ROUNDS);
+        assert_eq!(CIPHER_ROUNDS, 10);
+    }
+
+    #[test]
+    fn hash_is_a_width_12_rate_5_sponge_with_rescue_prime_rounds() {
+        // One absorbed or squeezed block is one cipher key
+        assert_eq!(HASH_WIDTH, 12);
+        assert_eq!(HASH_RATE, 5);
+        assert_eq!(HASH_RATE, CIPHER_BLOCK_SIZE);
+        let capacity = HASH_WIDTH - HASH_RATE;
+        assert!(capacity as u32 * 255 >= 2 * SECURITY);
+
+        // Rescue-Prime, section 2.3: the smallest l1 whose Gröbner basis
+        // attack costs more than 2^security, with 50% margin and at least 5
+        let m = HASH_WIDTH as u64;
+        let rate = HASH_RATE as u64;
+        let target = BigUint::from(1u8) << SECURITY;
+        let l1 = (1u64..)
+            .find(|&n| {
+                let v = m * (n - 1) + rate;
+                let dcon = (u64::from(ALPHA) - 1) * m * (n - 1) / 2 + 2;
+                binomial(v + dcon, v).pow(2) > target
+            })
+            .unwrap();
+        let rounds = (3 * l1.max(5)).div_ceil(2) as usize;
+        assert_eq!(rounds, HASH_ROUNDS);
+        assert_eq!(HASH_ROUNDS, 8);
+    }
+
+    #[test]
+    #[ignore = "needs tests/vectors/arcium-cipher.json from scripts/arcium-cipher-kats.ts"]
+    fn cipher_matches_arcium_client() {
+        for case in vectors()["cipher"].as_array().unwrap() {
+            let client_secret = StaticSecret::from(bytes32(&case["client_secret"]));
+            let mxe_public_key = bytes32(&case["mxe_public_key"]);
+            assert_eq!(
+                PublicKey::from(&StaticSecret::from(bytes32(&case["mxe_secret"]))).to_bytes(),
+                mxe_public_key
+            );
+            let secret = shared_secret(&client_secret, &mxe_public_key);
+            assert_eq!(secret, bytes32(&case["shared_secret"]));
+
+            let nonce: u128 = case["nonce"].as_str().unwrap().parse().unwrap();
+            let plaintext = fields(&case["plaintext"]);
+            let expected: Vec<[u8; 32]> = case["ciphertexts"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(bytes32)
+                .collect();
+            let cipher = RescueCipher::new(&secret);
+            assert_eq!(cipher.encrypt(&plaintext, nonce), expected);
+            assert_eq!(cipher.decrypt(&expected, nonce), plaintext);
+        }
+    }
+
+    #[test]
+    #[ignore = "needs tests/vectors/arcium-cipher.json from scripts/arcium-cipher-kats.ts"]
+    fn hash_matches_arcium_client() {
+        for case in vectors()["hash"].as_array().unwrap() {
+            let expected: Vec<BigUint> = case["output"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|value| field_from_le_bytes(&bytes32(value)))
+                .collect();
+            assert_eq!(rescue_prime_hash(&fields(&case["input"])), expected);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use thiserror::Error;
+
+/// Errors returned by the MXE client
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("account data is not a {0} account")]
+    AccountDiscriminator(&'static str),
+    #[error("{account} account data too short: expected {expected} bytes, got {actual}")]
+    AccountDataTooShort {
+        account: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("unknown circuit kind {0}")]
+    UnknownCircuitKind(u8),
+    #[error("unknown request status {0}")]
+    UnknownRequestStatus(u8),
+    #[error("unknown quorum status {0}")]
+    UnknownQuorumStatus(u8),
+    #[error("unknown client key status {0}")]
+    UnknownClientKeyStatus(u8),
+    #[error("client key {0} is not in the keystore")]
+    UnknownClientKey(u32),
+    #[error("no rotation staged in the keystore")]
+    NoStagedRotation,
+    #[error("on-chain registry does not list staged key {0} as active")]
+    RotationNotConfirmed(u32),
+    #[error("{input} is missing field `{field}`")]
+    MissingField {
+        input: &'static str,
+        field: &'static str,
+    },
+    #[error("{input}.{field} is {value}, above its maximum of {max}")]
+    OutOfRange {
+        input: &'static str,
+        field: &'static str,
+        value: u64,
+        max: u64,
+    },
+    #[error("{input}.{field} is {value}, below its minimum of {min}")]
+    BelowMinimum {
+        input: &'static str,
+        field: &'static str,
+        value: u64,
+        min: u64,
+    },
+    #[error("{input}.{min} is above {input}.{max}")]
+    InvertedRange {
+        input: &'static str,
+        min: &'static str,
+        max: &'static str,
+    },
+    #[error("multi-user analytics takes 1 to 16 profiles, got {0}")]
+    InvalidProfileCount(usize),
+    #[error("{output} expects {expected} fields, got {actual}")]
+    OutputFieldCount {
+        output: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("{output}.{field} does not fit its integer type")]
+    OutputFieldRange {
+        output: &'static str,
+        field: &'static str,
+    },
+    #[error("output input commitment does not match the request")]
+    CommitmentMismatch,
+    #[error("unknown privacy mode {0}")]
+    UnknownPrivacyMode(u8),
+    #[error("unknown MEV route {0}")]
+    UnknownMevRoute(u8),
+    #[error("unknown recommendation {0}")]
+    UnknownRecommendation(u8),
+    #[error("unknown risk class {0}")]
+    UnknownRiskClass(u8),
+    #[error("{0} account {1} does not exist")]
+    AccountNotFound(&'static str, solana_pubkey::Pubkey),
+    /// JSON-RPC transport or protocol failure, prefixed with the method
+    #[error("{0}")]
+    Rpc(String),
+    #[error("keystore I/O: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("keystore format: {0}")]
+    Json(#[from] serde_json::Error),
+}
@@ -0,0 +1,125 @@
+//! Result events emitted by the MXE callbacks.
+//!
+//! Anchor's `emit!` logs `Program data: <base64>` where the payload is the
+//! event discriminator followed by the Borsh-encoded event. Every callback
+//! event carries the circuit's encrypted output, which [`ResultEvent::decrypt`]
+//! turns into the typed struct from [`crate::outputs`].
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use solana_pubkey::Pubkey;
+
+use crate::cipher::ClientCipher;
+use crate::outputs::CircuitOutput;
+use crate::state::{CircuitKind, Reader};
+use crate::ClientError;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Anchor event discriminator: first 8 bytes of `sha256("event:<Name>")`
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("event:{name}").as_bytes());
+    hash[..8].try_into().unwrap()
+}
+
+/// Event payload of a `Program data:` log line
+pub fn program_data(log: &str) -> Option<Vec<u8>> {
+    STANDARD.decode(log.strip_prefix(PROGRAM_DATA_PREFIX)?).ok()
+}
+
+/// Event name, circuit and ciphertext count of each result event.
+/// Quorum legs report through `GmpcStrategyEvent` as well.
+const RESULT_EVENTS: [(&str, CircuitKind, usize); 5] = [
+    ("StrategyPlanEvent", CircuitKind::StrategyPlan, 21),
+    ("RiskScoreEvent", CircuitKind::RiskScore, 6),
+    ("CurveEvalEvent", CircuitKind::CurveEval, 7),
+    ("GmpcStrategyEvent", CircuitKind::GmpcStrategy, 8),
+    (
+        "MultiUserAnalyticsEvent",
+        CircuitKind::MultiUserAnalytics,
+        11,
+    ),
+];
+
+/// Encrypted result of one completed computation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultEvent {
+    pub kind: CircuitKind,
+    /// `plan_id`, `assessment_id`, ... of the event
+    pub result_id: Pubkey,
+    pub requester: Pubkey,
+    pub computation_offset: u64,
+    pub circuit_hash: [u8; 32],
+    /// Client key the result is encrypted to
+    pub client_key_id: u32,
+    pub ciphertexts: Vec<[u8; 32]>,
+    pub nonce: [u8; 16],
+}
+
+impl ResultEvent {
+    /// Decode an event payload; `Ok(None)` if it is not a result event
+    pub fn from_event_data(data: &[u8]) -> Result<Option<Self>, ClientError> {
+        let Some(&(name, kind, count)) = RESULT_EVENTS
+            .iter()
+            .find(|(name, _, _)| data.len() >= 8 && data[..8] == event_discriminator(name))
+        else {
+            return Ok(None);
+        };
+        let mut r = Reader::body(name, &data[8..]);
+        Ok(Some(ResultEvent {
+            kind,
+            result_id: r.pubkey()?,
+            requester: r.pubkey()?,
+            computation_offset: r.u64()?,
+            circuit_hash: r.bytes()?,
+            client_key_id: r.u32()?,
+            ciphertexts: (0..count).map(|_| r.bytes()).collect::<Result<_, _>>()?,
+            nonce: r.bytes()?,
+        }))
+    }
+
+    /// Decode the result event logged on `log`, if any
+    pub fn from_log(log: &str) -> Result<Option<Self>, ClientError> {
+        match program_data(log) {
+            Some(data) => Self::from_event_data(&data),
+            None => Ok(None),
+        }
+    }
+
+    /// Decrypt and decode the output with the cipher of `client_key_id`
+    pub fn decrypt<T: CircuitOutput>(&self, cipher: &ClientCipher) -> Result<T, ClientError> {
+        cipher.decrypt_output(&self.ciphertexts, &self.nonce)
+    }
+}
+
+/// `ComputationFailedEvent`: the MXE aborted the computation and its request
+/// record is marked `Failed`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailedEvent {
+    pub computation_offset: u64,
+    pub kind: CircuitKind,
+}
+
+impl FailedEvent {
+    /// Decode an event payload; `Ok(None)` if it is not a failure event
+    pub fn from_event_data(data: &[u8]) -> Result<Option<Self>, ClientError> {
+        const NAME: &str = "ComputationFailedEvent";
+        if data.len() < 8 || data[..8] != event_discriminator(NAME) {
+            return Ok(None);
+        }
+        let mut r = Reader::body(NAME, &data[8..]);
+        Ok(Some(FailedEvent {
+            computation_offset: r.u64()?,
+            kind: CircuitKind::try_from(r.u8()?)?,
+        }))
+    }
+
+    /// Decode the failure event logged on `log`, if any
+    pub fn from_log(log: &str) -> Result<Option<Self>, ClientError> {
+        match program_data(log) {
+            Some(data) => Self::from_event_data(&data),
+            None => Ok(None),
+        }
+    }
+}
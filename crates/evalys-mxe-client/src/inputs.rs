@@ -0,0 +1,747 @@
+//! Circuit inputs and their encrypted form.
+//!
+//! Each encrypted input mirrors a struct in `encrypted-ixs/` field for field,
+//! in declaration order, and comes with a builder that refuses to build until
+//! every field is set. Plaintext arguments (`CurveState`, `MarketConditions`,
+//! `CurveMetrics`) are passed to the program as-is; the circuit policies
+//! (`StrategyPolicy`, `RiskPolicy`, `CurvePolicy`, `GmpcPolicy`) are set by
+//! the config authority and read by the program from their policy accounts.
+//! All of them read from and write to JSON with their circuit field names,
+//! and reject values outside the range the circuit expects (e.g. a
+//! `win_rate` above 10000 bps) when built or deserialized; see
+//! [`crate::schema`] for their JSON Schema.
+
+use evalys_mxe_reference::{curve, gmpc, risk, strategy};
+use num_bigint::BigUint;
+use rand_core::{OsRng, RngCore};
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+use solana_pubkey::Pubkey;
+
+use crate::cipher::field_modulus;
+use crate::ClientError;
+
+/// Domain tag of the program's request input commitment
+pub const INPUT_COMMITMENT_DOMAIN: &[u8] = b"evalys-mxe-input-v1";
+
+/// Integer type of a circuit field, embedded in the base field. Negative
+/// values wrap to `p - |v|`.
+pub trait Scalar: Copy {
+    fn to_field(self) -> BigUint;
+}
+
+macro_rules! unsigned_scalar {
+    ($($ty:ty),*) => {
+        $(impl Scalar for $ty {
+            fn to_field(self) -> BigUint {
+                BigUint::from(self)
+            }
+        })*
+    };
+}
+
+macro_rules! signed_scalar {
+    ($($ty:ty),*) => {
+        $(impl Scalar for $ty {
+            fn to_field(self) -> BigUint {
+                let magnitude = BigUint::from(self.unsigned_abs());
+                if self < 0 {
+                    field_modulus() - magnitude
+                } else {
+                    magnitude
+                }
+            }
+        })*
+    };
+}
+
+unsigned_scalar!(u8, u16, u32, u64, u128);
+signed_scalar!(i8, i16, i32, i64);
+
+/// A struct that is passed to a circuit as `Enc<Shared, Self>`
+pub trait CircuitInput {
+    /// Number of scalar fields, and so of ciphertexts
+    const FIELD_COUNT: usize;
+
+    /// Field elements in circuit declaration order
+    fn to_field_elements(&self) -> Vec<BigUint>;
+}
+
+/// Define a circuit argument struct. Each field carries its unit and, where
+/// the circuit expects one, its maximum; both appear in the JSON Schema, and
+/// `validate` (also run on deserialization) enforces the maximum and the
+/// optional cross-field `where` check.
+macro_rules! circuit_struct {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[doc = $doc:literal])*
+                $field:ident: $ty:ty [$(unit = $unit:literal)? $(,)? $(max = $max:literal)?],
+            )*
+        }
+        $(where $check:ident)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+        #[serde(deny_unknown_fields)]
+        pub struct $name {
+            $(
+                $(#[doc = $doc])*
+                $(#[schemars(extend("x-unit" = $unit))])?
+                $(#[schemars(range(max = $max))])?
+                pub $field: $ty,
+            )*
+        }
+
+        impl $name {
+            /// Check every field is within the range the circuit expects
+            pub fn validate(&self) -> Result<(), ClientError> {
+                $($(
+                    if u64::from(self.$field) > $max {
+                        return Err(ClientError::OutOfRange {
+                            input: stringify!($name),
+                            field: stringify!($field),
+                            value: u64::from(self.$field),
+                            max: $max,
+                        });
+                    }
+                )?)*
+                $($check(self)?;)?
+                Ok(())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                #[derive(Deserialize)]
+                #[serde(deny_unknown_fields)]
+                struct Fields {
+                    $($field: $ty,)*
+                }
+
+                let Fields { $($field,)* } = Fields::deserialize(deserializer)?;
+                let value = $name { $($field,)* };
+                value.validate().map_err(de::Error::custom)?;
+                Ok(value)
+            }
+        }
+    };
+}
+
+macro_rules! circuit_input {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident, $builder:ident {
+            $(
+                $(#[doc = $doc:literal])*
+                $field:ident: $ty:ty [$($range:tt)*],
+            )*
+        }
+        $(where $check:ident)?
+    ) => {
+        circuit_struct! {
+            $(#[$meta])*
+            pub struct $name {
+                $($(#[doc = $doc])* $field: $ty [$($range)*],)*
+            }
+            $(where $check)?
+        }
+
+        impl $name {
+            pub fn builder() -> $builder {
+                $builder::default()
+            }
+        }
+
+        #[doc = concat!("Builder for [`", stringify!($name), "`]")]
+        #[derive(Debug, Clone, Default)]
+        pub struct $builder {
+            $($field: Option<$ty>,)*
+        }
+
+        impl $builder {
+            $(
+                $(#[doc = $doc])*
+                pub fn $field(mut self, value: $ty) -> Self {
+                    self.$field = Some(value);
+                    self
+                }
+            )*
+
+            /// Build the input once every field is set and in range
+            pub fn build(self) -> Result<$name, ClientError> {
+                let input = $name {
+                    $($field: self.$field.ok_or(ClientError::MissingField {
+                        input: stringify!($name),
+                        field: stringify!($field),
+                    })?,)*
+                };
+                input.validate()?;
+                Ok(input)
+            }
+        }
+
+        impl CircuitInput for $name {
+            const FIELD_COUNT: usize = [$(stringify!($field)),*].len();
+
+            fn to_field_elements(&self) -> Vec<BigUint> {
+                vec![$(Scalar::to_field(self.$field)),*]
+            }
+        }
+    };
+}
+
+circuit_input! {
+    /// Trader preferences for `confidential_strategy_plan`
+    pub struct UserPreferences, UserPreferencesBuilder {
+        /// Desired trade size
+        desired_size: u64 [unit = "lamports"],
+        /// Slippage tolerance
+        slippage_tolerance: u16 [unit = "bps", max = 10_000],
+        /// 0 = conservative, 255 = aggressive
+        risk_appetite: u8 [],
+        /// Preferred hold time
+        preferred_hold_time: u32 [unit = "seconds"],
+    }
+}
+
+circuit_input! {
+    /// Trading history for `confidential_strategy_plan`
+    pub struct UserHistory, UserHistoryBuilder {
+        /// Recent PnL
+        recent_pnl: i64 [unit = "lamports"],
+        /// Win rate
+        win_rate: u16 [unit = "bps", max = 10_000],
+        /// Average hold time
+        avg_hold_time: u32 [unit = "seconds"],
+        total_trades: u32 [unit = "trades"],
+    }
+}
+
+circuit_input! {
+    /// Portfolio state for `confidential_risk_score`
+    pub struct PortfolioContext, PortfolioContextBuilder {
+        /// Total portfolio value
+        total_capital: u64 [unit = "lamports"],
+        /// Current exposure
+        current_exposure: u64 [unit = "lamports"],
+        /// 0 = concentrated, 255 = diversified
+        diversification_score: u8 [],
+        /// Leverage (10000 = 1x)
+        leverage_ratio: u16 [unit = "bps"],
+    }
+}
+
+circuit_input! {
+    /// Track record for `confidential_risk_score`
+    pub struct PerformanceHistory, PerformanceHistoryBuilder {
+        /// Total PnL
+        total_pnl: i64 [unit = "lamports"],
+        /// Sharpe ratio scaled by 100
+        sharpe_ratio: i16 [unit = "hundredths"],
+        /// Max drawdown
+        max_drawdown: u16 [unit = "bps", max = 10_000],
+        /// 0-255
+        consistency_score: u8 [],
+    }
+}
+
+circuit_input! {
+    /// Position sizing for `confidential_curve_eval`
+    pub struct SizingPreferences, SizingPreferencesBuilder {
+        /// Target size
+        target_size: u64 [unit = "lamports"],
+        /// Minimum acceptable size, at most `max_size`
+        min_size: u64 [unit = "lamports"],
+        /// Maximum acceptable size
+        max_size: u64 [unit = "lamports"],
+        /// Capital allocation
+        capital_allocation_pct: u8 [unit = "percent", max = 100],
+    }
+    where sizing_bounds
+}
+
+circuit_input! {
+    /// Execution constraints for `confidential_curve_eval`
+    pub struct UserConstraints, UserConstraintsBuilder {
+        /// Max slippage
+        max_slippage_bps: u16 [unit = "bps", max = 10_000],
+        /// Time budget for execution
+        time_constraint_sec: u32 [unit = "seconds"],
+        /// 0-255
+        priority_level: u8 [],
+    }
+}
+
+circuit_input! {
+    /// Trade intent for `evalys_gmpc_strategy`
+    pub struct IntentInput, IntentInputBuilder {
+        /// Max size
+        max_size_sol: u64 [unit = "lamports"],
+        /// 0 = low, 1 = normal, 2 = high
+        risk_level: u8 [max = 2],
+        /// 0 = normal, 1 = stealth, 2 = max privacy
+        privacy_priority: u8 [max = 2],
+        /// Current market price
+        market_price: u64 [unit = "lamports"],
+        /// Bonding curve position
+        curve_position: u16 [unit = "bps", max = 10_000],
+        /// Volatility score
+        volatility_score: u16 [unit = "bps", max = 10_000],
+        /// Average hold time
+        avg_hold_time: u32 [unit = "seconds"],
+        /// Win rate
+        win_rate: u16 [unit = "bps", max = 10_000],
+        /// Max drawdown
+        max_dd: u16 [unit = "bps", max = 10_000],
+    }
+}
+
+circuit_input! {
+    /// One trader's profile for `confidential_multi_user_analytics`
+    pub struct UserProfile, UserProfileBuilder {
+        /// Hashed profile id (not reversible)
+        profile_id_hash: u64 [],
+        /// Average hold time
+        avg_hold_time: u32 [unit = "seconds"],
+        /// Win rate
+        win_rate: u16 [unit = "bps", max = 10_000],
+        /// Min preferred size, at most `preferred_size_range_max`
+        preferred_size_range_min: u64 [unit = "lamports"],
+        /// Max preferred size
+        preferred_size_range_max: u64 [unit = "lamports"],
+        /// 0 = low, 1 = normal, 2 = high
+        risk_tolerance: u8 [max = 2],
+    }
+    where preferred_size_bounds
+}
+
+circuit_input! {
+    /// Salt of a quorum request; both legs encrypt the same salt to their own MXE key
+    pub struct QuorumSalt, QuorumSaltBuilder {
+        /// Uniform random value
+        salt: u128 [],
+    }
+}
+
+fn sizing_bounds(sizing: &SizingPreferences) -> Result<(), ClientError> {
+    if sizing.min_size > sizing.max_size {
+        return Err(ClientError::InvertedRange {
+            input: "SizingPreferences",
+            min: "min_size",
+            max: "max_size",
+        });
+    }
+    Ok(())
+}
+
+fn preferred_size_bounds(profile: &UserProfile) -> Result<(), ClientError> {
+    if profile.preferred_size_range_min > profile.preferred_size_range_max {
+        return Err(ClientError::InvertedRange {
+            input: "UserProfile",
+            min: "preferred_size_range_min",
+            max: "preferred_size_range_max",
+        });
+    }
+    Ok(())
+}
+
+impl QuorumSalt {
+    /// Fresh uniformly random salt
+    pub fn random() -> Self {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        QuorumSalt {
+            salt: u128::from_le_bytes(salt),
+        }
+    }
+}
+
+circuit_struct! {
+    /// Public curve state for `request_strategy_plan`
+    pub struct CurveState {
+        /// Current token price
+        current_price: u64 [unit = "lamports"],
+        /// Available liquidity
+        liquidity_depth: u64 [unit = "lamports"],
+        /// Volatility metric (above 500 is high, above 200 medium)
+        volatility: u16 [],
+        /// Recent trading volume
+        recent_volume: u64 [unit = "lamports"],
+    }
+}
+
+impl CurveState {
+    pub(crate) fn serialize_into(&self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.current_price.to_le_bytes());
+        data.extend_from_slice(&self.liquidity_depth.to_le_bytes());
+        data.extend_from_slice(&self.volatility.to_le_bytes());
+        data.extend_from_slice(&self.recent_volume.to_le_bytes());
+    }
+}
+
+circuit_struct! {
+    /// Public market conditions for `request_risk_score`
+    pub struct MarketConditions {
+        /// Curve volatility (above 500 is very high, above 300 high)
+        curve_volatility: u16 [],
+        /// 0-255
+        liquidity_risk: u8 [],
+        /// -128 (bearish) to 127 (bullish)
+        market_sentiment: i8 [],
+    }
+}
+
+impl MarketConditions {
+    pub(crate) fn serialize_into(&self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.curve_volatility.to_le_bytes());
+        data.push(self.liquidity_risk);
+        data.extend_from_slice(&self.market_sentiment.to_le_bytes());
+    }
+}
+
+circuit_struct! {
+    /// Public curve metrics for `request_curve_eval`
+    pub struct CurveMetrics {
+        /// Current token price
+        current_price: u64 [unit = "lamports"],
+        /// 24h price change
+        price_change_24h: i32 [unit = "bps"],
+        /// Available liquidity
+        liquidity_depth: u64 [unit = "lamports"],
+        /// Buy pressure indicator
+        buy_pressure: u16 [],
+        /// Sell pressure indicator
+        sell_pressure: u16 [],
+    }
+}
+
+impl CurveMetrics {
+    pub(crate) fn serialize_into(&self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.current_price.to_le_bytes());
+        data.extend_from_slice(&self.price_change_24h.to_le_bytes());
+        data.extend_from_slice(&self.liquidity_depth.to_le_bytes());
+        data.extend_from_slice(&self.buy_pressure.to_le_bytes());
+        data.extend_from_slice(&self.sell_pressure.to_le_bytes());
+    }
+}
+
+circuit_struct! {
+    /// Thresholds of `confidential_strategy_plan`, from its policy account
+    pub struct StrategyPolicy {
+        /// Risk score above which plans use stealth mode
+        stealth_risk: u16 [],
+        /// Risk score above which plans use max ghost mode
+        ghost_risk: u16 [],
+        /// Win rate below which history adds risk
+        low_win_rate: u16 [unit = "bps", max = 10_000],
+        /// Volatility above which the timing window is medium
+        medium_volatility: u16 [],
+        /// Volatility above which the timing window is fast
+        high_volatility: u16 [],
+        /// Timing window at high volatility
+        fast_window_sec: u32 [unit = "seconds", max = 86_400],
+        /// Timing window at medium volatility
+        medium_window_sec: u32 [unit = "seconds", max = 86_400],
+        /// Timing window at low volatility
+        slow_window_sec: u32 [unit = "seconds", max = 86_400],
+        /// Fewest slices a plan uses, at least 1
+        min_slices: u8 [max = 8],
+        /// Largest share of recent volume one slice may take
+        participation_pct: u8 [unit = "percent", max = 100],
+        /// Risk appetite above which max notional is 1.5x the desired size
+        moderate_appetite: u8 [],
+        /// Risk appetite above which max notional may be 2x the desired size
+        aggressive_appetite: u8 [],
+        /// Win rate the 2x max notional also needs
+        aggressive_win_rate: u16 [unit = "bps", max = 10_000],
+        /// Take-profit distance above the entry price, widened by the volatility
+        take_profit_bps: u16 [unit = "bps"],
+        /// Stop-loss distance below the entry price, widened by half the
+        /// volatility and tightened by a quarter after recent losses
+        stop_loss_bps: u16 [unit = "bps", max = 10_000],
+        /// Exit window as a share of the hold time
+        exit_window_pct: u8 [unit = "percent", max = 100],
+        /// Trades after which the average hold time weighs as much as the
+        /// preferred one, at least 1
+        history_weight_trades: u16 [],
+    }
+    where strategy_policy_bounds
+}
+
+circuit_struct! {
+    /// Thresholds of `confidential_risk_score`, from its policy account
+    pub struct RiskPolicy {
+        /// Exposure ratio (0-255) above which exposure is high
+        high_exposure: u8 [],
+        /// Exposure ratio (0-255) above which the portfolio is over-exposed
+        over_exposure: u8 [],
+        /// Diversification score below which it adds risk
+        low_diversification: u8 [],
+        /// Curve volatility above which trade risk is high
+        high_volatility: u16 [],
+        /// Curve volatility above which trade risk is maximal
+        very_high_volatility: u16 [],
+        /// Liquidity risk above which trade risk is raised
+        high_liquidity_risk: u8 [],
+        /// Max drawdown above which a losing record adds risk
+        poor_drawdown: u16 [unit = "bps", max = 10_000],
+        /// Sharpe ratio (scaled by 100) above which a consistent record lowers risk
+        good_sharpe: i16 [unit = "hundredths"],
+        /// Consistency score the lower risk also needs
+        good_consistency: u8 [],
+        /// Overall risk above which the recommendation is caution
+        caution_risk: u8 [],
+        /// Overall risk above which the recommendation is avoid
+        avoid_risk: u8 [],
+    }
+}
+
+circuit_struct! {
+    /// Thresholds of `confidential_curve_eval`, from its policy account
+    pub struct CurvePolicy {
+        /// Liquidity above this multiple of `max_size` fills the full target
+        plenty_liquidity_multiple: u8 [],
+        /// Share of limited liquidity to use
+        liquidity_use_pct: u8 [unit = "percent", max = 100],
+        /// 24h change beyond which the entry price moves by 1%
+        momentum_bps: u16 [unit = "bps"],
+        /// Ratio of buy to sell pressure (or back) that sets the urgency
+        pressure_ratio: u8 [],
+        /// Urgency above which timing is capped at `high_urgency_max_timing_sec`
+        high_urgency: u8 [],
+        /// Urgency above which timing is capped at `urgent_max_timing_sec`
+        urgent: u8 [],
+        /// Longest timing at high urgency
+        high_urgency_max_timing_sec: u32 [unit = "seconds"],
+        /// Longest timing when urgent
+        urgent_max_timing_sec: u32 [unit = "seconds"],
+        /// Liquidity above this multiple of the size gives full confidence
+        confident_liquidity_multiple: u8 [],
+        /// 24h change within which the price is stable
+        stable_bps: u16 [unit = "bps"],
+        /// 24h change within which the movement is moderate
+        moderate_bps: u16 [unit = "bps"],
+    }
+}
+
+circuit_struct! {
+    /// Thresholds of `evalys_gmpc_strategy` and `evalys_gmpc_strategy_quorum`,
+    /// from their shared policy account
+    pub struct GmpcPolicy {
+        /// Share of the max size planned at risk level 0
+        low_risk_size_pct: u8 [unit = "percent", max = 100],
+        /// Share of the max size planned at risk level 1
+        normal_risk_size_pct: u8 [unit = "percent", max = 100],
+        /// Volatility score above which the size shrinks and the window widens
+        high_volatility: u16 [unit = "bps", max = 10_000],
+        /// Share of the size kept at high volatility
+        high_volatility_size_pct: u8 [unit = "percent", max = 100],
+        /// Slice count at privacy priority 0, at least 1
+        normal_slices: u8 [max = 32],
+        /// Slice count at privacy priority 1, at least 1
+        stealth_slices: u8 [max = 32],
+        /// Slice count at privacy priority 2, at least 1
+        max_privacy_slices: u8 [max = 32],
+        /// Time window at normal volatility
+        calm_window_sec: u32 [unit = "seconds", max = 86_400],
+        /// Time window at high volatility
+        volatile_window_sec: u32 [unit = "seconds", max = 86_400],
+        /// Volatility score below which risk level 0 is low risk
+        low_risk_volatility: u16 [unit = "bps", max = 10_000],
+        /// Volatility score above which any intent is high risk
+        high_risk_volatility: u16 [unit = "bps", max = 10_000],
+    }
+    where gmpc_policy_bounds
+}
+
+fn at_least_one(
+    input: &'static str,
+    field: &'static str,
+    value: impl Into<u64>,
+) -> Result<(), ClientError> {
+    if value.into() == 0 {
+        return Err(ClientError::BelowMinimum {
+            input,
+            field,
+            value: 0,
+            min: 1,
+        });
+    }
+    Ok(())
+}
+
+fn strategy_policy_bounds(policy: &StrategyPolicy) -> Result<(), ClientError> {
+    at_least_one("StrategyPolicy", "min_slices", policy.min_slices)?;
+    at_least_one(
+        "StrategyPolicy",
+        "history_weight_trades",
+        policy.history_weight_trades,
+    )
+}
+
+fn gmpc_policy_bounds(policy: &GmpcPolicy) -> Result<(), ClientError> {
+    at_least_one("GmpcPolicy", "normal_slices", policy.normal_slices)?;
+    at_least_one("GmpcPolicy", "stealth_slices", policy.stealth_slices)?;
+    at_least_one(
+        "GmpcPolicy",
+        "max_privacy_slices",
+        policy.max_privacy_slices,
+    )
+}
+
+/// A policy of `evalys-mxe-reference`, whose `Default` impls hold the one copy
+/// of the thresholds the circuits were tuned with. The types share their
+/// field names and JSON form.
+fn from_reference<T: Serialize, U: de::DeserializeOwned>(policy: T) -> U {
+    serde_json::to_value(policy)
+        .and_then(serde_json::from_value)
+        .expect("reference policy fields match the client's")
+}
+
+/// The thresholds the circuits were tuned with, which `evalys-mxe deploy` stores
+impl Default for StrategyPolicy {
+    fn default() -> Self {
+        from_reference(strategy::StrategyPolicy::default())
+    }
+}
+
+impl StrategyPolicy {
+    pub(crate) fn serialize_into(&self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.stealth_risk.to_le_bytes());
+        data.extend_from_slice(&self.ghost_risk.to_le_bytes());
+        data.extend_from_slice(&self.low_win_rate.to_le_bytes());
+        data.extend_from_slice(&self.medium_volatility.to_le_bytes());
+        data.extend_from_slice(&self.high_volatility.to_le_bytes());
+        data.extend_from_slice(&self.fast_window_sec.to_le_bytes());
+        data.extend_from_slice(&self.medium_window_sec.to_le_bytes());
+        data.extend_from_slice(&self.slow_window_sec.to_le_bytes());
+        data.push(self.min_slices);
+        data.push(self.participation_pct);
+        data.push(self.moderate_appetite);
+        data.push(self.aggressive_appetite);
+        data.extend_from_slice(&self.aggressive_win_rate.to_le_bytes());
+        data.extend_from_slice(&self.take_profit_bps.to_le_bytes());
+        data.extend_from_slice(&self.stop_loss_bps.to_le_bytes());
+        data.push(self.exit_window_pct);
+        data.extend_from_slice(&self.history_weight_trades.to_le_bytes());
+    }
+}
+
+/// The thresholds the circuit was tuned with, which `evalys-mxe deploy` stores
+impl Default for RiskPolicy {
+    fn default() -> Self {
+        from_reference(risk::RiskPolicy::default())
+    }
+}
+
+impl RiskPolicy {
+    pub(crate) fn serialize_into(&self, data: &mut Vec<u8>) {
+        data.push(self.high_exposure);
+        data.push(self.over_exposure);
+        data.push(self.low_diversification);
+        data.extend_from_slice(&self.high_volatility.to_le_bytes());
+        data.extend_from_slice(&self.very_high_volatility.to_le_bytes());
+        data.push(self.high_liquidity_risk);
+        data.extend_from_slice(&self.poor_drawdown.to_le_bytes());
+        data.extend_from_slice(&self.good_sharpe.to_le_bytes());
+        data.push(self.good_consistency);
+        data.push(self.caution_risk);
+        data.push(self.avoid_risk);
+    }
+}
+
+/// The thresholds the circuit was tuned with, which `evalys-mxe deploy` stores
+impl Default for CurvePolicy {
+    fn default() -> Self {
+        from_reference(curve::CurvePolicy::default())
+    }
+}
+
+impl CurvePolicy {
+    pub(crate) fn serialize_into(&self, data: &mut Vec<u8>) {
+        data.push(self.plenty_liquidity_multiple);
+        data.push(self.liquidity_use_pct);
+        data.extend_from_slice(&self.momentum_bps.to_le_bytes());
+        data.push(self.pressure_ratio);
+        data.push(self.high_urgency);
+        data.push(self.urgent);
+        data.extend_from_slice(&self.high_urgency_max_timing_sec.to_le_bytes());
+        data.extend_from_slice(&self.urgent_max_timing_sec.to_le_bytes());
+        data.push(self.confident_liquidity_multiple);
+        data.extend_from_slice(&self.stable_bps.to_le_bytes());
+        data.extend_from_slice(&self.moderate_bps.to_le_bytes());
+    }
+}
+
+/// The thresholds the circuits were tuned with, which `evalys-mxe deploy` stores
+impl Default for GmpcPolicy {
+    fn default() -> Self {
+        from_reference(gmpc::GmpcPolicy::default())
+    }
+}
+
+impl GmpcPolicy {
+    pub(crate) fn serialize_into(&self, data: &mut Vec<u8>) {
+        data.push(self.low_risk_size_pct);
+        data.push(self.normal_risk_size_pct);
+        data.extend_from_slice(&self.high_volatility.to_le_bytes());
+        data.push(self.high_volatility_size_pct);
+        data.push(self.normal_slices);
+        data.push(self.stealth_slices);
+        data.push(self.max_privacy_slices);
+        data.extend_from_slice(&self.calm_window_sec.to_le_bytes());
+        data.extend_from_slice(&self.volatile_window_sec.to_le_bytes());
+        data.extend_from_slice(&self.low_risk_volatility.to_le_bytes());
+        data.extend_from_slice(&self.high_risk_volatility.to_le_bytes());
+    }
+}
+
+/// One `Enc<Shared, T>` circuit input as the program receives it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedInput {
+    pub pub_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: Vec<[u8; 32]>,
+}
+
+impl EncryptedInput {
+    /// Bytes the program folds into the request's input commitment
+    pub fn commitment_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(48 + 32 * self.ciphertexts.len());
+        bytes.extend_from_slice(&self.pub_key);
+        bytes.extend_from_slice(&self.nonce.to_le_bytes());
+        for ciphertext in &self.ciphertexts {
+            bytes.extend_from_slice(ciphertext);
+        }
+        bytes
+    }
+
+    pub(crate) fn serialize_into(&self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.pub_key);
+        data.extend_from_slice(&self.nonce.to_le_bytes());
+        data.extend_from_slice(&(self.ciphertexts.len() as u32).to_le_bytes());
+        for ciphertext in &self.ciphertexts {
+            data.extend_from_slice(ciphertext);
+        }
+    }
+}
+
+/// Input commitment the program records for a request and the circuit echoes
+/// in its output: `sha256(domain || requester || offset || inputs...)`
+pub fn input_commitment(
+    requester: &Pubkey,
+    computation_offset: u64,
+    inputs: &[&EncryptedInput],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(INPUT_COMMITMENT_DOMAIN);
+    hasher.update(requester.as_ref());
+    hasher.update(computation_offset.to_le_bytes());
+    for input in inputs {
+        hasher.update(input.commitment_bytes());
+    }
+    hasher.finalize().into()
+}
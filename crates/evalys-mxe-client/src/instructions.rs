@@ -0,0 +1,509 @@
+//! Instruction builders for the MXE program.
+//!
+//! Account order follows the program's `#[derive(Accounts)]` structs; data is
+//! the Anchor instruction discriminator followed by Borsh-encoded arguments.
+
+use sha2::{Digest, Sha256};
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use crate::arcium::{ArciumAccounts, CompDefAccounts};
+use crate::inputs::{
+    CurveMetrics, CurvePolicy, CurveState, EncryptedInput, GmpcPolicy, MarketConditions,
+    RiskPolicy, StrategyPolicy,
+};
+use crate::pda;
+use crate::state::CircuitKind;
+use crate::ClientError;
+
+/// Most profiles one `request_multi_user_analytics` accepts
+pub const MAX_ANALYTICS_PROFILES: usize = 16;
+
+/// System program id (`11111111111111111111111111111111`)
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// Anchor instruction discriminator: first 8 bytes of `sha256("global:<name>")`
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{name}").as_bytes());
+    hash[..8].try_into().unwrap()
+}
+
+fn instruction_data(name: &str, args: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(8 + args.len());
+    data.extend_from_slice(&instruction_discriminator(name));
+    data.extend_from_slice(args);
+    data
+}
+
+/// `initialize_config`: create the program configuration with the peer
+/// deployment that runs the other quorum leg; `authority` must be the
+/// program's upgrade authority and becomes the config authority
+pub fn initialize_config(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    quorum_peer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(pda::config_address(program_id), false),
+            AccountMeta::new_readonly(*program_id, false),
+            AccountMeta::new_readonly(pda::program_data_address(program_id), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: instruction_data("initialize_config", quorum_peer.as_ref()),
+    }
+}
+
+/// `initialize_audit_log`: create the audit log ring buffer
+pub fn initialize_audit_log(program_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(pda::audit_log_address(program_id), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: instruction_data("initialize_audit_log", &[]),
+    }
+}
+
+/// `set_quorum_peer`: replace the peer deployment of quorums opened from now on
+pub fn set_quorum_peer(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    quorum_peer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pda::config_address(program_id), false),
+        ],
+        data: instruction_data("set_quorum_peer", quorum_peer.as_ref()),
+    }
+}
+
+/// `set_*_policy` of the policy account of `kind`, created on first use and
+/// paid for by the config authority
+fn set_policy(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    kind: CircuitKind,
+    name: &str,
+    policy: &[u8],
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(pda::config_address(program_id), false),
+            AccountMeta::new(pda::policy_address(program_id, kind), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: instruction_data(name, policy),
+    }
+}
+
+/// `set_strategy_policy`: retune `confidential_strategy_plan`
+pub fn set_strategy_policy(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    policy: &StrategyPolicy,
+) -> Result<Instruction, ClientError> {
+    policy.validate()?;
+    let mut args = Vec::new();
+    policy.serialize_into(&mut args);
+    Ok(set_policy(
+        program_id,
+        authority,
+        CircuitKind::StrategyPlan,
+        "set_strategy_policy",
+        &args,
+    ))
+}
+
+/// `set_risk_policy`: retune `confidential_risk_score`
+pub fn set_risk_policy(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    policy: &RiskPolicy,
+) -> Result<Instruction, ClientError> {
+    policy.validate()?;
+    let mut args = Vec::new();
+    policy.serialize_into(&mut args);
+    Ok(set_policy(
+        program_id,
+        authority,
+        CircuitKind::RiskScore,
+        "set_risk_policy",
+        &args,
+    ))
+}
+
+/// `set_curve_policy`: retune `confidential_curve_eval`
+pub fn set_curve_policy(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    policy: &CurvePolicy,
+) -> Result<Instruction, ClientError> {
+    policy.validate()?;
+    let mut args = Vec::new();
+    policy.serialize_into(&mut args);
+    Ok(set_policy(
+        program_id,
+        authority,
+        CircuitKind::CurveEval,
+        "set_curve_policy",
+        &args,
+    ))
+}
+
+/// `set_gmpc_policy`: retune `evalys_gmpc_strategy` and its quorum variant.
+/// Each quorum leg reads its own deployment's policy when queued, so both
+/// deployments of a quorum must be retuned alike or their legs disagree.
+pub fn set_gmpc_policy(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    policy: &GmpcPolicy,
+) -> Result<Instruction, ClientError> {
+    policy.validate()?;
+    let mut args = Vec::new();
+    policy.serialize_into(&mut args);
+    Ok(set_policy(
+        program_id,
+        authority,
+        CircuitKind::GmpcStrategy,
+        "set_gmpc_policy",
+        &args,
+    ))
+}
+
+/// `init_client_key_registry`: create the registry of the signing bridge
+pub fn init_client_key_registry(program_id: &Pubkey, bridge: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*bridge, true),
+            AccountMeta::new(pda::client_keys_address(program_id, bridge), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: instruction_data("init_client_key_registry", &[]),
+    }
+}
+
+/// `rotate_client_key`: register `pub_key` as active and retire the current key
+pub fn rotate_client_key(program_id: &Pubkey, bridge: &Pubkey, pub_key: &[u8; 32]) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*bridge, true),
+            AccountMeta::new(pda::client_keys_address(program_id, bridge), false),
+        ],
+        data: instruction_data("rotate_client_key", pub_key),
+    }
+}
+
+/// `remove_client_key`: drop a retired key from the registry
+pub fn remove_client_key(program_id: &Pubkey, bridge: &Pubkey, key_id: u32) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*bridge, true),
+            AccountMeta::new(pda::client_keys_address(program_id, bridge), false),
+        ],
+        data: instruction_data("remove_client_key", &key_id.to_le_bytes()),
+    }
+}
+
+/// `init_*_comp_def`: create the comp def of `kind` with the off-chain
+/// circuit at `circuit_source` and record its hash and version (at most 32
+/// bytes). Only the config authority can sign it.
+pub fn init_comp_def(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    kind: CircuitKind,
+    circuit_hash: &[u8; 32],
+    version: &str,
+    circuit_source: &str,
+    arcium: &CompDefAccounts,
+) -> Instruction {
+    let mut args = Vec::with_capacity(40 + version.len() + circuit_source.len());
+    args.extend_from_slice(circuit_hash);
+    args.extend_from_slice(&(version.len() as u32).to_le_bytes());
+    args.extend_from_slice(version.as_bytes());
+    args.extend_from_slice(&(circuit_source.len() as u32).to_le_bytes());
+    args.extend_from_slice(circuit_source.as_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(pda::config_address(program_id), false),
+        AccountMeta::new(pda::circuit_registration_address(program_id, kind), false),
+        AccountMeta::new(
+            pda::circuit_version_address(program_id, circuit_hash),
+            false,
+        ),
+    ];
+    accounts.extend(arcium.account_metas());
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction_data(kind.init_instruction(), &args),
+    }
+}
+
+/// `upgrade_circuit`: re-register `kind` with the circuit hash its comp def
+/// now enforces. Only the config authority can sign it.
+pub fn upgrade_circuit(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    kind: CircuitKind,
+    circuit_hash: &[u8; 32],
+    version: &str,
+    comp_def_account: &Pubkey,
+) -> Instruction {
+    let mut args = Vec::with_capacity(37 + version.len());
+    args.push(kind as u8);
+    args.extend_from_slice(circuit_hash);
+    args.extend_from_slice(&(version.len() as u32).to_le_bytes());
+    args.extend_from_slice(version.as_bytes());
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(pda::config_address(program_id), false),
+            AccountMeta::new(pda::circuit_registration_address(program_id, kind), false),
+            AccountMeta::new_readonly(*comp_def_account, false),
+            AccountMeta::new(
+                pda::circuit_version_address(program_id, circuit_hash),
+                false,
+            ),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: instruction_data("upgrade_circuit", &args),
+    }
+}
+
+/// Accounts and offset shared by every `request_*` instruction
+#[derive(Debug, Clone, Copy)]
+pub struct RequestAccounts {
+    /// Requester; pays for the request record and must own the client key registry
+    pub payer: Pubkey,
+    pub computation_offset: u64,
+    pub arcium: ArciumAccounts,
+}
+
+impl RequestAccounts {
+    fn account_metas(
+        &self,
+        program_id: &Pubkey,
+        kind: CircuitKind,
+        quorum_offset: Option<u64>,
+    ) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(self.payer, true),
+            AccountMeta::new_readonly(pda::client_keys_address(program_id, &self.payer), false),
+            AccountMeta::new(
+                pda::computation_request_address(program_id, self.computation_offset),
+                false,
+            ),
+            AccountMeta::new(pda::audit_log_address(program_id), false),
+        ];
+        if let Some(quorum_offset) = quorum_offset {
+            accounts.push(AccountMeta::new(
+                pda::quorum_request_address(program_id, quorum_offset),
+                false,
+            ));
+        }
+        accounts.push(AccountMeta::new_readonly(
+            pda::circuit_registration_address(program_id, kind),
+            false,
+        ));
+        if let Some(policy_kind) = kind.policy_kind() {
+            accounts.push(AccountMeta::new_readonly(
+                pda::policy_address(program_id, policy_kind),
+                false,
+            ));
+        }
+        accounts.extend(self.arcium.account_metas(program_id));
+        accounts
+    }
+
+    fn args(&self, client_key_id: u32) -> Vec<u8> {
+        let mut args = Vec::new();
+        args.extend_from_slice(&self.computation_offset.to_le_bytes());
+        args.extend_from_slice(&client_key_id.to_le_bytes());
+        args
+    }
+}
+
+/// `request_strategy_plan`: queue `confidential_strategy_plan`
+pub fn request_strategy_plan(
+    program_id: &Pubkey,
+    accounts: &RequestAccounts,
+    client_key_id: u32,
+    preferences: &EncryptedInput,
+    history: &EncryptedInput,
+    curve_state: &CurveState,
+) -> Instruction {
+    let mut args = accounts.args(client_key_id);
+    preferences.serialize_into(&mut args);
+    history.serialize_into(&mut args);
+    curve_state.serialize_into(&mut args);
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.account_metas(program_id, CircuitKind::StrategyPlan, None),
+        data: instruction_data("request_strategy_plan", &args),
+    }
+}
+
+/// `request_risk_score`: queue `confidential_risk_score`
+pub fn request_risk_score(
+    program_id: &Pubkey,
+    accounts: &RequestAccounts,
+    client_key_id: u32,
+    portfolio: &EncryptedInput,
+    performance: &EncryptedInput,
+    market: &MarketConditions,
+) -> Instruction {
+    let mut args = accounts.args(client_key_id);
+    portfolio.serialize_into(&mut args);
+    performance.serialize_into(&mut args);
+    market.serialize_into(&mut args);
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.account_metas(program_id, CircuitKind::RiskScore, None),
+        data: instruction_data("request_risk_score", &args),
+    }
+}
+
+/// `request_curve_eval`: queue `confidential_curve_eval`
+pub fn request_curve_eval(
+    program_id: &Pubkey,
+    accounts: &RequestAccounts,
+    client_key_id: u32,
+    sizing: &EncryptedInput,
+    constraints: &EncryptedInput,
+    curve: &CurveMetrics,
+) -> Instruction {
+    let mut args = accounts.args(client_key_id);
+    sizing.serialize_into(&mut args);
+    constraints.serialize_into(&mut args);
+    curve.serialize_into(&mut args);
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.account_metas(program_id, CircuitKind::CurveEval, None),
+        data: instruction_data("request_curve_eval", &args),
+    }
+}
+
+/// `request_gmpc_strategy`: queue `evalys_gmpc_strategy`
+pub fn request_gmpc_strategy(
+    program_id: &Pubkey,
+    accounts: &RequestAccounts,
+    client_key_id: u32,
+    intent: &EncryptedInput,
+) -> Instruction {
+    let mut args = accounts.args(client_key_id);
+    intent.serialize_into(&mut args);
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.account_metas(program_id, CircuitKind::GmpcStrategy, None),
+        data: instruction_data("request_gmpc_strategy", &args),
+    }
+}
+
+/// `request_multi_user_analytics`: queue `confidential_multi_user_analytics`
+/// over 1 to [`MAX_ANALYTICS_PROFILES`] encrypted profiles
+pub fn request_multi_user_analytics(
+    program_id: &Pubkey,
+    accounts: &RequestAccounts,
+    client_key_id: u32,
+    profiles: &[EncryptedInput],
+    aggregation_type: u8,
+) -> Result<Instruction, ClientError> {
+    if profiles.is_empty() || profiles.len() > MAX_ANALYTICS_PROFILES {
+        return Err(ClientError::InvalidProfileCount(profiles.len()));
+    }
+    let mut args = accounts.args(client_key_id);
+    args.extend_from_slice(&(profiles.len() as u32).to_le_bytes());
+    for profile in profiles {
+        profile.serialize_into(&mut args);
+    }
+    args.push(aggregation_type);
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: accounts.account_metas(program_id, CircuitKind::MultiUserAnalytics, None),
+        data: instruction_data("request_multi_user_analytics", &args),
+    })
+}
+
+/// `open_gmpc_quorum`: create this deployment's side of a quorum. Send it
+/// together with the peer deployment's `open_gmpc_quorum` of the same offset.
+pub fn open_gmpc_quorum(program_id: &Pubkey, payer: &Pubkey, quorum_offset: u64) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(pda::config_address(program_id), false),
+            AccountMeta::new(
+                pda::quorum_request_address(program_id, quorum_offset),
+                false,
+            ),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: instruction_data("open_gmpc_quorum", &quorum_offset.to_le_bytes()),
+    }
+}
+
+/// `request_gmpc_strategy_quorum`: queue this deployment's leg of a quorum on
+/// its own cluster, with `intent` and `salt` encrypted to its MXE key
+pub fn request_gmpc_strategy_quorum(
+    program_id: &Pubkey,
+    accounts: &RequestAccounts,
+    quorum_offset: u64,
+    client_key_id: u32,
+    intent: &EncryptedInput,
+    salt: &EncryptedInput,
+) -> Instruction {
+    let mut args = Vec::new();
+    args.extend_from_slice(&accounts.computation_offset.to_le_bytes());
+    args.extend_from_slice(&quorum_offset.to_le_bytes());
+    args.extend_from_slice(&client_key_id.to_le_bytes());
+    intent.serialize_into(&mut args);
+    salt.serialize_into(&mut args);
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.account_metas(
+            program_id,
+            CircuitKind::GmpcStrategyQuorum,
+            Some(quorum_offset),
+        ),
+        data: instruction_data("request_gmpc_strategy_quorum", &args),
+    }
+}
+
+/// `settle_gmpc_quorum`: settle this deployment's side of a quorum against the
+/// record `peer_program_id` keeps, once the peer's leg has reported
+pub fn settle_gmpc_quorum(
+    program_id: &Pubkey,
+    peer_program_id: &Pubkey,
+    quorum_offset: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(
+                pda::quorum_request_address(program_id, quorum_offset),
+                false,
+            ),
+            AccountMeta::new_readonly(
+                pda::quorum_request_address(peer_program_id, quorum_offset),
+                false,
+            ),
+        ],
+        data: instruction_data("settle_gmpc_quorum", &quorum_offset.to_le_bytes()),
+    }
+}
@@ -0,0 +1,422 @@
+//! Local store of the bridge's x25519 client keys.
+//!
+//! Results are encrypted to the client key a request was submitted with, so a
+//! rotated-out key must stay decryptable until every request that used it has
+//! been answered. The keystore keeps secrets by on-chain key id and follows the
+//! registry through a rotation:
+//!
+//! 1. [`Keystore::stage_rotation`] generates a key for the registry's
+//!    `next_key_id`; send [`rotate_client_key`] with its public key.
+//! 2. [`Keystore::confirm_rotation`] checks the registry now lists it as
+//!    active and marks older local keys retired. New requests use the new key;
+//!    in-flight results still decrypt with the retired one.
+//! 3. [`retired_keys_in_use`] reports which retired keys still have pending
+//!    requests. Send [`remove_client_key`] for the others, then
+//!    [`Keystore::prune`] drops secrets the registry no longer lists.
+//!
+//! [`rotate_client_key`]: crate::instructions::rotate_client_key
+//! [`remove_client_key`]: crate::instructions::remove_client_key
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::state::{ClientKeyRegistry, ClientKeyStatus, ComputationRequest, RequestStatus};
+use crate::ClientError;
+
+/// Local state of a client key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalKeyStatus {
+    /// Generated, rotation transaction not yet confirmed
+    Staged,
+    Active,
+    Retired,
+}
+
+/// One x25519 key pair, identified by its on-chain key id
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StoredKey {
+    pub key_id: u32,
+    #[serde(with = "hex::serde")]
+    secret_key: [u8; 32],
+    #[serde(with = "hex::serde")]
+    pub public_key: [u8; 32],
+    pub status: LocalKeyStatus,
+}
+
+impl StoredKey {
+    fn generate(key_id: u32) -> Self {
+        let secret = StaticSecret::random_from_rng(OsRng);
+        StoredKey {
+            key_id,
+            public_key: PublicKey::from(&secret).to_bytes(),
+            secret_key: secret.to_bytes(),
+            status: LocalKeyStatus::Staged,
+        }
+    }
+
+    /// x25519 secret for deriving the shared secret with the MXE
+    pub fn secret(&self) -> StaticSecret {
+        StaticSecret::from(self.secret_key)
+    }
+}
+
+impl std::fmt::Debug for StoredKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoredKey")
+            .field("key_id", &self.key_id)
+            .field("public_key", &hex::encode(self.public_key))
+            .field("status", &self.status)
+            .finish_non_exhaustive()
+    }
+}
+
+/// JSON keystore file holding all client keys of one bridge
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Keystore {
+    keys: Vec<StoredKey>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Keystore {
+    /// Open a keystore file, or start an empty one if it does not exist yet
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ClientError> {
+        let path = path.as_ref();
+        let mut store = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice::<Keystore>(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Keystore::default(),
+            Err(e) => return Err(e.into()),
+        };
+        store.path = Some(path.to_path_buf());
+        Ok(store)
+    }
+
+    /// Write the keystore back to its file (owner read/write only on Unix)
+    ///
+    /// The secrets go to a temporary file next to it, created with mode 0600,
+    /// which then replaces the keystore, so a crash mid-write never leaves a
+    /// truncated keystore and the secrets are never readable by others.
+    pub fn save(&self) -> Result<(), ClientError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut tmp = path.clone().into_os_string();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp)?;
+        // `mode` only applies when the file is created
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(&serde_json::to_vec_pretty(self)?)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn keys(&self) -> &[StoredKey] {
+        &self.keys
+    }
+
+    pub fn get(&self, key_id: u32) -> Result<&StoredKey, ClientError> {
+        self.keys
+            .iter()
+            .find(|k| k.key_id == key_id)
+            .ok_or(ClientError::UnknownClientKey(key_id))
+    }
+
+    /// Key new requests must be encrypted with
+    pub fn active(&self) -> Option<&StoredKey> {
+        self.keys.iter().find(|k| k.status == LocalKeyStatus::Active)
+    }
+
+    /// Generate the next key for `registry` and return it for the rotation transaction
+    ///
+    /// Any earlier staged key that was never confirmed is discarded.
+    pub fn stage_rotation(&mut self, registry: &ClientKeyRegistry) -> Result<&StoredKey, ClientError> {
+        self.keys.retain(|k| k.status != LocalKeyStatus::Staged);
+        self.keys.push(StoredKey::generate(registry.next_key_id));
+        self.save()?;
+        Ok(self.keys.last().unwrap())
+    }
+
+    /// Activate the staged key once `registry` shows the rotation landed
+    pub fn confirm_rotation(&mut self, registry: &ClientKeyRegistry) -> Result<u32, ClientError> {
+        let staged = self
+            .keys
+            .iter()
+            .find(|k| k.status == LocalKeyStatus::Staged)
+            .ok_or(ClientError::NoStagedRotation)?;
+        let key_id = staged.key_id;
+        let confirmed = registry.get(key_id).is_some_and(|k| {
+            k.status == ClientKeyStatus::Active && k.pub_key == staged.public_key
+        });
+        if !confirmed {
+            return Err(ClientError::RotationNotConfirmed(key_id));
+        }
+
+        for key in &mut self.keys {
+            key.status = if key.key_id == key_id {
+                LocalKeyStatus::Active
+            } else {
+                LocalKeyStatus::Retired
+            };
+        }
+        self.save()?;
+        Ok(key_id)
+    }
+
+    /// Drop retired secrets that `registry` no longer lists
+    pub fn prune(&mut self, registry: &ClientKeyRegistry) -> Result<Vec<u32>, ClientError> {
+        let mut removed = Vec::new();
+        self.keys.retain(|k| {
+            let keep = k.status != LocalKeyStatus::Retired || registry.get(k.key_id).is_some();
+            if !keep {
+                removed.push(k.key_id);
+            }
+            keep
+        });
+        self.save()?;
+        Ok(removed)
+    }
+}
+
+/// Retired key ids of `registry` that pending `requests` were encrypted with
+///
+/// Only retired keys outside this set are safe to remove.
+pub fn retired_keys_in_use(
+    registry: &ClientKeyRegistry,
+    requests: &[ComputationRequest],
+) -> BTreeSet<u32> {
+    let retired: BTreeSet<u32> = registry
+        .keys
+        .iter()
+        .filter(|k| k.status == ClientKeyStatus::Retired)
+        .map(|k| k.key_id)
+        .collect();
+    requests
+        .iter()
+        .filter(|r| r.requester == registry.bridge && r.status == RequestStatus::Pending)
+        .map(|r| r.client_key_id)
+        .filter(|id| retired.contains(id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_pubkey::Pubkey;
+
+    use super::*;
+    use crate::state::{CircuitKind, ClientKey};
+
+    fn registry(next_key_id: u32, keys: &[(u32, [u8; 32], ClientKeyStatus)]) -> ClientKeyRegistry {
+        ClientKeyRegistry {
+            bridge: Pubkey::new_from_array([1; 32]),
+            next_key_id,
+            keys: keys
+                .iter()
+                .map(|&(key_id, pub_key, status)| ClientKey {
+                    key_id,
+                    pub_key,
+                    status,
+                    registered_slot: 0,
+                    retired_slot: 0,
+                })
+                .collect(),
+        }
+    }
+
+    fn request(
+        requester: [u8; 32],
+        client_key_id: u32,
+        status: RequestStatus,
+    ) -> ComputationRequest {
+        ComputationRequest {
+            requester: Pubkey::new_from_array(requester),
+            kind: CircuitKind::GmpcStrategy,
+            computation_offset: 0,
+            input_commitment: [0; 32],
+            circuit_hash: [0; 32],
+            client_key_id,
+            status,
+            requested_slot: 0,
+            completed_slot: 0,
+        }
+    }
+
+    /// Keystore with key 0 active, as after a confirmed first rotation
+    fn rotated_once() -> Keystore {
+        let mut store = Keystore::default();
+        let key = store.stage_rotation(&registry(0, &[])).unwrap().public_key;
+        store
+            .confirm_rotation(&registry(1, &[(0, key, ClientKeyStatus::Active)]))
+            .unwrap();
+        store
+    }
+
+    #[test]
+    fn stage_rotation_replaces_an_unconfirmed_key() {
+        let mut store = rotated_once();
+        let first = store.stage_rotation(&registry(1, &[])).unwrap().public_key;
+        let second = store.stage_rotation(&registry(1, &[])).unwrap();
+        assert_eq!(second.key_id, 1);
+        assert_eq!(second.status, LocalKeyStatus::Staged);
+        assert_ne!(second.public_key, first);
+
+        let staged: Vec<u32> = store
+            .keys()
+            .iter()
+            .filter(|k| k.status == LocalKeyStatus::Staged)
+            .map(|k| k.key_id)
+            .collect();
+        assert_eq!(staged, [1]);
+        assert_eq!(store.active().unwrap().key_id, 0);
+    }
+
+    #[test]
+    fn confirm_rotation_needs_the_staged_key_active_on_chain() {
+        let mut store = rotated_once();
+        assert!(matches!(
+            store.confirm_rotation(&registry(1, &[])),
+            Err(ClientError::NoStagedRotation)
+        ));
+
+        let old = store.get(0).unwrap().public_key;
+        let staged = store.stage_rotation(&registry(1, &[])).unwrap().public_key;
+
+        // Rotation not landed yet, or landed with another key
+        for keys in [
+            &[(0, old, ClientKeyStatus::Active)][..],
+            &[
+                (0, old, ClientKeyStatus::Retired),
+                (1, [7; 32], ClientKeyStatus::Active),
+            ],
+        ] {
+            assert!(matches!(
+                store.confirm_rotation(&registry(2, keys)),
+                Err(ClientError::RotationNotConfirmed(1))
+            ));
+        }
+        assert_eq!(store.active().unwrap().key_id, 0);
+
+        let landed = registry(
+            2,
+            &[
+                (0, old, ClientKeyStatus::Retired),
+                (1, staged, ClientKeyStatus::Active),
+            ],
+        );
+        assert_eq!(store.confirm_rotation(&landed).unwrap(), 1);
+        assert_eq!(store.active().unwrap().key_id, 1);
+        assert_eq!(store.get(0).unwrap().status, LocalKeyStatus::Retired);
+    }
+
+    #[test]
+    fn prune_drops_only_retired_keys_the_registry_removed() {
+        let mut store = rotated_once();
+        let old = store.get(0).unwrap().public_key;
+        let staged = store.stage_rotation(&registry(1, &[])).unwrap().public_key;
+        store
+            .confirm_rotation(&registry(
+                2,
+                &[
+                    (0, old, ClientKeyStatus::Retired),
+                    (1, staged, ClientKeyStatus::Active),
+                ],
+            ))
+            .unwrap();
+
+        let still_listed = registry(
+            2,
+            &[
+                (0, old, ClientKeyStatus::Retired),
+                (1, staged, ClientKeyStatus::Active),
+            ],
+        );
+        assert!(store.prune(&still_listed).unwrap().is_empty());
+        assert_eq!(store.keys().len(), 2);
+
+        // A staged key is kept although the registry does not list it yet
+        store.stage_rotation(&registry(2, &[])).unwrap();
+        let removed = registry(3, &[(1, staged, ClientKeyStatus::Active)]);
+        assert_eq!(store.prune(&removed).unwrap(), [0]);
+        let ids: Vec<u32> = store.keys().iter().map(|k| k.key_id).collect();
+        assert_eq!(ids, [1, 2]);
+        assert!(matches!(
+            store.get(0),
+            Err(ClientError::UnknownClientKey(0))
+        ));
+    }
+
+    #[test]
+    fn retired_keys_in_use_counts_pending_requests_of_the_bridge() {
+        let registry = registry(
+            4,
+            &[
+                (0, [0; 32], ClientKeyStatus::Retired),
+                (1, [1; 32], ClientKeyStatus::Retired),
+                (2, [2; 32], ClientKeyStatus::Retired),
+                (3, [3; 32], ClientKeyStatus::Active),
+            ],
+        );
+        let requests = [
+            request([1; 32], 0, RequestStatus::Pending),
+            // Answered already
+            request([1; 32], 1, RequestStatus::Completed),
+            request([1; 32], 1, RequestStatus::Failed),
+            // Another bridge's request
+            request([9; 32], 2, RequestStatus::Pending),
+            // Active key
+            request([1; 32], 3, RequestStatus::Pending),
+        ];
+        assert_eq!(
+            retired_keys_in_use(&registry, &requests),
+            BTreeSet::from([0])
+        );
+    }
+
+    #[test]
+    fn save_writes_an_owner_only_file_and_reopens() {
+        let dir = std::env::temp_dir().join(format!("evalys-keystore-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("client-keys.json");
+
+        let mut store = Keystore::open(&path).unwrap();
+        let public_key = store.stage_rotation(&registry(0, &[])).unwrap().public_key;
+        assert!(!dir.join("client-keys.json.tmp").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let reopened = Keystore::open(&path).unwrap();
+        assert_eq!(reopened.keys().len(), 1);
+        assert_eq!(reopened.get(0).unwrap().public_key, public_key);
+        assert_eq!(
+            reopened.get(0).unwrap().secret().to_bytes(),
+            store.get(0).unwrap().secret().to_bytes()
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,31 @@
+//! Rust client for the Evalys Arcium gMPC MXE.
+//!
+//! Mirrors the account layouts, PDA seeds and enums of the
+//! `evalys-arcium-gmpc-mxe` program so off-chain services can read its state
+//! without the Anchor toolchain, and builds encrypted requests:
+//!
+//! 1. Fill a circuit input with its builder, e.g. [`inputs::IntentInput::builder`].
+//! 2. Encrypt it with a [`cipher::ClientCipher`] for the active client key and
+//!    the MXE public key.
+//! 3. Pass the [`inputs::EncryptedInput`] to the matching `request_*` builder
+//!    in [`instructions`].
+//! 4. Decode the callback's [`events::ResultEvent`] and decrypt it into the
+//!    typed output from [`outputs`].
+
+pub mod arcium;
+pub mod audit;
+pub mod cipher;
+mod error;
+pub mod events;
+pub mod inputs;
+pub mod instructions;
+pub mod keys;
+pub mod manifest;
+pub mod outputs;
+pub mod pda;
+pub mod rpc;
+pub mod schema;
+pub mod state;
+
+pub use error::ClientError;
+pub use solana_pubkey::Pubkey;
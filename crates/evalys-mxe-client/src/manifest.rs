@@ -0,0 +1,179 @@
+//! Deployment manifest: where the MXE lives, which Arcium cluster runs it and
+//! which circuits it has registered.
+//!
+//! `evalys-mxe deploy` writes it once the program is deployed and every comp
+//! def is initialized and verified; the CLI, the SDK and the bridge read it.
+//!
+//! ```json
+//! {
+//!   "network": "devnet",
+//!   "rpc_url": "https://api.devnet.solana.com",
+//!   "program_id": "<MXE program id>",
+//!   "arcium_program": "<Arcium program id>",
+//!   "cluster_offset": 1078779259,
+//!   "fee_pool": "<Arcium fee pool account>",
+//!   "clock_account": "<Arcium clock account>",
+//!   "mxe_x25519_pubkey": "<hex of the MXE x25519 public key>",
+//!   "quorum_peer": {
+//!     "program_id": "<peer MXE program id>",
+//!     "cluster_offset": 1078779260,
+//!     "mxe_x25519_pubkey": "<hex of the peer MXE x25519 public key>"
+//!   },
+//!   "comp_defs": [
+//!     {
+//!       "encrypted_ix": "confidential_strategy_plan",
+//!       "comp_def_offset": 1234567890,
+//!       "comp_def_account": "<Arcium comp def PDA>",
+//!       "registration": "<CircuitRegistration PDA>",
+//!       "circuit_hash": "<hex of sha256 of the compiled circuit>",
+//!       "version": "v0.1.0"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `mxe_x25519_pubkey` is known only once the cluster has generated the MXE
+//! key, and is needed to encrypt requests. `quorum_peer` is only needed to
+//! submit quorum requests: it is the second deployment of the program, bound
+//! to another cluster with its own MXE key, that the config names as its peer
+//! (`set_quorum_peer`). A hand-written deployment file without `network` and
+//! `comp_defs` is a valid manifest.
+
+use serde::{Deserialize, Serialize};
+use solana_pubkey::Pubkey;
+
+use crate::arcium::ArciumDeployment;
+use crate::state::CircuitKind;
+
+/// Deployment manifest of one MXE
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Cluster name, e.g. `devnet`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    pub rpc_url: String,
+    #[serde(with = "pubkey")]
+    pub program_id: Pubkey,
+    #[serde(with = "pubkey")]
+    pub arcium_program: Pubkey,
+    pub cluster_offset: u32,
+    /// Arcium fee pool account
+    #[serde(with = "pubkey")]
+    pub fee_pool: Pubkey,
+    /// Arcium clock account
+    #[serde(with = "pubkey")]
+    pub clock_account: Pubkey,
+    #[serde(
+        default,
+        with = "optional_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mxe_x25519_pubkey: Option<[u8; 32]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum_peer: Option<QuorumPeer>,
+    /// Registered comp defs, in [`CircuitKind::ALL`] order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comp_defs: Vec<CompDefRecord>,
+}
+
+impl Manifest {
+    pub fn arcium(&self) -> ArciumDeployment {
+        ArciumDeployment {
+            arcium_program: self.arcium_program,
+            cluster_offset: self.cluster_offset,
+            pool_account: self.fee_pool,
+            clock_account: self.clock_account,
+        }
+    }
+
+    /// Arcium accounts of the quorum peer, which shares this deployment's
+    /// Arcium program, fee pool and clock
+    pub fn peer_arcium(&self, peer: &QuorumPeer) -> ArciumDeployment {
+        ArciumDeployment {
+            cluster_offset: peer.cluster_offset,
+            ..self.arcium()
+        }
+    }
+
+    /// Recorded comp def of `kind`
+    pub fn comp_def(&self, kind: CircuitKind) -> Option<&CompDefRecord> {
+        self.comp_defs
+            .iter()
+            .find(|comp_def| comp_def.encrypted_ix == kind.encrypted_ix())
+    }
+}
+
+/// Deployment of the program that runs the other leg of every quorum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuorumPeer {
+    #[serde(with = "pubkey")]
+    pub program_id: Pubkey,
+    pub cluster_offset: u32,
+    #[serde(
+        default,
+        with = "optional_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mxe_x25519_pubkey: Option<[u8; 32]>,
+}
+
+/// One initialized comp def and the circuit registered with it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompDefRecord {
+    pub encrypted_ix: String,
+    pub comp_def_offset: u32,
+    /// Arcium computation definition account
+    #[serde(with = "pubkey")]
+    pub comp_def_account: Pubkey,
+    /// The program's `CircuitRegistration` account
+    #[serde(with = "pubkey")]
+    pub registration: Pubkey,
+    #[serde(with = "hex::serde")]
+    pub circuit_hash: [u8; 32],
+    pub version: String,
+}
+
+/// Pubkeys as base58 strings
+mod pubkey {
+    use std::str::FromStr;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use solana_pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(key)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Pubkey::from_str(&value)
+            .map_err(|_| de::Error::custom(format!("not a valid pubkey: {value}")))
+    }
+}
+
+/// An optional 32-byte key as hex
+mod optional_key {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        key: &Option<[u8; 32]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match key {
+            Some(key) => hex::serde::serialize(key, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<[u8; 32]>, D::Error> {
+        #[derive(Deserialize)]
+        struct Key(#[serde(with = "hex::serde")] [u8; 32]);
+
+        Ok(Option::<Key>::deserialize(deserializer)?.map(|Key(key)| key))
+    }
+}
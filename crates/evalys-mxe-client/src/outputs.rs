@@ -0,0 +1,568 @@
+//! Decrypted circuit outputs.
+//!
+//! Each output mirrors a struct in `encrypted-ixs/` field for field, in
+//! declaration order, so the ciphertext array of a result event decodes
+//! without hand-rolled offsets. Code fields keep their raw `u8` and expose an
+//! enum view; an out-of-range value is reported rather than guessed. The
+//! per-slice arrays of [`StrategyPlan`] arrive packed into u128 words and are
+//! unpacked here.
+
+use std::fmt;
+
+use num_bigint::BigUint;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ClientError;
+
+/// Privacy mode of a strategy plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum PrivacyMode {
+    Normal = 0,
+    Stealth = 1,
+    MaxGhost = 2,
+}
+
+impl TryFrom<u8> for PrivacyMode {
+    type Error = ClientError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PrivacyMode::Normal),
+            1 => Ok(PrivacyMode::Stealth),
+            2 => Ok(PrivacyMode::MaxGhost),
+            other => Err(ClientError::UnknownPrivacyMode(other)),
+        }
+    }
+}
+
+/// Order routing of a gMPC plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum MevRoute {
+    Standard = 0,
+    JitoBundle = 1,
+    PrivateRoute = 2,
+}
+
+impl TryFrom<u8> for MevRoute {
+    type Error = ClientError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MevRoute::Standard),
+            1 => Ok(MevRoute::JitoBundle),
+            2 => Ok(MevRoute::PrivateRoute),
+            other => Err(ClientError::UnknownMevRoute(other)),
+        }
+    }
+}
+
+/// Verdict of a risk assessment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum Recommendation {
+    Proceed = 0,
+    Caution = 1,
+    Avoid = 2,
+}
+
+impl TryFrom<u8> for Recommendation {
+    type Error = ClientError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Recommendation::Proceed),
+            1 => Ok(Recommendation::Caution),
+            2 => Ok(Recommendation::Avoid),
+            other => Err(ClientError::UnknownRecommendation(other)),
+        }
+    }
+}
+
+/// Risk class of a gMPC plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum RiskClass {
+    Low = 0,
+    Balanced = 1,
+    High = 2,
+}
+
+impl TryFrom<u8> for RiskClass {
+    type Error = ClientError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RiskClass::Low),
+            1 => Ok(RiskClass::Balanced),
+            2 => Ok(RiskClass::High),
+            other => Err(ClientError::UnknownRiskClass(other)),
+        }
+    }
+}
+
+/// Commitment to the request's encrypted inputs, echoed by every circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputCommitment {
+    pub hi: u128,
+    pub lo: u128,
+}
+
+impl InputCommitment {
+    /// The 32-byte commitment stored in `ComputationRequest.input_commitment`
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[..16].copy_from_slice(&self.hi.to_be_bytes());
+        bytes[16..].copy_from_slice(&self.lo.to_be_bytes());
+        bytes
+    }
+
+    /// Check the output answers the request whose commitment is `expected`
+    pub fn verify(&self, expected: &[u8; 32]) -> Result<(), ClientError> {
+        if self.to_bytes() == *expected {
+            Ok(())
+        } else {
+            Err(ClientError::CommitmentMismatch)
+        }
+    }
+}
+
+impl fmt::Display for InputCommitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.to_bytes()))
+    }
+}
+
+/// Serialized as the hex of [`InputCommitment::to_bytes`]
+impl Serialize for InputCommitment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InputCommitment {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(&hex, &mut bytes).map_err(de::Error::custom)?;
+        Ok(InputCommitment {
+            hi: u128::from_be_bytes(bytes[..16].try_into().unwrap()),
+            lo: u128::from_be_bytes(bytes[16..].try_into().unwrap()),
+        })
+    }
+}
+
+impl JsonSchema for InputCommitment {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "InputCommitment".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Hex of the 32-byte input commitment",
+            "type": "string",
+            "pattern": "^[0-9a-f]{64}$",
+        })
+    }
+}
+
+/// A struct a circuit returns as `Enc<Shared, Self>`
+pub trait CircuitOutput: Sized {
+    /// Number of scalar fields, and so of ciphertexts
+    const FIELD_COUNT: usize;
+
+    /// Decode field elements in circuit declaration order
+    fn from_field_elements(fields: &[BigUint]) -> Result<Self, ClientError>;
+
+    fn input_commitment(&self) -> InputCommitment;
+}
+
+/// Unsigned circuit integer type decoded from a field element
+trait OutputScalar: Sized + TryFrom<u128> {
+    const BITS: u64;
+}
+
+impl OutputScalar for u8 {
+    const BITS: u64 = 8;
+}
+
+impl OutputScalar for u16 {
+    const BITS: u64 = 16;
+}
+
+impl OutputScalar for u32 {
+    const BITS: u64 = 32;
+}
+
+impl OutputScalar for u64 {
+    const BITS: u64 = 64;
+}
+
+impl OutputScalar for u128 {
+    const BITS: u64 = 128;
+}
+
+/// Sequential reader over decrypted output fields
+struct FieldReader<'a> {
+    output: &'static str,
+    fields: std::slice::Iter<'a, BigUint>,
+}
+
+impl<'a> FieldReader<'a> {
+    fn new(
+        output: &'static str,
+        fields: &'a [BigUint],
+        expected: usize,
+    ) -> Result<Self, ClientError> {
+        if fields.len() != expected {
+            return Err(ClientError::OutputFieldCount {
+                output,
+                expected,
+                actual: fields.len(),
+            });
+        }
+        Ok(FieldReader {
+            output,
+            fields: fields.iter(),
+        })
+    }
+
+    fn next<T: OutputScalar>(&mut self, field: &'static str) -> Result<T, ClientError> {
+        let value = self
+            .fields
+            .next()
+            .expect("field count checked in FieldReader::new");
+        let out_of_range = ClientError::OutputFieldRange {
+            output: self.output,
+            field,
+        };
+        if value.bits() > T::BITS {
+            return Err(out_of_range);
+        }
+        let words = value.to_u64_digits();
+        let value = words
+            .iter()
+            .rev()
+            .fold(0u128, |acc, word| (acc << 64) | u128::from(*word));
+        T::try_from(value).map_err(|_| out_of_range)
+    }
+
+    /// The array `field` of `N` slots packed into u128 words, as many to a
+    /// word as fit, the first in the lowest bits
+    fn packed<T: OutputScalar + Copy + Default, const N: usize>(
+        &mut self,
+        field: &'static str,
+    ) -> Result<[T; N], ClientError> {
+        let per_word = (128 / T::BITS) as usize;
+        let mut values = [T::default(); N];
+        for chunk in values.chunks_mut(per_word) {
+            let mut word: u128 = self.next(field)?;
+            for value in chunk {
+                *value = T::try_from(word & (u128::MAX >> (128 - T::BITS)))
+                    .unwrap_or_else(|_| unreachable!("masked to T::BITS"));
+                word >>= T::BITS;
+            }
+        }
+        Ok(values)
+    }
+
+    fn input_commitment(&mut self) -> Result<InputCommitment, ClientError> {
+        Ok(InputCommitment {
+            hi: self.next("input_commitment.hi")?,
+            lo: self.next("input_commitment.lo")?,
+        })
+    }
+}
+
+/// Slots of a strategy plan's per-slice schedule
+pub const MAX_SLICES: usize = 8;
+
+/// Ciphertexts of a per-slice array of `bits`-bit values, which the circuit
+/// packs into u128 words
+const fn packed_words(bits: usize) -> usize {
+    MAX_SLICES * bits / 128
+}
+
+/// Output of `confidential_strategy_plan`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyPlan {
+    pub recommended_mode: u8,
+    pub num_slices: u8,
+    /// Base slice size in lamports
+    #[schemars(extend("x-unit" = "lamports"))]
+    pub slice_size_base: u64,
+    #[schemars(extend("x-unit" = "seconds"))]
+    pub timing_window_sec: u32,
+    /// 0-255
+    pub risk_level: u8,
+    /// Maximum notional in lamports
+    #[schemars(extend("x-unit" = "lamports"))]
+    pub max_notional: u64,
+    /// Size of each slice in lamports, 0 past `num_slices`; the sizes sum to
+    /// the desired size
+    #[schemars(extend("x-unit" = "lamports"))]
+    pub slice_sizes: [u64; MAX_SLICES],
+    /// Start of each slice after the plan's start, 0 past `num_slices`
+    #[schemars(extend("x-unit" = "seconds"))]
+    pub slice_delays_sec: [u32; MAX_SLICES],
+    /// Start of the exit after the plan's start
+    #[schemars(extend("x-unit" = "seconds"))]
+    pub exit_start_sec: u32,
+    #[schemars(extend("x-unit" = "seconds"))]
+    pub exit_window_sec: u32,
+    /// Share of the position each exit slice sells, 0 past `num_slices`; the
+    /// shares sum to 10000
+    #[schemars(extend("x-unit" = "bps"))]
+    pub exit_slice_bps: [u16; MAX_SLICES],
+    /// Start of each exit slice after `exit_start_sec`, 0 past `num_slices`
+    #[schemars(extend("x-unit" = "seconds"))]
+    pub exit_delays_sec: [u32; MAX_SLICES],
+    /// Price at or above which the whole position is sold
+    pub take_profit_price: u64,
+    /// Price at or below which the whole position is sold
+    pub stop_loss_price: u64,
+    pub input_commitment: InputCommitment,
+}
+
+impl StrategyPlan {
+    pub fn privacy_mode(&self) -> Result<PrivacyMode, ClientError> {
+        PrivacyMode::try_from(self.recommended_mode)
+    }
+
+    /// `(size, delay_sec)` of each scheduled slice, in execution order
+    pub fn slices(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        self.slice_sizes
+            .iter()
+            .copied()
+            .zip(self.slice_delays_sec.iter().copied())
+            .take(self.num_slices as usize)
+    }
+
+    /// `(share_bps, delay_sec)` of each exit slice, in execution order, with
+    /// delays from the plan's start
+    pub fn exit_slices(&self) -> impl Iterator<Item = (u16, u64)> + '_ {
+        let start = u64::from(self.exit_start_sec);
+        self.exit_slice_bps
+            .iter()
+            .copied()
+            .zip(
+                self.exit_delays_sec
+                    .iter()
+                    .map(move |delay| start + u64::from(*delay)),
+            )
+            .take(self.num_slices as usize)
+    }
+}
+
+impl CircuitOutput for StrategyPlan {
+    const FIELD_COUNT: usize =
+        12 + packed_words(64) + packed_words(32) + packed_words(16) + packed_words(32);
+
+    fn from_field_elements(fields: &[BigUint]) -> Result<Self, ClientError> {
+        let mut r = FieldReader::new("StrategyPlan", fields, Self::FIELD_COUNT)?;
+        Ok(StrategyPlan {
+            recommended_mode: r.next("recommended_mode")?,
+            num_slices: r.next("num_slices")?,
+            slice_size_base: r.next("slice_size_base")?,
+            timing_window_sec: r.next("timing_window_sec")?,
+            risk_level: r.next("risk_level")?,
+            max_notional: r.next("max_notional")?,
+            slice_sizes: r.packed("slice_sizes")?,
+            slice_delays_sec: r.packed("slice_delays_sec")?,
+            exit_start_sec: r.next("exit_start_sec")?,
+            exit_window_sec: r.next("exit_window_sec")?,
+            exit_slice_bps: r.packed("exit_slice_bps")?,
+            exit_delays_sec: r.packed("exit_delays_sec")?,
+            take_profit_price: r.next("take_profit_price")?,
+            stop_loss_price: r.next("stop_loss_price")?,
+            input_commitment: r.input_commitment()?,
+        })
+    }
+
+    fn input_commitment(&self) -> InputCommitment {
+        self.input_commitment
+    }
+}
+
+/// Output of `confidential_risk_score`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RiskAssessment {
+    /// 0-255
+    pub overall_risk_score: u8,
+    pub portfolio_risk: u8,
+    pub trade_risk: u8,
+    pub recommendation: u8,
+    pub input_commitment: InputCommitment,
+}
+
+impl RiskAssessment {
+    pub fn recommendation(&self) -> Result<Recommendation, ClientError> {
+        Recommendation::try_from(self.recommendation)
+    }
+}
+
+impl CircuitOutput for RiskAssessment {
+    const FIELD_COUNT: usize = 6;
+
+    fn from_field_elements(fields: &[BigUint]) -> Result<Self, ClientError> {
+        let mut r = FieldReader::new("RiskAssessment", fields, Self::FIELD_COUNT)?;
+        Ok(RiskAssessment {
+            overall_risk_score: r.next("overall_risk_score")?,
+            portfolio_risk: r.next("portfolio_risk")?,
+            trade_risk: r.next("trade_risk")?,
+            recommendation: r.next("recommendation")?,
+            input_commitment: r.input_commitment()?,
+        })
+    }
+
+    fn input_commitment(&self) -> InputCommitment {
+        self.input_commitment
+    }
+}
+
+/// Output of `confidential_curve_eval`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExecutionRecommendation {
+    /// Recommended size in lamports
+    #[schemars(extend("x-unit" = "lamports"))]
+    pub recommended_size: u64,
+    #[schemars(extend("x-unit" = "lamports"))]
+    pub entry_price_target: u64,
+    /// 0-255, higher is more urgent
+    pub execution_urgency: u8,
+    /// Timing window in seconds
+    #[schemars(extend("x-unit" = "seconds"))]
+    pub optimal_timing: u32,
+    /// 0-255
+    pub confidence_score: u8,
+    pub input_commitment: InputCommitment,
+}
+
+impl CircuitOutput for ExecutionRecommendation {
+    const FIELD_COUNT: usize = 7;
+
+    fn from_field_elements(fields: &[BigUint]) -> Result<Self, ClientError> {
+        let mut r = FieldReader::new("ExecutionRecommendation", fields, Self::FIELD_COUNT)?;
+        Ok(ExecutionRecommendation {
+            recommended_size: r.next("recommended_size")?,
+            entry_price_target: r.next("entry_price_target")?,
+            execution_urgency: r.next("execution_urgency")?,
+            optimal_timing: r.next("optimal_timing")?,
+            confidence_score: r.next("confidence_score")?,
+            input_commitment: r.input_commitment()?,
+        })
+    }
+
+    fn input_commitment(&self) -> InputCommitment {
+        self.input_commitment
+    }
+}
+
+/// Output of `evalys_gmpc_strategy` (and of each quorum leg)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PlanOutput {
+    /// Recommended size in lamports
+    #[schemars(extend("x-unit" = "lamports"))]
+    pub recommended_size_sol: u64,
+    pub slice_count: u8,
+    #[schemars(extend("x-unit" = "seconds"))]
+    pub time_window_sec: u32,
+    pub mev_route: u8,
+    pub privacy_mode: u8,
+    pub risk_class: u8,
+    pub input_commitment: InputCommitment,
+}
+
+impl PlanOutput {
+    pub fn mev_route(&self) -> Result<MevRoute, ClientError> {
+        MevRoute::try_from(self.mev_route)
+    }
+
+    pub fn privacy_mode(&self) -> Result<PrivacyMode, ClientError> {
+        PrivacyMode::try_from(self.privacy_mode)
+    }
+
+    pub fn risk_class(&self) -> Result<RiskClass, ClientError> {
+        RiskClass::try_from(self.risk_class)
+    }
+}
+
+impl CircuitOutput for PlanOutput {
+    const FIELD_COUNT: usize = 8;
+
+    fn from_field_elements(fields: &[BigUint]) -> Result<Self, ClientError> {
+        let mut r = FieldReader::new("PlanOutput", fields, Self::FIELD_COUNT)?;
+        Ok(PlanOutput {
+            recommended_size_sol: r.next("recommended_size_sol")?,
+            slice_count: r.next("slice_count")?,
+            time_window_sec: r.next("time_window_sec")?,
+            mev_route: r.next("mev_route")?,
+            privacy_mode: r.next("privacy_mode")?,
+            risk_class: r.next("risk_class")?,
+            input_commitment: r.input_commitment()?,
+        })
+    }
+
+    fn input_commitment(&self) -> InputCommitment {
+        self.input_commitment
+    }
+}
+
+/// Output of `confidential_multi_user_analytics`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AggregatedMetrics {
+    /// Average hold time in seconds
+    #[schemars(extend("x-unit" = "seconds"))]
+    pub avg_hold_time: u32,
+    /// Average win rate in basis points
+    #[schemars(extend("x-unit" = "bps"))]
+    pub avg_win_rate: u16,
+    #[schemars(extend("x-unit" = "lamports"))]
+    pub common_size_range_min: u64,
+    #[schemars(extend("x-unit" = "lamports"))]
+    pub common_size_range_max: u64,
+    /// Profile counts by risk tolerance: `[low, normal, high]`
+    pub risk_distribution: [u16; 3],
+    /// 0-10000
+    #[schemars(extend("x-unit" = "bps"))]
+    pub confidence_score: u16,
+    pub sample_size: u32,
+    pub input_commitment: InputCommitment,
+}
+
+impl CircuitOutput for AggregatedMetrics {
+    const FIELD_COUNT: usize = 11;
+
+    fn from_field_elements(fields: &[BigUint]) -> Result<Self, ClientError> {
+        let mut r = FieldReader::new("AggregatedMetrics", fields, Self::FIELD_COUNT)?;
+        Ok(AggregatedMetrics {
+            avg_hold_time: r.next("avg_hold_time")?,
+            avg_win_rate: r.next("avg_win_rate")?,
+            common_size_range_min: r.next("common_size_range_min")?,
+            common_size_range_max: r.next("common_size_range_max")?,
+            risk_distribution: [
+                r.next("risk_distribution[0]")?,
+                r.next("risk_distribution[1]")?,
+                r.next("risk_distribution[2]")?,
+            ],
+            confidence_score: r.next("confidence_score")?,
+            sample_size: r.next("sample_size")?,
+            input_commitment: r.input_commitment()?,
+        })
+    }
+
+    fn input_commitment(&self) -> InputCommitment {
+        self.input_commitment
+    }
+}
@@ -0,0 +1,65 @@
+//! Program-derived addresses of the MXE program.
+
+use solana_pubkey::Pubkey;
+
+use crate::state::CircuitKind;
+
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const COMPUTATION_REQUEST_SEED: &[u8] = b"computation_request";
+pub const CIRCUIT_SEED: &[u8] = b"circuit";
+pub const CIRCUIT_VERSION_SEED: &[u8] = b"circuit_version";
+pub const AUDIT_LOG_SEED: &[u8] = b"audit_log";
+pub const QUORUM_REQUEST_SEED: &[u8] = b"quorum_request";
+pub const CLIENT_KEYS_SEED: &[u8] = b"client_keys";
+pub const POLICY_SEED: &[u8] = b"policy";
+
+/// BPF upgradeable loader, owner of deployed programs
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+    Pubkey::from_str_const("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// `ProgramData` account holding the program's upgrade authority
+pub fn program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+}
+
+pub fn config_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id).0
+}
+
+pub fn computation_request_address(program_id: &Pubkey, computation_offset: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[COMPUTATION_REQUEST_SEED, &computation_offset.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn circuit_registration_address(program_id: &Pubkey, kind: CircuitKind) -> Pubkey {
+    Pubkey::find_program_address(&[CIRCUIT_SEED, &[kind as u8]], program_id).0
+}
+
+pub fn circuit_version_address(program_id: &Pubkey, circuit_hash: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[CIRCUIT_VERSION_SEED, circuit_hash], program_id).0
+}
+
+pub fn audit_log_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[AUDIT_LOG_SEED], program_id).0
+}
+
+pub fn quorum_request_address(program_id: &Pubkey, quorum_offset: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[QUORUM_REQUEST_SEED, &quorum_offset.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn client_keys_address(program_id: &Pubkey, bridge: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CLIENT_KEYS_SEED, bridge.as_ref()], program_id).0
+}
+
+/// Policy account of `kind`; see [`CircuitKind::policy_kind`] for the kind
+/// a request reads
+pub fn policy_address(program_id: &Pubkey, kind: CircuitKind) -> Pubkey {
+    Pubkey::find_program_address(&[POLICY_SEED, &[kind as u8]], program_id).0
+}
@@ -0,0 +1,302 @@
+//! Minimal Solana JSON-RPC client shared by the CLI, the bridge and the
+//! indexer: account reads, sending transactions and finding the logs of the
+//! program's transactions.
+
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+use solana_transaction::Transaction;
+
+use crate::events::ResultEvent;
+use crate::pda;
+use crate::state::ComputationRequest;
+use crate::ClientError;
+
+const COMMITMENT: &str = "confirmed";
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Largest page `getSignaturesForAddress` returns
+const SIGNATURE_PAGE: usize = 1000;
+/// Transactions touching a request record: its creation and its callback
+const RESULT_SIGNATURE_LIMIT: usize = 20;
+
+/// Signature of a program transaction and whether it succeeded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramSignature {
+    pub signature: String,
+    pub failed: bool,
+}
+
+/// Slot, block time and log messages of a confirmed transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmedTransaction {
+    pub slot: u64,
+    /// Unix seconds, if the node knows it
+    pub block_time: Option<i64>,
+    pub logs: Vec<String>,
+}
+
+fn rpc_error(method: &str, message: impl std::fmt::Display) -> ClientError {
+    ClientError::Rpc(format!("{method}: {message}"))
+}
+
+pub struct Rpc {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl Rpc {
+    pub fn new(url: &str) -> Self {
+        Rpc {
+            url: url.to_string(),
+            agent: ureq::Agent::new_with_defaults(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response = self
+            .agent
+            .post(&self.url)
+            .send_json(&request)
+            .map_err(|err| rpc_error(method, format!("request to {}: {err}", self.url)))?;
+        let mut body: Value = response
+            .body_mut()
+            .read_json()
+            .map_err(|err| rpc_error(method, err))?;
+        if let Some(error) = body.get("error") {
+            return Err(rpc_error(method, error));
+        }
+        Ok(body["result"].take())
+    }
+
+    /// `value` of `getAccountInfo`, null if the account does not exist
+    fn account_info(&self, address: &Pubkey) -> Result<Value, ClientError> {
+        let mut result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": COMMITMENT }]),
+        )?;
+        Ok(result["value"].take())
+    }
+
+    /// Data of an account, or `None` if it does not exist
+    pub fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, ClientError> {
+        let value = self.account_info(address)?;
+        if value.is_null() {
+            return Ok(None);
+        }
+        let data = value["data"][0]
+            .as_str()
+            .ok_or_else(|| rpc_error("getAccountInfo", format!("missing data for {address}")))?;
+        let data = STANDARD
+            .decode(data)
+            .map_err(|err| rpc_error("getAccountInfo", err))?;
+        Ok(Some(data))
+    }
+
+    /// Owner program of an account, or `None` if it does not exist
+    pub fn account_owner(&self, address: &Pubkey) -> Result<Option<Pubkey>, ClientError> {
+        let value = self.account_info(address)?;
+        if value.is_null() {
+            return Ok(None);
+        }
+        let owner = value["owner"]
+            .as_str()
+            .ok_or_else(|| rpc_error("getAccountInfo", format!("missing owner for {address}")))?;
+        let owner = Pubkey::from_str(owner).map_err(|err| rpc_error("getAccountInfo", err))?;
+        Ok(Some(owner))
+    }
+
+    /// Data of an account that must exist
+    pub fn require_account(
+        &self,
+        address: &Pubkey,
+        what: &'static str,
+    ) -> Result<Vec<u8>, ClientError> {
+        self.account_data(address)?
+            .ok_or(ClientError::AccountNotFound(what, *address))
+    }
+
+    /// Request record at `computation_offset`, or `None` if it does not exist
+    pub fn computation_request(
+        &self,
+        program_id: &Pubkey,
+        computation_offset: u64,
+    ) -> Result<Option<ComputationRequest>, ClientError> {
+        let address = pda::computation_request_address(program_id, computation_offset);
+        self.account_data(&address)?
+            .map(|data| ComputationRequest::from_account_data(&data))
+            .transpose()
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash, ClientError> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": COMMITMENT }]))?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| rpc_error("getLatestBlockhash", "missing blockhash"))?;
+        Hash::from_str(blockhash).map_err(|err| rpc_error("getLatestBlockhash", err))
+    }
+
+    /// Send a signed transaction and wait until it is confirmed
+    pub fn send_and_confirm(&self, transaction: &Transaction) -> Result<String, ClientError> {
+        let wire =
+            bincode::serialize(transaction).map_err(|err| rpc_error("sendTransaction", err))?;
+        let signature = self.call(
+            "sendTransaction",
+            json!([STANDARD.encode(wire), { "encoding": "base64", "preflightCommitment": COMMITMENT }]),
+        )?;
+        let signature = signature
+            .as_str()
+            .ok_or_else(|| rpc_error("sendTransaction", "missing signature"))?
+            .to_string();
+
+        let started = Instant::now();
+        while started.elapsed() < CONFIRM_TIMEOUT {
+            let statuses = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &statuses["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(ClientError::Rpc(format!(
+                        "transaction {signature} failed: {}",
+                        status["err"]
+                    )));
+                }
+                let level = status["confirmationStatus"].as_str().unwrap_or_default();
+                if level == "confirmed" || level == "finalized" {
+                    return Ok(signature);
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        Err(ClientError::Rpc(format!(
+            "transaction {signature} not confirmed after {CONFIRM_TIMEOUT:?}"
+        )))
+    }
+
+    /// One page of signatures of transactions touching `address`, newest first
+    fn signature_page(
+        &self,
+        address: &Pubkey,
+        limit: usize,
+        before: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<ProgramSignature>, ClientError> {
+        let mut config = json!({ "limit": limit, "commitment": COMMITMENT });
+        if let Some(until) = until {
+            config["until"] = json!(until);
+        }
+        if let Some(before) = before {
+            config["before"] = json!(before);
+        }
+        let page = self.call(
+            "getSignaturesForAddress",
+            json!([address.to_string(), config]),
+        )?;
+        let page = page
+            .as_array()
+            .ok_or_else(|| rpc_error("getSignaturesForAddress", "expected an array"))?;
+        page.iter()
+            .map(|entry| {
+                let signature = entry["signature"]
+                    .as_str()
+                    .ok_or_else(|| rpc_error("getSignaturesForAddress", "missing signature"))?;
+                Ok(ProgramSignature {
+                    signature: signature.to_string(),
+                    failed: !entry["err"].is_null(),
+                })
+            })
+            .collect()
+    }
+
+    /// Signatures of the latest `limit` transactions touching `address`,
+    /// newest first
+    pub fn signatures_for_address(
+        &self,
+        address: &Pubkey,
+        limit: usize,
+    ) -> Result<Vec<ProgramSignature>, ClientError> {
+        self.signature_page(address, limit, None, None)
+    }
+
+    /// Signatures of every transaction touching `address` after `until`
+    /// (all of them if `None`), oldest first
+    pub fn signatures_since(
+        &self,
+        address: &Pubkey,
+        until: Option<&str>,
+    ) -> Result<Vec<ProgramSignature>, ClientError> {
+        let mut signatures: Vec<ProgramSignature> = Vec::new();
+        loop {
+            let before = signatures.last().map(|s| s.signature.as_str());
+            let page = self.signature_page(address, SIGNATURE_PAGE, before, until)?;
+            let last_page = page.len() < SIGNATURE_PAGE;
+            signatures.extend(page);
+            if last_page {
+                break;
+            }
+        }
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    /// Slot, block time and log messages of a confirmed transaction
+    pub fn transaction(&self, signature: &str) -> Result<ConfirmedTransaction, ClientError> {
+        let result = self.call(
+            "getTransaction",
+            json!([signature, {
+                "encoding": "json",
+                "commitment": COMMITMENT,
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        )?;
+        if result.is_null() {
+            return Err(rpc_error(
+                "getTransaction",
+                format!("transaction {signature} not found"),
+            ));
+        }
+        let slot = result["slot"]
+            .as_u64()
+            .ok_or_else(|| rpc_error("getTransaction", format!("missing slot of {signature}")))?;
+        let logs = result["meta"]["logMessages"]
+            .as_array()
+            .map(|logs| {
+                logs.iter()
+                    .filter_map(|log| log.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(ConfirmedTransaction {
+            slot,
+            block_time: result["blockTime"].as_i64(),
+            logs,
+        })
+    }
+
+    /// Result event the callback of a request logged, found among the
+    /// transactions touching its request record; `None` until the callback
+    /// has landed
+    pub fn result_event(
+        &self,
+        program_id: &Pubkey,
+        computation_offset: u64,
+    ) -> Result<Option<ResultEvent>, ClientError> {
+        let address = pda::computation_request_address(program_id, computation_offset);
+        for entry in self.signatures_for_address(&address, RESULT_SIGNATURE_LIMIT)? {
+            for log in self.transaction(&entry.signature)?.logs {
+                if let Some(event) = ResultEvent::from_log(&log)? {
+                    if event.computation_offset == computation_offset {
+                        return Ok(Some(event));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,133 @@
+//! JSON request files and the published JSON Schema.
+//!
+//! A request file holds the arguments of one circuit, one object per
+//! argument under its circuit name (e.g. `{ "intent": { ... } }` for
+//! `evalys_gmpc_strategy`). The types here, and the inputs and outputs they
+//! are made of, deserialize with range validation: a value the circuit does
+//! not expect, such as a `win_rate` above 10000 bps, fails to parse and never
+//! reaches the cipher.
+//!
+//! [`json_schema`] describes all of them; the published copy lives in
+//! `docs/schema/evalys-mxe.schema.json`. Every integer field carries its unit
+//! as `x-unit` (`lamports`, `bps`, `seconds`, ...) and, where the circuit has
+//! one, its `maximum`.
+
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+
+use crate::inputs::{
+    CurveMetrics, CurvePolicy, CurveState, GmpcPolicy, IntentInput, MarketConditions,
+    PerformanceHistory, PortfolioContext, QuorumSalt, RiskPolicy, SizingPreferences,
+    StrategyPolicy, UserConstraints, UserHistory, UserPreferences, UserProfile,
+};
+use crate::instructions::MAX_ANALYTICS_PROFILES;
+use crate::outputs::{
+    AggregatedMetrics, ExecutionRecommendation, InputCommitment, PlanOutput, RiskAssessment,
+    StrategyPlan,
+};
+use crate::ClientError;
+
+/// Highest `aggregation_type` (`PatternDetection`)
+pub const MAX_AGGREGATION_TYPE: u8 = 2;
+
+/// Request file of `confidential_strategy_plan`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyPlanInputs {
+    pub preferences: UserPreferences,
+    pub history: UserHistory,
+    pub curve_state: CurveState,
+}
+
+/// Request file of `confidential_risk_score`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RiskScoreInputs {
+    pub portfolio: PortfolioContext,
+    pub performance: PerformanceHistory,
+    pub market: MarketConditions,
+}
+
+/// Request file of `confidential_curve_eval`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CurveEvalInputs {
+    pub sizing: SizingPreferences,
+    pub constraints: UserConstraints,
+    pub curve: CurveMetrics,
+}
+
+/// Request file of `evalys_gmpc_strategy` and `evalys_gmpc_strategy_quorum`
+/// (the quorum salt is drawn at submission)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GmpcStrategyInputs {
+    pub intent: IntentInput,
+}
+
+/// Request file of `confidential_multi_user_analytics`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MultiUserAnalyticsInputs {
+    /// 1 to 16 profiles
+    #[serde(deserialize_with = "profiles")]
+    #[schemars(length(min = 1, max = 16))]
+    pub profiles: Vec<UserProfile>,
+    /// 0 = curve insights, 1 = market sentiment, 2 = pattern detection
+    #[serde(deserialize_with = "aggregation_type")]
+    #[schemars(range(max = 2))]
+    pub aggregation_type: u8,
+}
+
+fn profiles<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<UserProfile>, D::Error> {
+    let profiles = Vec::<UserProfile>::deserialize(deserializer)?;
+    if profiles.is_empty() || profiles.len() > MAX_ANALYTICS_PROFILES {
+        return Err(de::Error::custom(ClientError::InvalidProfileCount(
+            profiles.len(),
+        )));
+    }
+    Ok(profiles)
+}
+
+fn aggregation_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    let value = u8::deserialize(deserializer)?;
+    if value > MAX_AGGREGATION_TYPE {
+        return Err(de::Error::custom(ClientError::OutOfRange {
+            input: "MultiUserAnalyticsInputs",
+            field: "aggregation_type",
+            value: value.into(),
+            max: MAX_AGGREGATION_TYPE.into(),
+        }));
+    }
+    Ok(value)
+}
+
+/// JSON Schema (draft 2020-12) of every request file, circuit input, policy
+/// and circuit output, each under `$defs/<TypeName>`
+pub fn json_schema() -> Value {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+    generator.subschema_for::<StrategyPlanInputs>();
+    generator.subschema_for::<RiskScoreInputs>();
+    generator.subschema_for::<CurveEvalInputs>();
+    generator.subschema_for::<GmpcStrategyInputs>();
+    generator.subschema_for::<MultiUserAnalyticsInputs>();
+    generator.subschema_for::<QuorumSalt>();
+    generator.subschema_for::<StrategyPolicy>();
+    generator.subschema_for::<RiskPolicy>();
+    generator.subschema_for::<CurvePolicy>();
+    generator.subschema_for::<GmpcPolicy>();
+    generator.subschema_for::<StrategyPlan>();
+    generator.subschema_for::<RiskAssessment>();
+    generator.subschema_for::<ExecutionRecommendation>();
+    generator.subschema_for::<PlanOutput>();
+    generator.subschema_for::<AggregatedMetrics>();
+    generator.subschema_for::<InputCommitment>();
+    let definitions = generator.take_definitions(true);
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Evalys MXE circuit inputs and outputs",
+        "$defs": definitions,
+    })
+}
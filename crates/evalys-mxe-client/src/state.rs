@@ -0,0 +1,552 @@
+//! Enums and account helpers shared with the on-chain program.
+
+use sha2::{Digest, Sha256};
+use solana_pubkey::Pubkey;
+
+use crate::inputs::{CurvePolicy, GmpcPolicy, RiskPolicy, StrategyPolicy};
+use crate::ClientError;
+
+/// Encrypted instruction a request was queued for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum CircuitKind {
+    StrategyPlan = 0,
+    RiskScore = 1,
+    CurveEval = 2,
+    GmpcStrategy = 3,
+    MultiUserAnalytics = 4,
+    GmpcStrategyQuorum = 5,
+}
+
+impl CircuitKind {
+    pub const ALL: [CircuitKind; 6] = [
+        CircuitKind::StrategyPlan,
+        CircuitKind::RiskScore,
+        CircuitKind::CurveEval,
+        CircuitKind::GmpcStrategy,
+        CircuitKind::MultiUserAnalytics,
+        CircuitKind::GmpcStrategyQuorum,
+    ];
+
+    /// Name of the encrypted instruction in `encrypted-ixs/`
+    pub fn encrypted_ix(self) -> &'static str {
+        match self {
+            CircuitKind::StrategyPlan => "confidential_strategy_plan",
+            CircuitKind::RiskScore => "confidential_risk_score",
+            CircuitKind::CurveEval => "confidential_curve_eval",
+            CircuitKind::GmpcStrategy => "evalys_gmpc_strategy",
+            CircuitKind::MultiUserAnalytics => "confidential_multi_user_analytics",
+            CircuitKind::GmpcStrategyQuorum => "evalys_gmpc_strategy_quorum",
+        }
+    }
+
+    /// Program instruction that registers this circuit's comp def
+    pub fn init_instruction(self) -> &'static str {
+        match self {
+            CircuitKind::StrategyPlan => "init_strategy_comp_def",
+            CircuitKind::RiskScore => "init_risk_comp_def",
+            CircuitKind::CurveEval => "init_curve_comp_def",
+            CircuitKind::GmpcStrategy => "init_gmpc_comp_def",
+            CircuitKind::MultiUserAnalytics => "init_multi_user_comp_def",
+            CircuitKind::GmpcStrategyQuorum => "init_gmpc_quorum_comp_def",
+        }
+    }
+
+    /// Kind whose policy account requests of this kind read: quorum legs share
+    /// the gMPC strategy policy, multi-user analytics has none
+    pub fn policy_kind(self) -> Option<CircuitKind> {
+        match self {
+            CircuitKind::GmpcStrategyQuorum => Some(CircuitKind::GmpcStrategy),
+            CircuitKind::MultiUserAnalytics => None,
+            kind => Some(kind),
+        }
+    }
+}
+
+impl TryFrom<u8> for CircuitKind {
+    type Error = ClientError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        CircuitKind::ALL
+            .get(value as usize)
+            .copied()
+            .ok_or(ClientError::UnknownCircuitKind(value))
+    }
+}
+
+/// Lifecycle of a queued computation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum RequestStatus {
+    Pending = 0,
+    Completed = 1,
+    Failed = 2,
+}
+
+impl TryFrom<u8> for RequestStatus {
+    type Error = ClientError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RequestStatus::Pending),
+            1 => Ok(RequestStatus::Completed),
+            2 => Ok(RequestStatus::Failed),
+            other => Err(ClientError::UnknownRequestStatus(other)),
+        }
+    }
+}
+
+/// Anchor account discriminator: first 8 bytes of `sha256("account:<Name>")`
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("account:{name}").as_bytes());
+    hash[..8].try_into().unwrap()
+}
+
+/// Strip and check the Anchor discriminator of an account
+pub(crate) fn account_body<'a>(
+    name: &'static str,
+    data: &'a [u8],
+    body_len: usize,
+) -> Result<&'a [u8], ClientError> {
+    if data.len() < 8 + body_len {
+        return Err(ClientError::AccountDataTooShort {
+            account: name,
+            expected: 8 + body_len,
+            actual: data.len(),
+        });
+    }
+    if data[..8] != account_discriminator(name) {
+        return Err(ClientError::AccountDiscriminator(name));
+    }
+    Ok(&data[8..8 + body_len])
+}
+
+/// Sequential reader over Borsh-encoded account fields
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    account: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    /// Start reading after the checked discriminator of `account`
+    pub(crate) fn account(account: &'static str, data: &'a [u8]) -> Result<Self, ClientError> {
+        account_body(account, data, 0)?;
+        Ok(Reader {
+            data: &data[8..],
+            account,
+        })
+    }
+
+    /// Read a payload whose discriminator the caller already matched
+    pub(crate) fn body(account: &'static str, data: &'a [u8]) -> Self {
+        Reader { data, account }
+    }
+
+    pub(crate) fn bytes<const N: usize>(&mut self) -> Result<[u8; N], ClientError> {
+        if self.data.len() < N {
+            return Err(ClientError::AccountDataTooShort {
+                account: self.account,
+                expected: N,
+                actual: self.data.len(),
+            });
+        }
+        let (head, rest) = self.data.split_at(N);
+        self.data = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, ClientError> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, ClientError> {
+        Ok(u16::from_le_bytes(self.bytes()?))
+    }
+
+    pub(crate) fn i16(&mut self) -> Result<i16, ClientError> {
+        Ok(i16::from_le_bytes(self.bytes()?))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, ClientError> {
+        Ok(u32::from_le_bytes(self.bytes()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, ClientError> {
+        Ok(u64::from_le_bytes(self.bytes()?))
+    }
+
+    pub(crate) fn u128(&mut self) -> Result<u128, ClientError> {
+        Ok(u128::from_le_bytes(self.bytes()?))
+    }
+
+    pub(crate) fn bool(&mut self) -> Result<bool, ClientError> {
+        Ok(self.u8()? != 0)
+    }
+
+    pub(crate) fn pubkey(&mut self) -> Result<Pubkey, ClientError> {
+        Ok(Pubkey::new_from_array(self.bytes()?))
+    }
+
+    pub(crate) fn string(&mut self) -> Result<String, ClientError> {
+        let len = self.u32()? as usize;
+        if self.data.len() < len {
+            return Err(ClientError::AccountDataTooShort {
+                account: self.account,
+                expected: len,
+                actual: self.data.len(),
+            });
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(String::from_utf8_lossy(head).into_owned())
+    }
+}
+
+/// Program configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MxeConfig {
+    pub authority: Pubkey,
+    /// Program id of the deployment running the other leg of every quorum
+    pub quorum_peer: Pubkey,
+}
+
+impl MxeConfig {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("MxeConfig", data)?;
+        Ok(MxeConfig {
+            authority: r.pubkey()?,
+            quorum_peer: r.pubkey()?,
+        })
+    }
+}
+
+/// Circuit currently registered for a comp def
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitRegistration {
+    pub kind: CircuitKind,
+    pub comp_def_offset: u32,
+    pub circuit_hash: [u8; 32],
+    pub version: String,
+    pub registered_slot: u64,
+}
+
+impl CircuitRegistration {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("CircuitRegistration", data)?;
+        Ok(CircuitRegistration {
+            kind: CircuitKind::try_from(r.u8()?)?,
+            comp_def_offset: r.u32()?,
+            circuit_hash: r.bytes()?,
+            version: r.string()?,
+            registered_slot: r.u64()?,
+        })
+    }
+}
+
+/// Version a circuit hash was registered under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitVersion {
+    pub kind: CircuitKind,
+    pub circuit_hash: [u8; 32],
+    pub version: String,
+    pub registered_slot: u64,
+}
+
+impl CircuitVersion {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("CircuitVersion", data)?;
+        Ok(CircuitVersion {
+            kind: CircuitKind::try_from(r.u8()?)?,
+            circuit_hash: r.bytes()?,
+            version: r.string()?,
+            registered_slot: r.u64()?,
+        })
+    }
+}
+
+/// Policy account of `confidential_strategy_plan`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrategyPolicyAccount {
+    pub policy: StrategyPolicy,
+    pub updated_slot: u64,
+}
+
+impl StrategyPolicyAccount {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("StrategyPolicyAccount", data)?;
+        Ok(StrategyPolicyAccount {
+            policy: StrategyPolicy {
+                stealth_risk: r.u16()?,
+                ghost_risk: r.u16()?,
+                low_win_rate: r.u16()?,
+                medium_volatility: r.u16()?,
+                high_volatility: r.u16()?,
+                fast_window_sec: r.u32()?,
+                medium_window_sec: r.u32()?,
+                slow_window_sec: r.u32()?,
+                min_slices: r.u8()?,
+                participation_pct: r.u8()?,
+                moderate_appetite: r.u8()?,
+                aggressive_appetite: r.u8()?,
+                aggressive_win_rate: r.u16()?,
+                take_profit_bps: r.u16()?,
+                stop_loss_bps: r.u16()?,
+                exit_window_pct: r.u8()?,
+                history_weight_trades: r.u16()?,
+            },
+            updated_slot: r.u64()?,
+        })
+    }
+}
+
+/// Policy account of `confidential_risk_score`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiskPolicyAccount {
+    pub policy: RiskPolicy,
+    pub updated_slot: u64,
+}
+
+impl RiskPolicyAccount {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("RiskPolicyAccount", data)?;
+        Ok(RiskPolicyAccount {
+            policy: RiskPolicy {
+                high_exposure: r.u8()?,
+                over_exposure: r.u8()?,
+                low_diversification: r.u8()?,
+                high_volatility: r.u16()?,
+                very_high_volatility: r.u16()?,
+                high_liquidity_risk: r.u8()?,
+                poor_drawdown: r.u16()?,
+                good_sharpe: r.i16()?,
+                good_consistency: r.u8()?,
+                caution_risk: r.u8()?,
+                avoid_risk: r.u8()?,
+            },
+            updated_slot: r.u64()?,
+        })
+    }
+}
+
+/// Policy account of `confidential_curve_eval`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurvePolicyAccount {
+    pub policy: CurvePolicy,
+    pub updated_slot: u64,
+}
+
+impl CurvePolicyAccount {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("CurvePolicyAccount", data)?;
+        Ok(CurvePolicyAccount {
+            policy: CurvePolicy {
+                plenty_liquidity_multiple: r.u8()?,
+                liquidity_use_pct: r.u8()?,
+                momentum_bps: r.u16()?,
+                pressure_ratio: r.u8()?,
+                high_urgency: r.u8()?,
+                urgent: r.u8()?,
+                high_urgency_max_timing_sec: r.u32()?,
+                urgent_max_timing_sec: r.u32()?,
+                confident_liquidity_multiple: r.u8()?,
+                stable_bps: r.u16()?,
+                moderate_bps: r.u16()?,
+            },
+            updated_slot: r.u64()?,
+        })
+    }
+}
+
+/// Policy account of `evalys_gmpc_strategy` and its quorum variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GmpcPolicyAccount {
+    pub policy: GmpcPolicy,
+    pub updated_slot: u64,
+}
+
+impl GmpcPolicyAccount {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("GmpcPolicyAccount", data)?;
+        Ok(GmpcPolicyAccount {
+            policy: GmpcPolicy {
+                low_risk_size_pct: r.u8()?,
+                normal_risk_size_pct: r.u8()?,
+                high_volatility: r.u16()?,
+                high_volatility_size_pct: r.u8()?,
+                normal_slices: r.u8()?,
+                stealth_slices: r.u8()?,
+                max_privacy_slices: r.u8()?,
+                calm_window_sec: r.u32()?,
+                volatile_window_sec: r.u32()?,
+                low_risk_volatility: r.u16()?,
+                high_risk_volatility: r.u16()?,
+            },
+            updated_slot: r.u64()?,
+        })
+    }
+}
+
+/// Outcome of a quorum request, as seen by one deployment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum QuorumStatus {
+    Open = 0,
+    Final = 1,
+    Mismatch = 2,
+    /// A leg aborted
+    Failed = 3,
+}
+
+impl TryFrom<u8> for QuorumStatus {
+    type Error = ClientError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(QuorumStatus::Open),
+            1 => Ok(QuorumStatus::Final),
+            2 => Ok(QuorumStatus::Mismatch),
+            3 => Ok(QuorumStatus::Failed),
+            other => Err(ClientError::UnknownQuorumStatus(other)),
+        }
+    }
+}
+
+/// One deployment's leg of a quorum request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuorumLeg {
+    pub computation_offset: u64,
+    pub queued: bool,
+    pub completed: bool,
+    pub failed: bool,
+    pub digest: u128,
+}
+
+/// One deployment's side of a quorum request; the peer deployment keeps the
+/// other side at the same offset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumRequest {
+    pub requester: Pubkey,
+    pub quorum_offset: u64,
+    /// Program id of the peer deployment
+    pub peer: Pubkey,
+    /// The peer's `QuorumRequest` address
+    pub peer_request: Pubkey,
+    pub leg: QuorumLeg,
+    pub status: QuorumStatus,
+}
+
+impl QuorumRequest {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("QuorumRequest", data)?;
+        Ok(QuorumRequest {
+            requester: r.pubkey()?,
+            quorum_offset: r.u64()?,
+            peer: r.pubkey()?,
+            peer_request: r.pubkey()?,
+            leg: QuorumLeg {
+                computation_offset: r.u64()?,
+                queued: r.bool()?,
+                completed: r.bool()?,
+                failed: r.bool()?,
+                digest: r.u128()?,
+            },
+            status: QuorumStatus::try_from(r.u8()?)?,
+        })
+    }
+}
+
+/// Per-computation request record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComputationRequest {
+    pub requester: Pubkey,
+    pub kind: CircuitKind,
+    pub computation_offset: u64,
+    pub input_commitment: [u8; 32],
+    pub circuit_hash: [u8; 32],
+    pub client_key_id: u32,
+    pub status: RequestStatus,
+    pub requested_slot: u64,
+    pub completed_slot: u64,
+}
+
+impl ComputationRequest {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("ComputationRequest", data)?;
+        Ok(ComputationRequest {
+            requester: r.pubkey()?,
+            kind: CircuitKind::try_from(r.u8()?)?,
+            computation_offset: r.u64()?,
+            input_commitment: r.bytes()?,
+            circuit_hash: r.bytes()?,
+            client_key_id: r.u32()?,
+            status: RequestStatus::try_from(r.u8()?)?,
+            requested_slot: r.u64()?,
+            completed_slot: r.u64()?,
+        })
+    }
+}
+
+/// Status of a registered client key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ClientKeyStatus {
+    Active = 0,
+    Retired = 1,
+}
+
+/// One x25519 public key of a bridge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientKey {
+    pub key_id: u32,
+    pub pub_key: [u8; 32],
+    pub status: ClientKeyStatus,
+    pub registered_slot: u64,
+    pub retired_slot: u64,
+}
+
+/// x25519 public keys a bridge encrypts its requests with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientKeyRegistry {
+    pub bridge: Pubkey,
+    pub next_key_id: u32,
+    pub keys: Vec<ClientKey>,
+}
+
+impl ClientKeyRegistry {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ClientError> {
+        let mut r = Reader::account("ClientKeyRegistry", data)?;
+        let bridge = r.pubkey()?;
+        let next_key_id = r.u32()?;
+        let len = r.u32()?;
+        let keys = (0..len)
+            .map(|_| {
+                Ok(ClientKey {
+                    key_id: r.u32()?,
+                    pub_key: r.bytes()?,
+                    status: match r.u8()? {
+                        0 => ClientKeyStatus::Active,
+                        1 => ClientKeyStatus::Retired,
+                        other => return Err(ClientError::UnknownClientKeyStatus(other)),
+                    },
+                    registered_slot: r.u64()?,
+                    retired_slot: r.u64()?,
+                })
+            })
+            .collect::<Result<Vec<_>, ClientError>>()?;
+        Ok(ClientKeyRegistry {
+            bridge,
+            next_key_id,
+            keys,
+        })
+    }
+
+    pub fn active(&self) -> Option<&ClientKey> {
+        self.keys
+            .iter()
+            .find(|k| k.status == ClientKeyStatus::Active)
+    }
+
+    pub fn get(&self, key_id: u32) -> Option<&ClientKey> {
+        self.keys.iter().find(|k| k.key_id == key_id)
+    }
+}
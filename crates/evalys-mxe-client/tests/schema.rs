@@ -0,0 +1,136 @@
+//! The published JSON Schema and the range validation it documents.
+//!
+//! After changing an input or output type, regenerate the published schema
+//! and review the diff:
+//!
+//! ```text
+//! EVALYS_UPDATE_SCHEMA=1 cargo test -p evalys-mxe-client --test schema
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+
+use evalys_mxe_client::inputs::{IntentInput, SizingPreferences, UserHistory};
+use evalys_mxe_client::schema::{self, GmpcStrategyInputs, MultiUserAnalyticsInputs};
+use evalys_mxe_client::ClientError;
+use serde_json::json;
+
+#[test]
+fn published_schema_is_current() {
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../docs/schema/evalys-mxe.schema.json");
+    let mut current = serde_json::to_string_pretty(&schema::json_schema()).unwrap();
+    current.push('\n');
+    if std::env::var_os("EVALYS_UPDATE_SCHEMA").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, current).unwrap();
+        return;
+    }
+    let published = fs::read_to_string(&path).unwrap();
+    assert!(
+        published == current,
+        "{} is stale (set EVALYS_UPDATE_SCHEMA=1 to regenerate)",
+        path.display()
+    );
+}
+
+#[test]
+fn schema_carries_units_and_ranges() {
+    let schema = schema::json_schema();
+    let win_rate = &schema["$defs"]["UserHistory"]["properties"]["win_rate"];
+    assert_eq!(win_rate["x-unit"], "bps");
+    assert_eq!(win_rate["maximum"], 10_000);
+    let size = &schema["$defs"]["IntentInput"]["properties"]["max_size_sol"];
+    assert_eq!(size["x-unit"], "lamports");
+    let window = &schema["$defs"]["PlanOutput"]["properties"]["time_window_sec"];
+    assert_eq!(window["x-unit"], "seconds");
+}
+
+fn history(win_rate: u64) -> serde_json::Value {
+    json!({
+        "recent_pnl": -5_000_000,
+        "win_rate": win_rate,
+        "avg_hold_time": 3_600,
+        "total_trades": 42,
+    })
+}
+
+#[test]
+fn rejects_out_of_range_values() {
+    assert!(serde_json::from_value::<UserHistory>(history(10_000)).is_ok());
+    let err = serde_json::from_value::<UserHistory>(history(10_001)).unwrap_err();
+    assert!(
+        err.to_string().contains("UserHistory.win_rate is 10001"),
+        "{err}"
+    );
+
+    let err = UserHistory::builder()
+        .recent_pnl(0)
+        .win_rate(12_000)
+        .avg_hold_time(0)
+        .total_trades(0)
+        .build()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ClientError::OutOfRange {
+            field: "win_rate",
+            value: 12_000,
+            max: 10_000,
+            ..
+        }
+    ));
+
+    let err = SizingPreferences::builder()
+        .target_size(5)
+        .min_size(10)
+        .max_size(1)
+        .capital_allocation_pct(50)
+        .build()
+        .unwrap_err();
+    assert!(matches!(err, ClientError::InvertedRange { .. }));
+}
+
+#[test]
+fn request_files_are_validated() {
+    let intent = IntentInput::builder()
+        .max_size_sol(1_000_000_000)
+        .risk_level(1)
+        .privacy_priority(3)
+        .market_price(100)
+        .curve_position(5_000)
+        .volatility_score(300)
+        .avg_hold_time(600)
+        .win_rate(5_500)
+        .max_dd(1_000);
+    assert!(intent.clone().build().is_err());
+    let intent = intent.privacy_priority(2).build().unwrap();
+
+    let file = serde_json::to_value(GmpcStrategyInputs { intent }).unwrap();
+    assert_eq!(
+        serde_json::from_value::<GmpcStrategyInputs>(file.clone()).unwrap(),
+        GmpcStrategyInputs { intent }
+    );
+    let mut bad = file;
+    bad["intent"]["privacy_priority"] = json!(3);
+    assert!(serde_json::from_value::<GmpcStrategyInputs>(bad).is_err());
+
+    let analytics = |profiles: usize, aggregation_type: u8| {
+        let profile = json!({
+            "profile_id_hash": 7,
+            "avg_hold_time": 600,
+            "win_rate": 5_000,
+            "preferred_size_range_min": 1,
+            "preferred_size_range_max": 2,
+            "risk_tolerance": 1,
+        });
+        serde_json::from_value::<MultiUserAnalyticsInputs>(json!({
+            "profiles": vec![profile; profiles],
+            "aggregation_type": aggregation_type,
+        }))
+    };
+    assert!(analytics(16, 2).is_ok());
+    assert!(analytics(0, 0).is_err());
+    assert!(analytics(17, 0).is_err());
+    assert!(analytics(1, 3).is_err());
+}
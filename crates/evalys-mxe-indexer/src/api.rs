@@ -0,0 +1,139 @@
+//! Read-only HTTP query API.
+//!
+//! | route                                                       | response                               |
+//! |-------------------------------------------------------------|----------------------------------------|
+//! | `GET /results/<computation_offset>`                         | one result, or 404                     |
+//! | `GET /results?requester=<pubkey>&from=<t>&to=<t>&limit=<n>` | `{ "results": [...] }`, oldest first   |
+//!
+//! Every query parameter is optional; `from` and `to` are inclusive Unix
+//! timestamps matched against the block time of the callback transaction.
+//! Results carry the encrypted output as logged; decrypt them with
+//! `evalys_mxe_client::events::ResultEvent::decrypt`.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use tiny_http::{Header, Server};
+
+use crate::db::{Db, IndexedResult, ResultQuery};
+
+/// Status code and JSON body of an API response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+/// JSON view of an indexed result; byte fields are hex
+pub fn result_json(result: &IndexedResult) -> Value {
+    let event = &result.event;
+    json!({
+        "computation_offset": event.computation_offset,
+        "circuit": event.kind.encrypted_ix(),
+        "result_id": event.result_id.to_string(),
+        "requester": event.requester.to_string(),
+        "circuit_hash": hex::encode(event.circuit_hash),
+        "client_key_id": event.client_key_id,
+        "ciphertexts": event.ciphertexts.iter().map(hex::encode).collect::<Vec<_>>(),
+        "nonce": hex::encode(event.nonce),
+        "signature": result.signature,
+        "slot": result.slot,
+        "block_time": result.block_time,
+    })
+}
+
+fn integer<T: FromStr>(name: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| anyhow!("{name} is not a valid integer: {value}"))
+}
+
+fn parse_query(query: &str) -> Result<ResultQuery> {
+    let mut parsed = ResultQuery::default();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = pair
+            .split_once('=')
+            .ok_or_else(|| anyhow!("query parameter {pair} has no value"))?;
+        match name {
+            "requester" => {
+                parsed.requester = Some(
+                    value
+                        .parse()
+                        .map_err(|_| anyhow!("requester is not a valid pubkey: {value}"))?,
+                )
+            }
+            "from" => parsed.from = Some(integer(name, value)?),
+            "to" => parsed.to = Some(integer(name, value)?),
+            "limit" => parsed.limit = Some(integer(name, value)?),
+            _ => return Err(anyhow!("unknown query parameter {name}")),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Answer one request against `db`
+pub fn handle(db: &Db, method: &str, url: &str) -> Response {
+    if method != "GET" {
+        return Response::error(405, "only GET is supported");
+    }
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let outcome = match path.trim_end_matches('/') {
+        "/results" => parse_query(query)
+            .map_err(|err| Response::error(400, err.to_string()))
+            .and_then(|query| {
+                db.query(&query)
+                    .map_err(|err| Response::error(500, err.to_string()))
+            })
+            .map(|results| {
+                Response::ok(json!({
+                    "results": results.iter().map(result_json).collect::<Vec<_>>(),
+                }))
+            }),
+        path => match path.strip_prefix("/results/") {
+            Some(offset) => offset
+                .parse::<u64>()
+                .map_err(|_| Response::error(400, format!("invalid computation offset {offset}")))
+                .and_then(|offset| {
+                    db.result(offset)
+                        .map_err(|err| Response::error(500, err.to_string()))?
+                        .ok_or_else(|| {
+                            Response::error(404, format!("no result for computation {offset}"))
+                        })
+                })
+                .map(|result| Response::ok(result_json(&result))),
+            None => Err(Response::error(404, format!("no route {path}"))),
+        },
+    };
+    outcome.unwrap_or_else(|response| response)
+}
+
+/// Serve the API on `listen` until the process exits
+pub fn serve(db: &Db, listen: &str) -> Result<()> {
+    let server = Server::http(listen).map_err(|err| anyhow!("listening on {listen}: {err}"))?;
+    let content_type = Header::from_bytes("Content-Type", "application/json")
+        .map_err(|_| anyhow!("invalid content type header"))?;
+    for request in server.incoming_requests() {
+        let response = handle(db, request.method().as_str(), request.url());
+        let reply = tiny_http::Response::from_string(response.body.to_string())
+            .with_status_code(response.status)
+            .with_header(content_type.clone());
+        if let Err(err) = request.respond(reply) {
+            eprintln!("responding to request: {err}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,467 @@
+//! SQLite store of indexed result events.
+//!
+//! One row per completed computation, keyed by computation offset, and one
+//! per failed computation. Offsets are `u64` and SQLite integers `i64`, so
+//! offsets are stored by their bit pattern; [`Db`] converts both ways.
+//! Re-indexing a transaction is a no-op.
+//!
+//! Indexing a new result or failure queues one delivery per registered
+//! webhook; [`crate::webhook`] sends them.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use evalys_mxe_client::events::{FailedEvent, ResultEvent};
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::Pubkey;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::source::LoggedTransaction;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS results (
+    computation_offset INTEGER PRIMARY KEY,
+    circuit            TEXT    NOT NULL,
+    result_id          TEXT    NOT NULL,
+    requester          TEXT    NOT NULL,
+    circuit_hash       BLOB    NOT NULL,
+    client_key_id      INTEGER NOT NULL,
+    ciphertexts        BLOB    NOT NULL,
+    nonce              BLOB    NOT NULL,
+    signature          TEXT    NOT NULL,
+    slot               INTEGER NOT NULL,
+    block_time         INTEGER
+);
+CREATE INDEX IF NOT EXISTS results_requester ON results (requester, slot);
+CREATE INDEX IF NOT EXISTS results_block_time ON results (block_time);
+CREATE TABLE IF NOT EXISTS failures (
+    computation_offset INTEGER PRIMARY KEY,
+    circuit            TEXT    NOT NULL,
+    signature          TEXT    NOT NULL,
+    slot               INTEGER NOT NULL,
+    block_time         INTEGER
+);
+CREATE TABLE IF NOT EXISTS cursor (
+    id        INTEGER PRIMARY KEY CHECK (id = 0),
+    signature TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS webhooks (
+    id      INTEGER PRIMARY KEY AUTOINCREMENT,
+    url     TEXT    NOT NULL,
+    secret  TEXT    NOT NULL,
+    decrypt INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS deliveries (
+    id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook_id         INTEGER NOT NULL,
+    computation_offset INTEGER NOT NULL,
+    attempts           INTEGER NOT NULL DEFAULT 0,
+    next_attempt       INTEGER NOT NULL DEFAULT 0,
+    last_error         TEXT,
+    dead               INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (webhook_id, computation_offset)
+);
+CREATE INDEX IF NOT EXISTS deliveries_due ON deliveries (dead, next_attempt);
+";
+
+/// Queue a delivery of `?1` to every registered webhook
+const ENQUEUE: &str = "INSERT OR IGNORE INTO deliveries (webhook_id, computation_offset) \
+     SELECT id, ?1 FROM webhooks";
+
+const RESULT_COLUMNS: &str = "computation_offset, circuit, result_id, requester, circuit_hash, \
+     client_key_id, ciphertexts, nonce, signature, slot, block_time";
+
+/// Most rows one query returns
+pub const MAX_LIMIT: usize = 1000;
+
+/// A result event and the transaction that logged it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedResult {
+    pub event: ResultEvent,
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+/// A failure event and the transaction that logged it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedFailure {
+    pub event: FailedEvent,
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+/// An indexed computation: a result or a failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Completed(IndexedResult),
+    Failed(IndexedFailure),
+}
+
+/// A registered webhook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    /// HMAC-SHA256 key of the payload signature
+    pub secret: String,
+    /// Send decrypted outputs instead of ciphertexts
+    pub decrypt: bool,
+}
+
+/// A queued delivery of one computation to one webhook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+    pub id: i64,
+    pub webhook: Webhook,
+    pub computation_offset: u64,
+    /// Failed attempts so far
+    pub attempts: u32,
+    /// Unix timestamp the next attempt is due at
+    pub next_attempt: i64,
+    pub last_error: Option<String>,
+}
+
+/// Filter of [`Db::query`]; time bounds are inclusive Unix timestamps
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResultQuery {
+    pub requester: Option<Pubkey>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// Capped at [`MAX_LIMIT`]
+    pub limit: Option<usize>,
+}
+
+pub struct Db {
+    conn: Connection,
+}
+
+fn offset_to_sql(offset: u64) -> i64 {
+    offset as i64
+}
+
+fn offset_from_sql(offset: i64) -> u64 {
+    offset as u64
+}
+
+fn circuit_kind(name: &str) -> Result<CircuitKind> {
+    CircuitKind::ALL
+        .into_iter()
+        .find(|kind| kind.encrypted_ix() == name)
+        .ok_or_else(|| anyhow!("unknown circuit {name}"))
+}
+
+fn fixed<const N: usize>(column: &str, bytes: &[u8]) -> Result<[u8; N]> {
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("{column}: expected {N} bytes, got {}", bytes.len()))
+}
+
+fn read_result(row: &Row) -> Result<IndexedResult> {
+    let circuit: String = row.get(1)?;
+    let result_id: String = row.get(2)?;
+    let requester: String = row.get(3)?;
+    let circuit_hash: Vec<u8> = row.get(4)?;
+    let ciphertexts: Vec<u8> = row.get(6)?;
+    let nonce: Vec<u8> = row.get(7)?;
+    Ok(IndexedResult {
+        event: ResultEvent {
+            kind: circuit_kind(&circuit)?,
+            result_id: result_id.parse()?,
+            requester: requester.parse()?,
+            computation_offset: offset_from_sql(row.get(0)?),
+            circuit_hash: fixed("circuit_hash", &circuit_hash)?,
+            client_key_id: row.get(5)?,
+            ciphertexts: ciphertexts
+                .chunks(32)
+                .map(|chunk| fixed("ciphertexts", chunk))
+                .collect::<Result<_>>()?,
+            nonce: fixed("nonce", &nonce)?,
+        },
+        signature: row.get(8)?,
+        slot: row.get::<_, i64>(9)? as u64,
+        block_time: row.get(10)?,
+    })
+}
+
+const DELIVERY_COLUMNS: &str = "deliveries.id, webhooks.id, webhooks.url, webhooks.secret, \
+     webhooks.decrypt, deliveries.computation_offset, deliveries.attempts, \
+     deliveries.next_attempt, deliveries.last_error";
+
+fn read_webhook(row: &Row) -> rusqlite::Result<Webhook> {
+    Ok(Webhook {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        secret: row.get(2)?,
+        decrypt: row.get(3)?,
+    })
+}
+
+fn read_delivery(row: &Row) -> rusqlite::Result<Delivery> {
+    Ok(Delivery {
+        id: row.get(0)?,
+        webhook: Webhook {
+            id: row.get(1)?,
+            url: row.get(2)?,
+            secret: row.get(3)?,
+            decrypt: row.get(4)?,
+        },
+        computation_offset: offset_from_sql(row.get(5)?),
+        attempts: row.get(6)?,
+        next_attempt: row.get(7)?,
+        last_error: row.get(8)?,
+    })
+}
+
+impl Db {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("opening database {}", path.display()))?;
+        Self::with_connection(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Db { conn })
+    }
+
+    /// Store the result and failure events of a transaction and queue their
+    /// webhook deliveries; returns how many were new
+    pub fn index(&mut self, transaction: &LoggedTransaction) -> Result<usize> {
+        let events = transaction.result_events()?;
+        let failures = transaction.failure_events()?;
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+        for event in &events {
+            let offset = offset_to_sql(event.computation_offset);
+            let new = tx.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO results ({RESULT_COLUMNS}) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"
+                ),
+                params![
+                    offset,
+                    event.kind.encrypted_ix(),
+                    event.result_id.to_string(),
+                    event.requester.to_string(),
+                    event.circuit_hash.as_slice(),
+                    event.client_key_id,
+                    event.ciphertexts.concat(),
+                    event.nonce.as_slice(),
+                    transaction.signature,
+                    transaction.slot as i64,
+                    transaction.block_time,
+                ],
+            )?;
+            if new > 0 {
+                tx.execute(ENQUEUE, [offset])?;
+            }
+            inserted += new;
+        }
+        for event in &failures {
+            let offset = offset_to_sql(event.computation_offset);
+            let new = tx.execute(
+                "INSERT OR IGNORE INTO failures \
+                 (computation_offset, circuit, signature, slot, block_time) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    offset,
+                    event.kind.encrypted_ix(),
+                    transaction.signature,
+                    transaction.slot as i64,
+                    transaction.block_time,
+                ],
+            )?;
+            if new > 0 {
+                tx.execute(ENQUEUE, [offset])?;
+            }
+            inserted += new;
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Last transaction signature a live run indexed
+    pub fn cursor(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT signature FROM cursor WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    pub fn set_cursor(&self, signature: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO cursor (id, signature) VALUES (0, ?1) \
+             ON CONFLICT (id) DO UPDATE SET signature = excluded.signature",
+            [signature],
+        )?;
+        Ok(())
+    }
+
+    /// Result of the computation at `computation_offset`, if indexed
+    pub fn result(&self, computation_offset: u64) -> Result<Option<IndexedResult>> {
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT {RESULT_COLUMNS} FROM results WHERE computation_offset = ?1"
+        ))?;
+        let mut rows = statement.query([offset_to_sql(computation_offset)])?;
+        rows.next()?.map(read_result).transpose()
+    }
+
+    /// Results matching `query`, oldest first
+    pub fn query(&self, query: &ResultQuery) -> Result<Vec<IndexedResult>> {
+        let limit = query.limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT);
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT {RESULT_COLUMNS} FROM results \
+             WHERE (?1 IS NULL OR requester = ?1) \
+               AND (?2 IS NULL OR block_time >= ?2) \
+               AND (?3 IS NULL OR block_time <= ?3) \
+             ORDER BY slot, computation_offset LIMIT ?4"
+        ))?;
+        let mut rows = statement.query(params![
+            query.requester.map(|requester| requester.to_string()),
+            query.from,
+            query.to,
+            limit as i64,
+        ])?;
+        let mut results = Vec::new();
+        while let Some(row) = rows.next()? {
+            results.push(read_result(row)?);
+        }
+        Ok(results)
+    }
+
+    /// Failure of the computation at `computation_offset`, if indexed
+    pub fn failure(&self, computation_offset: u64) -> Result<Option<IndexedFailure>> {
+        let row = self
+            .conn
+            .query_row(
+                "SELECT circuit, signature, slot, block_time FROM failures \
+                 WHERE computation_offset = ?1",
+                [offset_to_sql(computation_offset)],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+        row.map(|(circuit, signature, slot, block_time)| {
+            Ok(IndexedFailure {
+                event: FailedEvent {
+                    computation_offset,
+                    kind: circuit_kind(&circuit)?,
+                },
+                signature,
+                slot: slot as u64,
+                block_time,
+            })
+        })
+        .transpose()
+    }
+
+    /// Result or failure of the computation at `computation_offset`
+    pub fn outcome(&self, computation_offset: u64) -> Result<Option<Outcome>> {
+        if let Some(result) = self.result(computation_offset)? {
+            return Ok(Some(Outcome::Completed(result)));
+        }
+        Ok(self.failure(computation_offset)?.map(Outcome::Failed))
+    }
+
+    /// Register a webhook; it is sent every computation indexed from now on
+    pub fn add_webhook(&self, url: &str, secret: &str, decrypt: bool) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO webhooks (url, secret, decrypt) VALUES (?1, ?2, ?3)",
+            params![url, secret, decrypt],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn webhooks(&self) -> Result<Vec<Webhook>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT id, url, secret, decrypt FROM webhooks ORDER BY id")?;
+        let webhooks = statement
+            .query_map([], read_webhook)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(webhooks)
+    }
+
+    /// Remove a webhook and its queued deliveries; returns whether it existed
+    pub fn remove_webhook(&mut self, id: i64) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM deliveries WHERE webhook_id = ?1", [id])?;
+        let removed = tx.execute("DELETE FROM webhooks WHERE id = ?1", [id])?;
+        tx.commit()?;
+        Ok(removed > 0)
+    }
+
+    fn deliveries(
+        &self,
+        filter: &str,
+        params: impl rusqlite::Params,
+        limit: Option<usize>,
+    ) -> Result<Vec<Delivery>> {
+        let limit = limit.map_or(-1, |limit| limit as i64);
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM deliveries \
+             JOIN webhooks ON webhooks.id = deliveries.webhook_id \
+             WHERE {filter} ORDER BY deliveries.next_attempt, deliveries.id LIMIT {limit}"
+        ))?;
+        let deliveries = statement
+            .query_map(params, read_delivery)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(deliveries)
+    }
+
+    /// Deliveries due at `now`, oldest first
+    pub fn due_deliveries(&self, now: i64, limit: usize) -> Result<Vec<Delivery>> {
+        self.deliveries(
+            "deliveries.dead = 0 AND deliveries.next_attempt <= ?1",
+            [now],
+            Some(limit),
+        )
+    }
+
+    /// Deliveries that ran out of attempts
+    pub fn dead_letters(&self) -> Result<Vec<Delivery>> {
+        self.deliveries("deliveries.dead = 1", [], None)
+    }
+
+    /// Drop a delivery the webhook accepted
+    pub fn delivered(&self, id: i64) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM deliveries WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// Record a failed attempt; the delivery is retried at `next_attempt`,
+    /// or dead-lettered if `None`
+    pub fn attempt_failed(&self, id: i64, error: &str, next_attempt: Option<i64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE deliveries SET attempts = attempts + 1, last_error = ?2, \
+             next_attempt = coalesce(?3, next_attempt), dead = ?3 IS NULL WHERE id = ?1",
+            params![id, error, next_attempt],
+        )?;
+        Ok(())
+    }
+
+    /// Queue dead letters again with a fresh attempt budget, all of them or
+    /// only delivery `id`; returns how many
+    pub fn replay_dead_letters(&self, id: Option<i64>) -> Result<usize> {
+        Ok(self.conn.execute(
+            "UPDATE deliveries SET dead = 0, attempts = 0, next_attempt = 0 \
+             WHERE dead = 1 AND (?1 IS NULL OR id = ?1)",
+            [id],
+        )?)
+    }
+}
@@ -0,0 +1,14 @@
+//! Indexer of the MXE's result events.
+//!
+//! Reads program transactions, live from RPC (`evalys_mxe_client::rpc`) or from a recording
+//! ([`source`]), decodes `StrategyPlanEvent`, `RiskScoreEvent`,
+//! `CurveEvalEvent`, `GmpcStrategyEvent`, `MultiUserAnalyticsEvent` and
+//! `ComputationFailedEvent`, stores them in SQLite ([`db`]) and serves
+//! lookups by computation offset, requester and time range over HTTP
+//! ([`api`]). Registered webhooks are pushed every new result and failure
+//! ([`webhook`]).
+
+pub mod api;
+pub mod db;
+pub mod source;
+pub mod webhook;
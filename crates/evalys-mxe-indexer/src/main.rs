@@ -0,0 +1,246 @@
+//! `evalys-mxe-indexer`: index the MXE's result events and serve them.
+
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use evalys_mxe_client::keys::Keystore;
+use evalys_mxe_client::rpc::Rpc;
+use evalys_mxe_client::Pubkey;
+use evalys_mxe_indexer::api;
+use evalys_mxe_indexer::db::Db;
+use evalys_mxe_indexer::source::{self, LoggedTransaction};
+use evalys_mxe_indexer::webhook::{Decryptor, Dispatcher, RetryPolicy};
+use rand_core::{OsRng, RngCore};
+
+#[derive(Parser)]
+#[command(
+    name = "evalys-mxe-indexer",
+    version,
+    about = "Index Evalys MXE result events into SQLite and serve them over HTTP"
+)]
+struct Cli {
+    /// SQLite database file
+    #[arg(long, global = true, default_value = "evalys-mxe-index.sqlite")]
+    db: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Follow the program's transactions over RPC and serve the query API
+    Run {
+        #[arg(long)]
+        rpc_url: String,
+        #[arg(long)]
+        program_id: Pubkey,
+        /// Address of the query API
+        #[arg(long, default_value = "127.0.0.1:8787")]
+        listen: String,
+        /// Seconds between polls for new transactions
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+        /// Also append every fetched transaction to this recording
+        #[arg(long)]
+        record: Option<PathBuf>,
+        /// Client keystore for webhooks that want decrypted results
+        #[arg(long, requires = "mxe_x25519_pubkey")]
+        keystore: Option<PathBuf>,
+        /// MXE x25519 public key (hex) the results are encrypted with
+        #[arg(long, value_parser = parse_key)]
+        mxe_x25519_pubkey: Option<[u8; 32]>,
+        /// Webhook attempts before a delivery is dead-lettered
+        #[arg(long, default_value_t = RetryPolicy::default().max_attempts)]
+        max_attempts: u32,
+    },
+    /// Index a recorded log file
+    Import { recording: PathBuf },
+    /// Serve the query API without indexing
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8787")]
+        listen: String,
+    },
+    /// Manage the webhooks `run` pushes results to
+    #[command(subcommand)]
+    Webhook(WebhookCommand),
+}
+
+#[derive(Subcommand)]
+enum WebhookCommand {
+    /// Register a webhook for every computation indexed from now on
+    Add {
+        url: String,
+        /// HMAC key of the payload signature; generated if not given
+        #[arg(long)]
+        secret: Option<String>,
+        /// Send decrypted outputs; `run` then needs `--keystore`
+        #[arg(long)]
+        decrypt: bool,
+    },
+    /// List registered webhooks
+    List,
+    /// Remove a webhook and its queued deliveries
+    Remove { id: i64 },
+    /// List deliveries that ran out of attempts
+    DeadLetters,
+    /// Queue dead letters again, all of them or one delivery
+    Replay { delivery: Option<i64> },
+}
+
+fn parse_key(value: &str) -> Result<[u8; 32]> {
+    hex::decode(value)?
+        .try_into()
+        .map_err(|_| anyhow!("expected 32 hex-encoded bytes"))
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+fn index(db: &mut Db, transaction: &LoggedTransaction) -> Result<()> {
+    let inserted = db.index(transaction)?;
+    if inserted > 0 {
+        println!(
+            "{}: indexed {inserted} computation(s) at slot {}",
+            transaction.signature, transaction.slot
+        );
+    }
+    Ok(())
+}
+
+/// Index every program transaction after the stored cursor
+fn sync(db: &mut Db, rpc: &Rpc, program_id: &Pubkey, record: Option<&Path>) -> Result<()> {
+    let cursor = db.cursor()?;
+    for signature in rpc.signatures_since(program_id, cursor.as_deref())? {
+        // Failed transactions may log events before they revert
+        if !signature.failed {
+            let transaction = LoggedTransaction::confirmed(
+                &signature.signature,
+                rpc.transaction(&signature.signature)?,
+            );
+            if let Some(record) = record {
+                source::append_recording(record, &transaction)?;
+            }
+            index(db, &transaction)?;
+        }
+        db.set_cursor(&signature.signature)?;
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Run {
+            rpc_url,
+            program_id,
+            listen,
+            interval,
+            record,
+            keystore,
+            mxe_x25519_pubkey,
+            max_attempts,
+        } => {
+            let decryptor = match (keystore, mxe_x25519_pubkey) {
+                (Some(path), Some(mxe_x25519_pubkey)) => {
+                    Some(Decryptor::new(Keystore::open(&path)?, mxe_x25519_pubkey))
+                }
+                _ => None,
+            };
+            let policy = RetryPolicy {
+                max_attempts,
+                ..RetryPolicy::default()
+            };
+            let dispatcher = Dispatcher::new(policy, decryptor);
+            let api_db = Db::open(&cli.db)?;
+            thread::spawn(move || {
+                if let Err(err) = api::serve(&api_db, &listen) {
+                    eprintln!("query API stopped: {err:#}");
+                    std::process::exit(1);
+                }
+            });
+            let mut db = Db::open(&cli.db)?;
+            let rpc = Rpc::new(&rpc_url);
+            loop {
+                if let Err(err) = sync(&mut db, &rpc, &program_id, record.as_deref()) {
+                    eprintln!("sync failed, retrying: {err:#}");
+                }
+                match dispatcher.dispatch(&db, now()) {
+                    Ok(report) if report.retrying + report.dead_lettered > 0 => eprintln!(
+                        "webhooks: {} delivered, {} to retry, {} dead-lettered",
+                        report.delivered, report.retrying, report.dead_lettered
+                    ),
+                    Ok(_) => {}
+                    Err(err) => eprintln!("webhook dispatch failed: {err:#}"),
+                }
+                thread::sleep(Duration::from_secs(interval));
+            }
+        }
+        Command::Import { recording } => {
+            let mut db = Db::open(&cli.db)?;
+            for transaction in source::read_recording(&recording)? {
+                index(&mut db, &transaction)
+                    .with_context(|| format!("indexing {}", transaction.signature))?;
+            }
+            Ok(())
+        }
+        Command::Serve { listen } => api::serve(&Db::open(&cli.db)?, &listen),
+        Command::Webhook(command) => webhook(&mut Db::open(&cli.db)?, command),
+    }
+}
+
+fn webhook(db: &mut Db, command: WebhookCommand) -> Result<()> {
+    match command {
+        WebhookCommand::Add {
+            url,
+            secret,
+            decrypt,
+        } => {
+            let secret = secret.unwrap_or_else(|| {
+                let mut bytes = [0; 32];
+                OsRng.fill_bytes(&mut bytes);
+                hex::encode(bytes)
+            });
+            let id = db.add_webhook(&url, &secret, decrypt)?;
+            println!("webhook {id}: {url}");
+            println!("secret: {secret}");
+        }
+        WebhookCommand::List => {
+            for webhook in db.webhooks()? {
+                let payload = if webhook.decrypt {
+                    "decrypted"
+                } else {
+                    "encrypted"
+                };
+                println!("{}\t{}\t{payload}", webhook.id, webhook.url);
+            }
+        }
+        WebhookCommand::Remove { id } => {
+            if !db.remove_webhook(id)? {
+                bail!("no webhook {id}");
+            }
+        }
+        WebhookCommand::DeadLetters => {
+            for delivery in db.dead_letters()? {
+                println!(
+                    "{}\twebhook {}\tcomputation {}\t{} attempt(s)\t{}",
+                    delivery.id,
+                    delivery.webhook.id,
+                    delivery.computation_offset,
+                    delivery.attempts,
+                    delivery.last_error.unwrap_or_default()
+                );
+            }
+        }
+        WebhookCommand::Replay { delivery } => {
+            let replayed = db.replay_dead_letters(delivery)?;
+            println!("queued {replayed} delivery(ies) again");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Transactions the indexer consumes, live from RPC or from a recording.
+//!
+//! A recording is a JSON-lines file with one [`LoggedTransaction`] per line,
+//! oldest first:
+//!
+//! ```json
+//! {"signature":"5h6x...","slot":312004551,"block_time":1760000000,"logs":["Program ... invoke [1]","Program data: ..."]}
+//! ```
+//!
+//! `evalys-mxe-indexer run --record <file>` appends every transaction it
+//! fetches, so a recording replays exactly what a live run indexed.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use evalys_mxe_client::events::{FailedEvent, ResultEvent};
+use evalys_mxe_client::rpc::ConfirmedTransaction;
+use serde::{Deserialize, Serialize};
+
+/// Log messages of one confirmed program transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoggedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    /// Unix timestamp of the block; `None` if the node did not record one
+    pub block_time: Option<i64>,
+    pub logs: Vec<String>,
+}
+
+impl LoggedTransaction {
+    /// Logs of transaction `signature` as fetched over RPC
+    pub fn confirmed(signature: &str, transaction: ConfirmedTransaction) -> Self {
+        LoggedTransaction {
+            signature: signature.to_string(),
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+            logs: transaction.logs,
+        }
+    }
+
+    /// Result events logged by this transaction, in log order
+    pub fn result_events(&self) -> Result<Vec<ResultEvent>> {
+        let mut events = Vec::new();
+        for log in &self.logs {
+            if let Some(event) = ResultEvent::from_log(log)
+                .with_context(|| format!("decoding result event of {}", self.signature))?
+            {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+
+    /// `ComputationFailedEvent`s logged by this transaction, in log order
+    pub fn failure_events(&self) -> Result<Vec<FailedEvent>> {
+        let mut events = Vec::new();
+        for log in &self.logs {
+            if let Some(event) = FailedEvent::from_log(log)
+                .with_context(|| format!("decoding failure event of {}", self.signature))?
+            {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Read a recording, oldest transaction first
+pub fn read_recording(path: &Path) -> Result<Vec<LoggedTransaction>> {
+    let recording = fs::read_to_string(path)
+        .with_context(|| format!("reading recording {}", path.display()))?;
+    recording
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("{}:{}: invalid transaction", path.display(), index + 1))
+        })
+        .collect()
+}
+
+/// Append one transaction to a recording
+pub fn append_recording(path: &Path, transaction: &LoggedTransaction) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("opening recording {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(transaction)?)?;
+    Ok(())
+}
@@ -0,0 +1,264 @@
+//! Push delivery of indexed computations to registered webhooks.
+//!
+//! Every result or failure indexed after a webhook is registered is queued
+//! for it ([`Db::index`]). [`Dispatcher::dispatch`] POSTs the due deliveries
+//! as JSON:
+//!
+//! ```json
+//! {"computation_offset":1001,"circuit":"evalys_gmpc_strategy","status":"completed",
+//!  "requester":"...","client_key_id":1,"ciphertexts":["..."],"nonce":"...",
+//!  "signature":"...","slot":312000100,"block_time":1760000040}
+//! ```
+//!
+//! `status` is the request record's final status, `completed` or `failed`;
+//! failures carry no output. Webhooks registered with `decrypt` get the
+//! decrypted `output` in place of `ciphertexts` and `nonce`, which needs the
+//! dispatcher to hold the client keystore.
+//!
+//! Each request is signed with the webhook's secret: [`TIMESTAMP_HEADER`]
+//! holds the Unix time of the attempt and [`SIGNATURE_HEADER`]
+//! `sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">`; check it with
+//! [`verify`], which also refuses timestamps more than
+//! [`TIMESTAMP_TOLERANCE`] away from the receiver's clock so a captured
+//! request cannot be replayed later. [`DELIVERY_HEADER`] stays the same across retries of one
+//! delivery. A non-2xx answer or a transport error is retried with
+//! exponential backoff ([`RetryPolicy`]); a delivery that runs out of
+//! attempts is dead-lettered until replayed with
+//! [`Db::replay_dead_letters`].
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use evalys_mxe_client::cipher::ClientCipher;
+use evalys_mxe_client::events::ResultEvent;
+use evalys_mxe_client::keys::Keystore;
+use evalys_mxe_client::outputs::{
+    AggregatedMetrics, ExecutionRecommendation, PlanOutput, RiskAssessment, StrategyPlan,
+};
+use evalys_mxe_client::state::CircuitKind;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::api::result_json;
+use crate::db::{Db, Delivery, Outcome};
+
+pub const SIGNATURE_HEADER: &str = "X-Evalys-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Evalys-Timestamp";
+pub const DELIVERY_HEADER: &str = "X-Evalys-Delivery";
+/// Largest difference [`verify`] accepts between a request's timestamp and
+/// the receiver's clock
+pub const TIMESTAMP_TOLERANCE: Duration = Duration::from_secs(5 * 60);
+
+/// Deliveries one [`Dispatcher::dispatch`] call sends at most
+const BATCH: usize = 100;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// When failed deliveries are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts before a delivery is dead-lettered
+    pub max_attempts: u32,
+    /// Delay after the first failed attempt; doubles after each one
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 8,
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the next attempt after `attempts` failed ones, or `None`
+    /// once they are used up
+    pub fn delay(&self, attempts: u32) -> Option<Duration> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+        let factor = 1u32
+            .checked_shl(attempts.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        Some(self.base_delay.saturating_mul(factor).min(self.max_delay))
+    }
+}
+
+/// `sha256=<hex>` signature of a payload sent at `timestamp`
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("any key length");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Whether `header` is the signature of `body` sent at `timestamp`, compared
+/// in constant time, and `timestamp` is within [`TIMESTAMP_TOLERANCE`] of
+/// `now` (Unix seconds)
+pub fn verify(secret: &str, timestamp: i64, body: &str, header: &str, now: i64) -> bool {
+    if timestamp.abs_diff(now) > TIMESTAMP_TOLERANCE.as_secs() {
+        return false;
+    }
+    let Some(tag) = header
+        .strip_prefix("sha256=")
+        .and_then(|tag| hex::decode(tag).ok())
+    else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("any key length");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    mac.verify_slice(&tag).is_ok()
+}
+
+/// Decrypts result events with the client keys they were encrypted to
+pub struct Decryptor {
+    keystore: Keystore,
+    mxe_x25519_pubkey: [u8; 32],
+}
+
+impl Decryptor {
+    pub fn new(keystore: Keystore, mxe_x25519_pubkey: [u8; 32]) -> Self {
+        Decryptor {
+            keystore,
+            mxe_x25519_pubkey,
+        }
+    }
+
+    /// Decrypted output of `event` as JSON
+    pub fn output(&self, event: &ResultEvent) -> Result<Value> {
+        let key = self.keystore.get(event.client_key_id)?;
+        let cipher = ClientCipher::new(&key.secret(), &self.mxe_x25519_pubkey);
+        Ok(match event.kind {
+            CircuitKind::StrategyPlan => {
+                serde_json::to_value(event.decrypt::<StrategyPlan>(&cipher)?)?
+            }
+            CircuitKind::RiskScore => {
+                serde_json::to_value(event.decrypt::<RiskAssessment>(&cipher)?)?
+            }
+            CircuitKind::CurveEval => {
+                serde_json::to_value(event.decrypt::<ExecutionRecommendation>(&cipher)?)?
+            }
+            CircuitKind::GmpcStrategy | CircuitKind::GmpcStrategyQuorum => {
+                serde_json::to_value(event.decrypt::<PlanOutput>(&cipher)?)?
+            }
+            CircuitKind::MultiUserAnalytics => {
+                serde_json::to_value(event.decrypt::<AggregatedMetrics>(&cipher)?)?
+            }
+        })
+    }
+}
+
+/// Payload of a delivery; `output` replaces the ciphertexts of a result
+pub fn payload(outcome: &Outcome, output: Option<Value>) -> Value {
+    match outcome {
+        Outcome::Completed(result) => {
+            let mut body = result_json(result);
+            body["status"] = json!("completed");
+            if let (Some(output), Some(fields)) = (output, body.as_object_mut()) {
+                fields.remove("ciphertexts");
+                fields.remove("nonce");
+                fields.insert("output".to_string(), output);
+            }
+            body
+        }
+        Outcome::Failed(failure) => json!({
+            "computation_offset": failure.event.computation_offset,
+            "circuit": failure.event.kind.encrypted_ix(),
+            "status": "failed",
+            "signature": failure.signature,
+            "slot": failure.slot,
+            "block_time": failure.block_time,
+        }),
+    }
+}
+
+/// What one [`Dispatcher::dispatch`] call did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DispatchReport {
+    pub delivered: usize,
+    pub retrying: usize,
+    pub dead_lettered: usize,
+}
+
+/// Sends queued deliveries
+pub struct Dispatcher {
+    agent: ureq::Agent,
+    policy: RetryPolicy,
+    decryptor: Option<Decryptor>,
+}
+
+impl Dispatcher {
+    pub fn new(policy: RetryPolicy, decryptor: Option<Decryptor>) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .http_status_as_error(false)
+            .build()
+            .into();
+        Dispatcher {
+            agent,
+            policy,
+            decryptor,
+        }
+    }
+
+    /// Send every delivery due at `now` (Unix seconds) once
+    pub fn dispatch(&self, db: &Db, now: i64) -> Result<DispatchReport> {
+        let mut report = DispatchReport::default();
+        for delivery in db.due_deliveries(now, BATCH)? {
+            match self.send(db, &delivery, now) {
+                Ok(()) => {
+                    db.delivered(delivery.id)?;
+                    report.delivered += 1;
+                }
+                Err(err) => {
+                    let next_attempt = self
+                        .policy
+                        .delay(delivery.attempts + 1)
+                        .map(|delay| now.saturating_add(delay.as_secs() as i64));
+                    db.attempt_failed(delivery.id, &format!("{err:#}"), next_attempt)?;
+                    match next_attempt {
+                        Some(_) => report.retrying += 1,
+                        None => report.dead_lettered += 1,
+                    }
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    fn send(&self, db: &Db, delivery: &Delivery, now: i64) -> Result<()> {
+        let offset = delivery.computation_offset;
+        let outcome = db
+            .outcome(offset)?
+            .ok_or_else(|| anyhow!("computation {offset} is not indexed"))?;
+        let output = match (&outcome, delivery.webhook.decrypt) {
+            (Outcome::Completed(result), true) => {
+                let decryptor = self.decryptor.as_ref().ok_or_else(|| {
+                    anyhow!("webhook wants decrypted results but no keystore is loaded")
+                })?;
+                Some(decryptor.output(&result.event)?)
+            }
+            _ => None,
+        };
+        let body = payload(&outcome, output).to_string();
+        let response = self
+            .agent
+            .post(&delivery.webhook.url)
+            .header("Content-Type", "application/json")
+            .header(TIMESTAMP_HEADER, now.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                signature(&delivery.webhook.secret, now, &body),
+            )
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .send(&body)?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("{} answered {status}", delivery.webhook.url);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+//! Index `fixtures/result-events.jsonl` and query it through the database and
+//! the HTTP handler.
+//!
+//! The recording holds a request, five callbacks (one per result event, the
+//! multi-user one without a block time), a `ComputationFailedEvent` and a
+//! replay of the first callback.
+
+use std::path::Path;
+
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::Pubkey;
+use evalys_mxe_indexer::api;
+use evalys_mxe_indexer::db::{Db, ResultQuery};
+use evalys_mxe_indexer::source;
+
+const REQUESTER_A: &str = "GWAQHcJbStXXwdS2NsQUWj6ZbqZnwwQanj8vYbbziE3S";
+const REQUESTER_B: &str = "DF4qVzVv8nbX6L6Z5DKNapc192qSCEBeihvABb22jzMF";
+const LARGE_OFFSET: u64 = 18_446_744_073_709_551_000;
+
+fn indexed() -> Db {
+    let recording =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/result-events.jsonl");
+    let mut db = Db::open_in_memory().unwrap();
+    for transaction in source::read_recording(&recording).unwrap() {
+        db.index(&transaction).unwrap();
+    }
+    db
+}
+
+fn offsets(db: &Db, query: &ResultQuery) -> Vec<u64> {
+    db.query(query)
+        .unwrap()
+        .iter()
+        .map(|result| result.event.computation_offset)
+        .collect()
+}
+
+#[test]
+fn indexes_every_result_event_once() {
+    let db = indexed();
+    assert_eq!(
+        offsets(&db, &ResultQuery::default()),
+        [1001, 1002, 1003, LARGE_OFFSET, 1005]
+    );
+}
+
+#[test]
+fn looks_up_by_computation_offset() {
+    let db = indexed();
+    let result = db.result(1001).unwrap().unwrap();
+    assert_eq!(result.event.kind, CircuitKind::StrategyPlan);
+    assert_eq!(
+        result.event.requester,
+        REQUESTER_A.parse::<Pubkey>().unwrap()
+    );
+    assert_eq!(result.event.client_key_id, 1);
+    assert_eq!(result.event.ciphertexts.len(), 21);
+    assert_eq!(result.slot, 312_000_100);
+    assert_eq!(result.block_time, Some(1_760_000_040));
+
+    let large = db.result(LARGE_OFFSET).unwrap().unwrap();
+    assert_eq!(large.event.kind, CircuitKind::GmpcStrategy);
+    assert_eq!(large.event.computation_offset, LARGE_OFFSET);
+
+    assert_eq!(db.result(1006).unwrap(), None);
+}
+
+#[test]
+fn filters_by_requester_and_time_range() {
+    let db = indexed();
+    let requester_a = Some(REQUESTER_A.parse().unwrap());
+    let requester_b = Some(REQUESTER_B.parse().unwrap());
+
+    let query = ResultQuery {
+        requester: requester_a,
+        ..Default::default()
+    };
+    assert_eq!(offsets(&db, &query), [1001, 1002, 1005]);
+
+    let query = ResultQuery {
+        requester: requester_b,
+        ..Default::default()
+    };
+    assert_eq!(offsets(&db, &query), [1003, LARGE_OFFSET]);
+
+    // Bounds are inclusive; results without a block time never match a range
+    let query = ResultQuery {
+        from: Some(1_760_000_600),
+        to: Some(1_760_001_800),
+        ..Default::default()
+    };
+    assert_eq!(offsets(&db, &query), [1002, 1003, LARGE_OFFSET]);
+
+    let query = ResultQuery {
+        requester: requester_a,
+        from: Some(1_760_000_100),
+        ..Default::default()
+    };
+    assert_eq!(offsets(&db, &query), [1002]);
+
+    let query = ResultQuery {
+        limit: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(offsets(&db, &query), [1001, 1002]);
+}
+
+#[test]
+fn serves_lookups_over_http_routes() {
+    let db = indexed();
+
+    let response = api::handle(&db, "GET", "/results/1003");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body["circuit"], "confidential_curve_eval");
+    assert_eq!(response.body["requester"], REQUESTER_B);
+    assert_eq!(response.body["ciphertexts"].as_array().unwrap().len(), 7);
+
+    let response = api::handle(
+        &db,
+        "GET",
+        &format!("/results?requester={REQUESTER_A}&to=1760000600"),
+    );
+    assert_eq!(response.status, 200);
+    let results = response.body["results"].as_array().unwrap();
+    let offsets: Vec<_> = results
+        .iter()
+        .map(|result| result["computation_offset"].as_u64().unwrap())
+        .collect();
+    assert_eq!(offsets, [1001, 1002]);
+
+    assert_eq!(api::handle(&db, "GET", "/results/1006").status, 404);
+    assert_eq!(api::handle(&db, "GET", "/results/abc").status, 400);
+    assert_eq!(
+        api::handle(&db, "GET", "/results?requester=nope").status,
+        400
+    );
+    assert_eq!(api::handle(&db, "GET", "/results?page=2").status, 400);
+    assert_eq!(api::handle(&db, "GET", "/plans").status, 404);
+    assert_eq!(api::handle(&db, "POST", "/results").status, 405);
+}
@@ -0,0 +1,334 @@
+//! Webhook deliveries of `fixtures/result-events.jsonl` and of a mock
+//! callback to a local receiver.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use evalys_mxe_client::arcium::ArciumDeployment;
+use evalys_mxe_client::cipher::ClientCipher;
+use evalys_mxe_client::inputs::IntentInput;
+use evalys_mxe_client::instructions::{self, RequestAccounts};
+use evalys_mxe_client::keys::Keystore;
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::Pubkey;
+use evalys_mxe_indexer::db::Db;
+use evalys_mxe_indexer::source::{self, LoggedTransaction};
+use evalys_mxe_indexer::webhook::{
+    self, Decryptor, DispatchReport, Dispatcher, RetryPolicy, DELIVERY_HEADER, SIGNATURE_HEADER,
+    TIMESTAMP_HEADER, TIMESTAMP_TOLERANCE,
+};
+use evalys_mxe_mock::MockCluster;
+use serde_json::{json, Value};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+const SECRET: &str = "webhook-secret";
+const NOW: i64 = 1_760_010_000;
+
+/// One request the receiver got
+struct Received {
+    timestamp: i64,
+    signature: String,
+    delivery: String,
+    body: String,
+}
+
+impl Received {
+    fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap()
+    }
+}
+
+/// Local webhook endpoint answering with the status in the returned handle
+fn receiver() -> (String, Arc<AtomicU16>, Receiver<Received>) {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", server.server_addr().to_ip().unwrap());
+    let status = Arc::new(AtomicU16::new(200));
+    let (sender, received) = mpsc::channel();
+    let answer = status.clone();
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let header = |name: &str| {
+                request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.to_string().eq_ignore_ascii_case(name))
+                    .map(|header| header.value.to_string())
+                    .unwrap_or_default()
+            };
+            let timestamp = header(TIMESTAMP_HEADER).parse().unwrap_or_default();
+            let signature = header(SIGNATURE_HEADER);
+            let delivery = header(DELIVERY_HEADER);
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            sender
+                .send(Received {
+                    timestamp,
+                    signature,
+                    delivery,
+                    body,
+                })
+                .unwrap();
+            let status = answer.load(Ordering::SeqCst);
+            request.respond(tiny_http::Response::empty(status)).unwrap();
+        }
+    });
+    (url, status, received)
+}
+
+fn index_recording(db: &mut Db) {
+    let recording =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/result-events.jsonl");
+    for transaction in source::read_recording(&recording).unwrap() {
+        db.index(&transaction).unwrap();
+    }
+}
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_secs(10),
+        max_delay: Duration::from_secs(60),
+    }
+}
+
+#[test]
+fn pushes_signed_results_and_failures_once() {
+    let (url, _, received) = receiver();
+    let mut db = Db::open_in_memory().unwrap();
+    db.add_webhook(&url, SECRET, false).unwrap();
+    index_recording(&mut db);
+
+    let dispatcher = Dispatcher::new(policy(), None);
+    let report = dispatcher.dispatch(&db, NOW).unwrap();
+    assert_eq!(
+        report,
+        DispatchReport {
+            delivered: 6,
+            ..Default::default()
+        }
+    );
+    let received: Vec<_> = received.try_iter().collect();
+    assert_eq!(received.len(), 6);
+    for request in &received {
+        assert_eq!(request.timestamp, NOW);
+        assert!(webhook::verify(
+            SECRET,
+            request.timestamp,
+            &request.body,
+            &request.signature,
+            NOW
+        ));
+        assert!(!webhook::verify(
+            SECRET,
+            request.timestamp + 1,
+            &request.body,
+            &request.signature,
+            NOW
+        ));
+        assert!(!webhook::verify(
+            "other-secret",
+            request.timestamp,
+            &request.body,
+            &request.signature,
+            NOW
+        ));
+        assert!(!request.delivery.is_empty());
+    }
+
+    let completed = received
+        .iter()
+        .map(Received::json)
+        .find(|body| body["computation_offset"] == 1003)
+        .unwrap();
+    assert_eq!(completed["status"], "completed");
+    assert_eq!(completed["circuit"], "confidential_curve_eval");
+    assert_eq!(completed["ciphertexts"].as_array().unwrap().len(), 7);
+
+    let failed = received
+        .iter()
+        .map(Received::json)
+        .find(|body| body["status"] == "failed")
+        .unwrap();
+    assert_eq!(failed["computation_offset"], 1006);
+    assert_eq!(failed["circuit"], "confidential_curve_eval");
+    assert!(failed.get("ciphertexts").is_none());
+
+    // Delivered once; re-indexing the same transactions queues nothing
+    index_recording(&mut db);
+    assert_eq!(
+        dispatcher.dispatch(&db, NOW + 3600).unwrap(),
+        DispatchReport::default()
+    );
+}
+
+#[test]
+fn retries_with_backoff_then_dead_letters() {
+    let (url, status, received) = receiver();
+    status.store(500, Ordering::SeqCst);
+    let mut db = Db::open_in_memory().unwrap();
+    let webhook = db.add_webhook(&url, SECRET, false).unwrap();
+    index_recording(&mut db);
+    let dispatcher = Dispatcher::new(policy(), None);
+
+    let report = dispatcher.dispatch(&db, NOW).unwrap();
+    assert_eq!(report.retrying, 6);
+    // Not due again before the backoff elapses: 10 s, then 20 s
+    assert_eq!(
+        dispatcher.dispatch(&db, NOW + 9).unwrap(),
+        DispatchReport::default()
+    );
+    assert_eq!(dispatcher.dispatch(&db, NOW + 10).unwrap().retrying, 6);
+    assert_eq!(
+        dispatcher.dispatch(&db, NOW + 29).unwrap(),
+        DispatchReport::default()
+    );
+    assert_eq!(dispatcher.dispatch(&db, NOW + 30).unwrap().dead_lettered, 6);
+    assert_eq!(received.try_iter().count(), 18);
+
+    let dead = db.dead_letters().unwrap();
+    assert_eq!(dead.len(), 6);
+    assert!(dead.iter().all(|delivery| delivery.attempts == 3
+        && delivery.webhook.id == webhook
+        && delivery.last_error.as_deref().unwrap().contains("500")));
+    assert_eq!(
+        dispatcher.dispatch(&db, NOW + 3600).unwrap(),
+        DispatchReport::default()
+    );
+
+    status.store(204, Ordering::SeqCst);
+    assert_eq!(db.replay_dead_letters(Some(dead[0].id)).unwrap(), 1);
+    assert_eq!(dispatcher.dispatch(&db, NOW + 3600).unwrap().delivered, 1);
+    assert_eq!(db.dead_letters().unwrap().len(), 5);
+    assert_eq!(db.replay_dead_letters(None).unwrap(), 5);
+    assert_eq!(dispatcher.dispatch(&db, NOW + 3600).unwrap().delivered, 5);
+
+    assert!(db.remove_webhook(webhook).unwrap());
+    assert!(!db.remove_webhook(webhook).unwrap());
+    assert!(db.webhooks().unwrap().is_empty());
+}
+
+#[test]
+fn refuses_stale_and_future_timestamps() {
+    let body = r#"{"computation_offset":1001}"#;
+    let tolerance = TIMESTAMP_TOLERANCE.as_secs() as i64;
+    let signed = |timestamp| webhook::signature(SECRET, timestamp, body);
+
+    assert!(webhook::verify(SECRET, NOW, body, &signed(NOW), NOW));
+    let sent = NOW - tolerance;
+    assert!(webhook::verify(SECRET, sent, body, &signed(sent), NOW));
+    let sent = NOW + tolerance;
+    assert!(webhook::verify(SECRET, sent, body, &signed(sent), NOW));
+
+    // Correctly signed, but replayed too late or sent from too far ahead
+    let stale = NOW - tolerance - 1;
+    assert!(!webhook::verify(SECRET, stale, body, &signed(stale), NOW));
+    let future = NOW + tolerance + 1;
+    assert!(!webhook::verify(SECRET, future, body, &signed(future), NOW));
+}
+
+#[test]
+fn backoff_doubles_up_to_the_cap() {
+    let policy = policy();
+    let delays: Vec<_> = (1..=3).map(|attempts| policy.delay(attempts)).collect();
+    assert_eq!(
+        delays,
+        [
+            Some(Duration::from_secs(10)),
+            Some(Duration::from_secs(20)),
+            None
+        ]
+    );
+    let policy = RetryPolicy {
+        max_attempts: 40,
+        ..policy
+    };
+    assert_eq!(policy.delay(39), Some(Duration::from_secs(60)));
+}
+
+#[test]
+fn decrypts_results_for_decrypt_webhooks() {
+    let mxe_secret = StaticSecret::from([1; 32]);
+    let client_secret = StaticSecret::from([4; 32]);
+    let mut cluster = MockCluster::new(mxe_secret);
+    cluster.register_circuit(CircuitKind::GmpcStrategy, [0; 32]);
+    let mxe_x25519_pubkey = cluster.public_key();
+    let cipher = ClientCipher::new(&client_secret, &mxe_x25519_pubkey);
+
+    let program_id = Pubkey::new_from_array([3; 32]);
+    let deployment = ArciumDeployment {
+        arcium_program: Pubkey::new_from_array([1; 32]),
+        cluster_offset: 0,
+        pool_account: Pubkey::new_from_array([2; 32]),
+        clock_account: Pubkey::new_from_array([3; 32]),
+    };
+    let computation_offset = 42;
+    let accounts = RequestAccounts {
+        payer: Pubkey::new_from_array([2; 32]),
+        computation_offset,
+        arcium: deployment.accounts(
+            &program_id,
+            CircuitKind::GmpcStrategy.encrypted_ix(),
+            computation_offset,
+        ),
+    };
+    let intent = cipher.encrypt(&IntentInput {
+        max_size_sol: 3_000_000_000,
+        risk_level: 2,
+        privacy_priority: 1,
+        market_price: 1_000,
+        curve_position: 4_000,
+        volatility_score: 7_000,
+        avg_hold_time: 900,
+        win_rate: 5_500,
+        max_dd: 1_800,
+    });
+    cluster
+        .queue(&instructions::request_gmpc_strategy(
+            &program_id,
+            &accounts,
+            1,
+            &intent,
+        ))
+        .unwrap();
+    let callback = cluster.execute_all().unwrap().remove(0);
+
+    let (url, _, received) = receiver();
+    let mut db = Db::open_in_memory().unwrap();
+    db.add_webhook(&url, SECRET, true).unwrap();
+    db.index(&LoggedTransaction {
+        signature: "mock-callback".to_string(),
+        slot: 1,
+        block_time: Some(NOW),
+        logs: callback.logs,
+    })
+    .unwrap();
+
+    // Without the keystore the delivery waits for a retry
+    let report = Dispatcher::new(policy(), None).dispatch(&db, NOW).unwrap();
+    assert_eq!(report.retrying, 1);
+    assert_eq!(received.try_iter().count(), 0);
+
+    let keystore: Keystore = serde_json::from_value(json!({
+        "keys": [{
+            "key_id": 1,
+            "secret_key": hex::encode(client_secret.to_bytes()),
+            "public_key": hex::encode(PublicKey::from(&client_secret).to_bytes()),
+            "status": "active",
+        }]
+    }))
+    .unwrap();
+    let dispatcher = Dispatcher::new(policy(), Some(Decryptor::new(keystore, mxe_x25519_pubkey)));
+    assert_eq!(dispatcher.dispatch(&db, NOW + 10).unwrap().delivered, 1);
+    let body = received.try_iter().next().unwrap().json();
+    assert_eq!(body["computation_offset"], computation_offset);
+    assert_eq!(body["status"], "completed");
+    assert!(body.get("ciphertexts").is_none());
+    // High risk, normal volatility: full size, stealth slicing over Jito
+    // (plus up to one jittered slice)
+    assert_eq!(body["output"]["recommended_size_sol"], 3_000_000_000u64);
+    assert!((5..=6).contains(&body["output"]["slice_count"].as_u64().unwrap()));
+}
@@ -0,0 +1,399 @@
+//! The mock cluster: queue requests, run the reference circuits, emit the
+//! callbacks' result events.
+
+use std::collections::{HashMap, VecDeque};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use evalys_mxe_client::events::event_discriminator;
+use evalys_mxe_client::inputs::Scalar;
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_reference::{curve, gmpc, multi_user, risk, strategy};
+use num_bigint::BigUint;
+use solana_instruction::Instruction;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::fields::{owner_cipher, Fields};
+use crate::request::{PolicyUpdate, QueuedComputation, RequestArgs};
+use crate::MockError;
+
+/// What the program's callback leaves behind for one computation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Callback {
+    pub kind: CircuitKind,
+    pub computation_offset: u64,
+    /// `Program data:` line of the result event
+    pub logs: Vec<String>,
+    /// Revealed digest of a quorum leg
+    pub quorum_digest: Option<u128>,
+}
+
+/// Contents of the program's policy accounts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Policies {
+    strategy: strategy::StrategyPolicy,
+    risk: risk::RiskPolicy,
+    curve: curve::CurvePolicy,
+    gmpc: gmpc::GmpcPolicy,
+}
+
+/// Stand-in for an Arcium cluster and the MXE program's callbacks
+pub struct MockCluster {
+    secret: StaticSecret,
+    circuits: HashMap<CircuitKind, [u8; 32]>,
+    policies: Policies,
+    /// Each computation with the policies in force when it was queued, which
+    /// the program passes as circuit arguments
+    queue: VecDeque<(QueuedComputation, Policies)>,
+}
+
+fn commitment_words(commitment: &[u8; 32]) -> (u128, u128) {
+    (
+        u128::from_be_bytes(commitment[..16].try_into().unwrap()),
+        u128::from_be_bytes(commitment[16..].try_into().unwrap()),
+    )
+}
+
+fn event_name(kind: CircuitKind) -> &'static str {
+    match kind {
+        CircuitKind::StrategyPlan => "StrategyPlanEvent",
+        CircuitKind::RiskScore => "RiskScoreEvent",
+        CircuitKind::CurveEval => "CurveEvalEvent",
+        CircuitKind::GmpcStrategy | CircuitKind::GmpcStrategyQuorum => "GmpcStrategyEvent",
+        CircuitKind::MultiUserAnalytics => "MultiUserAnalyticsEvent",
+    }
+}
+
+fn plan_fields(plan: &gmpc::PlanOutput) -> Vec<BigUint> {
+    vec![
+        plan.recommended_size_sol.to_field(),
+        plan.slice_count.to_field(),
+        plan.time_window_sec.to_field(),
+        plan.mev_route.to_field(),
+        plan.privacy_mode.to_field(),
+        plan.risk_class.to_field(),
+        plan.input_commitment.hi.to_field(),
+        plan.input_commitment.lo.to_field(),
+    ]
+}
+
+fn intent_input(
+    secret: &StaticSecret,
+    intent: &evalys_mxe_client::inputs::EncryptedInput,
+) -> Result<gmpc::IntentInput, MockError> {
+    let mut f = Fields::decrypt(secret, "intent", intent);
+    let input = gmpc::IntentInput {
+        max_size_sol: f.next("max_size_sol")?,
+        risk_level: f.next("risk_level")?,
+        privacy_priority: f.next("privacy_priority")?,
+        market_price: f.next("market_price")?,
+        curve_position: f.next("curve_position")?,
+        volatility_score: f.next("volatility_score")?,
+        avg_hold_time: f.next("avg_hold_time")?,
+        win_rate: f.next("win_rate")?,
+        max_dd: f.next("max_dd")?,
+    };
+    f.finish()?;
+    Ok(input)
+}
+
+impl MockCluster {
+    /// Cluster whose MXE holds `secret`; clients encrypt to [`Self::public_key`]
+    pub fn new(secret: StaticSecret) -> Self {
+        MockCluster {
+            secret,
+            circuits: HashMap::new(),
+            policies: Policies::default(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// MXE x25519 public key
+    pub fn public_key(&self) -> [u8; 32] {
+        PublicKey::from(&self.secret).to_bytes()
+    }
+
+    /// Register the comp def of `kind`, as `init_*_comp_def` does
+    pub fn register_circuit(&mut self, kind: CircuitKind, circuit_hash: [u8; 32]) {
+        self.circuits.insert(kind, circuit_hash);
+    }
+
+    /// Apply a `set_*_policy` instruction. Policies start out as the
+    /// reference defaults, which `evalys-mxe deploy` stores.
+    pub fn set_policy(&mut self, instruction: &Instruction) -> Result<(), MockError> {
+        match PolicyUpdate::from_instruction(instruction)? {
+            PolicyUpdate::Strategy(policy) => self.policies.strategy = policy,
+            PolicyUpdate::Risk(policy) => self.policies.risk = policy,
+            PolicyUpdate::Curve(policy) => self.policies.curve = policy,
+            PolicyUpdate::Gmpc(policy) => self.policies.gmpc = policy,
+        }
+        Ok(())
+    }
+
+    /// Accept a `request_*` instruction, as `queue_computation` does
+    pub fn queue(&mut self, instruction: &Instruction) -> Result<&QueuedComputation, MockError> {
+        let computation = QueuedComputation::from_instruction(instruction)?;
+        if !self.circuits.contains_key(&computation.kind) {
+            return Err(MockError::UnregisteredCircuit(computation.kind));
+        }
+        self.queue.push_back((computation, self.policies));
+        Ok(&self.queue.back().unwrap().0)
+    }
+
+    /// Computations queued and not yet executed, oldest first
+    pub fn pending(&self) -> impl Iterator<Item = &QueuedComputation> {
+        self.queue.iter().map(|(computation, _)| computation)
+    }
+
+    /// Execute the oldest queued computation
+    pub fn execute_next(&mut self) -> Result<Option<Callback>, MockError> {
+        match self.queue.pop_front() {
+            Some((computation, policies)) => self.execute(&computation, &policies).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Execute every queued computation in order
+    pub fn execute_all(&mut self) -> Result<Vec<Callback>, MockError> {
+        let mut callbacks = Vec::with_capacity(self.queue.len());
+        while let Some(callback) = self.execute_next()? {
+            callbacks.push(callback);
+        }
+        Ok(callbacks)
+    }
+
+    fn execute(
+        &self,
+        computation: &QueuedComputation,
+        policies: &Policies,
+    ) -> Result<Callback, MockError> {
+        let secret = &self.secret;
+        let (hi, lo) = commitment_words(&computation.input_commitment);
+        // In-MPC randomness, reproducible per computation
+        let rng_seed = computation.computation_offset;
+        let mut quorum_digest = None;
+
+        let fields = match &computation.args {
+            RequestArgs::StrategyPlan {
+                preferences,
+                history,
+                curve_state,
+            } => {
+                let mut f = Fields::decrypt(secret, "preferences", preferences);
+                let preferences = strategy::UserPreferences {
+                    desired_size: f.next("desired_size")?,
+                    slippage_tolerance: f.next("slippage_tolerance")?,
+                    risk_appetite: f.next("risk_appetite")?,
+                    preferred_hold_time: f.next("preferred_hold_time")?,
+                };
+                f.finish()?;
+                let mut f = Fields::decrypt(secret, "history", history);
+                let history = strategy::UserHistory {
+                    recent_pnl: f.next("recent_pnl")?,
+                    win_rate: f.next("win_rate")?,
+                    avg_hold_time: f.next("avg_hold_time")?,
+                    total_trades: f.next("total_trades")?,
+                };
+                f.finish()?;
+                let plan = evalys_mxe_reference::strategy_plan(
+                    preferences,
+                    history,
+                    *curve_state,
+                    policies.strategy,
+                    strategy::InputCommitment { hi, lo },
+                    rng_seed,
+                );
+                let mut fields = vec![
+                    plan.recommended_mode.to_field(),
+                    plan.num_slices.to_field(),
+                    plan.slice_size_base.to_field(),
+                    plan.timing_window_sec.to_field(),
+                    plan.risk_level.to_field(),
+                    plan.max_notional.to_field(),
+                ];
+                fields.extend(plan.slice_size_words.iter().map(|n| n.to_field()));
+                fields.extend(plan.slice_delay_words.iter().map(|n| n.to_field()));
+                fields.extend([plan.exit_start_sec.to_field(), plan.exit_window_sec.to_field()]);
+                fields.extend(plan.exit_bps_words.iter().map(|n| n.to_field()));
+                fields.extend(plan.exit_delay_words.iter().map(|n| n.to_field()));
+                fields.extend([
+                    plan.take_profit_price.to_field(),
+                    plan.stop_loss_price.to_field(),
+                ]);
+                fields.extend([
+                    plan.input_commitment.hi.to_field(),
+                    plan.input_commitment.lo.to_field(),
+                ]);
+                fields
+            }
+            RequestArgs::RiskScore {
+                portfolio,
+                performance,
+                market,
+            } => {
+                let mut f = Fields::decrypt(secret, "portfolio", portfolio);
+                let portfolio = risk::PortfolioContext {
+                    total_capital: f.next("total_capital")?,
+                    current_exposure: f.next("current_exposure")?,
+                    diversification_score: f.next("diversification_score")?,
+                    leverage_ratio: f.next("leverage_ratio")?,
+                };
+                f.finish()?;
+                let mut f = Fields::decrypt(secret, "performance", performance);
+                let performance = risk::PerformanceHistory {
+                    total_pnl: f.next("total_pnl")?,
+                    sharpe_ratio: f.next("sharpe_ratio")?,
+                    max_drawdown: f.next("max_drawdown")?,
+                    consistency_score: f.next("consistency_score")?,
+                };
+                f.finish()?;
+                let assessment = evalys_mxe_reference::risk_score(
+                    portfolio,
+                    performance,
+                    *market,
+                    policies.risk,
+                    risk::InputCommitment { hi, lo },
+                );
+                vec![
+                    assessment.overall_risk_score.to_field(),
+                    assessment.portfolio_risk.to_field(),
+                    assessment.trade_risk.to_field(),
+                    assessment.recommendation.to_field(),
+                    assessment.input_commitment.hi.to_field(),
+                    assessment.input_commitment.lo.to_field(),
+                ]
+            }
+            RequestArgs::CurveEval {
+                sizing,
+                constraints,
+                curve,
+            } => {
+                let mut f = Fields::decrypt(secret, "sizing", sizing);
+                let sizing = curve::SizingPreferences {
+                    target_size: f.next("target_size")?,
+                    min_size: f.next("min_size")?,
+                    max_size: f.next("max_size")?,
+                    capital_allocation_pct: f.next("capital_allocation_pct")?,
+                };
+                f.finish()?;
+                let mut f = Fields::decrypt(secret, "constraints", constraints);
+                let constraints = curve::UserConstraints {
+                    max_slippage_bps: f.next("max_slippage_bps")?,
+                    time_constraint_sec: f.next("time_constraint_sec")?,
+                    priority_level: f.next("priority_level")?,
+                };
+                f.finish()?;
+                let recommendation = evalys_mxe_reference::curve_eval(
+                    sizing,
+                    constraints,
+                    *curve,
+                    policies.curve,
+                    curve::InputCommitment { hi, lo },
+                );
+                vec![
+                    recommendation.recommended_size.to_field(),
+                    recommendation.entry_price_target.to_field(),
+                    recommendation.execution_urgency.to_field(),
+                    recommendation.optimal_timing.to_field(),
+                    recommendation.confidence_score.to_field(),
+                    recommendation.input_commitment.hi.to_field(),
+                    recommendation.input_commitment.lo.to_field(),
+                ]
+            }
+            RequestArgs::GmpcStrategy { intent } => {
+                let plan = evalys_mxe_reference::gmpc_strategy(
+                    intent_input(secret, intent)?,
+                    policies.gmpc,
+                    gmpc::InputCommitment { hi, lo },
+                    rng_seed,
+                );
+                plan_fields(&plan)
+            }
+            RequestArgs::GmpcStrategyQuorum { intent, salt, .. } => {
+                let mut f = Fields::decrypt(secret, "salt", salt);
+                let salt = gmpc::QuorumSalt {
+                    salt: f.next("salt")?,
+                };
+                f.finish()?;
+                let (plan, digest) = evalys_mxe_reference::gmpc_strategy_quorum(
+                    intent_input(secret, intent)?,
+                    salt,
+                    policies.gmpc,
+                    gmpc::InputCommitment { hi, lo },
+                );
+                quorum_digest = Some(digest);
+                plan_fields(&plan)
+            }
+            RequestArgs::MultiUserAnalytics {
+                profiles,
+                aggregation_type,
+            } => {
+                let profiles = profiles
+                    .iter()
+                    .map(|profile| {
+                        let mut f = Fields::decrypt(secret, "profile", profile);
+                        let profile = multi_user::UserProfile {
+                            profile_id_hash: f.next("profile_id_hash")?,
+                            avg_hold_time: f.next("avg_hold_time")?,
+                            win_rate: f.next("win_rate")?,
+                            preferred_size_range_min: f.next("preferred_size_range_min")?,
+                            preferred_size_range_max: f.next("preferred_size_range_max")?,
+                            risk_tolerance: f.next("risk_tolerance")?,
+                        };
+                        f.finish()?;
+                        Ok(profile)
+                    })
+                    .collect::<Result<Vec<_>, MockError>>()?;
+                let aggregation_type = match aggregation_type {
+                    0 => multi_user::AggregationType::CurveInsights,
+                    1 => multi_user::AggregationType::MarketSentiment,
+                    2 => multi_user::AggregationType::PatternDetection,
+                    other => return Err(MockError::UnknownAggregationType(*other)),
+                };
+                let metrics = evalys_mxe_reference::multi_user_analytics(
+                    &profiles,
+                    aggregation_type,
+                    multi_user::InputCommitment { hi, lo },
+                );
+                let mut fields = vec![
+                    metrics.avg_hold_time.to_field(),
+                    metrics.avg_win_rate.to_field(),
+                    metrics.common_size_range_min.to_field(),
+                    metrics.common_size_range_max.to_field(),
+                ];
+                fields.extend(metrics.risk_distribution.iter().map(|n| n.to_field()));
+                fields.extend([
+                    metrics.confidence_score.to_field(),
+                    metrics.sample_size.to_field(),
+                    metrics.input_commitment.hi.to_field(),
+                    metrics.input_commitment.lo.to_field(),
+                ]);
+                fields
+            }
+        };
+
+        // The output goes to the owner of the first encrypted input, under
+        // the nonce following that input's
+        let owner = computation.args.encrypted_inputs()[0];
+        let nonce = owner.nonce.wrapping_add(1);
+        let ciphertexts = owner_cipher(secret, &owner.pub_key).encrypt(&fields, nonce);
+
+        let mut event = event_discriminator(event_name(computation.kind)).to_vec();
+        event.extend_from_slice(computation.computation_account.as_ref());
+        event.extend_from_slice(computation.requester.as_ref());
+        event.extend_from_slice(&computation.computation_offset.to_le_bytes());
+        event.extend_from_slice(&self.circuits[&computation.kind]);
+        event.extend_from_slice(&computation.client_key_id.to_le_bytes());
+        for ciphertext in &ciphertexts {
+            event.extend_from_slice(ciphertext);
+        }
+        event.extend_from_slice(&nonce.to_le_bytes());
+
+        Ok(Callback {
+            kind: computation.kind,
+            computation_offset: computation.computation_offset,
+            logs: vec![format!("Program data: {}", STANDARD.encode(event))],
+            quorum_digest,
+        })
+    }
+}
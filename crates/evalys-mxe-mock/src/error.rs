@@ -0,0 +1,32 @@
+use evalys_mxe_client::state::CircuitKind;
+use thiserror::Error;
+
+/// Errors returned by the mock executor
+#[derive(Debug, Error)]
+pub enum MockError {
+    #[error("instruction is not an MXE request")]
+    NotARequest,
+    #[error("instruction is not a policy update")]
+    NotAPolicyUpdate,
+    #[error("request arguments truncated")]
+    Truncated,
+    #[error("{0} bytes left after the request arguments")]
+    TrailingBytes(usize),
+    #[error("request has {0} accounts, fewer than its layout needs")]
+    MissingAccounts(usize),
+    #[error("no circuit registered for {0:?}")]
+    UnregisteredCircuit(CircuitKind),
+    #[error("{0} has the wrong number of ciphertexts")]
+    FieldCount(&'static str),
+    #[error("{input}.{field} does not fit its type")]
+    FieldRange {
+        input: &'static str,
+        field: &'static str,
+    },
+    #[error("{0} profiles, outside 1 to MAX_ANALYTICS_PROFILES")]
+    ProfileCount(usize),
+    #[error("encrypted inputs use different client keys")]
+    ClientKeyMismatch,
+    #[error("unknown aggregation type {0}")]
+    UnknownAggregationType(u8),
+}
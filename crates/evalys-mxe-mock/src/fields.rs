@@ -0,0 +1,88 @@
+//! MXE side of the encryption: decrypt inputs into circuit structs and
+//! encrypt outputs back to their owner.
+
+use evalys_mxe_client::cipher::{field_modulus, shared_secret, RescueCipher};
+use evalys_mxe_client::inputs::EncryptedInput;
+use num_bigint::BigUint;
+use x25519_dalek::StaticSecret;
+
+use crate::MockError;
+
+/// Integer type of a circuit field, read back from the base field
+pub(crate) trait FieldScalar: Sized {
+    fn from_field(value: &BigUint) -> Option<Self>;
+}
+
+macro_rules! unsigned_field_scalar {
+    ($($ty:ty),*) => {
+        $(impl FieldScalar for $ty {
+            fn from_field(value: &BigUint) -> Option<Self> {
+                Self::try_from(value).ok()
+            }
+        })*
+    };
+}
+
+macro_rules! signed_field_scalar {
+    ($($ty:ty),*) => {
+        $(impl FieldScalar for $ty {
+            fn from_field(value: &BigUint) -> Option<Self> {
+                let p = field_modulus();
+                let signed = if value > &(p >> 1) {
+                    -i128::try_from(&(p - value)).ok()?
+                } else {
+                    i128::try_from(value).ok()?
+                };
+                Self::try_from(signed).ok()
+            }
+        })*
+    };
+}
+
+unsigned_field_scalar!(u8, u16, u32, u64, u128);
+signed_field_scalar!(i8, i16, i32, i64);
+
+/// Cipher the MXE shares with the owner of `pub_key`
+pub(crate) fn owner_cipher(secret: &StaticSecret, pub_key: &[u8; 32]) -> RescueCipher {
+    RescueCipher::new(&shared_secret(secret, pub_key))
+}
+
+/// Decrypted fields of one encrypted input, consumed in declaration order
+pub(crate) struct Fields {
+    input: &'static str,
+    values: std::vec::IntoIter<BigUint>,
+}
+
+impl Fields {
+    pub(crate) fn decrypt(
+        secret: &StaticSecret,
+        input: &'static str,
+        encrypted: &EncryptedInput,
+    ) -> Self {
+        let values = owner_cipher(secret, &encrypted.pub_key)
+            .decrypt(&encrypted.ciphertexts, encrypted.nonce);
+        Fields {
+            input,
+            values: values.into_iter(),
+        }
+    }
+
+    pub(crate) fn next<T: FieldScalar>(&mut self, field: &'static str) -> Result<T, MockError> {
+        let value = self
+            .values
+            .next()
+            .ok_or(MockError::FieldCount(self.input))?;
+        T::from_field(&value).ok_or(MockError::FieldRange {
+            input: self.input,
+            field,
+        })
+    }
+
+    /// Check every ciphertext was consumed
+    pub(crate) fn finish(self) -> Result<(), MockError> {
+        match self.values.len() {
+            0 => Ok(()),
+            _ => Err(MockError::FieldCount(self.input)),
+        }
+    }
+}
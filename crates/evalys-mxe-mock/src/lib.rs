@@ -0,0 +1,30 @@
+//! Mock MPC executor for the MXE.
+//!
+//! [`MockCluster`] stands in for an Arcium cluster and the program's
+//! callbacks: it takes the `request_*` instructions built by
+//! `evalys-mxe-client`, decodes them and computes their input commitment as
+//! the program does ([`request`]), decrypts the inputs with the mock MXE key,
+//! runs the plaintext circuits from `evalys-mxe-reference`, encrypts the
+//! output to the owner of the first input and emits the result event in the
+//! program's layout. Tests drive the whole client path — encrypt, request,
+//! decode the event, decrypt, check the commitment — without a cluster.
+//!
+//! This simulates the program on the host; it does not execute it. Key
+//! registration and the callback account checks are not modelled, and a
+//! divergence between this decoding and the program's is only caught where
+//! `tests/program_layout.rs` reads the program source: encrypted argument
+//! widths and result event sizes.
+//!
+//! The program-level harness is separate, outstanding work: loading the built
+//! program into LiteSVM or `solana-program-test` with a stub Arcium program
+//! that records `queue_computation` and invokes the real callbacks. It needs
+//! the SBF build of the program and `arcium-anchor`, neither available to
+//! this workspace.
+
+mod cluster;
+mod error;
+mod fields;
+pub mod request;
+
+pub use cluster::{Callback, MockCluster};
+pub use error::MockError;
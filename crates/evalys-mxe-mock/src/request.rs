@@ -0,0 +1,370 @@
+//! Request instructions decoded the way the program receives them.
+
+use evalys_mxe_client::inputs::{input_commitment, EncryptedInput};
+use evalys_mxe_client::instructions::{instruction_discriminator, MAX_ANALYTICS_PROFILES};
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_reference::{curve, gmpc, risk, strategy};
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+use crate::MockError;
+
+/// Program accounts ahead of the Arcium accounts; quorum legs and requests
+/// of circuits with a policy add one each
+const PROGRAM_ACCOUNTS: usize = 5;
+/// Position of the computation account among the Arcium accounts
+const COMPUTATION_ACCOUNT: usize = 4;
+
+/// Arguments of one `request_*` instruction after the offset and key id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestArgs {
+    StrategyPlan {
+        preferences: EncryptedInput,
+        history: EncryptedInput,
+        curve_state: strategy::CurveState,
+    },
+    RiskScore {
+        portfolio: EncryptedInput,
+        performance: EncryptedInput,
+        market: risk::MarketConditions,
+    },
+    CurveEval {
+        sizing: EncryptedInput,
+        constraints: EncryptedInput,
+        curve: curve::CurveMetrics,
+    },
+    GmpcStrategy {
+        intent: EncryptedInput,
+    },
+    MultiUserAnalytics {
+        profiles: Vec<EncryptedInput>,
+        aggregation_type: u8,
+    },
+    GmpcStrategyQuorum {
+        quorum_offset: u64,
+        intent: EncryptedInput,
+        salt: EncryptedInput,
+    },
+}
+
+impl RequestArgs {
+    /// Encrypted inputs in argument order
+    pub fn encrypted_inputs(&self) -> Vec<&EncryptedInput> {
+        match self {
+            RequestArgs::StrategyPlan {
+                preferences,
+                history,
+                ..
+            } => vec![preferences, history],
+            RequestArgs::RiskScore {
+                portfolio,
+                performance,
+                ..
+            } => vec![portfolio, performance],
+            RequestArgs::CurveEval {
+                sizing,
+                constraints,
+                ..
+            } => vec![sizing, constraints],
+            RequestArgs::GmpcStrategy { intent } => vec![intent],
+            RequestArgs::MultiUserAnalytics { profiles, .. } => profiles.iter().collect(),
+            RequestArgs::GmpcStrategyQuorum { intent, salt, .. } => vec![intent, salt],
+        }
+    }
+}
+
+/// A computation queued with the mock cluster
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedComputation {
+    pub kind: CircuitKind,
+    pub requester: Pubkey,
+    pub computation_offset: u64,
+    pub client_key_id: u32,
+    /// Arcium computation account; result events report it as their id
+    pub computation_account: Pubkey,
+    /// Commitment the program records and passes to the circuit
+    pub input_commitment: [u8; 32],
+    pub args: RequestArgs,
+}
+
+/// Little-endian Borsh reader over instruction arguments
+struct Args<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Args<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], MockError> {
+        if self.data.len() < N {
+            return Err(MockError::Truncated);
+        }
+        let (bytes, rest) = self.data.split_at(N);
+        self.data = rest;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, MockError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn i8(&mut self) -> Result<i8, MockError> {
+        Ok(i8::from_le_bytes(self.take()?))
+    }
+
+    fn u16(&mut self) -> Result<u16, MockError> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    fn i16(&mut self) -> Result<i16, MockError> {
+        Ok(i16::from_le_bytes(self.take()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, MockError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, MockError> {
+        Ok(i32::from_le_bytes(self.take()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, MockError> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    fn encrypted_input(&mut self) -> Result<EncryptedInput, MockError> {
+        let pub_key = self.take()?;
+        let nonce = u128::from_le_bytes(self.take()?);
+        let count = self.u32()? as usize;
+        let ciphertexts = (0..count).map(|_| self.take()).collect::<Result<_, _>>()?;
+        Ok(EncryptedInput {
+            pub_key,
+            nonce,
+            ciphertexts,
+        })
+    }
+
+    fn finish(self) -> Result<(), MockError> {
+        match self.data.len() {
+            0 => Ok(()),
+            left => Err(MockError::TrailingBytes(left)),
+        }
+    }
+}
+
+const REQUESTS: [(&str, CircuitKind); 6] = [
+    ("request_strategy_plan", CircuitKind::StrategyPlan),
+    ("request_risk_score", CircuitKind::RiskScore),
+    ("request_curve_eval", CircuitKind::CurveEval),
+    ("request_gmpc_strategy", CircuitKind::GmpcStrategy),
+    (
+        "request_multi_user_analytics",
+        CircuitKind::MultiUserAnalytics,
+    ),
+    (
+        "request_gmpc_strategy_quorum",
+        CircuitKind::GmpcStrategyQuorum,
+    ),
+];
+
+impl QueuedComputation {
+    /// Decode a `request_*` instruction and compute its input commitment as
+    /// the program does
+    pub fn from_instruction(instruction: &Instruction) -> Result<Self, MockError> {
+        let data = &instruction.data;
+        let &(_, kind) = REQUESTS
+            .iter()
+            .find(|(name, _)| data.len() >= 8 && data[..8] == instruction_discriminator(name))
+            .ok_or(MockError::NotARequest)?;
+        let mut r = Args { data: &data[8..] };
+
+        let computation_offset = r.u64()?;
+        let (args, client_key_id) = match kind {
+            CircuitKind::GmpcStrategyQuorum => {
+                let quorum_offset = r.u64()?;
+                let client_key_id = r.u32()?;
+                let args = RequestArgs::GmpcStrategyQuorum {
+                    quorum_offset,
+                    intent: r.encrypted_input()?,
+                    salt: r.encrypted_input()?,
+                };
+                (args, client_key_id)
+            }
+            _ => {
+                let client_key_id = r.u32()?;
+                let args = match kind {
+                    CircuitKind::StrategyPlan => RequestArgs::StrategyPlan {
+                        preferences: r.encrypted_input()?,
+                        history: r.encrypted_input()?,
+                        curve_state: strategy::CurveState {
+                            current_price: r.u64()?,
+                            liquidity_depth: r.u64()?,
+                            volatility: r.u16()?,
+                            recent_volume: r.u64()?,
+                        },
+                    },
+                    CircuitKind::RiskScore => RequestArgs::RiskScore {
+                        portfolio: r.encrypted_input()?,
+                        performance: r.encrypted_input()?,
+                        market: risk::MarketConditions {
+                            curve_volatility: r.u16()?,
+                            liquidity_risk: r.u8()?,
+                            market_sentiment: r.i8()?,
+                        },
+                    },
+                    CircuitKind::CurveEval => RequestArgs::CurveEval {
+                        sizing: r.encrypted_input()?,
+                        constraints: r.encrypted_input()?,
+                        curve: curve::CurveMetrics {
+                            current_price: r.u64()?,
+                            price_change_24h: r.i32()?,
+                            liquidity_depth: r.u64()?,
+                            buy_pressure: r.u16()?,
+                            sell_pressure: r.u16()?,
+                        },
+                    },
+                    CircuitKind::GmpcStrategy => RequestArgs::GmpcStrategy {
+                        intent: r.encrypted_input()?,
+                    },
+                    _ => {
+                        // The program rejects other counts before queueing
+                        let count = r.u32()? as usize;
+                        if count == 0 || count > MAX_ANALYTICS_PROFILES {
+                            return Err(MockError::ProfileCount(count));
+                        }
+                        RequestArgs::MultiUserAnalytics {
+                            profiles: (0..count)
+                                .map(|_| r.encrypted_input())
+                                .collect::<Result<_, _>>()?,
+                            aggregation_type: r.u8()?,
+                        }
+                    }
+                };
+                (args, client_key_id)
+            }
+        };
+        r.finish()?;
+        // The program checks every input against the one active client key
+        let inputs = args.encrypted_inputs();
+        if inputs
+            .iter()
+            .any(|input| input.pub_key != inputs[0].pub_key)
+        {
+            return Err(MockError::ClientKeyMismatch);
+        }
+
+        let mut program_accounts = match &args {
+            RequestArgs::GmpcStrategyQuorum { .. } => PROGRAM_ACCOUNTS + 1,
+            _ => PROGRAM_ACCOUNTS,
+        };
+        if kind.policy_kind().is_some() {
+            program_accounts += 1;
+        }
+        let accounts = &instruction.accounts;
+        let computation_account = accounts
+            .get(program_accounts + COMPUTATION_ACCOUNT)
+            .ok_or(MockError::MissingAccounts(accounts.len()))?
+            .pubkey;
+        let requester = accounts[0].pubkey;
+
+        Ok(QueuedComputation {
+            kind,
+            requester,
+            computation_offset,
+            client_key_id,
+            computation_account,
+            input_commitment: input_commitment(
+                &requester,
+                computation_offset,
+                &args.encrypted_inputs(),
+            ),
+            args,
+        })
+    }
+}
+
+/// Policy carried by one `set_*_policy` instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyUpdate {
+    Strategy(strategy::StrategyPolicy),
+    Risk(risk::RiskPolicy),
+    Curve(curve::CurvePolicy),
+    Gmpc(gmpc::GmpcPolicy),
+}
+
+impl PolicyUpdate {
+    /// Decode a `set_*_policy` instruction
+    pub fn from_instruction(instruction: &Instruction) -> Result<Self, MockError> {
+        let data = &instruction.data;
+        if data.len() < 8 {
+            return Err(MockError::NotAPolicyUpdate);
+        }
+        let is = |name: &str| data[..8] == instruction_discriminator(name);
+        let mut r = Args { data: &data[8..] };
+        let update = if is("set_strategy_policy") {
+            PolicyUpdate::Strategy(strategy::StrategyPolicy {
+                stealth_risk: r.u16()?,
+                ghost_risk: r.u16()?,
+                low_win_rate: r.u16()?,
+                medium_volatility: r.u16()?,
+                high_volatility: r.u16()?,
+                fast_window_sec: r.u32()?,
+                medium_window_sec: r.u32()?,
+                slow_window_sec: r.u32()?,
+                min_slices: r.u8()?,
+                participation_pct: r.u8()?,
+                moderate_appetite: r.u8()?,
+                aggressive_appetite: r.u8()?,
+                aggressive_win_rate: r.u16()?,
+                take_profit_bps: r.u16()?,
+                stop_loss_bps: r.u16()?,
+                exit_window_pct: r.u8()?,
+                history_weight_trades: r.u16()?,
+            })
+        } else if is("set_risk_policy") {
+            PolicyUpdate::Risk(risk::RiskPolicy {
+                high_exposure: r.u8()?,
+                over_exposure: r.u8()?,
+                low_diversification: r.u8()?,
+                high_volatility: r.u16()?,
+                very_high_volatility: r.u16()?,
+                high_liquidity_risk: r.u8()?,
+                poor_drawdown: r.u16()?,
+                good_sharpe: r.i16()?,
+                good_consistency: r.u8()?,
+                caution_risk: r.u8()?,
+                avoid_risk: r.u8()?,
+            })
+        } else if is("set_curve_policy") {
+            PolicyUpdate::Curve(curve::CurvePolicy {
+                plenty_liquidity_multiple: r.u8()?,
+                liquidity_use_pct: r.u8()?,
+                momentum_bps: r.u16()?,
+                pressure_ratio: r.u8()?,
+                high_urgency: r.u8()?,
+                urgent: r.u8()?,
+                high_urgency_max_timing_sec: r.u32()?,
+                urgent_max_timing_sec: r.u32()?,
+                confident_liquidity_multiple: r.u8()?,
+                stable_bps: r.u16()?,
+                moderate_bps: r.u16()?,
+            })
+        } else if is("set_gmpc_policy") {
+            PolicyUpdate::Gmpc(gmpc::GmpcPolicy {
+                low_risk_size_pct: r.u8()?,
+                normal_risk_size_pct: r.u8()?,
+                high_volatility: r.u16()?,
+                high_volatility_size_pct: r.u8()?,
+                normal_slices: r.u8()?,
+                stealth_slices: r.u8()?,
+                max_privacy_slices: r.u8()?,
+                calm_window_sec: r.u32()?,
+                volatile_window_sec: r.u32()?,
+                low_risk_volatility: r.u16()?,
+                high_risk_volatility: r.u16()?,
+            })
+        } else {
+            return Err(MockError::NotAPolicyUpdate);
+        };
+        r.finish()?;
+        Ok(update)
+    }
+}
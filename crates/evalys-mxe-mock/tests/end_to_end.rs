@@ -0,0 +1,641 @@
+//! Request → callback flows through the mock cluster: encrypt with the
+//! client, queue the request instruction, decode and decrypt the result event
+//! and compare it with the plaintext reference.
+
+use evalys_mxe_client::arcium::ArciumDeployment;
+use evalys_mxe_client::cipher::ClientCipher;
+use evalys_mxe_client::events::ResultEvent;
+use evalys_mxe_client::inputs::{self, input_commitment, EncryptedInput};
+use evalys_mxe_client::instructions::{self, RequestAccounts};
+use evalys_mxe_client::outputs::{self, CircuitOutput};
+use evalys_mxe_client::state::CircuitKind;
+use evalys_mxe_client::{ClientError, Pubkey};
+use evalys_mxe_mock::request::PolicyUpdate;
+use evalys_mxe_mock::{Callback, MockCluster, MockError};
+use evalys_mxe_reference::{curve, gmpc, multi_user, risk, strategy};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use x25519_dalek::StaticSecret;
+
+const CIRCUIT_HASH: [u8; 32] = [7; 32];
+
+struct Setup {
+    program_id: Pubkey,
+    payer: Pubkey,
+    deployment: ArciumDeployment,
+    client: ClientCipher,
+    cluster: MockCluster,
+}
+
+fn setup() -> Setup {
+    let mut cluster = MockCluster::new(StaticSecret::from([1; 32]));
+    for kind in [
+        CircuitKind::StrategyPlan,
+        CircuitKind::RiskScore,
+        CircuitKind::CurveEval,
+        CircuitKind::GmpcStrategy,
+        CircuitKind::GmpcStrategyQuorum,
+        CircuitKind::MultiUserAnalytics,
+    ] {
+        cluster.register_circuit(kind, CIRCUIT_HASH);
+    }
+    let client = ClientCipher::new(&StaticSecret::from([2; 32]), &cluster.public_key());
+    Setup {
+        program_id: Pubkey::new_from_array([3; 32]),
+        payer: Pubkey::new_from_array([4; 32]),
+        deployment: ArciumDeployment {
+            arcium_program: Pubkey::new_from_array([5; 32]),
+            cluster_offset: 1,
+            pool_account: Pubkey::new_from_array([6; 32]),
+            clock_account: Pubkey::new_from_array([8; 32]),
+        },
+        client,
+        cluster,
+    }
+}
+
+impl Setup {
+    fn accounts(&self, encrypted_ix: &str, computation_offset: u64) -> RequestAccounts {
+        RequestAccounts {
+            payer: self.payer,
+            computation_offset,
+            arcium: self
+                .deployment
+                .accounts(&self.program_id, encrypted_ix, computation_offset),
+        }
+    }
+}
+
+/// Client input or output as the reference circuit type of the same shape
+fn reference<T: Serialize, U: DeserializeOwned>(value: &T) -> U {
+    serde_json::from_value(serde_json::to_value(value).unwrap()).unwrap()
+}
+
+/// Output fields other than the input commitment. Goes through a string
+/// since `Value` cannot hold the reference commitment's `u128` words.
+fn plain<T: Serialize>(output: &T) -> Value {
+    let mut value: Value = serde_json::from_str(&serde_json::to_string(output).unwrap()).unwrap();
+    value.as_object_mut().unwrap().remove("input_commitment");
+    value
+}
+
+/// [`plain`] fields of a reference plan, with its per-slice arrays unpacked
+/// as the client decodes them
+fn unpacked(plan: &strategy::StrategyPlan) -> Value {
+    let mut value = plain(plan);
+    let fields = value.as_object_mut().unwrap();
+    for words in [
+        "slice_size_words",
+        "slice_delay_words",
+        "exit_bps_words",
+        "exit_delay_words",
+    ] {
+        fields.remove(words).unwrap();
+    }
+    fields.insert("slice_sizes".into(), json!(plan.slice_sizes()));
+    fields.insert("slice_delays_sec".into(), json!(plan.slice_delays_sec()));
+    fields.insert("exit_slice_bps".into(), json!(plan.exit_slice_bps()));
+    fields.insert("exit_delays_sec".into(), json!(plan.exit_delays_sec()));
+    value
+}
+
+/// Decode the callback's event, check its header and decrypt the output
+fn decrypt<T: CircuitOutput>(
+    setup: &Setup,
+    callback: &Callback,
+    kind: CircuitKind,
+    commitment: &[u8; 32],
+) -> T {
+    assert_eq!(callback.logs.len(), 1);
+    let event = ResultEvent::from_log(&callback.logs[0]).unwrap().unwrap();
+    assert_eq!(event.kind, kind);
+    assert_eq!(event.requester, setup.payer);
+    assert_eq!(event.computation_offset, callback.computation_offset);
+    assert_eq!(event.circuit_hash, CIRCUIT_HASH);
+    assert_eq!(event.client_key_id, 1);
+    let output: T = event.decrypt(&setup.client).unwrap();
+    output.input_commitment().verify(commitment).unwrap();
+    output
+}
+
+/// `hi` and `lo` words the circuits take for a stored commitment
+fn commitment_words(commitment: &[u8; 32]) -> (u128, u128) {
+    (
+        u128::from_be_bytes(commitment[..16].try_into().unwrap()),
+        u128::from_be_bytes(commitment[16..].try_into().unwrap()),
+    )
+}
+
+#[test]
+fn strategy_plan_round_trip() {
+    let mut setup = setup();
+    let preferences = inputs::UserPreferences {
+        desired_size: 5_000_000_000,
+        slippage_tolerance: 150,
+        risk_appetite: 180,
+        preferred_hold_time: 3_600,
+    };
+    let history = inputs::UserHistory {
+        recent_pnl: -250_000_000,
+        win_rate: 6_200,
+        avg_hold_time: 5_400,
+        total_trades: 42,
+    };
+    let curve_state = inputs::CurveState {
+        current_price: 1_200,
+        liquidity_depth: 80_000_000_000,
+        volatility: 3_500,
+        recent_volume: 12_000_000_000,
+    };
+    let preferences_ct = setup.client.encrypt_with_nonce(&preferences, 10);
+    let history_ct = setup.client.encrypt_with_nonce(&history, 20);
+    let instruction = instructions::request_strategy_plan(
+        &setup.program_id,
+        &setup.accounts("confidential_strategy_plan", 1001),
+        1,
+        &preferences_ct,
+        &history_ct,
+        &curve_state,
+    );
+
+    let queued = setup.cluster.queue(&instruction).unwrap();
+    let commitment = input_commitment(&setup.payer, 1001, &[&preferences_ct, &history_ct]);
+    assert_eq!(queued.input_commitment, commitment);
+    assert_eq!(setup.cluster.pending().count(), 1);
+
+    let callback = setup.cluster.execute_next().unwrap().unwrap();
+    assert_eq!(callback.quorum_digest, None);
+    assert_eq!(setup.cluster.execute_next().unwrap(), None);
+    let plan: outputs::StrategyPlan =
+        decrypt(&setup, &callback, CircuitKind::StrategyPlan, &commitment);
+
+    let (hi, lo) = commitment_words(&commitment);
+    let expected = evalys_mxe_reference::strategy_plan(
+        reference(&preferences),
+        reference(&history),
+        reference(&curve_state),
+        reference(&inputs::StrategyPolicy::default()),
+        strategy::InputCommitment { hi, lo },
+        1001,
+    );
+    assert_eq!(plain(&plan), unpacked(&expected));
+    let sizes: Vec<u64> = plan.slices().map(|(size, _)| size).collect();
+    assert_eq!(sizes.len(), plan.num_slices as usize);
+    assert_eq!(sizes.iter().sum::<u64>(), preferences.desired_size);
+    // The exit sells the whole position, starting after the entry window
+    let exits: Vec<(u16, u64)> = plan.exit_slices().collect();
+    assert_eq!(exits.len(), plan.num_slices as usize);
+    assert_eq!(exits.iter().map(|(bps, _)| *bps).sum::<u16>(), 10_000);
+    assert_eq!(exits[0].1, u64::from(plan.exit_start_sec));
+    assert!(plan.exit_start_sec > plan.timing_window_sec);
+    assert!(plan.stop_loss_price < curve_state.current_price);
+    assert!(plan.take_profit_price > curve_state.current_price);
+}
+
+#[test]
+fn every_circuit_answers_its_request() {
+    let mut setup = setup();
+    let portfolio = inputs::PortfolioContext {
+        total_capital: 100_000_000_000,
+        current_exposure: 30_000_000_000,
+        diversification_score: 120,
+        leverage_ratio: 15_000,
+    };
+    let portfolio_ct = setup.client.encrypt_with_nonce(&portfolio, 1);
+    let performance = inputs::PerformanceHistory {
+        total_pnl: -5_000_000_000,
+        sharpe_ratio: -40,
+        max_drawdown: 2_500,
+        consistency_score: 90,
+    };
+    let performance_ct = setup.client.encrypt_with_nonce(&performance, 2);
+    let market = inputs::MarketConditions {
+        curve_volatility: 6_000,
+        liquidity_risk: 140,
+        market_sentiment: -30,
+    };
+    let risk_request = instructions::request_risk_score(
+        &setup.program_id,
+        &setup.accounts("confidential_risk_score", 2001),
+        1,
+        &portfolio_ct,
+        &performance_ct,
+        &market,
+    );
+
+    let sizing = inputs::SizingPreferences {
+        target_size: 2_000_000_000,
+        min_size: 500_000_000,
+        max_size: 4_000_000_000,
+        capital_allocation_pct: 25,
+    };
+    let sizing_ct = setup.client.encrypt_with_nonce(&sizing, 3);
+    let constraints = inputs::UserConstraints {
+        max_slippage_bps: 200,
+        time_constraint_sec: 600,
+        priority_level: 200,
+    };
+    let constraints_ct = setup.client.encrypt_with_nonce(&constraints, 4);
+    let metrics = inputs::CurveMetrics {
+        current_price: 950,
+        price_change_24h: -1_200,
+        liquidity_depth: 40_000_000_000,
+        buy_pressure: 6_500,
+        sell_pressure: 3_500,
+    };
+    let curve_request = instructions::request_curve_eval(
+        &setup.program_id,
+        &setup.accounts("confidential_curve_eval", 2002),
+        1,
+        &sizing_ct,
+        &constraints_ct,
+        &metrics,
+    );
+
+    let intent = inputs::IntentInput {
+        max_size_sol: 3_000_000_000,
+        risk_level: 2,
+        privacy_priority: 1,
+        market_price: 1_000,
+        curve_position: 4_000,
+        volatility_score: 7_000,
+        avg_hold_time: 900,
+        win_rate: 5_500,
+        max_dd: 1_800,
+    };
+    let intent_ct = setup.client.encrypt_with_nonce(&intent, 5);
+    let gmpc_request = instructions::request_gmpc_strategy(
+        &setup.program_id,
+        &setup.accounts("evalys_gmpc_strategy", 2003),
+        1,
+        &intent_ct,
+    );
+
+    let profiles = [
+        (11, 3_600, 6_000, 1_000_000_000, 3_000_000_000, 0),
+        (12, 7_200, 4_500, 2_000_000_000, 5_000_000_000, 2),
+        (13, 1_800, 7_000, 500_000_000, 2_500_000_000, 1),
+    ]
+    .map(|(id, hold, win, min, max, risk)| inputs::UserProfile {
+        profile_id_hash: id,
+        avg_hold_time: hold,
+        win_rate: win,
+        preferred_size_range_min: min,
+        preferred_size_range_max: max,
+        risk_tolerance: risk,
+    });
+    let profiles_ct: Vec<EncryptedInput> = profiles
+        .iter()
+        .zip(6..)
+        .map(|(profile, nonce)| setup.client.encrypt_with_nonce(profile, nonce))
+        .collect();
+    let analytics = instructions::request_multi_user_analytics(
+        &setup.program_id,
+        &setup.accounts("confidential_multi_user_analytics", 2004),
+        1,
+        &profiles_ct,
+        1,
+    )
+    .unwrap();
+
+    for instruction in [&risk_request, &curve_request, &gmpc_request, &analytics] {
+        setup.cluster.queue(instruction).unwrap();
+    }
+    let callbacks = setup.cluster.execute_all().unwrap();
+    assert_eq!(callbacks.len(), 4);
+
+    let commitment = input_commitment(&setup.payer, 2001, &[&portfolio_ct, &performance_ct]);
+    let assessment: outputs::RiskAssessment =
+        decrypt(&setup, &callbacks[0], CircuitKind::RiskScore, &commitment);
+    let (hi, lo) = commitment_words(&commitment);
+    let expected = evalys_mxe_reference::risk_score(
+        reference(&portfolio),
+        reference(&performance),
+        reference(&market),
+        reference(&inputs::RiskPolicy::default()),
+        risk::InputCommitment { hi, lo },
+    );
+    assert_eq!(plain(&assessment), plain(&expected));
+
+    let commitment = input_commitment(&setup.payer, 2002, &[&sizing_ct, &constraints_ct]);
+    let recommendation: outputs::ExecutionRecommendation =
+        decrypt(&setup, &callbacks[1], CircuitKind::CurveEval, &commitment);
+    let (hi, lo) = commitment_words(&commitment);
+    let expected = evalys_mxe_reference::curve_eval(
+        reference(&sizing),
+        reference(&constraints),
+        reference(&metrics),
+        reference(&inputs::CurvePolicy::default()),
+        curve::InputCommitment { hi, lo },
+    );
+    assert_eq!(plain(&recommendation), plain(&expected));
+
+    let commitment = input_commitment(&setup.payer, 2003, &[&intent_ct]);
+    let plan: outputs::PlanOutput = decrypt(
+        &setup,
+        &callbacks[2],
+        CircuitKind::GmpcStrategy,
+        &commitment,
+    );
+    let (hi, lo) = commitment_words(&commitment);
+    let expected = evalys_mxe_reference::gmpc_strategy(
+        reference(&intent),
+        reference(&inputs::GmpcPolicy::default()),
+        gmpc::InputCommitment { hi, lo },
+        2003,
+    );
+    assert_eq!(plain(&plan), plain(&expected));
+
+    let commitment = input_commitment(&setup.payer, 2004, &profiles_ct.iter().collect::<Vec<_>>());
+    let metrics: outputs::AggregatedMetrics = decrypt(
+        &setup,
+        &callbacks[3],
+        CircuitKind::MultiUserAnalytics,
+        &commitment,
+    );
+    let (hi, lo) = commitment_words(&commitment);
+    let expected = evalys_mxe_reference::multi_user_analytics(
+        &profiles.map(|profile| reference(&profile)),
+        multi_user::AggregationType::MarketSentiment,
+        multi_user::InputCommitment { hi, lo },
+    );
+    assert_eq!(plain(&metrics), plain(&expected));
+    assert_eq!(metrics.sample_size, 3);
+}
+
+#[test]
+fn quorum_legs_reveal_the_same_digest() {
+    let mut setup = setup();
+    // The peer deployment: the same program under another id, bound to
+    // another cluster whose MXE holds its own key
+    let peer_program_id = Pubkey::new_from_array([9; 32]);
+    let peer_deployment = ArciumDeployment {
+        cluster_offset: 2,
+        ..setup.deployment
+    };
+    let mut peer_cluster = MockCluster::new(StaticSecret::from([10; 32]));
+    peer_cluster.register_circuit(CircuitKind::GmpcStrategyQuorum, CIRCUIT_HASH);
+    let peer_client = ClientCipher::new(&StaticSecret::from([2; 32]), &peer_cluster.public_key());
+
+    let intent = inputs::IntentInput {
+        max_size_sol: 1_500_000_000,
+        risk_level: 1,
+        privacy_priority: 2,
+        market_price: 2_000,
+        curve_position: 8_000,
+        volatility_score: 2_000,
+        avg_hold_time: 10_800,
+        win_rate: 6_500,
+        max_dd: 900,
+    };
+    let salt = inputs::QuorumSalt { salt: 0xfeed_beef };
+
+    let mut legs = Vec::new();
+    for (program_id, deployment, client, computation_offset) in [
+        (setup.program_id, setup.deployment, &setup.client, 3001),
+        (peer_program_id, peer_deployment, &peer_client, 3002),
+    ] {
+        let intent_ct = client.encrypt_with_nonce(&intent, 100);
+        let salt_ct = client.encrypt_with_nonce(&salt, 200);
+        let accounts = RequestAccounts {
+            payer: setup.payer,
+            computation_offset,
+            arcium: deployment.accounts(
+                &program_id,
+                "evalys_gmpc_strategy_quorum",
+                computation_offset,
+            ),
+        };
+        let instruction = instructions::request_gmpc_strategy_quorum(
+            &program_id,
+            &accounts,
+            77,
+            1,
+            &intent_ct,
+            &salt_ct,
+        );
+        let commitment =
+            input_commitment(&setup.payer, computation_offset, &[&intent_ct, &salt_ct]);
+        legs.push((instruction, commitment, intent_ct));
+    }
+    // Each leg's inputs are encrypted to its own MXE key
+    assert_ne!(legs[0].2.ciphertexts, legs[1].2.ciphertexts);
+    setup.cluster.queue(&legs[0].0).unwrap();
+    peer_cluster.queue(&legs[1].0).unwrap();
+    let callback = setup.cluster.execute_next().unwrap().unwrap();
+    let peer_callback = peer_cluster.execute_next().unwrap().unwrap();
+
+    let policy: gmpc::GmpcPolicy = reference(&inputs::GmpcPolicy::default());
+    let mut digests = Vec::new();
+    for (callback, client, (_, commitment, _)) in [
+        (&callback, &setup.client, &legs[0]),
+        (&peer_callback, &peer_client, &legs[1]),
+    ] {
+        let (hi, lo) = commitment_words(commitment);
+        let (expected, digest) = evalys_mxe_reference::gmpc_strategy_quorum(
+            reference(&intent),
+            gmpc::QuorumSalt { salt: salt.salt },
+            policy,
+            gmpc::InputCommitment { hi, lo },
+        );
+        assert_eq!(callback.kind, CircuitKind::GmpcStrategyQuorum);
+        assert_eq!(callback.quorum_digest, Some(digest));
+        let event = ResultEvent::from_log(&callback.logs[0]).unwrap().unwrap();
+        assert_eq!(event.kind, CircuitKind::GmpcStrategy);
+        let plan: outputs::PlanOutput = event.decrypt(client).unwrap();
+        plan.input_commitment().verify(commitment).unwrap();
+        assert_eq!(plain(&plan), plain(&expected));
+        digests.push(digest);
+    }
+    // The digests cover the plan, not the per-leg commitment, so the
+    // deployments' callbacks agree
+    assert_eq!(digests[0], digests[1]);
+}
+
+#[test]
+fn client_policy_defaults_are_the_reference_defaults() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let decode = |instruction: Result<_, ClientError>| {
+        PolicyUpdate::from_instruction(&instruction.unwrap()).unwrap()
+    };
+
+    assert_eq!(
+        decode(instructions::set_strategy_policy(
+            &program_id,
+            &authority,
+            &inputs::StrategyPolicy::default()
+        )),
+        PolicyUpdate::Strategy(strategy::StrategyPolicy::default())
+    );
+    assert_eq!(
+        decode(instructions::set_risk_policy(
+            &program_id,
+            &authority,
+            &inputs::RiskPolicy::default()
+        )),
+        PolicyUpdate::Risk(risk::RiskPolicy::default())
+    );
+    assert_eq!(
+        decode(instructions::set_curve_policy(
+            &program_id,
+            &authority,
+            &inputs::CurvePolicy::default()
+        )),
+        PolicyUpdate::Curve(curve::CurvePolicy::default())
+    );
+    assert_eq!(
+        decode(instructions::set_gmpc_policy(
+            &program_id,
+            &authority,
+            &inputs::GmpcPolicy::default()
+        )),
+        PolicyUpdate::Gmpc(gmpc::GmpcPolicy::default())
+    );
+}
+
+#[test]
+fn requests_run_under_the_policy_when_queued() {
+    let mut setup = setup();
+    let intent = inputs::IntentInput {
+        max_size_sol: 2_000_000_000,
+        risk_level: 0,
+        privacy_priority: 0,
+        market_price: 1_000,
+        curve_position: 4_000,
+        volatility_score: 8_000,
+        avg_hold_time: 900,
+        win_rate: 5_500,
+        max_dd: 1_800,
+    };
+    let intent_ct = setup.client.encrypt_with_nonce(&intent, 1);
+    let request = |setup: &Setup, computation_offset| {
+        instructions::request_gmpc_strategy(
+            &setup.program_id,
+            &setup.accounts("evalys_gmpc_strategy", computation_offset),
+            1,
+            &intent_ct,
+        )
+    };
+    let authority = Pubkey::new_from_array([9; 32]);
+    let tuned = inputs::GmpcPolicy {
+        low_risk_size_pct: 25,
+        high_volatility: 9_000,
+        normal_slices: 9,
+        calm_window_sec: 600,
+        ..inputs::GmpcPolicy::default()
+    };
+
+    setup.cluster.queue(&request(&setup, 5001)).unwrap();
+    let update = instructions::set_gmpc_policy(&setup.program_id, &authority, &tuned).unwrap();
+    setup.cluster.set_policy(&update).unwrap();
+    setup.cluster.queue(&request(&setup, 5002)).unwrap();
+    let callbacks = setup.cluster.execute_all().unwrap();
+
+    // Queued before the update: the default policy
+    let commitment = input_commitment(&setup.payer, 5001, &[&intent_ct]);
+    let plan: outputs::PlanOutput = decrypt(
+        &setup,
+        &callbacks[0],
+        CircuitKind::GmpcStrategy,
+        &commitment,
+    );
+    assert_eq!(plan.recommended_size_sol, 700_000_000);
+    assert_eq!(plan.slice_count, 3);
+
+    // Low risk at 25%, and 8000 is no longer high volatility
+    let commitment = input_commitment(&setup.payer, 5002, &[&intent_ct]);
+    let plan: outputs::PlanOutput = decrypt(
+        &setup,
+        &callbacks[1],
+        CircuitKind::GmpcStrategy,
+        &commitment,
+    );
+    let (hi, lo) = commitment_words(&commitment);
+    let expected = evalys_mxe_reference::gmpc_strategy(
+        reference(&intent),
+        reference(&tuned),
+        gmpc::InputCommitment { hi, lo },
+        5002,
+    );
+    assert_eq!(plain(&plan), plain(&expected));
+    assert_eq!(plan.recommended_size_sol, 500_000_000);
+    assert_eq!(plan.slice_count, 9);
+    assert!((540..=660).contains(&plan.time_window_sec));
+
+    let no_slices = inputs::GmpcPolicy {
+        normal_slices: 0,
+        ..tuned
+    };
+    assert!(matches!(
+        instructions::set_gmpc_policy(&setup.program_id, &authority, &no_slices),
+        Err(ClientError::BelowMinimum {
+            field: "normal_slices",
+            ..
+        })
+    ));
+    assert!(matches!(
+        setup.cluster.set_policy(&request(&setup, 5003)),
+        Err(MockError::NotAPolicyUpdate)
+    ));
+}
+
+#[test]
+fn rejects_requests_it_cannot_run() {
+    let mut setup = setup();
+    let mut cluster = MockCluster::new(StaticSecret::from([1; 32]));
+    let intent = setup
+        .client
+        .encrypt_with_nonce(&inputs::QuorumSalt { salt: 1 }, 1);
+    let instruction = instructions::request_gmpc_strategy(
+        &setup.program_id,
+        &setup.accounts("evalys_gmpc_strategy", 4001),
+        1,
+        &intent,
+    );
+    assert!(matches!(
+        cluster.queue(&instruction),
+        Err(MockError::UnregisteredCircuit(CircuitKind::GmpcStrategy))
+    ));
+
+    // A salt where the intent belongs decrypts to too few fields
+    setup.cluster.queue(&instruction).unwrap();
+    assert!(matches!(
+        setup.cluster.execute_next(),
+        Err(MockError::FieldCount("intent"))
+    ));
+
+    // Every profile must be encrypted to the bridge's one client key
+    let profile = inputs::UserProfile {
+        profile_id_hash: 1,
+        avg_hold_time: 600,
+        win_rate: 5_000,
+        preferred_size_range_min: 10,
+        preferred_size_range_max: 100,
+        risk_tolerance: 1,
+    };
+    let other = ClientCipher::new(&StaticSecret::from([9; 32]), &setup.cluster.public_key());
+    let profiles = [
+        setup.client.encrypt_with_nonce(&profile, 1),
+        other.encrypt_with_nonce(&profile, 2),
+    ];
+    let analytics = instructions::request_multi_user_analytics(
+        &setup.program_id,
+        &setup.accounts("confidential_multi_user_analytics", 4002),
+        1,
+        &profiles,
+        0,
+    )
+    .unwrap();
+    assert!(matches!(
+        setup.cluster.queue(&analytics),
+        Err(MockError::ClientKeyMismatch)
+    ));
+
+    let init = instructions::init_client_key_registry(&setup.program_id, &setup.payer);
+    assert!(matches!(
+        setup.cluster.queue(&init),
+        Err(MockError::NotARequest)
+    ));
+}
@@ -0,0 +1,132 @@
+//! The mock decodes requests and encodes results with the client's types
+//! instead of running the program. These checks read the program source and
+//! fail when its encrypted argument widths or event ciphertext counts drift
+//! from those types.
+
+use std::collections::HashMap;
+use std::fs;
+
+use evalys_mxe_client::inputs::{self, CircuitInput};
+use evalys_mxe_client::outputs::{self, CircuitOutput};
+use syn::visit::{self, Visit};
+use syn::{Expr, ExprMethodCall, File, Item};
+
+const PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../programs/evalys-arcium-gmpc-mxe/src/lib.rs"
+);
+
+fn program() -> File {
+    syn::parse_file(&fs::read_to_string(PROGRAM).unwrap()).unwrap()
+}
+
+/// `usize` constants and `EncWidth` arrays declared at the top level
+fn constants(file: &File) -> (HashMap<String, usize>, HashMap<String, usize>) {
+    let mut sizes = HashMap::new();
+    let mut width_arrays = HashMap::new();
+    for item in &file.items {
+        let Item::Const(item) = item else { continue };
+        match &*item.expr {
+            Expr::Lit(lit) => {
+                if let syn::Lit::Int(value) = &lit.lit {
+                    sizes.insert(item.ident.to_string(), value.base10_parse().unwrap());
+                }
+            }
+            Expr::Array(array) => {
+                width_arrays.insert(item.ident.to_string(), array.elems.len());
+            }
+            _ => {}
+        }
+    }
+    (sizes, width_arrays)
+}
+
+/// Number of widths each encrypted input is pushed with, by receiver name
+#[derive(Default)]
+struct PushArgs {
+    width_arrays: HashMap<String, usize>,
+    widths: Vec<(String, usize)>,
+}
+
+impl<'ast> Visit<'ast> for PushArgs {
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        if call.method == "push_args" && call.args.len() == 2 {
+            let receiver = match &*call.receiver {
+                Expr::Path(path) => path.path.segments.last().unwrap().ident.to_string(),
+                _ => panic!("push_args on something other than an input variable"),
+            };
+            let Expr::Reference(widths) = &call.args[1] else {
+                panic!("{receiver}: widths are not a reference")
+            };
+            let count = match &*widths.expr {
+                Expr::Array(array) => array.elems.len(),
+                Expr::Path(path) => {
+                    let name = path.path.segments.last().unwrap().ident.to_string();
+                    self.width_arrays[&name]
+                }
+                _ => panic!("{receiver}: widths are neither an array nor a constant"),
+            };
+            self.widths.push((receiver, count));
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+}
+
+#[test]
+fn encrypted_inputs_match_the_client_types() {
+    let file = program();
+    let (_, width_arrays) = constants(&file);
+    let mut push_args = PushArgs {
+        width_arrays,
+        ..PushArgs::default()
+    };
+    push_args.visit_file(&file);
+
+    let expected: HashMap<&str, usize> = HashMap::from([
+        ("preferences", inputs::UserPreferences::FIELD_COUNT),
+        ("history", inputs::UserHistory::FIELD_COUNT),
+        ("portfolio", inputs::PortfolioContext::FIELD_COUNT),
+        ("performance", inputs::PerformanceHistory::FIELD_COUNT),
+        ("sizing", inputs::SizingPreferences::FIELD_COUNT),
+        ("constraints", inputs::UserConstraints::FIELD_COUNT),
+        ("intent", inputs::IntentInput::FIELD_COUNT),
+        ("salt", inputs::QuorumSalt::FIELD_COUNT),
+        ("profile", inputs::UserProfile::FIELD_COUNT),
+    ]);
+    // Two inputs for strategy, risk, curve and quorum, one for gMPC and one
+    // per analytics profile
+    assert_eq!(push_args.widths.len(), 10);
+    for (receiver, count) in &push_args.widths {
+        assert_eq!(
+            Some(count),
+            expected.get(receiver.as_str()),
+            "{receiver} is pushed with {count} widths"
+        );
+    }
+}
+
+#[test]
+fn result_events_match_the_client_outputs() {
+    let (sizes, _) = constants(&program());
+    for (constant, fields) in [
+        (
+            "STRATEGY_PLAN_CIPHERTEXTS",
+            outputs::StrategyPlan::FIELD_COUNT,
+        ),
+        (
+            "RISK_ASSESSMENT_CIPHERTEXTS",
+            outputs::RiskAssessment::FIELD_COUNT,
+        ),
+        (
+            "EXECUTION_RECOMMENDATION_CIPHERTEXTS",
+            outputs::ExecutionRecommendation::FIELD_COUNT,
+        ),
+        ("PLAN_OUTPUT_CIPHERTEXTS", outputs::PlanOutput::FIELD_COUNT),
+        (
+            "AGGREGATED_METRICS_CIPHERTEXTS",
+            outputs::AggregatedMetrics::FIELD_COUNT,
+        ),
+    ] {
+        assert_eq!(sizes[constant], fields, "{constant}");
+    }
+}
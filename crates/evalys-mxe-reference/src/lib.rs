@@ -0,0 +1,275 @@
+//! Plaintext reference implementation of the MXE circuits.
+//!
+//! The circuit sources in `encrypted-ixs/` are compiled here unchanged, with
+//! `evalys-arcis-shim` standing in for `arcis-imports`, so the logic that runs
+//! in MPC can be executed, tested and compared on the host. Each circuit
+//! module re-exports the Arcis module of its source file; the functions below
+//! take and return plain values.
+//!
+//! Results match what the MXE computes as long as the circuit does not rely
+//! on MPC-specific behaviour (e.g. integer overflow: the host panics in debug
+//! builds where a circuit would wrap). Circuits that draw randomness inside
+//! MPC draw it here from a host stream seeded by the wrapper's `rng_seed`, so
+//! a seed reproduces one possible MXE result.
+//!
+//! The policy types' `Default` is the policy `evalys-mxe deploy` stores, the
+//! thresholds the circuits were tuned with. `evalys-mxe-client` builds its
+//! own policy defaults from these.
+
+use arcis_imports::{ArcisRNG, Enc};
+
+// Circuits index arrays from counted loops, which unroll at compile time, and
+// spell out divisions instead of calling `checked_div` or `div_ceil`
+#[allow(
+    unused_imports,
+    clippy::needless_range_loop,
+    clippy::manual_checked_ops,
+    clippy::manual_div_ceil
+)]
+#[path = "../../../encrypted-ixs/confidential_strategy.rs"]
+mod confidential_strategy;
+
+// Circuits branch on a zero divisor instead of calling `checked_div`
+#[allow(unused_imports, clippy::manual_checked_ops)]
+#[path = "../../../encrypted-ixs/confidential_risk.rs"]
+mod confidential_risk;
+
+#[allow(unused_imports)]
+#[path = "../../../encrypted-ixs/confidential_curve.rs"]
+mod confidential_curve;
+
+#[allow(unused_imports)]
+#[path = "../../../encrypted-ixs/evalys_gmpc_strategy.rs"]
+mod evalys_gmpc_strategy;
+
+// The circuit takes `aggregation_type` from the program without using it yet
+#[allow(unused_imports, unused_variables)]
+#[path = "../../../encrypted-ixs/confidential_multi_user.rs"]
+mod confidential_multi_user;
+
+/// Types of `confidential_curve_eval`
+pub use confidential_curve::circuits as curve;
+/// Types of `confidential_multi_user_analytics`
+pub use confidential_multi_user::circuits as multi_user;
+/// Types of `confidential_risk_score`
+pub use confidential_risk::circuits as risk;
+/// Types of `confidential_strategy_plan`
+pub use confidential_strategy::circuits as strategy;
+/// Types of `evalys_gmpc_strategy` and `evalys_gmpc_strategy_quorum`
+pub use evalys_gmpc_strategy::circuits as gmpc;
+
+impl Default for strategy::StrategyPolicy {
+    fn default() -> Self {
+        strategy::StrategyPolicy {
+            stealth_risk: 100,
+            ghost_risk: 200,
+            low_win_rate: 5_000,
+            medium_volatility: 200,
+            high_volatility: 500,
+            fast_window_sec: 60,
+            medium_window_sec: 120,
+            slow_window_sec: 300,
+            min_slices: 3,
+            participation_pct: 10,
+            moderate_appetite: 150,
+            aggressive_appetite: 200,
+            aggressive_win_rate: 6_000,
+            take_profit_bps: 2_000,
+            stop_loss_bps: 1_000,
+            exit_window_pct: 20,
+            history_weight_trades: 20,
+        }
+    }
+}
+
+impl Default for risk::RiskPolicy {
+    fn default() -> Self {
+        risk::RiskPolicy {
+            high_exposure: 150,
+            over_exposure: 200,
+            low_diversification: 100,
+            high_volatility: 300,
+            very_high_volatility: 500,
+            high_liquidity_risk: 200,
+            poor_drawdown: 5_000,
+            good_sharpe: 100,
+            good_consistency: 200,
+            caution_risk: 150,
+            avoid_risk: 200,
+        }
+    }
+}
+
+impl Default for curve::CurvePolicy {
+    fn default() -> Self {
+        curve::CurvePolicy {
+            plenty_liquidity_multiple: 2,
+            liquidity_use_pct: 75,
+            momentum_bps: 1_000,
+            pressure_ratio: 2,
+            high_urgency: 150,
+            urgent: 200,
+            high_urgency_max_timing_sec: 300,
+            urgent_max_timing_sec: 60,
+            confident_liquidity_multiple: 3,
+            stable_bps: 500,
+            moderate_bps: 2_000,
+        }
+    }
+}
+
+impl Default for gmpc::GmpcPolicy {
+    fn default() -> Self {
+        gmpc::GmpcPolicy {
+            low_risk_size_pct: 50,
+            normal_risk_size_pct: 80,
+            high_volatility: 7_000,
+            high_volatility_size_pct: 70,
+            normal_slices: 3,
+            stealth_slices: 5,
+            max_privacy_slices: 7,
+            calm_window_sec: 38,
+            volatile_window_sec: 60,
+            low_risk_volatility: 5_000,
+            high_risk_volatility: 8_000,
+        }
+    }
+}
+
+/// Slots of a strategy plan's per-slice schedule, `MAX_SLICES` of
+/// `confidential_strategy_plan`
+pub const MAX_SLICES: usize = 8;
+
+/// The per-slice schedule, unpacked from the plan's output words
+impl strategy::StrategyPlan {
+    pub fn slice_sizes(&self) -> [u64; MAX_SLICES] {
+        unpack(&self.slice_size_words, 64).map(|size| size as u64)
+    }
+
+    pub fn slice_delays_sec(&self) -> [u32; MAX_SLICES] {
+        unpack(&self.slice_delay_words, 32).map(|delay| delay as u32)
+    }
+
+    pub fn exit_slice_bps(&self) -> [u16; MAX_SLICES] {
+        unpack(&self.exit_bps_words, 16).map(|bps| bps as u16)
+    }
+
+    pub fn exit_delays_sec(&self) -> [u32; MAX_SLICES] {
+        unpack(&self.exit_delay_words, 32).map(|delay| delay as u32)
+    }
+}
+
+/// Slots of `bits` bits packed into `words`, the first in the lowest bits
+fn unpack<const N: usize>(words: &[u128], bits: usize) -> [u128; N] {
+    let per_word = 128 / bits;
+    let mask = u128::MAX >> (128 - bits);
+    std::array::from_fn(|i| (words[i / per_word] >> (bits * (i % per_word))) & mask)
+}
+
+/// `confidential_strategy_plan`
+pub fn strategy_plan(
+    preferences: strategy::UserPreferences,
+    history: strategy::UserHistory,
+    curve_state: strategy::CurveState,
+    policy: strategy::StrategyPolicy,
+    input_commitment: strategy::InputCommitment,
+    rng_seed: u64,
+) -> strategy::StrategyPlan {
+    ArcisRNG::seed(rng_seed);
+    strategy::confidential_strategy_plan(
+        Enc::shared(preferences),
+        Enc::shared(history),
+        curve_state,
+        policy,
+        input_commitment,
+    )
+    .into_inner()
+}
+
+/// `confidential_risk_score`
+pub fn risk_score(
+    portfolio: risk::PortfolioContext,
+    performance: risk::PerformanceHistory,
+    market: risk::MarketConditions,
+    policy: risk::RiskPolicy,
+    input_commitment: risk::InputCommitment,
+) -> risk::RiskAssessment {
+    risk::confidential_risk_score(
+        Enc::shared(portfolio),
+        Enc::shared(performance),
+        market,
+        policy,
+        input_commitment,
+    )
+    .into_inner()
+}
+
+/// `confidential_curve_eval`
+pub fn curve_eval(
+    sizing: curve::SizingPreferences,
+    constraints: curve::UserConstraints,
+    metrics: curve::CurveMetrics,
+    policy: curve::CurvePolicy,
+    input_commitment: curve::InputCommitment,
+) -> curve::ExecutionRecommendation {
+    curve::confidential_curve_eval(
+        Enc::shared(sizing),
+        Enc::shared(constraints),
+        metrics,
+        policy,
+        input_commitment,
+    )
+    .into_inner()
+}
+
+/// `evalys_gmpc_strategy`
+pub fn gmpc_strategy(
+    intent: gmpc::IntentInput,
+    policy: gmpc::GmpcPolicy,
+    input_commitment: gmpc::InputCommitment,
+    rng_seed: u64,
+) -> gmpc::PlanOutput {
+    ArcisRNG::seed(rng_seed);
+    gmpc::evalys_gmpc_strategy(Enc::shared(intent), policy, input_commitment).into_inner()
+}
+
+/// `evalys_gmpc_strategy_quorum`: the plan and the revealed digest
+pub fn gmpc_strategy_quorum(
+    intent: gmpc::IntentInput,
+    salt: gmpc::QuorumSalt,
+    policy: gmpc::GmpcPolicy,
+    input_commitment: gmpc::InputCommitment,
+) -> (gmpc::PlanOutput, u128) {
+    let (plan, digest) = gmpc::evalys_gmpc_strategy_quorum(
+        Enc::shared(intent),
+        Enc::shared(salt),
+        policy,
+        input_commitment,
+    );
+    (plan.into_inner(), digest)
+}
+
+/// `confidential_multi_user_analytics`. The program only queues 1 to
+/// `MAX_ANALYTICS_PROFILES` profiles; an empty batch has no owner to encrypt
+/// to and yields zeroed metrics instead of running the circuit.
+pub fn multi_user_analytics(
+    profiles: &[multi_user::UserProfile],
+    aggregation_type: multi_user::AggregationType,
+    input_commitment: multi_user::InputCommitment,
+) -> multi_user::AggregatedMetrics {
+    if profiles.is_empty() {
+        return multi_user::AggregatedMetrics {
+            avg_hold_time: 0,
+            avg_win_rate: 0,
+            common_size_range_min: 0,
+            common_size_range_max: 0,
+            risk_distribution: [0; 3],
+            confidence_score: 0,
+            sample_size: 0,
+            input_commitment,
+        };
+    }
+    let profiles = profiles.iter().copied().map(Enc::shared).collect();
+    multi_user::confidential_multi_user_analytics(profiles, aggregation_type, input_commitment)
+        .into_inner()
+}
@@ -0,0 +1,246 @@
+//! Golden input/output vectors for every circuit, in `tests/vectors/`.
+//!
+//! Each file holds the vectors of one circuit; the test recomputes every
+//! output with the reference and fails on any difference. After an
+//! intentional logic change, or to fill in the output of a new vector,
+//! regenerate the outputs and review the diff:
+//!
+//! ```text
+//! EVALYS_UPDATE_VECTORS=1 cargo test -p evalys-mxe-reference --test golden
+//! ```
+
+use std::fmt::Debug;
+use std::fs;
+use std::path::PathBuf;
+
+use evalys_mxe_reference::{curve, gmpc, multi_user, risk, strategy};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Format version of the vector files
+const VECTORS_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Corpus<I, O> {
+    version: u32,
+    circuit: String,
+    vectors: Vec<Vector<I, O>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Vector<I, O> {
+    name: String,
+    input: I,
+    /// Missing for a vector not generated yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<O>,
+}
+
+/// Check (or with `EVALYS_UPDATE_VECTORS` set, rewrite) the outputs of
+/// `tests/vectors/<circuit>.json`
+fn check<I, O>(circuit: &str, run: impl Fn(&I) -> O)
+where
+    I: Serialize + DeserializeOwned,
+    O: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/vectors")
+        .join(format!("{circuit}.json"));
+    let json = fs::read_to_string(&path).unwrap();
+    let mut corpus: Corpus<I, O> =
+        serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    assert_eq!(corpus.version, VECTORS_VERSION, "{}", path.display());
+    assert_eq!(corpus.circuit, circuit, "{}", path.display());
+    assert!(!corpus.vectors.is_empty(), "{}: no vectors", path.display());
+
+    if std::env::var_os("EVALYS_UPDATE_VECTORS").is_some() {
+        for vector in &mut corpus.vectors {
+            vector.output = Some(run(&vector.input));
+        }
+        let mut json = serde_json::to_string_pretty(&corpus).unwrap();
+        json.push('\n');
+        fs::write(&path, json).unwrap();
+        return;
+    }
+
+    let mismatches: Vec<String> = corpus
+        .vectors
+        .iter()
+        .filter_map(|vector| {
+            let actual = run(&vector.input);
+            match &vector.output {
+                Some(expected) if *expected == actual => None,
+                Some(expected) => Some(format!(
+                    "{}: expected {expected:?}, got {actual:?}",
+                    vector.name
+                )),
+                None => Some(format!("{}: no output recorded", vector.name)),
+            }
+        })
+        .collect();
+    assert!(
+        mismatches.is_empty(),
+        "{circuit} differs from tests/vectors/{circuit}.json \
+         (set EVALYS_UPDATE_VECTORS=1 to regenerate):\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StrategyPlanInput {
+    preferences: strategy::UserPreferences,
+    history: strategy::UserHistory,
+    curve_state: strategy::CurveState,
+    /// Policy account contents; the default policy when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    policy: Option<strategy::StrategyPolicy>,
+    input_commitment: strategy::InputCommitment,
+    /// Seed of the in-MPC randomness
+    #[serde(default)]
+    rng_seed: u64,
+}
+
+#[test]
+fn confidential_strategy_plan() {
+    check("confidential_strategy_plan", |i: &StrategyPlanInput| {
+        evalys_mxe_reference::strategy_plan(
+            i.preferences,
+            i.history,
+            i.curve_state,
+            i.policy.unwrap_or_default(),
+            i.input_commitment,
+            i.rng_seed,
+        )
+    });
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RiskScoreInput {
+    portfolio: risk::PortfolioContext,
+    performance: risk::PerformanceHistory,
+    market: risk::MarketConditions,
+    /// Policy account contents; the default policy when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    policy: Option<risk::RiskPolicy>,
+    input_commitment: risk::InputCommitment,
+}
+
+#[test]
+fn confidential_risk_score() {
+    check("confidential_risk_score", |i: &RiskScoreInput| {
+        evalys_mxe_reference::risk_score(
+            i.portfolio,
+            i.performance,
+            i.market,
+            i.policy.unwrap_or_default(),
+            i.input_commitment,
+        )
+    });
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CurveEvalInput {
+    sizing: curve::SizingPreferences,
+    constraints: curve::UserConstraints,
+    curve: curve::CurveMetrics,
+    /// Policy account contents; the default policy when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    policy: Option<curve::CurvePolicy>,
+    input_commitment: curve::InputCommitment,
+}
+
+#[test]
+fn confidential_curve_eval() {
+    check("confidential_curve_eval", |i: &CurveEvalInput| {
+        evalys_mxe_reference::curve_eval(
+            i.sizing,
+            i.constraints,
+            i.curve,
+            i.policy.unwrap_or_default(),
+            i.input_commitment,
+        )
+    });
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GmpcStrategyInput {
+    intent: gmpc::IntentInput,
+    /// Policy account contents; the default policy when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    policy: Option<gmpc::GmpcPolicy>,
+    input_commitment: gmpc::InputCommitment,
+    /// Seed of the in-MPC randomness
+    #[serde(default)]
+    rng_seed: u64,
+}
+
+#[test]
+fn evalys_gmpc_strategy() {
+    check("evalys_gmpc_strategy", |i: &GmpcStrategyInput| {
+        evalys_mxe_reference::gmpc_strategy(
+            i.intent,
+            i.policy.unwrap_or_default(),
+            i.input_commitment,
+            i.rng_seed,
+        )
+    });
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GmpcQuorumInput {
+    intent: gmpc::IntentInput,
+    salt: gmpc::QuorumSalt,
+    /// Policy account contents; the default policy when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    policy: Option<gmpc::GmpcPolicy>,
+    input_commitment: gmpc::InputCommitment,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GmpcQuorumOutput {
+    plan: gmpc::PlanOutput,
+    digest: u128,
+}
+
+#[test]
+fn evalys_gmpc_strategy_quorum() {
+    check("evalys_gmpc_strategy_quorum", |i: &GmpcQuorumInput| {
+        let (plan, digest) = evalys_mxe_reference::gmpc_strategy_quorum(
+            i.intent,
+            i.salt,
+            i.policy.unwrap_or_default(),
+            i.input_commitment,
+        );
+        GmpcQuorumOutput { plan, digest }
+    });
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MultiUserAnalyticsInput {
+    profiles: Vec<multi_user::UserProfile>,
+    aggregation_type: multi_user::AggregationType,
+    input_commitment: multi_user::InputCommitment,
+}
+
+#[test]
+fn confidential_multi_user_analytics() {
+    check(
+        "confidential_multi_user_analytics",
+        |i: &MultiUserAnalyticsInput| {
+            evalys_mxe_reference::multi_user_analytics(
+                &i.profiles,
+                i.aggregation_type,
+                i.input_commitment,
+            )
+        },
+    );
+}
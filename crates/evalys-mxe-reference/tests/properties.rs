@@ -0,0 +1,766 @@
+//! Arithmetic invariants of the circuits over their whole input domain.
+//!
+//! Tests build in debug mode, so any integer overflow in a circuit panics and
+//! fails the property it occurs in.
+
+use evalys_mxe_reference::{curve, gmpc, multi_user, risk, strategy};
+use proptest::prelude::*;
+
+prop_compose! {
+    fn preferences()(
+        desired_size in any::<u64>(),
+        slippage_tolerance in any::<u16>(),
+        risk_appetite in any::<u8>(),
+        preferred_hold_time in any::<u32>(),
+    ) -> strategy::UserPreferences {
+        strategy::UserPreferences { desired_size, slippage_tolerance, risk_appetite, preferred_hold_time }
+    }
+}
+
+prop_compose! {
+    fn history()(
+        recent_pnl in any::<i64>(),
+        win_rate in any::<u16>(),
+        avg_hold_time in any::<u32>(),
+        total_trades in any::<u32>(),
+    ) -> strategy::UserHistory {
+        strategy::UserHistory { recent_pnl, win_rate, avg_hold_time, total_trades }
+    }
+}
+
+prop_compose! {
+    fn curve_state()(
+        current_price in any::<u64>(),
+        liquidity_depth in any::<u64>(),
+        volatility in any::<u16>(),
+        recent_volume in any::<u64>(),
+    ) -> strategy::CurveState {
+        strategy::CurveState { current_price, liquidity_depth, volatility, recent_volume }
+    }
+}
+
+prop_compose! {
+    fn portfolio()(
+        total_capital in any::<u64>(),
+        current_exposure in any::<u64>(),
+        diversification_score in any::<u8>(),
+        leverage_ratio in any::<u16>(),
+    ) -> risk::PortfolioContext {
+        risk::PortfolioContext { total_capital, current_exposure, diversification_score, leverage_ratio }
+    }
+}
+
+prop_compose! {
+    fn performance()(
+        total_pnl in any::<i64>(),
+        sharpe_ratio in any::<i16>(),
+        max_drawdown in any::<u16>(),
+        consistency_score in any::<u8>(),
+    ) -> risk::PerformanceHistory {
+        risk::PerformanceHistory { total_pnl, sharpe_ratio, max_drawdown, consistency_score }
+    }
+}
+
+prop_compose! {
+    fn market()(
+        curve_volatility in any::<u16>(),
+        liquidity_risk in any::<u8>(),
+        market_sentiment in any::<i8>(),
+    ) -> risk::MarketConditions {
+        risk::MarketConditions { curve_volatility, liquidity_risk, market_sentiment }
+    }
+}
+
+prop_compose! {
+    /// Sizing with `min_size <= max_size`
+    fn sizing()(
+        target_size in any::<u64>(),
+        bounds in any::<(u64, u64)>(),
+        capital_allocation_pct in any::<u8>(),
+    ) -> curve::SizingPreferences {
+        curve::SizingPreferences {
+            target_size,
+            min_size: bounds.0.min(bounds.1),
+            max_size: bounds.0.max(bounds.1),
+            capital_allocation_pct,
+        }
+    }
+}
+
+prop_compose! {
+    fn constraints()(
+        max_slippage_bps in any::<u16>(),
+        time_constraint_sec in any::<u32>(),
+        priority_level in any::<u8>(),
+    ) -> curve::UserConstraints {
+        curve::UserConstraints { max_slippage_bps, time_constraint_sec, priority_level }
+    }
+}
+
+prop_compose! {
+    fn curve_metrics()(
+        current_price in any::<u64>(),
+        price_change_24h in any::<i32>(),
+        liquidity_depth in any::<u64>(),
+        buy_pressure in any::<u16>(),
+        sell_pressure in any::<u16>(),
+    ) -> curve::CurveMetrics {
+        curve::CurveMetrics { current_price, price_change_24h, liquidity_depth, buy_pressure, sell_pressure }
+    }
+}
+
+prop_compose! {
+    fn intent()(
+        max_size_sol in any::<u64>(),
+        risk_level in any::<u8>(),
+        privacy_priority in any::<u8>(),
+        market_price in any::<u64>(),
+        curve_position in any::<u16>(),
+        volatility_score in any::<u16>(),
+        avg_hold_time in any::<u32>(),
+        win_rate in any::<u16>(),
+        max_dd in any::<u16>(),
+    ) -> gmpc::IntentInput {
+        gmpc::IntentInput {
+            max_size_sol,
+            risk_level,
+            privacy_priority,
+            market_price,
+            curve_position,
+            volatility_score,
+            avg_hold_time,
+            win_rate,
+            max_dd,
+        }
+    }
+}
+
+prop_compose! {
+    /// A policy the program accepts: percentages up to 100, 1 to 32 slices
+    /// and windows up to a day
+    fn gmpc_policy()(
+        size_pcts in (0..=100u8, 0..=100u8, 0..=100u8),
+        high_volatility in any::<u16>(),
+        slices in (1..=32u8, 1..=32u8, 1..=32u8),
+        windows in (0..=86_400u32, 0..=86_400u32),
+        risk_volatilities in any::<(u16, u16)>(),
+    ) -> gmpc::GmpcPolicy {
+        gmpc::GmpcPolicy {
+            low_risk_size_pct: size_pcts.0,
+            normal_risk_size_pct: size_pcts.1,
+            high_volatility,
+            high_volatility_size_pct: size_pcts.2,
+            normal_slices: slices.0,
+            stealth_slices: slices.1,
+            max_privacy_slices: slices.2,
+            calm_window_sec: windows.0,
+            volatile_window_sec: windows.1,
+            low_risk_volatility: risk_volatilities.0,
+            high_risk_volatility: risk_volatilities.1,
+        }
+    }
+}
+
+/// `pair` with its lower value first, for thresholds a tier apart
+fn ordered<T: Ord>(pair: (T, T)) -> (T, T) {
+    if pair.0 <= pair.1 {
+        pair
+    } else {
+        (pair.1, pair.0)
+    }
+}
+
+prop_compose! {
+    /// A policy the program accepts: each tier's threshold at or below the
+    /// next one's, win rates and the stop loss up to 100%, windows up to a
+    /// day and fastest at high volatility, 1 to 8 slices
+    fn strategy_policy()(
+        (stealth_risk, ghost_risk) in any::<(u16, u16)>().prop_map(ordered),
+        win_rates in (0..=10_000u16, 0..=10_000u16),
+        (medium_volatility, high_volatility) in any::<(u16, u16)>().prop_map(ordered),
+        windows in prop::array::uniform3(0..=86_400u32),
+        min_slices in 1..=8u8,
+        pcts in (0..=100u8, 0..=100u8),
+        (moderate_appetite, aggressive_appetite) in any::<(u8, u8)>().prop_map(ordered),
+        take_profit_bps in any::<u16>(),
+        stop_loss_bps in 0..=10_000u16,
+        history_weight_trades in 1..=u16::MAX,
+    ) -> strategy::StrategyPolicy {
+        let mut windows = windows;
+        windows.sort();
+        strategy::StrategyPolicy {
+            stealth_risk,
+            ghost_risk,
+            low_win_rate: win_rates.0,
+            medium_volatility,
+            high_volatility,
+            fast_window_sec: windows[0],
+            medium_window_sec: windows[1],
+            slow_window_sec: windows[2],
+            min_slices,
+            participation_pct: pcts.0,
+            moderate_appetite,
+            aggressive_appetite,
+            aggressive_win_rate: win_rates.1,
+            take_profit_bps,
+            stop_loss_bps,
+            exit_window_pct: pcts.1,
+            history_weight_trades,
+        }
+    }
+}
+
+prop_compose! {
+    /// A policy the program accepts: each tier's threshold at or below the
+    /// next one's and the drawdown up to 100%
+    fn risk_policy()(
+        (high_exposure, over_exposure) in any::<(u8, u8)>().prop_map(ordered),
+        low_diversification in any::<u8>(),
+        (high_volatility, very_high_volatility) in any::<(u16, u16)>().prop_map(ordered),
+        high_liquidity_risk in any::<u8>(),
+        poor_drawdown in 0..=10_000u16,
+        good_sharpe in any::<i16>(),
+        good_consistency in any::<u8>(),
+        (caution_risk, avoid_risk) in any::<(u8, u8)>().prop_map(ordered),
+    ) -> risk::RiskPolicy {
+        risk::RiskPolicy {
+            high_exposure,
+            over_exposure,
+            low_diversification,
+            high_volatility,
+            very_high_volatility,
+            high_liquidity_risk,
+            poor_drawdown,
+            good_sharpe,
+            good_consistency,
+            caution_risk,
+            avoid_risk,
+        }
+    }
+}
+
+prop_compose! {
+    /// A policy the program accepts: each tier's threshold at or below the
+    /// next one's, the more urgent timing cap the shorter, and up to 100% of
+    /// the liquidity used
+    fn curve_policy()(
+        multiples in any::<(u8, u8)>(),
+        liquidity_use_pct in 0..=100u8,
+        momentum_bps in any::<u16>(),
+        pressure_ratio in any::<u8>(),
+        (high_urgency, urgent) in any::<(u8, u8)>().prop_map(ordered),
+        (urgent_max_timing_sec, high_urgency_max_timing_sec) in any::<(u32, u32)>().prop_map(ordered),
+        (stable_bps, moderate_bps) in any::<(u16, u16)>().prop_map(ordered),
+    ) -> curve::CurvePolicy {
+        curve::CurvePolicy {
+            plenty_liquidity_multiple: multiples.0,
+            liquidity_use_pct,
+            momentum_bps,
+            pressure_ratio,
+            high_urgency,
+            urgent,
+            high_urgency_max_timing_sec,
+            urgent_max_timing_sec,
+            confident_liquidity_multiple: multiples.1,
+            stable_bps,
+            moderate_bps,
+        }
+    }
+}
+
+prop_compose! {
+    fn profile()(
+        profile_id_hash in any::<u64>(),
+        avg_hold_time in any::<u32>(),
+        win_rate in any::<u16>(),
+        preferred_size_range_min in any::<u64>(),
+        preferred_size_range_max in any::<u64>(),
+        risk_tolerance in any::<u8>(),
+    ) -> multi_user::UserProfile {
+        multi_user::UserProfile {
+            profile_id_hash,
+            avg_hold_time,
+            win_rate,
+            preferred_size_range_min,
+            preferred_size_range_max,
+            risk_tolerance,
+        }
+    }
+}
+
+fn aggregation_type() -> impl Strategy<Value = multi_user::AggregationType> {
+    prop_oneof![
+        Just(multi_user::AggregationType::CurveInsights),
+        Just(multi_user::AggregationType::MarketSentiment),
+        Just(multi_user::AggregationType::PatternDetection),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn strategy_plan_stays_in_range(
+        preferences in preferences(),
+        history in history(),
+        curve_state in curve_state(),
+        (hi, lo) in any::<(u128, u128)>(),
+        rng_seed in any::<u64>(),
+    ) {
+        let commitment = strategy::InputCommitment { hi, lo };
+        let plan = evalys_mxe_reference::strategy_plan(
+            preferences,
+            history,
+            curve_state,
+            strategy::StrategyPolicy::default(),
+            commitment,
+            rng_seed,
+        );
+        let desired = preferences.desired_size;
+
+        // risk_level is capped rather than truncated: it never falls below the
+        // volatility component, which is at most 6553
+        prop_assert!(plan.risk_level as u16 >= (curve_state.volatility / 10).min(255));
+        prop_assert_eq!(
+            plan.recommended_mode,
+            match plan.risk_level {
+                201.. => 2,
+                101..=200 => 1,
+                _ => 0,
+            }
+        );
+        prop_assert!(plan.max_notional as u128 <= (desired as u128 * 2).min(u64::MAX as u128));
+        // No slice takes more than the curve absorbs: impact within the
+        // slippage tolerance and at most 10% of recent volume (plus the
+        // remainder's lamport); a curve without a price takes nothing
+        let slice_cap = if curve_state.current_price == 0 {
+            0
+        } else {
+            (curve_state.liquidity_depth as u128 * preferences.slippage_tolerance as u128 / 10_000)
+                .min(curve_state.recent_volume as u128 / 10)
+        };
+        prop_assert!(plan.slice_sizes().iter().all(|size| *size as u128 <= slice_cap + 1));
+        prop_assert!(plan.max_notional as u128 <= slice_cap * 8);
+        prop_assert!((3..=8).contains(&plan.num_slices));
+        // The schedule covers the desired size exactly, up to max_notional,
+        // within the window; jitter moves a slice by at most 30% of the base
+        // size
+        let planned = desired.min(plan.max_notional);
+        let slices = plan.num_slices as usize;
+        let jitter = plan.slice_size_base * 3 / 10;
+        prop_assert!(plan.slice_size_base * plan.num_slices as u64 <= planned);
+        prop_assert!(planned - plan.slice_size_base * (plan.num_slices as u64) < plan.num_slices as u64);
+        prop_assert_eq!(plan.slice_sizes().iter().sum::<u64>(), planned);
+        prop_assert!(plan.slice_sizes()[..slices]
+            .iter()
+            .all(|size| size + jitter >= plan.slice_size_base
+                && *size <= plan.slice_size_base + 1 + jitter));
+        prop_assert!(plan.slice_sizes()[slices..].iter().all(|size| *size == 0));
+        prop_assert_eq!(plan.slice_delays_sec()[0], 0);
+        prop_assert!(plan.slice_delays_sec()[..slices]
+            .windows(2)
+            .all(|pair| pair[0] < pair[1]));
+        prop_assert!(plan.slice_delays_sec()[..slices]
+            .iter()
+            .all(|delay| *delay < plan.timing_window_sec));
+        prop_assert!(plan.slice_delays_sec()[slices..].iter().all(|delay| *delay == 0));
+        prop_assert_eq!(plan.input_commitment, commitment);
+    }
+
+    #[test]
+    fn strategy_plan_follows_a_tuned_policy(
+        preferences in preferences(),
+        history in history(),
+        curve_state in curve_state(),
+        policy in strategy_policy(),
+        rng_seed in any::<u64>(),
+    ) {
+        let commitment = strategy::InputCommitment { hi: 0, lo: 0 };
+        let plan = evalys_mxe_reference::strategy_plan(preferences, history, curve_state, policy, commitment, rng_seed);
+
+        prop_assert_eq!(
+            plan.recommended_mode,
+            if plan.risk_level as u16 > policy.ghost_risk {
+                2
+            } else if plan.risk_level as u16 > policy.stealth_risk {
+                1
+            } else {
+                0
+            }
+        );
+        prop_assert_eq!(
+            plan.timing_window_sec,
+            if curve_state.volatility > policy.high_volatility {
+                policy.fast_window_sec
+            } else if curve_state.volatility > policy.medium_volatility {
+                policy.medium_window_sec
+            } else {
+                policy.slow_window_sec
+            }
+        );
+        prop_assert!((policy.min_slices..=8).contains(&plan.num_slices));
+        let slices = plan.num_slices as usize;
+        prop_assert_eq!(
+            plan.slice_sizes().iter().sum::<u64>(),
+            preferences.desired_size.min(plan.max_notional)
+        );
+        prop_assert!(plan.slice_delays_sec()[..slices]
+            .iter()
+            .all(|delay| *delay < plan.timing_window_sec.max(1)));
+        prop_assert!(plan.exit_start_sec >= plan.timing_window_sec);
+        prop_assert_eq!(plan.exit_slice_bps().iter().sum::<u16>(), 10_000);
+        prop_assert!(plan.stop_loss_price <= curve_state.current_price);
+        prop_assert!(plan.take_profit_price >= curve_state.current_price);
+    }
+
+    #[test]
+    fn strategy_exit_follows_the_hold_time(
+        preferences in preferences(),
+        history in history(),
+        curve_state in curve_state(),
+        rng_seed in any::<u64>(),
+    ) {
+        let commitment = strategy::InputCommitment { hi: 0, lo: 0 };
+        let plan = evalys_mxe_reference::strategy_plan(
+            preferences,
+            history,
+            curve_state,
+            strategy::StrategyPolicy::default(),
+            commitment,
+            rng_seed,
+        );
+
+        // The target exit, hold_sec after the entry window, lies between the
+        // preferred and the average hold time (both halved on a volatile
+        // curve), and is the preference alone without history
+        let halve = |hold: u32| if curve_state.volatility > 500 { hold / 2 } else { hold };
+        let bounds = (
+            halve(preferences.preferred_hold_time.min(history.avg_hold_time)),
+            halve(preferences.preferred_hold_time.max(history.avg_hold_time)),
+        );
+        prop_assert!(plan.exit_start_sec >= plan.timing_window_sec);
+        if plan.exit_start_sec < u32::MAX {
+            let hold = plan.exit_start_sec as u64 + plan.exit_window_sec as u64 / 2
+                - plan.timing_window_sec as u64;
+            prop_assert!((bounds.0 as u64..=bounds.1 as u64).contains(&hold));
+            prop_assert_eq!(plan.exit_window_sec as u64, hold * 20 / 100);
+            if history.total_trades == 0 {
+                prop_assert_eq!(hold, halve(preferences.preferred_hold_time) as u64);
+            }
+        }
+
+        // Equal shares of the whole position, one exit slice per entry slice,
+        // in order within the exit window
+        let slices = plan.num_slices as usize;
+        let share = 10_000 / plan.num_slices as u16;
+        prop_assert_eq!(plan.exit_slice_bps().iter().sum::<u16>(), 10_000);
+        prop_assert!(plan.exit_slice_bps()[..slices].iter().all(|bps| *bps == share || *bps == share + 1));
+        prop_assert!(plan.exit_slice_bps()[slices..].iter().all(|bps| *bps == 0));
+        prop_assert_eq!(plan.exit_delays_sec()[0], 0);
+        prop_assert!(plan.exit_delays_sec()[..slices].windows(2).all(|pair| pair[0] <= pair[1]));
+        prop_assert!(plan.exit_delays_sec()[..slices]
+            .iter()
+            .all(|delay| *delay < plan.exit_window_sec.max(1)));
+        prop_assert!(plan.exit_delays_sec()[slices..].iter().all(|delay| *delay == 0));
+
+        // Levels on either side of the price: at least 20% above, and at
+        // least 10% below (7.5% after losses)
+        let price = curve_state.current_price as u128;
+        let stop_floor_bps = if history.recent_pnl < 0 { 750 } else { 1_000 };
+        prop_assert!(plan.take_profit_price as u128 >= (price * 12_000 / 10_000).min(u64::MAX as u128));
+        prop_assert!(plan.stop_loss_price as u128 <= price * (10_000 - stop_floor_bps) / 10_000);
+    }
+
+    #[test]
+    fn strategy_risk_is_monotonic_in_volatility(
+        preferences in preferences(),
+        history in history(),
+        curve_state in curve_state(),
+        policy in strategy_policy(),
+        volatilities in any::<(u16, u16)>(),
+    ) {
+        // Holds for every accepted policy, as the program keeps its tiers in order
+        let (low, high) = (volatilities.0.min(volatilities.1), volatilities.0.max(volatilities.1));
+        let commitment = strategy::InputCommitment { hi: 0, lo: 0 };
+        let calm = strategy::CurveState { volatility: low, ..curve_state };
+        let volatile = strategy::CurveState { volatility: high, ..curve_state };
+        let calm = evalys_mxe_reference::strategy_plan(preferences, history, calm, policy, commitment, 0);
+        let volatile = evalys_mxe_reference::strategy_plan(preferences, history, volatile, policy, commitment, 0);
+        prop_assert!(calm.risk_level <= volatile.risk_level);
+        prop_assert!(calm.recommended_mode <= volatile.recommended_mode);
+        prop_assert!(calm.timing_window_sec >= volatile.timing_window_sec);
+    }
+
+    #[test]
+    fn strategy_slicing_follows_the_curve(
+        preferences in preferences(),
+        history in history(),
+        curve_state in curve_state(),
+        depths in any::<(u64, u64)>(),
+    ) {
+        // Deeper liquidity never needs more slices nor takes less
+        let (shallow, deep) = (depths.0.min(depths.1), depths.0.max(depths.1));
+        let commitment = strategy::InputCommitment { hi: 0, lo: 0 };
+        let shallow = strategy::CurveState { liquidity_depth: shallow, ..curve_state };
+        let deep = strategy::CurveState { liquidity_depth: deep, ..curve_state };
+        let shallow = evalys_mxe_reference::strategy_plan(preferences, history, shallow, strategy::StrategyPolicy::default(), commitment, 0);
+        let deep = evalys_mxe_reference::strategy_plan(preferences, history, deep, strategy::StrategyPolicy::default(), commitment, 0);
+        prop_assert!(deep.num_slices <= shallow.num_slices);
+        prop_assert!(deep.max_notional >= shallow.max_notional);
+        prop_assert!(
+            deep.slice_sizes().iter().sum::<u64>() >= shallow.slice_sizes().iter().sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn risk_score_stays_in_range(
+        portfolio in portfolio(),
+        performance in performance(),
+        market in market(),
+        (hi, lo) in any::<(u128, u128)>(),
+    ) {
+        let commitment = risk::InputCommitment { hi, lo };
+        let assessment = evalys_mxe_reference::risk_score(portfolio, performance, market, risk::RiskPolicy::default(), commitment);
+
+        prop_assert!(assessment.portfolio_risk >= 100);
+        prop_assert!(assessment.trade_risk >= 100);
+        // Within the performance adjustment (-30..=+50) of the component mean
+        let base = (assessment.portfolio_risk as i16 + assessment.trade_risk as i16) / 2;
+        prop_assert!((assessment.overall_risk_score as i16 - base) >= -30);
+        prop_assert!((assessment.overall_risk_score as i16 - base) <= 50);
+        prop_assert_eq!(
+            assessment.recommendation,
+            match assessment.overall_risk_score {
+                201.. => 2,
+                151..=200 => 1,
+                _ => 0,
+            }
+        );
+        prop_assert_eq!(assessment.input_commitment, commitment);
+    }
+
+    #[test]
+    fn risk_score_follows_a_tuned_policy(
+        portfolio in portfolio(),
+        performance in performance(),
+        market in market(),
+        policy in risk_policy(),
+    ) {
+        let commitment = risk::InputCommitment { hi: 0, lo: 0 };
+        let assessment = evalys_mxe_reference::risk_score(portfolio, performance, market, policy, commitment);
+
+        prop_assert_eq!(
+            assessment.trade_risk,
+            if market.curve_volatility > policy.very_high_volatility {
+                255
+            } else if market.curve_volatility > policy.high_volatility {
+                200
+            } else if market.liquidity_risk > policy.high_liquidity_risk {
+                180
+            } else {
+                100
+            }
+        );
+        prop_assert_eq!(
+            assessment.recommendation,
+            if assessment.overall_risk_score > policy.avoid_risk {
+                2
+            } else if assessment.overall_risk_score > policy.caution_risk {
+                1
+            } else {
+                0
+            }
+        );
+    }
+
+    #[test]
+    fn risk_score_is_monotonic_in_volatility(
+        portfolio in portfolio(),
+        performance in performance(),
+        market in market(),
+        policy in risk_policy(),
+        volatilities in any::<(u16, u16)>(),
+    ) {
+        // Holds for every accepted policy, as the program keeps its tiers in order
+        let (low, high) = (volatilities.0.min(volatilities.1), volatilities.0.max(volatilities.1));
+        let commitment = risk::InputCommitment { hi: 0, lo: 0 };
+        let calm = risk::MarketConditions { curve_volatility: low, ..market };
+        let volatile = risk::MarketConditions { curve_volatility: high, ..market };
+        let calm = evalys_mxe_reference::risk_score(portfolio, performance, calm, policy, commitment);
+        let volatile = evalys_mxe_reference::risk_score(portfolio, performance, volatile, policy, commitment);
+        prop_assert!(calm.trade_risk <= volatile.trade_risk);
+        prop_assert!(calm.overall_risk_score <= volatile.overall_risk_score);
+    }
+
+    #[test]
+    fn curve_eval_respects_size_bounds(
+        sizing in sizing(),
+        constraints in constraints(),
+        metrics in curve_metrics(),
+        (hi, lo) in any::<(u128, u128)>(),
+    ) {
+        let commitment = curve::InputCommitment { hi, lo };
+        let recommendation = evalys_mxe_reference::curve_eval(sizing, constraints, metrics, curve::CurvePolicy::default(), commitment);
+
+        prop_assert!(recommendation.recommended_size >= sizing.min_size);
+        prop_assert!(recommendation.recommended_size <= sizing.max_size);
+        // Entry target within 1% of the current price
+        let price = metrics.current_price as u128;
+        let target = recommendation.entry_price_target as u128;
+        prop_assert!(target * 100 + 99 >= price * 99);
+        prop_assert!(target * 100 <= price * 101);
+        prop_assert!(recommendation.optimal_timing <= constraints.time_constraint_sec);
+        prop_assert!((100..=200).contains(&recommendation.confidence_score));
+        prop_assert_eq!(recommendation.input_commitment, commitment);
+    }
+
+    #[test]
+    fn curve_eval_follows_a_tuned_policy(
+        sizing in sizing(),
+        constraints in constraints(),
+        metrics in curve_metrics(),
+        policy in curve_policy(),
+    ) {
+        let commitment = curve::InputCommitment { hi: 0, lo: 0 };
+        let recommendation = evalys_mxe_reference::curve_eval(sizing, constraints, metrics, policy, commitment);
+
+        prop_assert!(recommendation.recommended_size >= sizing.min_size);
+        prop_assert!(recommendation.recommended_size <= sizing.max_size);
+        let time_constraint = constraints.time_constraint_sec;
+        prop_assert_eq!(
+            recommendation.optimal_timing,
+            if recommendation.execution_urgency > policy.urgent {
+                time_constraint.min(policy.urgent_max_timing_sec)
+            } else if recommendation.execution_urgency > policy.high_urgency {
+                time_constraint.min(policy.high_urgency_max_timing_sec)
+            } else {
+                time_constraint
+            }
+        );
+        // More urgent never means a later start
+        prop_assert!(recommendation.optimal_timing >= time_constraint.min(policy.urgent_max_timing_sec));
+        prop_assert!((100..=200).contains(&recommendation.confidence_score));
+    }
+
+    #[test]
+    fn gmpc_strategy_stays_in_range(
+        intent in intent(),
+        (hi, lo) in any::<(u128, u128)>(),
+        rng_seed in any::<u64>(),
+    ) {
+        let commitment = gmpc::InputCommitment { hi, lo };
+        let plan = evalys_mxe_reference::gmpc_strategy(intent, gmpc::GmpcPolicy::default(), commitment, rng_seed);
+
+        prop_assert!(plan.recommended_size_sol <= intent.max_size_sol);
+        // At least 35% (low risk, high volatility) of the maximum, rounded down
+        prop_assert!(plan.recommended_size_sol as u128 * 10_000 + 9_999 >= intent.max_size_sol as u128 * 3_500);
+        // Up to `privacy_priority` extra slices, and the window moved by up to
+        // 10% per privacy level
+        let level = intent.privacy_priority.min(2);
+        let slices = 3 + 2 * level;
+        prop_assert!((slices..=slices + level).contains(&plan.slice_count));
+        let window = if intent.volatility_score > 7000 { 60 } else { 38 };
+        let jitter = window * (10 * (level as u32 + 1)) / 100;
+        prop_assert!((window - jitter..=window + jitter).contains(&plan.time_window_sec));
+        prop_assert!(plan.mev_route <= 1 && plan.privacy_mode <= 2 && plan.risk_class <= 2);
+        prop_assert_eq!(plan.input_commitment, commitment);
+    }
+
+    #[test]
+    fn gmpc_strategy_follows_a_tuned_policy(
+        intent in intent(),
+        policy in gmpc_policy(),
+        rng_seed in any::<u64>(),
+    ) {
+        let commitment = gmpc::InputCommitment { hi: 0, lo: 0 };
+        let plan = evalys_mxe_reference::gmpc_strategy(intent, policy, commitment, rng_seed);
+
+        prop_assert!(plan.recommended_size_sol <= intent.max_size_sol);
+        let level = intent.privacy_priority.min(2);
+        let slices = match level {
+            0 => policy.normal_slices,
+            1 => policy.stealth_slices,
+            _ => policy.max_privacy_slices,
+        };
+        prop_assert!((slices..=slices + level).contains(&plan.slice_count));
+        let window = if intent.volatility_score > policy.high_volatility {
+            policy.volatile_window_sec
+        } else {
+            policy.calm_window_sec
+        };
+        let jitter = window * (10 * (level as u32 + 1)) / 100;
+        prop_assert!((window - jitter..=window + jitter).contains(&plan.time_window_sec));
+    }
+
+    #[test]
+    fn gmpc_risk_class_is_monotonic_in_volatility(
+        intent in intent(),
+        volatilities in any::<(u16, u16)>(),
+    ) {
+        let (low, high) = (volatilities.0.min(volatilities.1), volatilities.0.max(volatilities.1));
+        let commitment = gmpc::InputCommitment { hi: 0, lo: 0 };
+        let calm = gmpc::IntentInput { volatility_score: low, ..intent };
+        let volatile = gmpc::IntentInput { volatility_score: high, ..intent };
+        let calm = evalys_mxe_reference::gmpc_strategy(calm, gmpc::GmpcPolicy::default(), commitment, 0);
+        let volatile = evalys_mxe_reference::gmpc_strategy(volatile, gmpc::GmpcPolicy::default(), commitment, 0);
+        prop_assert!(calm.risk_class <= volatile.risk_class);
+        prop_assert!(calm.recommended_size_sol >= volatile.recommended_size_sol);
+    }
+
+    #[test]
+    fn quorum_digest_masks_the_plan(
+        intent in intent(),
+        salt in any::<u128>(),
+        (hi, lo) in any::<(u128, u128)>(),
+    ) {
+        let commitment = gmpc::InputCommitment { hi, lo };
+        let (plan, digest) =
+            evalys_mxe_reference::gmpc_strategy_quorum(intent, gmpc::QuorumSalt { salt }, gmpc::GmpcPolicy::default(), commitment);
+
+        // The unjittered plan: both legs must agree
+        let jittered = evalys_mxe_reference::gmpc_strategy(intent, gmpc::GmpcPolicy::default(), commitment, 0);
+        prop_assert_eq!(plan.slice_count, 3 + 2 * intent.privacy_priority.min(2));
+        prop_assert_eq!(
+            plan.time_window_sec,
+            if intent.volatility_score > 7000 { 60 } else { 38 }
+        );
+        prop_assert_eq!(
+            plan,
+            gmpc::PlanOutput {
+                slice_count: plan.slice_count,
+                time_window_sec: plan.time_window_sec,
+                ..jittered
+            }
+        );
+        let packed = ((plan.recommended_size_sol as u128) << 64)
+            | ((plan.time_window_sec as u128) << 32)
+            | ((plan.slice_count as u128) << 24)
+            | ((plan.mev_route as u128) << 16)
+            | ((plan.privacy_mode as u128) << 8)
+            | plan.risk_class as u128;
+        prop_assert_eq!(digest.wrapping_sub(salt), packed);
+    }
+
+    #[test]
+    fn multi_user_analytics_aggregates_every_profile(
+        profiles in prop::collection::vec(profile(), 0..=16),
+        aggregation_type in aggregation_type(),
+        (hi, lo) in any::<(u128, u128)>(),
+    ) {
+        let commitment = multi_user::InputCommitment { hi, lo };
+        let metrics = evalys_mxe_reference::multi_user_analytics(&profiles, aggregation_type, commitment);
+
+        prop_assert_eq!(metrics.sample_size as usize, profiles.len());
+        prop_assert_eq!(metrics.risk_distribution.iter().sum::<u16>() as usize, profiles.len());
+        prop_assert_eq!(metrics.input_commitment, commitment);
+        if profiles.is_empty() {
+            prop_assert_eq!((metrics.avg_hold_time, metrics.avg_win_rate, metrics.confidence_score), (0, 0, 0));
+            return Ok(());
+        }
+        let hold_times = profiles.iter().map(|p| p.avg_hold_time);
+        prop_assert!(metrics.avg_hold_time >= hold_times.clone().min().unwrap());
+        prop_assert!(metrics.avg_hold_time <= hold_times.max().unwrap());
+        let win_rates = profiles.iter().map(|p| p.win_rate);
+        prop_assert!(metrics.avg_win_rate >= win_rates.clone().min().unwrap());
+        prop_assert!(metrics.avg_win_rate <= win_rates.max().unwrap());
+        prop_assert!(metrics.confidence_score <= 10_000);
+    }
+}
@@ -32,6 +32,27 @@ mod circuits {
         pub sell_pressure: u16,            // Sell pressure indicator
     }
 
+    /// Tunable thresholds (plaintext, from the program's policy account)
+    pub struct CurvePolicy {
+        pub plenty_liquidity_multiple: u8, // Liquidity above this multiple of max_size fills the full target
+        pub liquidity_use_pct: u8,         // Share of limited liquidity to use, in percent (0-100)
+        pub momentum_bps: u16,             // 24h change beyond which the entry price moves 1%
+        pub pressure_ratio: u8,            // Ratio of one pressure to the other that sets urgency
+        pub high_urgency: u8,              // Urgency above which timing is capped at high_urgency_max_timing_sec
+        pub urgent: u8,                    // Urgency above which timing is capped at urgent_max_timing_sec
+        pub high_urgency_max_timing_sec: u32, // Longest timing at high urgency
+        pub urgent_max_timing_sec: u32,    // Longest timing when urgent
+        pub confident_liquidity_multiple: u8, // Liquidity above this multiple of the size gives full confidence
+        pub stable_bps: u16,               // 24h change within which the price is stable
+        pub moderate_bps: u16,             // 24h change within which the movement is moderate
+    }
+
+    /// Commitment to the request's encrypted inputs (plaintext, computed on-chain)
+    pub struct InputCommitment {
+        pub hi: u128,                     // Upper 16 bytes of the SHA-256 commitment
+        pub lo: u128,                     // Lower 16 bytes of the SHA-256 commitment
+    }
+
     /// Execution recommendation (encrypted)
     pub struct ExecutionRecommendation {
         pub recommended_size: u64,        // Recommended execution size
@@ -39,6 +60,7 @@ mod circuits {
         pub execution_urgency: u8,         // Urgency: 0-255 (higher = more urgent)
         pub optimal_timing: u32,          // Optimal timing window in seconds
         pub confidence_score: u8,          // Confidence: 0-255
+        pub input_commitment: InputCommitment, // Echo of the request commitment
     }
 
     #[instruction]
@@ -46,17 +68,22 @@ mod circuits {
         sizing: Enc<Shared, SizingPreferences>,
         constraints: Enc<Shared, UserConstraints>,
         curve: CurveMetrics,
+        policy: CurvePolicy,
+        input_commitment: InputCommitment,
     ) -> Enc<Shared, ExecutionRecommendation> {
         let size_prefs = sizing.to_arcis();
         let user_constraints = constraints.to_arcis();
 
         // Calculate recommended size based on curve liquidity and user preferences
-        let recommended_size = if curve.liquidity_depth > size_prefs.max_size * 2 {
+        // (products are widened to u128 so large sizes cannot overflow)
+        let recommended_size = if curve.liquidity_depth as u128
+            > size_prefs.max_size as u128 * policy.plenty_liquidity_multiple as u128
+        {
             // Plenty of liquidity: can execute full target
             size_prefs.target_size
         } else if curve.liquidity_depth > size_prefs.min_size {
             // Limited liquidity: use available liquidity with buffer
-            (curve.liquidity_depth * 3) / 4 // Use 75% of available liquidity
+            (curve.liquidity_depth as u128 * policy.liquidity_use_pct as u128 / 100) as u64
         } else {
             // Very limited liquidity: use minimum
             size_prefs.min_size
@@ -66,12 +93,18 @@ mod circuits {
         let recommended_size = recommended_size.min(size_prefs.max_size).max(size_prefs.min_size);
 
         // Entry price target: consider current price and momentum
-        let price_adjustment = if curve.price_change_24h > 1000 {
-            // Strong upward momentum: slight premium
-            (curve.current_price * 101) / 100
-        } else if curve.price_change_24h < -1000 {
+        let momentum = policy.momentum_bps as i32;
+        let price_adjustment = if curve.price_change_24h > momentum {
+            // Strong upward momentum: slight premium (capped at u64::MAX)
+            let premium = curve.current_price as u128 * 101 / 100;
+            if premium > u64::MAX as u128 {
+                u64::MAX
+            } else {
+                premium as u64
+            }
+        } else if curve.price_change_24h < -momentum {
             // Downward momentum: can get discount
-            (curve.current_price * 99) / 100
+            (curve.current_price as u128 * 99 / 100) as u64
         } else {
             // Stable: use current price
             curve.current_price
@@ -79,9 +112,10 @@ mod circuits {
         let entry_price_target = price_adjustment;
 
         // Execution urgency based on buy/sell pressure and user priority
-        let pressure_urgency = if curve.buy_pressure > curve.sell_pressure * 2 {
+        let ratio = policy.pressure_ratio as u32;
+        let pressure_urgency = if curve.buy_pressure as u32 > curve.sell_pressure as u32 * ratio {
             200u8 // High buy pressure: urgent
-        } else if curve.sell_pressure > curve.buy_pressure * 2 {
+        } else if curve.sell_pressure as u32 > curve.buy_pressure as u32 * ratio {
             50u8 // High sell pressure: wait
         } else {
             100u8 // Balanced: moderate urgency
@@ -90,16 +124,18 @@ mod circuits {
         let execution_urgency = ((pressure_urgency as u16 + user_constraints.priority_level as u16) / 2) as u8;
 
         // Optimal timing: balance user constraints with market conditions
-        let optimal_timing = if execution_urgency > 200 {
-            user_constraints.time_constraint_sec.min(60) // Urgent: max 60s
-        } else if execution_urgency > 150 {
-            user_constraints.time_constraint_sec.min(300) // High urgency: max 5min
+        let optimal_timing = if execution_urgency > policy.urgent {
+            user_constraints.time_constraint_sec.min(policy.urgent_max_timing_sec) // Urgent
+        } else if execution_urgency > policy.high_urgency {
+            user_constraints.time_constraint_sec.min(policy.high_urgency_max_timing_sec) // High urgency
         } else {
             user_constraints.time_constraint_sec // Normal: use user constraint
         };
 
         // Confidence score: based on liquidity depth and price stability
-        let liquidity_confidence = if curve.liquidity_depth > recommended_size * 3 {
+        let liquidity_confidence = if curve.liquidity_depth as u128
+            > recommended_size as u128 * policy.confident_liquidity_multiple as u128
+        {
             200u8 // Plenty of liquidity
         } else if curve.liquidity_depth > recommended_size {
             150u8 // Adequate liquidity
@@ -107,9 +143,12 @@ mod circuits {
             100u8 // Limited liquidity
         };
 
-        let stability_confidence = if curve.price_change_24h.abs() < 500 {
+        // Range checks rather than abs(), which overflows on i32::MIN
+        let stable = policy.stable_bps as i32;
+        let moderate = policy.moderate_bps as i32;
+        let stability_confidence = if curve.price_change_24h > -stable && curve.price_change_24h < stable {
             200u8 // Stable price
-        } else if curve.price_change_24h.abs() < 2000 {
+        } else if curve.price_change_24h > -moderate && curve.price_change_24h < moderate {
             150u8 // Moderate movement
         } else {
             100u8 // High volatility
@@ -123,6 +162,7 @@ mod circuits {
             execution_urgency,
             optimal_timing,
             confidence_score,
+            input_commitment,
         };
 
         sizing.owner.from_arcis(recommendation)
@@ -25,6 +25,12 @@ mod circuits {
         PatternDetection = 2,
     }
 
+    /// Commitment to the request's encrypted inputs (plaintext, computed on-chain)
+    pub struct InputCommitment {
+        pub hi: u128,                     // Upper 16 bytes of the SHA-256 commitment
+        pub lo: u128,                     // Lower 16 bytes of the SHA-256 commitment
+    }
+
     /// Aggregated metrics output (encrypted)
     pub struct AggregatedMetrics {
         pub avg_hold_time: u32,            // Average hold time across users
@@ -34,33 +40,24 @@ mod circuits {
         pub risk_distribution: [u16; 3],   // Distribution: [low, normal, high]
         pub confidence_score: u16,          // Confidence in aggregation (0-10000)
         pub sample_size: u32,              // Number of users in aggregation
+        pub input_commitment: InputCommitment, // Echo of the request commitment
     }
 
     #[instruction]
     pub fn confidential_multi_user_analytics(
         profiles: Vec<Enc<Shared, UserProfile>>,
         aggregation_type: AggregationType,
+        input_commitment: InputCommitment,
     ) -> Enc<Shared, AggregatedMetrics> {
         // Decrypt all profiles (inside MPC, never exposed)
         let decrypted_profiles: Vec<_> = profiles.iter()
             .map(|p| p.to_arcis())
             .collect();
 
+        // Never zero: the program only queues 1 to MAX_ANALYTICS_PROFILES
+        // profiles, and the result is encrypted to the first one's owner
         let sample_size = decrypted_profiles.len() as u32;
 
-        if sample_size == 0 {
-            // Return empty metrics if no profiles
-            return profiles[0].owner.from_arcis(AggregatedMetrics {
-                avg_hold_time: 0,
-                avg_win_rate: 0,
-                common_size_range_min: 0,
-                common_size_range_max: 0,
-                risk_distribution: [0, 0, 0],
-                confidence_score: 0,
-                sample_size: 0,
-            });
-        }
-
         // Compute aggregated metrics (all inside MPC)
         let total_hold_time: u64 = decrypted_profiles.iter()
             .map(|p| p.avg_hold_time as u64)
@@ -119,6 +116,7 @@ mod circuits {
             risk_distribution: risk_dist,
             confidence_score,
             sample_size,
+            input_commitment,
         };
 
         // Return encrypted result (owned by first profile's owner)
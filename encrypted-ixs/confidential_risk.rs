@@ -31,12 +31,34 @@ mod circuits {
         pub market_sentiment: i8,          // Market sentiment: -128 to 127
     }
 
+    /// Tunable thresholds (plaintext, from the program's policy account)
+    pub struct RiskPolicy {
+        pub high_exposure: u8,            // Exposure ratio (0-255) above which exposure is high
+        pub over_exposure: u8,            // Exposure ratio (0-255) above which the portfolio is over-exposed
+        pub low_diversification: u8,      // Diversification score below which it adds risk
+        pub high_volatility: u16,         // Curve volatility above which trade risk is high
+        pub very_high_volatility: u16,    // Curve volatility above which trade risk is maximal
+        pub high_liquidity_risk: u8,      // Liquidity risk above which trade risk is raised
+        pub poor_drawdown: u16,           // Max drawdown (bps) above which a losing record adds risk
+        pub good_sharpe: i16,             // Sharpe ratio (x100) above which a consistent record lowers risk
+        pub good_consistency: u8,         // Consistency score the lower risk also needs
+        pub caution_risk: u8,             // Overall risk above which the recommendation is caution
+        pub avoid_risk: u8,               // Overall risk above which the recommendation is avoid
+    }
+
+    /// Commitment to the request's encrypted inputs (plaintext, computed on-chain)
+    pub struct InputCommitment {
+        pub hi: u128,                     // Upper 16 bytes of the SHA-256 commitment
+        pub lo: u128,                     // Lower 16 bytes of the SHA-256 commitment
+    }
+
     /// Risk assessment output (encrypted)
     pub struct RiskAssessment {
         pub overall_risk_score: u8,      // Overall risk: 0-255
         pub portfolio_risk: u8,           // Portfolio-specific risk
         pub trade_risk: u8,               // Trade-specific risk
         pub recommendation: u8,           // 0=proceed, 1=caution, 2=avoid
+        pub input_commitment: InputCommitment, // Echo of the request commitment
     }
 
     #[instruction]
@@ -44,42 +66,59 @@ mod circuits {
         portfolio: Enc<Shared, PortfolioContext>,
         performance: Enc<Shared, PerformanceHistory>,
         market: MarketConditions,
+        policy: RiskPolicy,
+        input_commitment: InputCommitment,
     ) -> Enc<Shared, RiskAssessment> {
         let port = portfolio.to_arcis();
         let perf = performance.to_arcis();
 
         // Portfolio risk: based on exposure and diversification
+        // Widened so large exposures cannot overflow, capped at 255
         let exposure_ratio = if port.total_capital > 0 {
-            (port.current_exposure * 255) / port.total_capital
+            let ratio = (port.current_exposure as u128 * 255) / port.total_capital as u128;
+            if ratio > 255 {
+                255u64
+            } else {
+                ratio as u64
+            }
         } else {
             255u64 // No capital = max risk
         };
         
-        let portfolio_risk = if exposure_ratio > 200 {
+        let portfolio_risk = if exposure_ratio > policy.over_exposure as u64 {
             255u8 // Over-exposed
-        } else if exposure_ratio > 150 {
+        } else if exposure_ratio > policy.high_exposure as u64 {
             200u8 // High exposure
-        } else if port.diversification_score < 100 {
-            (150u64 + (100u64 - port.diversification_score as u64)) as u8 // Low diversification
+        } else if port.diversification_score < policy.low_diversification {
+            // Low diversification, capped at 255
+            let penalty =
+                150u64 + (policy.low_diversification as u64 - port.diversification_score as u64);
+            if penalty > 255 {
+                255u8
+            } else {
+                penalty as u8
+            }
         } else {
             100u8 // Reasonable exposure
         };
 
         // Trade risk: based on market conditions
-        let trade_risk = if market.curve_volatility > 500 {
+        let trade_risk = if market.curve_volatility > policy.very_high_volatility {
             255u8 // Very high volatility
-        } else if market.curve_volatility > 300 {
+        } else if market.curve_volatility > policy.high_volatility {
             200u8 // High volatility
-        } else if market.liquidity_risk > 200 {
+        } else if market.liquidity_risk > policy.high_liquidity_risk {
             180u8 // High liquidity risk
         } else {
             100u8 // Moderate risk
         };
 
         // Performance-based risk adjustment
-        let performance_adjustment = if perf.total_pnl < 0 && perf.max_drawdown > 5000 {
-            +50i16 // Poor performance increases risk
-        } else if perf.sharpe_ratio > 100 && perf.consistency_score > 200 {
+        let performance_adjustment = if perf.total_pnl < 0 && perf.max_drawdown > policy.poor_drawdown {
+            50i16 // Poor performance increases risk
+        } else if perf.sharpe_ratio > policy.good_sharpe
+            && perf.consistency_score > policy.good_consistency
+        {
             -30i16 // Good performance reduces risk
         } else {
             0i16
@@ -90,9 +129,9 @@ mod circuits {
         let overall_risk = (base_risk + performance_adjustment).clamp(0, 255) as u8;
 
         // Recommendation
-        let recommendation = if overall_risk > 200 {
+        let recommendation = if overall_risk > policy.avoid_risk {
             2u8 // Avoid
-        } else if overall_risk > 150 {
+        } else if overall_risk > policy.caution_risk {
             1u8 // Caution
         } else {
             0u8 // Proceed
@@ -103,6 +142,7 @@ mod circuits {
             portfolio_risk,
             trade_risk,
             recommendation,
+            input_commitment,
         };
 
         portfolio.owner.from_arcis(assessment)
@@ -3,11 +3,23 @@ use arcis_imports::*;
 /// Confidential strategy planning for Evalys execution
 /// 
 /// Takes encrypted user preferences and history, combines with public curve state,
-/// and returns an encrypted execution plan.
+/// and returns an encrypted execution plan: the entry schedule and the exit
+/// that follows it.
 #[encrypted]
 mod circuits {
     use arcis_imports::*;
 
+    /// Slots of the per-slice schedule; a plan fills the first `num_slices`
+    const MAX_SLICES: usize = 8;
+
+    /// u128 words of the packed per-slice arrays of a plan: two u64, four u32
+    /// or eight u16 slots to a word, the first slot in the lowest bits. One
+    /// ciphertext per slot would make the callback carrying the plan larger
+    /// than a transaction.
+    const SIZE_WORDS: usize = MAX_SLICES / 2;
+    const DELAY_WORDS: usize = MAX_SLICES / 4;
+    const BPS_WORDS: usize = MAX_SLICES / 8;
+
     /// User preferences (encrypted)
     pub struct UserPreferences {
         pub desired_size: u64,           // Desired trade size in SOL
@@ -32,6 +44,33 @@ mod circuits {
         pub recent_volume: u64,           // Recent trading volume
     }
 
+    /// Tunable thresholds (plaintext, from the program's policy account)
+    pub struct StrategyPolicy {
+        pub stealth_risk: u16,            // Risk score above which plans use Stealth
+        pub ghost_risk: u16,              // Risk score above which plans use Max Ghost
+        pub low_win_rate: u16,            // Win rate (bps) below which history adds risk
+        pub medium_volatility: u16,       // Volatility above which the window is medium
+        pub high_volatility: u16,         // Volatility above which the window is fast
+        pub fast_window_sec: u32,         // Timing window at high volatility
+        pub medium_window_sec: u32,       // Timing window at medium volatility
+        pub slow_window_sec: u32,         // Timing window at low volatility
+        pub min_slices: u8,               // Fewest slices a plan uses (1 to MAX_SLICES)
+        pub participation_pct: u8,        // Largest share of recent volume one slice may take
+        pub moderate_appetite: u8,        // Risk appetite above which max notional is 1.5x
+        pub aggressive_appetite: u8,      // Risk appetite above which max notional may be 2x
+        pub aggressive_win_rate: u16,     // Win rate (bps) the 2x notional also needs
+        pub take_profit_bps: u16,         // Take-profit distance above the price, before volatility
+        pub stop_loss_bps: u16,           // Stop-loss distance below the price, before volatility
+        pub exit_window_pct: u8,          // Exit window as a share of the hold time
+        pub history_weight_trades: u16,   // Trades after which history weighs as much as the preference (at least 1)
+    }
+
+    /// Commitment to the request's encrypted inputs (plaintext, computed on-chain)
+    pub struct InputCommitment {
+        pub hi: u128,                     // Upper 16 bytes of the SHA-256 commitment
+        pub lo: u128,                     // Lower 16 bytes of the SHA-256 commitment
+    }
+
     /// Strategy plan output (encrypted)
     pub struct StrategyPlan {
         pub recommended_mode: u8,         // 0=Normal, 1=Stealth, 2=Max Ghost
@@ -39,7 +78,16 @@ mod circuits {
         pub slice_size_base: u64,         // Base slice size
         pub timing_window_sec: u32,       // Recommended timing window
         pub risk_level: u8,               // Computed risk level: 0-255
-        pub max_notional: u64,            // Maximum notional to commit
+        pub max_notional: u64,            // Maximum notional to commit, capped by what the curve can take
+        pub slice_size_words: [u128; SIZE_WORDS],   // Packed u64 size of each slice (0 past num_slices), summing to min(desired_size, max_notional)
+        pub slice_delay_words: [u128; DELAY_WORDS], // Packed u32 start of each slice after the plan's start (0 past num_slices)
+        pub exit_start_sec: u32,          // Start of the exit after the plan's start
+        pub exit_window_sec: u32,         // Length of the exit window
+        pub exit_bps_words: [u128; BPS_WORDS],      // Packed u16 share of the position each exit slice sells (0 past num_slices), summing to 10000
+        pub exit_delay_words: [u128; DELAY_WORDS],  // Packed u32 start of each exit slice after exit_start_sec (0 past num_slices)
+        pub take_profit_price: u64,       // Exit everything at or above this price
+        pub stop_loss_price: u64,         // Exit everything at or below this price
+        pub input_commitment: InputCommitment, // Echo of the request commitment
     }
 
     #[instruction]
@@ -47,52 +95,150 @@ mod circuits {
         preferences: Enc<Shared, UserPreferences>,
         history: Enc<Shared, UserHistory>,
         curve_state: CurveState,
+        policy: StrategyPolicy,
+        input_commitment: InputCommitment,
     ) -> Enc<Shared, StrategyPlan> {
         let prefs = preferences.to_arcis();
         let hist = history.to_arcis();
 
         // Risk assessment based on history and preferences
-        let risk_score = compute_risk_score(&prefs, &hist, &curve_state);
+        let risk_score = compute_risk_score(&prefs, &hist, &curve_state, &policy);
         
         // Determine recommended privacy mode
-        let recommended_mode = if risk_score > 200 {
+        let recommended_mode = if risk_score > policy.ghost_risk {
             2u8 // Max Ghost for high risk
-        } else if risk_score > 100 {
+        } else if risk_score > policy.stealth_risk {
             1u8 // Stealth for medium risk
         } else {
             0u8 // Normal for low risk
         };
 
-        // Calculate optimal slicing
-        let num_slices = if prefs.desired_size > 10_000_000_000 {
-            8u8 // Large orders: more slices
-        } else if prefs.desired_size > 1_000_000_000 {
-            5u8 // Medium orders
+        // Jitter range, wider for more private modes: 10%, 20% or 30% of the
+        // base slice size and of the spacing between slice starts
+        let jitter_pct = 10 * (recommended_mode as u64 + 1);
+
+        // What the curve absorbs per slice: the largest order whose price
+        // impact (size / liquidity_depth) stays within the slippage tolerance,
+        // and at most participation_pct of recent volume. A curve without a
+        // price takes nothing.
+        let depth_cap =
+            curve_state.liquidity_depth as u128 * prefs.slippage_tolerance as u128 / 10_000;
+        let volume_cap = curve_state.recent_volume as u128 * policy.participation_pct as u128 / 100;
+        let slice_cap = if curve_state.current_price == 0 {
+            0u128
+        } else if depth_cap < volume_cap {
+            depth_cap
+        } else {
+            volume_cap
+        };
+        // Base slices leave room for the jitter, and one plan takes at most
+        // MAX_SLICES of them
+        let base_cap = slice_cap * 100 / (100 + jitter_pct as u128);
+        let capacity = base_cap * MAX_SLICES as u128;
+        let planned_size = if (prefs.desired_size as u128) < capacity {
+            prefs.desired_size
+        } else {
+            capacity as u64
+        };
+
+        // Slice count: enough base slices for the planned size to fit under
+        // the cap, at least min_slices (at most MAX_SLICES, as the planned
+        // size fits in the capacity)
+        let needed = if base_cap == 0 {
+            0u128
         } else {
-            3u8 // Small orders: fewer slices
+            (planned_size as u128 + base_cap - 1) / base_cap
+        };
+        let num_slices = if needed > policy.min_slices as u128 {
+            needed as u8
+        } else {
+            policy.min_slices
         };
 
-        // Base slice size (desired_size / num_slices, with some randomization room)
-        let slice_size_base = prefs.desired_size / (num_slices as u64);
+        // Base slice size (planned_size / num_slices, rounded down)
+        let slice_size_base = planned_size / (num_slices as u64);
 
         // Timing window based on volatility and risk
-        let timing_window_sec = if curve_state.volatility > 500 {
-            60u32 // High volatility: faster execution
-        } else if curve_state.volatility > 200 {
-            120u32 // Medium volatility
+        let timing_window_sec = if curve_state.volatility > policy.high_volatility {
+            policy.fast_window_sec // High volatility: faster execution
+        } else if curve_state.volatility > policy.medium_volatility {
+            policy.medium_window_sec // Medium volatility
         } else {
-            300u32 // Low volatility: can take more time
+            policy.slow_window_sec // Low volatility: can take more time
         };
 
-        // Max notional: consider user's risk appetite and history
-        let max_notional = if prefs.risk_appetite > 200 && hist.win_rate > 6000 {
-            prefs.desired_size * 2 // Aggressive with good history: allow 2x
-        } else if prefs.risk_appetite > 150 {
-            prefs.desired_size * 3 / 2 // Moderate aggressive: 1.5x
+        let size_jitter = (slice_size_base as u128 * jitter_pct as u128 / 100) as u64;
+        let spacing = timing_window_sec / (num_slices as u32);
+        let delay_jitter = spacing * (jitter_pct as u32) / 100;
+
+        // Random draws made inside MPC, so nobody (the bridge included) can
+        // predict the schedule before decrypting it
+        let mut size_draws = [0u64; MAX_SLICES];
+        let mut delay_draws = [0u32; MAX_SLICES];
+        for i in 0..MAX_SLICES {
+            size_draws[i] = random_below(size_jitter as u128 + 1) as u64;
+            delay_draws[i] = random_below(2 * delay_jitter as u128 + 1) as u32;
+        }
+
+        // Per-slice schedule: the division's remainder goes one unit each to
+        // the first slices, and each slice gains its own draw and gives up the
+        // next slice's (the last one the first's), so the sizes sum to
+        // planned_size exactly and stay within a lamport of slice_cap. Slices start evenly spread over the timing
+        // window, each after the first moved by up to delay_jitter seconds
+        // either way, which keeps them in order and inside the window.
+        let remainder = planned_size - slice_size_base * (num_slices as u64);
+        let mut slice_sizes = [0u64; MAX_SLICES];
+        let mut slice_delays_sec = [0u32; MAX_SLICES];
+        for i in 0..MAX_SLICES {
+            if (i as u8) < num_slices {
+                let extra = if (i as u64) < remainder { 1u64 } else { 0u64 };
+                let given = if ((i + 1) as u8) < num_slices {
+                    size_draws[(i + 1) % MAX_SLICES]
+                } else {
+                    size_draws[0]
+                };
+                slice_sizes[i] = slice_size_base + extra + size_draws[i] - given;
+                if i > 0 {
+                    slice_delays_sec[i] = timing_window_sec * (i as u32) / (num_slices as u32)
+                        + delay_draws[i]
+                        - delay_jitter;
+                }
+            }
+        }
+
+        // Max notional: consider user's risk appetite and history, capped by
+        // what the curve can take (computed in u128, so large sizes cannot
+        // overflow)
+        let notional = if prefs.risk_appetite > policy.aggressive_appetite
+            && hist.win_rate > policy.aggressive_win_rate
+        {
+            prefs.desired_size as u128 * 2 // Aggressive with good history: allow 2x
+        } else if prefs.risk_appetite > policy.moderate_appetite {
+            prefs.desired_size as u128 * 3 / 2 // Moderate aggressive: 1.5x
+        } else {
+            prefs.desired_size as u128 // Conservative: stick to desired size
+        };
+        let notional_cap = if capacity > u64::MAX as u128 {
+            u64::MAX as u128
+        } else {
+            capacity
+        };
+        let max_notional = if notional > notional_cap {
+            notional_cap as u64
         } else {
-            prefs.desired_size // Conservative: stick to desired size
+            notional as u64
         };
 
+        let exit = plan_exit(
+            &prefs,
+            &hist,
+            &curve_state,
+            &policy,
+            timing_window_sec,
+            num_slices,
+            jitter_pct,
+        );
+
         let plan = StrategyPlan {
             recommended_mode,
             num_slices,
@@ -100,16 +246,156 @@ mod circuits {
             timing_window_sec,
             risk_level: risk_score as u8,
             max_notional,
+            slice_size_words: pack_sizes(slice_sizes),
+            slice_delay_words: pack_delays(slice_delays_sec),
+            exit_start_sec: exit.start_sec,
+            exit_window_sec: exit.window_sec,
+            exit_bps_words: pack_bps(exit.slice_bps),
+            exit_delay_words: pack_delays(exit.delays_sec),
+            take_profit_price: exit.take_profit_price,
+            stop_loss_price: exit.stop_loss_price,
+            input_commitment,
         };
 
         preferences.owner.from_arcis(plan)
     }
 
+    fn pack_sizes(sizes: [u64; MAX_SLICES]) -> [u128; SIZE_WORDS] {
+        let mut words = [0u128; SIZE_WORDS];
+        for i in 0..MAX_SLICES {
+            words[i / 2] += (sizes[i] as u128) << (64 * (i % 2));
+        }
+        words
+    }
+
+    fn pack_delays(delays: [u32; MAX_SLICES]) -> [u128; DELAY_WORDS] {
+        let mut words = [0u128; DELAY_WORDS];
+        for i in 0..MAX_SLICES {
+            words[i / 4] += (delays[i] as u128) << (32 * (i % 4));
+        }
+        words
+    }
+
+    fn pack_bps(bps: [u16; MAX_SLICES]) -> [u128; BPS_WORDS] {
+        let mut words = [0u128; BPS_WORDS];
+        for i in 0..MAX_SLICES {
+            words[i / 8] += (bps[i] as u128) << (16 * (i % 8));
+        }
+        words
+    }
+
+    /// Exit half of a strategy plan
+    struct ExitPlan {
+        start_sec: u32,
+        window_sec: u32,
+        slice_bps: [u16; MAX_SLICES],
+        delays_sec: [u32; MAX_SLICES],
+        take_profit_price: u64,
+        stop_loss_price: u64,
+    }
+
+    /// Plan the exit of the position the entry builds: when to sell, in how
+    /// many slices, and the price levels that end it early
+    fn plan_exit(
+        prefs: &UserPreferences,
+        hist: &UserHistory,
+        curve: &CurveState,
+        policy: &StrategyPolicy,
+        timing_window_sec: u32,
+        num_slices: u8,
+        jitter_pct: u64,
+    ) -> ExitPlan {
+        // Hold time: the preference, pulled toward the user's history as
+        // trades accumulate (equal weight after history_weight_trades), and
+        // halved on a highly volatile curve
+        let weight = policy.history_weight_trades as u64;
+        let trades = if (hist.total_trades as u64) < weight {
+            hist.total_trades as u64
+        } else {
+            weight
+        };
+        let blended = (prefs.preferred_hold_time as u64 * weight + hist.avg_hold_time as u64 * trades)
+            / (weight + trades);
+        let hold_sec = if curve.volatility > policy.high_volatility {
+            blended / 2
+        } else {
+            blended
+        };
+
+        // Exit window centred on the target exit time, which is hold_sec
+        // after the entry window closes
+        let window_sec = hold_sec * policy.exit_window_pct as u64 / 100;
+        let start = timing_window_sec as u64 + hold_sec - window_sec / 2;
+        let start_sec = if start > u32::MAX as u64 {
+            u32::MAX
+        } else {
+            start as u32
+        };
+
+        // As many exit slices as entry slices, sharing the position equally
+        // (the remainder one bps each to the first), each start after the
+        // first moved by up to jitter_pct% of the spacing either way
+        let delay_jitter = (window_sec / num_slices as u64 * jitter_pct / 100) as u32;
+        let mut delay_draws = [0u32; MAX_SLICES];
+        for i in 0..MAX_SLICES {
+            delay_draws[i] = random_below(2 * delay_jitter as u128 + 1) as u32;
+        }
+        let share = 10_000 / num_slices as u16;
+        let remainder = 10_000 - share * num_slices as u16;
+        let mut slice_bps = [0u16; MAX_SLICES];
+        let mut delays_sec = [0u32; MAX_SLICES];
+        for i in 0..MAX_SLICES {
+            if (i as u8) < num_slices {
+                let extra = if (i as u16) < remainder { 1u16 } else { 0u16 };
+                slice_bps[i] = share + extra;
+                if i > 0 {
+                    delays_sec[i] = (window_sec * i as u64 / num_slices as u64) as u32
+                        + delay_draws[i]
+                        - delay_jitter;
+                }
+            }
+        }
+
+        // Price levels widen with volatility; after recent losses the stop
+        // is a quarter tighter. A stop past 100% is never hit (price 0).
+        let take_profit_bps = policy.take_profit_bps as u128 + curve.volatility as u128;
+        let widened_stop = policy.stop_loss_bps as u128 + curve.volatility as u128 / 2;
+        let stop = if hist.recent_pnl < 0 {
+            widened_stop * 3 / 4
+        } else {
+            widened_stop
+        };
+        let stop_loss_bps = if stop > 10_000 { 10_000 } else { stop };
+        let price = curve.current_price as u128;
+        let take_profit = price * (10_000 + take_profit_bps) / 10_000;
+        let take_profit_price = if take_profit > u64::MAX as u128 {
+            u64::MAX
+        } else {
+            take_profit as u64
+        };
+        let stop_loss_price = (price * (10_000 - stop_loss_bps) / 10_000) as u64;
+
+        ExitPlan {
+            start_sec,
+            window_sec: window_sec as u32,
+            slice_bps,
+            delays_sec,
+            take_profit_price,
+            stop_loss_price,
+        }
+    }
+
+    /// Uniform integer in `0..bound` (`bound` at most 2^64), drawn inside MPC
+    fn random_below(bound: u128) -> u128 {
+        (ArcisRNG::gen_integer_from_width(64) * bound) >> 64
+    }
+
     /// Compute risk score (0-255) based on user data and curve state
     fn compute_risk_score(
         prefs: &UserPreferences,
         hist: &UserHistory,
         curve: &CurveState,
+        policy: &StrategyPolicy,
     ) -> u16 {
         // Base risk from user preferences
         let preference_risk = prefs.risk_appetite as u16;
@@ -117,24 +403,24 @@ mod circuits {
         // History-based risk adjustment
         let history_risk = if hist.recent_pnl < 0 {
             // Negative PnL increases risk
-            (prefs.risk_appetite as u16) + 50
-        } else if hist.win_rate < 5000 {
+            preference_risk + 50
+        } else if hist.win_rate < policy.low_win_rate {
             // Low win rate increases risk
-            (prefs.risk_appetite as u16) + 30
+            preference_risk + 30
         } else {
             // Good history reduces risk
-            (prefs.risk_appetite as u16).saturating_sub(20)
+            preference_risk.saturating_sub(20)
         };
 
         // Curve volatility adds to risk
-        let volatility_risk = (curve.volatility as u16) / 10;
+        let volatility_risk = curve.volatility / 10;
 
         // Combine risks (cap at 255)
         let total_risk = history_risk + volatility_risk;
         if total_risk > 255 {
             255
         } else {
-            total_risk as u16
+            total_risk
         }
     }
 }
@@ -21,6 +21,27 @@ mod circuits {
         pub max_dd: u16,                   // Max drawdown (0-10000, basis points)
     }
 
+    /// Tunable thresholds (plaintext, from the program's policy account)
+    pub struct GmpcPolicy {
+        pub low_risk_size_pct: u8,        // Share of max size at risk level 0, in percent
+        pub normal_risk_size_pct: u8,     // Share of max size at risk level 1, in percent
+        pub high_volatility: u16,         // Volatility score (bps) above which size shrinks and the window widens
+        pub high_volatility_size_pct: u8, // Share of the size kept at high volatility, in percent
+        pub normal_slices: u8,            // Slice count at privacy priority 0
+        pub stealth_slices: u8,           // Slice count at privacy priority 1
+        pub max_privacy_slices: u8,       // Slice count at privacy priority 2
+        pub calm_window_sec: u32,         // Time window at normal volatility
+        pub volatile_window_sec: u32,     // Time window at high volatility
+        pub low_risk_volatility: u16,     // Volatility score (bps) below which risk level 0 is low risk
+        pub high_risk_volatility: u16,    // Volatility score (bps) above which any intent is high risk
+    }
+
+    /// Commitment to the request's encrypted inputs (plaintext, computed on-chain)
+    pub struct InputCommitment {
+        pub hi: u128,                     // Upper 16 bytes of the SHA-256 commitment
+        pub lo: u128,                     // Lower 16 bytes of the SHA-256 commitment
+    }
+
     /// Execution plan output from gMPC (encrypted)
     pub struct PlanOutput {
         pub recommended_size_sol: u64,    // Recommended size in lamports
@@ -29,46 +50,122 @@ mod circuits {
         pub mev_route: u8,                // 0=standard, 1=jito_bundle, 2=private_route
         pub privacy_mode: u8,              // 0=normal, 1=stealth, 2=max_ghost
         pub risk_class: u8,               // 0=low, 1=balanced, 2=high
+        pub input_commitment: InputCommitment, // Echo of the request commitment
+    }
+
+    /// Per-request salt for quorum digests (encrypted, random per request)
+    pub struct QuorumSalt {
+        pub salt: u128,                   // Uniform random u128 chosen by the client
     }
 
     #[instruction]
     pub fn evalys_gmpc_strategy(
         intent: Enc<Shared, IntentInput>,
+        policy: GmpcPolicy,
+        input_commitment: InputCommitment,
     ) -> Enc<Shared, PlanOutput> {
         let input = intent.to_arcis();
+        let plan = jitter(&input, plan_intent(&input, &policy, input_commitment));
+
+        intent.owner.from_arcis(plan)
+    }
+
+    /// gMPC strategy for quorum execution on two deployments
+    ///
+    /// Same plan as `evalys_gmpc_strategy`, plus a revealed digest so the two
+    /// deployments can compare their clusters' results without decrypting them. Both legs
+    /// must return the same plan, so the quorum plan is not jittered. The digest
+    /// packs the plan fields into 128 bits and masks them with the encrypted salt,
+    /// so it reveals nothing about the plan while equal plans give equal digests.
+    #[instruction]
+    pub fn evalys_gmpc_strategy_quorum(
+        intent: Enc<Shared, IntentInput>,
+        salt: Enc<Shared, QuorumSalt>,
+        policy: GmpcPolicy,
+        input_commitment: InputCommitment,
+    ) -> (Enc<Shared, PlanOutput>, u128) {
+        let input = intent.to_arcis();
+        let mask = salt.to_arcis().salt;
+        let plan = plan_intent(&input, &policy, input_commitment);
 
+        let packed = ((plan.recommended_size_sol as u128) << 64)
+            | ((plan.time_window_sec as u128) << 32)
+            | ((plan.slice_count as u128) << 24)
+            | ((plan.mev_route as u128) << 16)
+            | ((plan.privacy_mode as u128) << 8)
+            | (plan.risk_class as u128);
+        let digest = packed.wrapping_add(mask);
+
+        (intent.owner.from_arcis(plan), digest.reveal())
+    }
+
+    /// Randomize the plan's slicing and timing inside MPC, so nobody (the bridge
+    /// included) can predict the pattern before decrypting it: up to
+    /// `privacy_priority` extra slices (0, 1 or 2), and a time window moved by
+    /// up to 10%, 20% or 30% either way
+    fn jitter(input: &IntentInput, plan: PlanOutput) -> PlanOutput {
+        let level = if input.privacy_priority > 2 {
+            2u8
+        } else {
+            input.privacy_priority
+        };
+        let extra_slices = random_below(level as u128 + 1) as u8;
+        let window_jitter = plan.time_window_sec * (10 * (level as u32 + 1)) / 100;
+        let window_draw = random_below(2 * window_jitter as u128 + 1) as u32;
+
+        PlanOutput {
+            slice_count: plan.slice_count + extra_slices,
+            time_window_sec: plan.time_window_sec + window_draw - window_jitter,
+            ..plan
+        }
+    }
+
+    /// Uniform integer in `0..bound` (`bound` at most 2^64), drawn inside MPC
+    fn random_below(bound: u128) -> u128 {
+        (ArcisRNG::gen_integer_from_width(64) * bound) >> 64
+    }
+
+    /// Compute the execution plan for a decrypted intent
+    fn plan_intent(
+        input: &IntentInput,
+        policy: &GmpcPolicy,
+        input_commitment: InputCommitment,
+    ) -> PlanOutput {
         // Base size calculation with risk adjustment
         let base = input.max_size_sol;
         
         // Risk factor based on risk level
         let risk_factor = match input.risk_level {
-            0 => 50u64,   // Low risk - reduce to 50%
-            1 => 80u64,   // Normal risk - 80%
+            0 => policy.low_risk_size_pct as u64,    // Low risk - reduced
+            1 => policy.normal_risk_size_pct as u64, // Normal risk
             _ => 100u64,  // High risk - full size
         };
         
         // Volatility penalty (volatility_score is in basis points, so 7000 = 0.7)
-        let vol_penalty = if input.volatility_score > 7000 {
-            70u64  // High volatility - reduce to 70%
+        let vol_penalty = if input.volatility_score > policy.high_volatility {
+            policy.high_volatility_size_pct as u64 // High volatility - reduced
         } else {
             100u64 // Normal volatility
         };
         
         // Recommended size: base * risk_factor% * vol_penalty%
-        let recommended = (base * risk_factor * vol_penalty) / 10000;
+        // (in u128 so large sizes cannot overflow; the result is at most base,
+        // as the policy percentages are at most 100)
+        let recommended =
+            ((base as u128 * risk_factor as u128 * vol_penalty as u128) / 10000) as u64;
         
         // Slice count based on privacy priority
         let slice_count = match input.privacy_priority {
-            0 => 3u8,  // Normal privacy
-            1 => 5u8,  // Stealth
-            _ => 7u8,  // Max privacy
+            0 => policy.normal_slices,      // Normal privacy
+            1 => policy.stealth_slices,     // Stealth
+            _ => policy.max_privacy_slices, // Max privacy
         };
         
         // Time window based on volatility
-        let time_window = if input.volatility_score > 7000 {
-            60u32  // High volatility - longer window
+        let time_window = if input.volatility_score > policy.high_volatility {
+            policy.volatile_window_sec // High volatility - longer window
         } else {
-            38u32  // Standard window
+            policy.calm_window_sec     // Standard window
         };
         
         // MEV route selection
@@ -86,24 +183,23 @@ mod circuits {
         };
         
         // Risk class
-        let risk_class = if input.risk_level == 0 && input.volatility_score < 5000 {
+        let risk_class = if input.risk_level == 0 && input.volatility_score < policy.low_risk_volatility {
             0u8  // Low risk
-        } else if input.risk_level == 2 || input.volatility_score > 8000 {
+        } else if input.risk_level == 2 || input.volatility_score > policy.high_risk_volatility {
             2u8  // High risk
         } else {
             1u8  // Balanced
         };
         
-        let plan = PlanOutput {
+        PlanOutput {
             recommended_size_sol: recommended,
             slice_count,
             time_window_sec: time_window,
             mev_route,
             privacy_mode,
             risk_class,
-        };
-
-        intent.owner.from_arcis(plan)
+            input_commitment,
+        }
     }
 }
 
@@ -23,6 +23,60 @@ const RPC_URL = process.env.SOLANA_RPC_URL || "http://localhost:8899";
 const KEYPAIR_PATH = process.env.SOLANA_KEYPAIR_PATH || 
   path.join(process.env.HOME || process.env.USERPROFILE || "", ".config/solana/id.json");
 
+// Golden vectors checked against the plaintext reference by `cargo test`
+const VECTORS_DIR = path.join(__dirname, "../crates/evalys-mxe-reference/tests/vectors");
+
+interface GoldenVector {
+  name: string;
+  input: Record<string, any>;
+  output: Record<string, any>;
+}
+
+/**
+ * Load one vector of a circuit's golden corpus
+ */
+function goldenVector(circuit: string, name: string): GoldenVector {
+  const corpus = JSON.parse(fs.readFileSync(path.join(VECTORS_DIR, `${circuit}.json`), "utf-8"));
+  const vector = corpus.vectors.find((v: GoldenVector) => v.name === name);
+  if (!vector) {
+    throw new Error(`no vector ${name} in ${circuit}.json`);
+  }
+  return vector;
+}
+
+/**
+ * Print a vector's inputs and expected output (commitments omitted: they
+ * depend on the request and exceed JavaScript's number precision, like the
+ * u128 words the strategy plan packs its per-slice arrays into)
+ */
+function printVector(
+  outputName: string,
+  vector: GoldenVector,
+  encrypted: string[],
+  plaintext: string[]
+) {
+  const print = (label: string, fields: Record<string, any>) => {
+    console.log(`  ${label}:`);
+    for (const [field, value] of Object.entries(fields)) {
+      if (field !== "input_commitment" && !field.endsWith("_words")) {
+        console.log(`    ${field}: ${JSON.stringify(value)}`);
+      }
+    }
+  };
+
+  console.log("Input (encrypted):");
+  encrypted.forEach((input) => print(input, vector.input[input]));
+  console.log();
+  if (plaintext.length > 0) {
+    console.log("Input (plaintext):");
+    plaintext.forEach((input) => print(input, vector.input[input]));
+    console.log();
+  }
+  console.log("Expected Output (encrypted):");
+  print(outputName, vector.output);
+  console.log();
+}
+
 /**
  * Test confidential strategy plan computation
  */
@@ -49,38 +103,8 @@ async function testConfidentialStrategyPlan() {
   // const idl = JSON.parse(fs.readFileSync("target/idl/evalys_arcium_gmpc_mxe.json", "utf-8"));
   // const program = new Program(idl, programId, provider);
 
-  console.log("Input (encrypted):");
-  console.log("  UserPreferences:");
-  console.log("    desired_size: 1_000_000_000 (1 SOL)");
-  console.log("    slippage_tolerance: 100 (1%)");
-  console.log("    risk_appetite: 150");
-  console.log("    preferred_hold_time: 3600 (1 hour)");
-  console.log();
-  console.log("  UserHistory:");
-  console.log("    recent_pnl: 5_000_000");
-  console.log("    win_rate: 6500 (65%)");
-  console.log("    avg_hold_time: 1800 (30 min)");
-  console.log("    total_trades: 50");
-  console.log();
-  console.log("  CurveState (plaintext):");
-  console.log("    current_price: 1_000_000");
-  console.log("    liquidity_depth: 5_000_000_000");
-  console.log("    volatility: 300");
-  console.log("    recent_volume: 10_000_000_000");
-  console.log();
-
-  // TODO: Implement actual test when program is deployed
-  // This is a placeholder showing expected flow
-
-  console.log("Expected Output (encrypted):");
-  console.log("  StrategyPlan:");
-  console.log("    recommended_mode: 1 (Stealth)");
-  console.log("    num_slices: 3");
-  console.log("    slice_size_base: 333_333_333");
-  console.log("    timing_window_sec: 120");
-  console.log("    risk_level: 160");
-  console.log("    max_notional: 1_000_000_000");
-  console.log();
+  const vector = goldenVector("confidential_strategy_plan", "local_test_example");
+  printVector("StrategyPlan", vector, ["preferences", "history"], ["curve_state"]);
 
   console.log("Expected Receipt:");
   console.log("  receipt_id: <32-byte hash>");
@@ -104,24 +128,8 @@ async function testGmpcStrategy() {
   console.log("=".repeat(70));
   console.log();
 
-  console.log("Input (encrypted):");
-  console.log("  IntentInput:");
-  console.log("    max_size_sol: 2.0 SOL");
-  console.log("    risk_level: 150");
-  console.log("    privacy_priority: 200");
-  console.log("    market_snapshot: {...}");
-  console.log("    historical_stats: {...}");
-  console.log();
-
-  console.log("Expected Output (encrypted):");
-  console.log("  PlanOutput:");
-  console.log("    recommended_size_sol: 1.6 SOL");
-  console.log("    slice_count: 5");
-  console.log("    time_window_sec: 38");
-  console.log("    mev_route: 1 (Jito bundle)");
-  console.log("    privacy_mode: 2 (Max Ghost)");
-  console.log("    risk_class: 1 (balanced)");
-  console.log();
+  const vector = goldenVector("evalys_gmpc_strategy", "local_test_example");
+  printVector("PlanOutput", vector, ["intent"], []);
 
   console.log("✅ Test structure validated");
   console.log();
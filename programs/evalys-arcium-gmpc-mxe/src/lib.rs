@@ -1,8 +1,57 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 use arcium_anchor::prelude::*;
 
 declare_id!("EVALYSARCIUMGMPCMXE11111111111111111111111");
 
+/// Seed for the program configuration
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Seed for per-computation request records
+pub const COMPUTATION_REQUEST_SEED: &[u8] = b"computation_request";
+
+/// Domain separator for input commitments
+pub const INPUT_COMMITMENT_DOMAIN: &[u8] = b"evalys-mxe-input-v1";
+
+/// Seed for the per-circuit registration (current circuit hash of a kind)
+pub const CIRCUIT_SEED: &[u8] = b"circuit";
+
+/// Seed for circuit hash -> version records
+pub const CIRCUIT_VERSION_SEED: &[u8] = b"circuit_version";
+
+/// Maximum length of a human-readable circuit version
+pub const MAX_CIRCUIT_VERSION_LEN: usize = 32;
+
+/// Seed for per-bridge client key registries
+pub const CLIENT_KEYS_SEED: &[u8] = b"client_keys";
+
+/// Maximum number of active and retired keys kept per bridge
+pub const MAX_CLIENT_KEYS: usize = 8;
+
+/// Seed for the audit log ring buffer
+pub const AUDIT_LOG_SEED: &[u8] = b"audit_log";
+
+/// Number of entries kept by the audit log before it wraps
+pub const AUDIT_LOG_CAPACITY: usize = 100;
+
+/// Seed for this deployment's side of a quorum
+pub const QUORUM_REQUEST_SEED: &[u8] = b"quorum_request";
+
+/// Maximum number of profiles accepted by multi-user analytics
+pub const MAX_ANALYTICS_PROFILES: usize = 16;
+
+/// Seed for per-circuit policy accounts
+pub const POLICY_SEED: &[u8] = b"policy";
+
+/// Longest time window a policy may set, in seconds
+pub const MAX_POLICY_WINDOW_SEC: u32 = 86_400;
+
+/// Most slices of a `confidential_strategy_plan` schedule
+pub const MAX_STRATEGY_SLICES: u8 = 8;
+
+/// Most slices a gMPC policy may set per privacy level
+pub const MAX_GMPC_POLICY_SLICES: u8 = 32;
+
 #[arcium_program]
 pub mod evalys_arcium_gmpc_mxe {
     use super::*;
@@ -13,28 +62,226 @@ pub mod evalys_arcium_gmpc_mxe {
     const COMP_DEF_OFFSET_CURVE: u32 = comp_def_offset("confidential_curve_eval");
     const COMP_DEF_OFFSET_GMPC: u32 = comp_def_offset("evalys_gmpc_strategy");
     const COMP_DEF_OFFSET_MULTI_USER: u32 = comp_def_offset("confidential_multi_user_analytics");
+    const COMP_DEF_OFFSET_GMPC_QUORUM: u32 = comp_def_offset("evalys_gmpc_strategy_quorum");
 
-    // ========== Confidential Strategy Plan ==========
+    // ========== Configuration ==========
 
-    /// Initialize the strategy plan computation definition
-    pub fn init_strategy_comp_def(ctx: Context<InitStrategyCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, COMP_DEF_OFFSET_STRATEGY, None, None)?;
+    /// Create the program configuration with the peer deployment that runs
+    /// the other leg of every quorum. Only the program's upgrade authority can
+    /// sign it and become config authority.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, quorum_peer: Pubkey) -> Result<()> {
+        require_keys_neq!(quorum_peer, crate::ID, ErrorCode::InvalidQuorumPeer);
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.quorum_peer = quorum_peer;
+        config.bump = ctx.bumps.config;
         Ok(())
     }
 
+    /// Create the audit log ring buffer appended by every request and callback
+    pub fn initialize_audit_log(ctx: Context<InitializeAuditLog>) -> Result<()> {
+        ctx.accounts.audit_log.load_init()?.head = 0;
+        Ok(())
+    }
+
+    /// Create the client key registry of the signing bridge
+    pub fn init_client_key_registry(ctx: Context<InitClientKeyRegistry>) -> Result<()> {
+        let registry = &mut ctx.accounts.client_keys;
+        registry.bridge = ctx.accounts.bridge.key();
+        registry.next_key_id = 1;
+        registry.keys = Vec::new();
+        registry.bump = ctx.bumps.client_keys;
+        Ok(())
+    }
+
+    /// Register a new active x25519 key and retire the previously active ones
+    ///
+    /// Retired keys stay listed so results of requests queued before the
+    /// rotation remain attributable; remove them once those results are decrypted.
+    pub fn rotate_client_key(ctx: Context<ManageClientKeys>, pub_key: [u8; 32]) -> Result<()> {
+        let registry = &mut ctx.accounts.client_keys;
+        require!(registry.keys.len() < MAX_CLIENT_KEYS, ErrorCode::ClientKeyRegistryFull);
+        require!(
+            registry.keys.iter().all(|k| k.pub_key != pub_key),
+            ErrorCode::DuplicateClientKey
+        );
+
+        let slot = Clock::get()?.slot;
+        let mut retired_key_ids = Vec::new();
+        for key in registry.keys.iter_mut().filter(|k| k.status == ClientKeyStatus::Active) {
+            key.status = ClientKeyStatus::Retired;
+            key.retired_slot = slot;
+            retired_key_ids.push(key.key_id);
+        }
+
+        let key_id = registry.next_key_id;
+        registry.next_key_id += 1;
+        registry.keys.push(ClientKey {
+            key_id,
+            pub_key,
+            status: ClientKeyStatus::Active,
+            registered_slot: slot,
+            retired_slot: 0,
+        });
+
+        emit!(ClientKeyRotatedEvent {
+            bridge: registry.bridge,
+            key_id,
+            pub_key,
+            retired_key_ids,
+        });
+        Ok(())
+    }
+
+    /// Drop a retired key once no in-flight results are encrypted to it
+    pub fn remove_client_key(ctx: Context<ManageClientKeys>, key_id: u32) -> Result<()> {
+        let registry = &mut ctx.accounts.client_keys;
+        let index = registry
+            .keys
+            .iter()
+            .position(|k| k.key_id == key_id)
+            .ok_or(ErrorCode::UnknownClientKey)?;
+        require!(
+            registry.keys[index].status == ClientKeyStatus::Retired,
+            ErrorCode::ClientKeyActive
+        );
+        registry.keys.remove(index);
+        Ok(())
+    }
+
+    /// Replace the peer deployment of quorums opened from now on
+    pub fn set_quorum_peer(ctx: Context<UpdateConfig>, quorum_peer: Pubkey) -> Result<()> {
+        require_keys_neq!(quorum_peer, crate::ID, ErrorCode::InvalidQuorumPeer);
+        ctx.accounts.config.quorum_peer = quorum_peer;
+        Ok(())
+    }
+
+    // ========== Policies ==========
+
+    /// Store the thresholds `confidential_strategy_plan` runs under
+    pub fn set_strategy_policy(ctx: Context<SetStrategyPolicy>, policy: StrategyPolicy) -> Result<()> {
+        policy.validate()?;
+        let account = &mut ctx.accounts.policy;
+        account.policy = policy;
+        account.bump = ctx.bumps.policy;
+        account.updated_slot = policy_updated(CircuitKind::StrategyPlan)?;
+        Ok(())
+    }
+
+    /// Store the thresholds `confidential_risk_score` runs under
+    pub fn set_risk_policy(ctx: Context<SetRiskPolicy>, policy: RiskPolicy) -> Result<()> {
+        policy.validate()?;
+        let account = &mut ctx.accounts.policy;
+        account.policy = policy;
+        account.bump = ctx.bumps.policy;
+        account.updated_slot = policy_updated(CircuitKind::RiskScore)?;
+        Ok(())
+    }
+
+    /// Store the thresholds `confidential_curve_eval` runs under
+    pub fn set_curve_policy(ctx: Context<SetCurvePolicy>, policy: CurvePolicy) -> Result<()> {
+        policy.validate()?;
+        let account = &mut ctx.accounts.policy;
+        account.policy = policy;
+        account.bump = ctx.bumps.policy;
+        account.updated_slot = policy_updated(CircuitKind::CurveEval)?;
+        Ok(())
+    }
+
+    /// Store the thresholds `evalys_gmpc_strategy` and its quorum variant run
+    /// under. Each leg reads its own deployment's policy when queued, so both
+    /// deployments of a quorum must hold the same policy or the digests differ.
+    pub fn set_gmpc_policy(ctx: Context<SetGmpcPolicy>, policy: GmpcPolicy) -> Result<()> {
+        policy.validate()?;
+        let account = &mut ctx.accounts.policy;
+        account.policy = policy;
+        account.bump = ctx.bumps.policy;
+        account.updated_slot = policy_updated(CircuitKind::GmpcStrategy)?;
+        Ok(())
+    }
+
+    // ========== Confidential Strategy Plan ==========
+
+    /// Initialize the strategy plan computation definition and register its circuit hash
+    pub fn init_strategy_comp_def(
+        ctx: Context<InitStrategyCompDef>,
+        circuit_hash: [u8; 32],
+        version: String,
+        circuit_source: String,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            COMP_DEF_OFFSET_STRATEGY,
+            Some(off_chain_circuit(circuit_source, circuit_hash)),
+            None,
+        )?;
+        register_circuit(
+            &mut ctx.accounts.circuit_registration,
+            &mut ctx.accounts.circuit_version,
+            CircuitKind::StrategyPlan,
+            COMP_DEF_OFFSET_STRATEGY,
+            &ctx.accounts.comp_def_account,
+            circuit_hash,
+            version,
+            (ctx.bumps.circuit_registration, ctx.bumps.circuit_version),
+        )
+    }
+
     /// Invoke confidential strategy plan computation
     pub fn request_strategy_plan(
         ctx: Context<RequestStrategyPlan>,
         computation_offset: u64,
+        client_key_id: u32,
+        preferences: EncryptedInput,
+        history: EncryptedInput,
+        curve_state: CurveStateArgs,
     ) -> Result<()> {
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.client_keys.require_active(client_key_id, &[&preferences, &history])?;
+
+        let commitment = input_commitment(
+            &ctx.accounts.payer.key(),
+            computation_offset,
+            &[preferences.commitment_bytes(), history.commitment_bytes()],
+        );
+        ctx.accounts.computation_request.open(
+            ctx.accounts.payer.key(),
+            CircuitKind::StrategyPlan,
+            computation_offset,
+            commitment,
+            ctx.accounts.circuit_registration.circuit_hash,
+            client_key_id,
+            ctx.bumps.computation_request,
+        )?;
+        append_audit(&ctx.accounts.audit_log, &ctx.accounts.computation_request, [0; 32])?;
+
+        let mut args = Vec::new();
+        preferences.push_args(&mut args, &[EncWidth::U64, EncWidth::U16, EncWidth::U8, EncWidth::U32])?;
+        history.push_args(&mut args, &[EncWidth::I64, EncWidth::U16, EncWidth::U32, EncWidth::U32])?;
+        args.extend([
+            Argument::PlaintextU64(curve_state.current_price),
+            Argument::PlaintextU64(curve_state.liquidity_depth),
+            Argument::PlaintextU16(curve_state.volatility),
+            Argument::PlaintextU64(curve_state.recent_volume),
+        ]);
+        ctx.accounts.policy.policy.push_args(&mut args);
+        push_commitment_args(&mut args, &commitment);
 
         queue_computation(
             ctx.accounts,
             computation_offset,
-            vec![], // Encrypted arguments passed from client
+            args,
             None,
-            vec![StrategyPlanCallback::callback_ix(&[])],
+            vec![StrategyPlanCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.computation_request.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.audit_log.key(),
+                    is_writable: true,
+                },
+            ])],
             1,
         )?;
         Ok(())
@@ -48,12 +295,25 @@ pub mod evalys_arcium_gmpc_mxe {
     ) -> Result<()> {
         let result = match output {
             ComputationOutputs::Success(ConfidentialStrategyPlanOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
+            _ => {
+                return fail_computation(&mut ctx.accounts.computation_request, &ctx.accounts.audit_log)
+            }
         };
 
+        let request = &mut ctx.accounts.computation_request;
+        complete_computation(
+            request,
+            &ctx.accounts.audit_log,
+            result_hash(&result.ciphertexts, result.nonce),
+        )?;
+
         emit!(StrategyPlanEvent {
             plan_id: ctx.accounts.computation_account.key(),
-            encrypted_plan: result.ciphertexts[0],
+            requester: request.requester,
+            computation_offset: request.computation_offset,
+            circuit_hash: request.circuit_hash,
+            client_key_id: request.client_key_id,
+            encrypted_plan: result.ciphertexts,
             nonce: result.nonce.to_le_bytes(),
         });
 
@@ -62,25 +322,85 @@ pub mod evalys_arcium_gmpc_mxe {
 
     // ========== Confidential Risk Score ==========
 
-    /// Initialize the risk score computation definition
-    pub fn init_risk_comp_def(ctx: Context<InitRiskCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, COMP_DEF_OFFSET_RISK, None, None)?;
-        Ok(())
+    /// Initialize the risk score computation definition and register its circuit hash
+    pub fn init_risk_comp_def(
+        ctx: Context<InitRiskCompDef>,
+        circuit_hash: [u8; 32],
+        version: String,
+        circuit_source: String,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            COMP_DEF_OFFSET_RISK,
+            Some(off_chain_circuit(circuit_source, circuit_hash)),
+            None,
+        )?;
+        register_circuit(
+            &mut ctx.accounts.circuit_registration,
+            &mut ctx.accounts.circuit_version,
+            CircuitKind::RiskScore,
+            COMP_DEF_OFFSET_RISK,
+            &ctx.accounts.comp_def_account,
+            circuit_hash,
+            version,
+            (ctx.bumps.circuit_registration, ctx.bumps.circuit_version),
+        )
     }
 
     /// Invoke confidential risk score computation
     pub fn request_risk_score(
         ctx: Context<RequestRiskScore>,
         computation_offset: u64,
+        client_key_id: u32,
+        portfolio: EncryptedInput,
+        performance: EncryptedInput,
+        market: MarketConditionsArgs,
     ) -> Result<()> {
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.client_keys.require_active(client_key_id, &[&portfolio, &performance])?;
+
+        let commitment = input_commitment(
+            &ctx.accounts.payer.key(),
+            computation_offset,
+            &[portfolio.commitment_bytes(), performance.commitment_bytes()],
+        );
+        ctx.accounts.computation_request.open(
+            ctx.accounts.payer.key(),
+            CircuitKind::RiskScore,
+            computation_offset,
+            commitment,
+            ctx.accounts.circuit_registration.circuit_hash,
+            client_key_id,
+            ctx.bumps.computation_request,
+        )?;
+        append_audit(&ctx.accounts.audit_log, &ctx.accounts.computation_request, [0; 32])?;
+
+        let mut args = Vec::new();
+        portfolio.push_args(&mut args, &[EncWidth::U64, EncWidth::U64, EncWidth::U8, EncWidth::U16])?;
+        performance.push_args(&mut args, &[EncWidth::I64, EncWidth::I16, EncWidth::U16, EncWidth::U8])?;
+        args.extend([
+            Argument::PlaintextU16(market.curve_volatility),
+            Argument::PlaintextU8(market.liquidity_risk),
+            Argument::PlaintextI8(market.market_sentiment),
+        ]);
+        ctx.accounts.policy.policy.push_args(&mut args);
+        push_commitment_args(&mut args, &commitment);
 
         queue_computation(
             ctx.accounts,
             computation_offset,
-            vec![],
+            args,
             None,
-            vec![RiskScoreCallback::callback_ix(&[])],
+            vec![RiskScoreCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.computation_request.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.audit_log.key(),
+                    is_writable: true,
+                },
+            ])],
             1,
         )?;
         Ok(())
@@ -94,12 +414,25 @@ pub mod evalys_arcium_gmpc_mxe {
     ) -> Result<()> {
         let result = match output {
             ComputationOutputs::Success(ConfidentialRiskScoreOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
+            _ => {
+                return fail_computation(&mut ctx.accounts.computation_request, &ctx.accounts.audit_log)
+            }
         };
 
+        let request = &mut ctx.accounts.computation_request;
+        complete_computation(
+            request,
+            &ctx.accounts.audit_log,
+            result_hash(&result.ciphertexts, result.nonce),
+        )?;
+
         emit!(RiskScoreEvent {
             assessment_id: ctx.accounts.computation_account.key(),
-            encrypted_assessment: result.ciphertexts[0],
+            requester: request.requester,
+            computation_offset: request.computation_offset,
+            circuit_hash: request.circuit_hash,
+            client_key_id: request.client_key_id,
+            encrypted_assessment: result.ciphertexts,
             nonce: result.nonce.to_le_bytes(),
         });
 
@@ -108,25 +441,87 @@ pub mod evalys_arcium_gmpc_mxe {
 
     // ========== Confidential Curve Evaluation ==========
 
-    /// Initialize the curve eval computation definition
-    pub fn init_curve_comp_def(ctx: Context<InitCurveCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, COMP_DEF_OFFSET_CURVE, None, None)?;
-        Ok(())
+    /// Initialize the curve eval computation definition and register its circuit hash
+    pub fn init_curve_comp_def(
+        ctx: Context<InitCurveCompDef>,
+        circuit_hash: [u8; 32],
+        version: String,
+        circuit_source: String,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            COMP_DEF_OFFSET_CURVE,
+            Some(off_chain_circuit(circuit_source, circuit_hash)),
+            None,
+        )?;
+        register_circuit(
+            &mut ctx.accounts.circuit_registration,
+            &mut ctx.accounts.circuit_version,
+            CircuitKind::CurveEval,
+            COMP_DEF_OFFSET_CURVE,
+            &ctx.accounts.comp_def_account,
+            circuit_hash,
+            version,
+            (ctx.bumps.circuit_registration, ctx.bumps.circuit_version),
+        )
     }
 
     /// Invoke confidential curve evaluation computation
     pub fn request_curve_eval(
         ctx: Context<RequestCurveEval>,
         computation_offset: u64,
+        client_key_id: u32,
+        sizing: EncryptedInput,
+        constraints: EncryptedInput,
+        curve: CurveMetricsArgs,
     ) -> Result<()> {
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.client_keys.require_active(client_key_id, &[&sizing, &constraints])?;
+
+        let commitment = input_commitment(
+            &ctx.accounts.payer.key(),
+            computation_offset,
+            &[sizing.commitment_bytes(), constraints.commitment_bytes()],
+        );
+        ctx.accounts.computation_request.open(
+            ctx.accounts.payer.key(),
+            CircuitKind::CurveEval,
+            computation_offset,
+            commitment,
+            ctx.accounts.circuit_registration.circuit_hash,
+            client_key_id,
+            ctx.bumps.computation_request,
+        )?;
+        append_audit(&ctx.accounts.audit_log, &ctx.accounts.computation_request, [0; 32])?;
+
+        let mut args = Vec::new();
+        sizing.push_args(&mut args, &[EncWidth::U64, EncWidth::U64, EncWidth::U64, EncWidth::U8])?;
+        constraints.push_args(&mut args, &[EncWidth::U16, EncWidth::U32, EncWidth::U8])?;
+        args.extend([
+            Argument::PlaintextU64(curve.current_price),
+            Argument::PlaintextI32(curve.price_change_24h),
+            Argument::PlaintextU64(curve.liquidity_depth),
+            Argument::PlaintextU16(curve.buy_pressure),
+            Argument::PlaintextU16(curve.sell_pressure),
+        ]);
+        ctx.accounts.policy.policy.push_args(&mut args);
+        push_commitment_args(&mut args, &commitment);
 
         queue_computation(
             ctx.accounts,
             computation_offset,
-            vec![],
+            args,
             None,
-            vec![CurveEvalCallback::callback_ix(&[])],
+            vec![CurveEvalCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.computation_request.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.audit_log.key(),
+                    is_writable: true,
+                },
+            ])],
             1,
         )?;
         Ok(())
@@ -140,12 +535,25 @@ pub mod evalys_arcium_gmpc_mxe {
     ) -> Result<()> {
         let result = match output {
             ComputationOutputs::Success(ConfidentialCurveEvalOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
+            _ => {
+                return fail_computation(&mut ctx.accounts.computation_request, &ctx.accounts.audit_log)
+            }
         };
 
+        let request = &mut ctx.accounts.computation_request;
+        complete_computation(
+            request,
+            &ctx.accounts.audit_log,
+            result_hash(&result.ciphertexts, result.nonce),
+        )?;
+
         emit!(CurveEvalEvent {
             recommendation_id: ctx.accounts.computation_account.key(),
-            encrypted_recommendation: result.ciphertexts[0],
+            requester: request.requester,
+            computation_offset: request.computation_offset,
+            circuit_hash: request.circuit_hash,
+            client_key_id: request.client_key_id,
+            encrypted_recommendation: result.ciphertexts,
             nonce: result.nonce.to_le_bytes(),
         });
 
@@ -154,25 +562,77 @@ pub mod evalys_arcium_gmpc_mxe {
 
     // ========== gMPC Strategy ==========
 
-    /// Initialize the gMPC strategy computation definition
-    pub fn init_gmpc_comp_def(ctx: Context<InitGmpcCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, COMP_DEF_OFFSET_GMPC, None, None)?;
-        Ok(())
+    /// Initialize the gMPC strategy computation definition and register its circuit hash
+    pub fn init_gmpc_comp_def(
+        ctx: Context<InitGmpcCompDef>,
+        circuit_hash: [u8; 32],
+        version: String,
+        circuit_source: String,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            COMP_DEF_OFFSET_GMPC,
+            Some(off_chain_circuit(circuit_source, circuit_hash)),
+            None,
+        )?;
+        register_circuit(
+            &mut ctx.accounts.circuit_registration,
+            &mut ctx.accounts.circuit_version,
+            CircuitKind::GmpcStrategy,
+            COMP_DEF_OFFSET_GMPC,
+            &ctx.accounts.comp_def_account,
+            circuit_hash,
+            version,
+            (ctx.bumps.circuit_registration, ctx.bumps.circuit_version),
+        )
     }
 
     /// Invoke gMPC strategy computation
     pub fn request_gmpc_strategy(
         ctx: Context<RequestGmpcStrategy>,
         computation_offset: u64,
+        client_key_id: u32,
+        intent: EncryptedInput,
     ) -> Result<()> {
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.client_keys.require_active(client_key_id, &[&intent])?;
+
+        let commitment = input_commitment(
+            &ctx.accounts.payer.key(),
+            computation_offset,
+            &[intent.commitment_bytes()],
+        );
+        ctx.accounts.computation_request.open(
+            ctx.accounts.payer.key(),
+            CircuitKind::GmpcStrategy,
+            computation_offset,
+            commitment,
+            ctx.accounts.circuit_registration.circuit_hash,
+            client_key_id,
+            ctx.bumps.computation_request,
+        )?;
+        append_audit(&ctx.accounts.audit_log, &ctx.accounts.computation_request, [0; 32])?;
+
+        let mut args = Vec::new();
+        intent.push_args(&mut args, &INTENT_INPUT_WIDTHS)?;
+        ctx.accounts.policy.policy.push_args(&mut args);
+        push_commitment_args(&mut args, &commitment);
 
         queue_computation(
             ctx.accounts,
             computation_offset,
-            vec![],
+            args,
             None,
-            vec![GmpcStrategyCallback::callback_ix(&[])],
+            vec![GmpcStrategyCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.computation_request.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.audit_log.key(),
+                    is_writable: true,
+                },
+            ])],
             1,
         )?;
         Ok(())
@@ -186,39 +646,311 @@ pub mod evalys_arcium_gmpc_mxe {
     ) -> Result<()> {
         let result = match output {
             ComputationOutputs::Success(GmpcStrategyOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
+            _ => {
+                return fail_computation(&mut ctx.accounts.computation_request, &ctx.accounts.audit_log)
+            }
         };
 
+        let request = &mut ctx.accounts.computation_request;
+        complete_computation(
+            request,
+            &ctx.accounts.audit_log,
+            result_hash(&result.ciphertexts, result.nonce),
+        )?;
+
         emit!(GmpcStrategyEvent {
             plan_id: ctx.accounts.computation_account.key(),
-            encrypted_plan: result.ciphertexts[0],
+            requester: request.requester,
+            computation_offset: request.computation_offset,
+            circuit_hash: request.circuit_hash,
+            client_key_id: request.client_key_id,
+            encrypted_plan: result.ciphertexts,
             nonce: result.nonce.to_le_bytes(),
         });
 
         Ok(())
     }
 
-    // ========== Multi-User Analytics ==========
+    // ========== gMPC Strategy Quorum ==========
 
-    /// Initialize the multi-user analytics computation definition
-    pub fn init_multi_user_comp_def(ctx: Context<InitMultiUserCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, COMP_DEF_OFFSET_MULTI_USER, None, None)?;
+    /// Initialize the quorum gMPC strategy computation definition and register its circuit hash
+    pub fn init_gmpc_quorum_comp_def(
+        ctx: Context<InitGmpcQuorumCompDef>,
+        circuit_hash: [u8; 32],
+        version: String,
+        circuit_source: String,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            COMP_DEF_OFFSET_GMPC_QUORUM,
+            Some(off_chain_circuit(circuit_source, circuit_hash)),
+            None,
+        )?;
+        register_circuit(
+            &mut ctx.accounts.circuit_registration,
+            &mut ctx.accounts.circuit_version,
+            CircuitKind::GmpcStrategyQuorum,
+            COMP_DEF_OFFSET_GMPC_QUORUM,
+            &ctx.accounts.comp_def_account,
+            circuit_hash,
+            version,
+            (ctx.bumps.circuit_registration, ctx.bumps.circuit_version),
+        )
+    }
+
+    /// Open this deployment's side of a quorum. The requester opens the same
+    /// `quorum_offset` on the peer deployment, which runs the other leg on its
+    /// own cluster under its own MXE key.
+    pub fn open_gmpc_quorum(ctx: Context<OpenGmpcQuorum>, quorum_offset: u64) -> Result<()> {
+        let peer = ctx.accounts.config.quorum_peer;
+        let quorum = &mut ctx.accounts.quorum_request;
+        quorum.requester = ctx.accounts.payer.key();
+        quorum.quorum_offset = quorum_offset;
+        quorum.peer = peer;
+        quorum.peer_request =
+            Pubkey::find_program_address(&[QUORUM_REQUEST_SEED, &quorum_offset.to_le_bytes()], &peer).0;
+        quorum.leg = QuorumLeg::default();
+        quorum.status = QuorumStatus::Open;
+        quorum.bump = ctx.bumps.quorum_request;
+
+        emit!(QuorumOpenedEvent {
+            quorum_offset,
+            requester: quorum.requester,
+            peer,
+        });
         Ok(())
     }
 
+    /// Queue this deployment's leg of a quorum gMPC strategy on its own cluster
+    ///
+    /// The requester encrypts the same intent and salt to each deployment's
+    /// MXE key. The callback compares the revealed plaintext digest with the
+    /// peer's, so the legs agree only if both clusters computed the same plan.
+    pub fn request_gmpc_strategy_quorum(
+        ctx: Context<RequestGmpcStrategyQuorum>,
+        computation_offset: u64,
+        _quorum_offset: u64,
+        client_key_id: u32,
+        intent: EncryptedInput,
+        salt: EncryptedInput,
+    ) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        ctx.accounts.client_keys.require_active(client_key_id, &[&intent, &salt])?;
+
+        let quorum = &mut ctx.accounts.quorum_request;
+        require!(quorum.status == QuorumStatus::Open, ErrorCode::QuorumNotOpen);
+        require!(!quorum.leg.queued, ErrorCode::QuorumLegAlreadyQueued);
+        quorum.leg = QuorumLeg {
+            computation_offset,
+            queued: true,
+            ..QuorumLeg::default()
+        };
+
+        let commitment = input_commitment(
+            &ctx.accounts.payer.key(),
+            computation_offset,
+            &[intent.commitment_bytes(), salt.commitment_bytes()],
+        );
+        ctx.accounts.computation_request.open(
+            ctx.accounts.payer.key(),
+            CircuitKind::GmpcStrategyQuorum,
+            computation_offset,
+            commitment,
+            ctx.accounts.circuit_registration.circuit_hash,
+            client_key_id,
+            ctx.bumps.computation_request,
+        )?;
+        append_audit(&ctx.accounts.audit_log, &ctx.accounts.computation_request, [0; 32])?;
+
+        let mut args = Vec::new();
+        intent.push_args(&mut args, &INTENT_INPUT_WIDTHS)?;
+        salt.push_args(&mut args, &[EncWidth::U128])?;
+        ctx.accounts.policy.policy.push_args(&mut args);
+        push_commitment_args(&mut args, &commitment);
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![GmpcStrategyQuorumCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.computation_request.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.audit_log.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.quorum_request.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.quorum_request.peer_request,
+                    is_writable: false,
+                },
+            ])],
+            1,
+        )?;
+        Ok(())
+    }
+
+    /// Callback for this deployment's quorum leg; records the digest and
+    /// settles against the peer's record if its leg already reported
+    #[arcium_callback(encrypted_ix = "evalys_gmpc_strategy_quorum")]
+    pub fn gmpc_strategy_quorum_callback(
+        ctx: Context<GmpcStrategyQuorumCallback>,
+        output: ComputationOutputs<GmpcStrategyQuorumOutput>,
+    ) -> Result<()> {
+        let (plan, digest) = match output {
+            ComputationOutputs::Success(GmpcStrategyQuorumOutput {
+                field_0: GmpcStrategyQuorumOutputStruct0 { field_0, field_1 },
+            }) => (field_0, field_1),
+            _ => {
+                let request = &mut ctx.accounts.computation_request;
+                fail_computation(request, &ctx.accounts.audit_log)?;
+                return fail_quorum(&mut ctx.accounts.quorum_request, request.computation_offset);
+            }
+        };
+
+        let request = &mut ctx.accounts.computation_request;
+        complete_computation(
+            request,
+            &ctx.accounts.audit_log,
+            result_hash(&plan.ciphertexts, plan.nonce),
+        )?;
+
+        emit!(GmpcStrategyEvent {
+            plan_id: ctx.accounts.computation_account.key(),
+            requester: request.requester,
+            computation_offset: request.computation_offset,
+            circuit_hash: request.circuit_hash,
+            client_key_id: request.client_key_id,
+            encrypted_plan: plan.ciphertexts,
+            nonce: plan.nonce.to_le_bytes(),
+        });
+
+        let quorum = &mut ctx.accounts.quorum_request;
+        // The peer's leg already aborted; this result can no longer be agreed on
+        if quorum.status == QuorumStatus::Failed {
+            return Ok(());
+        }
+        require!(quorum.status == QuorumStatus::Open, ErrorCode::QuorumNotOpen);
+        require!(
+            quorum.leg.queued && !quorum.leg.completed && quorum.leg.computation_offset == request.computation_offset,
+            ErrorCode::InvalidQuorumLeg
+        );
+        quorum.leg.completed = true;
+        quorum.leg.digest = digest;
+
+        // The peer may not have opened its side yet; `settle_gmpc_quorum`
+        // catches up once it reports
+        if let Some(peer) = peer_quorum(quorum, &ctx.accounts.peer_quorum_request)? {
+            settle_quorum(quorum, &peer);
+        }
+        Ok(())
+    }
+
+    /// Settle a quorum whose leg reported before the peer's did. Anyone may
+    /// call it once the peer deployment's record holds its leg's outcome.
+    pub fn settle_gmpc_quorum(ctx: Context<SettleGmpcQuorum>, _quorum_offset: u64) -> Result<()> {
+        let quorum = &mut ctx.accounts.quorum_request;
+        require!(quorum.status == QuorumStatus::Open, ErrorCode::QuorumNotOpen);
+        let peer = peer_quorum(quorum, &ctx.accounts.peer_quorum_request)?
+            .ok_or(ErrorCode::QuorumPeerMismatch)?;
+        settle_quorum(quorum, &peer);
+        Ok(())
+    }
+
+    // ========== Multi-User Analytics ==========
+
+    /// Initialize the multi-user analytics computation definition and register its circuit hash
+    pub fn init_multi_user_comp_def(
+        ctx: Context<InitMultiUserCompDef>,
+        circuit_hash: [u8; 32],
+        version: String,
+        circuit_source: String,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            COMP_DEF_OFFSET_MULTI_USER,
+            Some(off_chain_circuit(circuit_source, circuit_hash)),
+            None,
+        )?;
+        register_circuit(
+            &mut ctx.accounts.circuit_registration,
+            &mut ctx.accounts.circuit_version,
+            CircuitKind::MultiUserAnalytics,
+            COMP_DEF_OFFSET_MULTI_USER,
+            &ctx.accounts.comp_def_account,
+            circuit_hash,
+            version,
+            (ctx.bumps.circuit_registration, ctx.bumps.circuit_version),
+        )
+    }
+
     /// Invoke multi-user analytics computation
     pub fn request_multi_user_analytics(
         ctx: Context<RequestMultiUserAnalytics>,
         computation_offset: u64,
+        client_key_id: u32,
+        profiles: Vec<EncryptedInput>,
+        aggregation_type: u8,
     ) -> Result<()> {
+        require!(
+            !profiles.is_empty() && profiles.len() <= MAX_ANALYTICS_PROFILES,
+            ErrorCode::InvalidProfileCount
+        );
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        let profile_refs: Vec<&EncryptedInput> = profiles.iter().collect();
+        ctx.accounts.client_keys.require_active(client_key_id, &profile_refs)?;
+
+        let inputs: Vec<Vec<u8>> = profiles.iter().map(|p| p.commitment_bytes()).collect();
+        let commitment = input_commitment(&ctx.accounts.payer.key(), computation_offset, &inputs);
+        ctx.accounts.computation_request.open(
+            ctx.accounts.payer.key(),
+            CircuitKind::MultiUserAnalytics,
+            computation_offset,
+            commitment,
+            ctx.accounts.circuit_registration.circuit_hash,
+            client_key_id,
+            ctx.bumps.computation_request,
+        )?;
+        append_audit(&ctx.accounts.audit_log, &ctx.accounts.computation_request, [0; 32])?;
+
+        let mut args = Vec::new();
+        for profile in &profiles {
+            profile.push_args(
+                &mut args,
+                &[
+                    EncWidth::U64,
+                    EncWidth::U32,
+                    EncWidth::U16,
+                    EncWidth::U64,
+                    EncWidth::U64,
+                    EncWidth::U8,
+                ],
+            )?;
+        }
+        args.push(Argument::PlaintextU8(aggregation_type));
+        push_commitment_args(&mut args, &commitment);
 
         queue_computation(
             ctx.accounts,
             computation_offset,
-            vec![],
+            args,
             None,
-            vec![MultiUserAnalyticsCallback::callback_ix(&[])],
+            vec![MultiUserAnalyticsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: ctx.accounts.computation_request.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: ctx.accounts.audit_log.key(),
+                    is_writable: true,
+                },
+            ])],
             1,
         )?;
         Ok(())
@@ -232,137 +964,1709 @@ pub mod evalys_arcium_gmpc_mxe {
     ) -> Result<()> {
         let result = match output {
             ComputationOutputs::Success(MultiUserAnalyticsOutput { field_0 }) => field_0,
-            _ => return Err(ErrorCode::AbortedComputation.into()),
+            _ => {
+                return fail_computation(&mut ctx.accounts.computation_request, &ctx.accounts.audit_log)
+            }
         };
 
+        let request = &mut ctx.accounts.computation_request;
+        complete_computation(
+            request,
+            &ctx.accounts.audit_log,
+            result_hash(&result.ciphertexts, result.nonce),
+        )?;
+
         emit!(MultiUserAnalyticsEvent {
             analytics_id: ctx.accounts.computation_account.key(),
-            encrypted_metrics: result.ciphertexts[0],
+            requester: request.requester,
+            computation_offset: request.computation_offset,
+            circuit_hash: request.circuit_hash,
+            client_key_id: request.client_key_id,
+            encrypted_metrics: result.ciphertexts,
             nonce: result.nonce.to_le_bytes(),
         });
 
         Ok(())
     }
+
+    // ========== Circuit Versions ==========
+
+    /// Re-register the circuit of `kind` once its comp def enforces a new
+    /// circuit hash; the previous hash keeps its `CircuitVersion` record
+    pub fn upgrade_circuit(
+        ctx: Context<UpgradeCircuit>,
+        kind: CircuitKind,
+        circuit_hash: [u8; 32],
+        version: String,
+    ) -> Result<()> {
+        let registration = &mut ctx.accounts.circuit_registration;
+        let comp_def_offset = registration.comp_def_offset;
+        let registration_bump = registration.bump;
+        register_circuit(
+            registration,
+            &mut ctx.accounts.circuit_version,
+            kind,
+            comp_def_offset,
+            &ctx.accounts.comp_def_account,
+            circuit_hash,
+            version,
+            (registration_bump, ctx.bumps.circuit_version),
+        )
+    }
+
+    /// Look up the human-readable version registered for a circuit hash
+    pub fn circuit_version(
+        ctx: Context<CircuitVersionQuery>,
+        _circuit_hash: [u8; 32],
+    ) -> Result<CircuitVersionInfo> {
+        let record = &ctx.accounts.circuit_version;
+        Ok(CircuitVersionInfo {
+            kind: record.kind,
+            version: record.version.clone(),
+            registered_slot: record.registered_slot,
+        })
+    }
+}
+
+// ========== Input Commitments ==========
+
+/// Compute the commitment binding a request to its encrypted inputs.
+///
+/// SHA-256 over the domain separator, requester, computation offset and every
+/// encrypted argument (x25519 public key, nonce, ciphertexts) in submission
+/// order. The same value is stored in the request record and echoed by the
+/// circuit inside its encrypted output.
+pub fn input_commitment(requester: &Pubkey, computation_offset: u64, inputs: &[Vec<u8>]) -> [u8; 32] {
+    let offset = computation_offset.to_le_bytes();
+    let mut parts: Vec<&[u8]> = vec![INPUT_COMMITMENT_DOMAIN, requester.as_ref(), &offset];
+    parts.extend(inputs.iter().map(|input| input.as_slice()));
+    hashv(&parts).to_bytes()
+}
+
+/// Pass the commitment to the circuit as its plaintext `InputCommitment`
+fn push_commitment_args(args: &mut Vec<Argument>, commitment: &[u8; 32]) {
+    let hi = u128::from_be_bytes(commitment[..16].try_into().unwrap());
+    let lo = u128::from_be_bytes(commitment[16..].try_into().unwrap());
+    args.push(Argument::PlaintextU128(hi));
+    args.push(Argument::PlaintextU128(lo));
+}
+
+// ========== Instruction Arguments ==========
+
+/// One `Enc<Shared, T>` circuit input: client x25519 public key, nonce and
+/// one ciphertext per scalar field of `T`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EncryptedInput {
+    pub pub_key: [u8; 32],
+    pub nonce: u128,
+    pub ciphertexts: Vec<[u8; 32]>,
+}
+
+/// Integer width of an encrypted scalar field
+#[derive(Clone, Copy)]
+pub enum EncWidth {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I16,
+    I64,
+}
+
+/// Field widths of the gMPC `IntentInput`
+const INTENT_INPUT_WIDTHS: [EncWidth; 9] = [
+    EncWidth::U64,
+    EncWidth::U8,
+    EncWidth::U8,
+    EncWidth::U64,
+    EncWidth::U16,
+    EncWidth::U16,
+    EncWidth::U32,
+    EncWidth::U16,
+    EncWidth::U16,
+];
+
+impl EncryptedInput {
+    /// Bytes covered by the input commitment: `pub_key || nonce (LE) || ciphertexts`
+    pub fn commitment_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(48 + 32 * self.ciphertexts.len());
+        bytes.extend_from_slice(&self.pub_key);
+        bytes.extend_from_slice(&self.nonce.to_le_bytes());
+        for ciphertext in &self.ciphertexts {
+            bytes.extend_from_slice(ciphertext);
+        }
+        bytes
+    }
+
+    /// Append the Arcium arguments for this input in circuit field order
+    fn push_args(&self, args: &mut Vec<Argument>, widths: &[EncWidth]) -> Result<()> {
+        require!(
+            self.ciphertexts.len() == widths.len(),
+            ErrorCode::InvalidCiphertextCount
+        );
+        args.push(Argument::ArcisPubkey(self.pub_key));
+        args.push(Argument::PlaintextU128(self.nonce));
+        for (ciphertext, width) in self.ciphertexts.iter().zip(widths) {
+            args.push(match width {
+                EncWidth::U8 => Argument::EncryptedU8(*ciphertext),
+                EncWidth::U16 => Argument::EncryptedU16(*ciphertext),
+                EncWidth::U32 => Argument::EncryptedU32(*ciphertext),
+                EncWidth::U64 => Argument::EncryptedU64(*ciphertext),
+                EncWidth::U128 => Argument::EncryptedU128(*ciphertext),
+                EncWidth::I16 => Argument::EncryptedI16(*ciphertext),
+                EncWidth::I64 => Argument::EncryptedI64(*ciphertext),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Public curve state for `confidential_strategy_plan`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CurveStateArgs {
+    pub current_price: u64,
+    pub liquidity_depth: u64,
+    pub volatility: u16,
+    pub recent_volume: u64,
+}
+
+/// Public market conditions for `confidential_risk_score`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MarketConditionsArgs {
+    pub curve_volatility: u16,
+    pub liquidity_risk: u8,
+    pub market_sentiment: i8,
+}
+
+/// Public curve metrics for `confidential_curve_eval`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CurveMetricsArgs {
+    pub current_price: u64,
+    pub price_change_24h: i32,
+    pub liquidity_depth: u64,
+    pub buy_pressure: u16,
+    pub sell_pressure: u16,
+}
+
+// ========== State ==========
+
+/// Encrypted instruction a request was queued for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum CircuitKind {
+    StrategyPlan,
+    RiskScore,
+    CurveEval,
+    GmpcStrategy,
+    MultiUserAnalytics,
+    GmpcStrategyQuorum,
+}
+
+/// Lifecycle of a queued computation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum RequestStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+/// Program configuration
+#[account]
+#[derive(InitSpace)]
+pub struct MxeConfig {
+    pub authority: Pubkey,
+    /// Program id of the deployment, bound to another cluster, that runs the
+    /// other leg of every quorum
+    pub quorum_peer: Pubkey,
+    pub bump: u8,
+}
+
+/// Outcome of a quorum, as seen by one deployment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum QuorumStatus {
+    Open,
+    /// Both legs returned matching result digests
+    Final,
+    /// The legs disagreed; neither result may be used
+    Mismatch,
+    /// A leg aborted; the quorum can no longer agree
+    Failed,
+}
+
+/// One deployment's computation within a quorum
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct QuorumLeg {
+    pub computation_offset: u64,
+    pub queued: bool,
+    pub completed: bool,
+    /// The leg's computation aborted
+    pub failed: bool,
+    /// Salted plan digest revealed by `evalys_gmpc_strategy_quorum`
+    pub digest: u128,
+}
+
+/// This deployment's side of a quorum gMPC strategy request. The peer
+/// deployment keeps the other side at the same seeds under its program id.
+#[account]
+#[derive(InitSpace)]
+pub struct QuorumRequest {
+    pub requester: Pubkey,
+    pub quorum_offset: u64,
+    /// Program id of the peer deployment when the quorum was opened
+    pub peer: Pubkey,
+    /// The peer's `QuorumRequest` address for this quorum
+    pub peer_request: Pubkey,
+    pub leg: QuorumLeg,
+    pub status: QuorumStatus,
+    pub bump: u8,
+}
+
+/// Status of a registered client key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ClientKeyStatus {
+    /// New requests may use this key
+    Active,
+    /// Kept only so in-flight results encrypted to it can still be attributed
+    Retired,
+}
+
+/// One x25519 public key of a bridge
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct ClientKey {
+    pub key_id: u32,
+    pub pub_key: [u8; 32],
+    pub status: ClientKeyStatus,
+    pub registered_slot: u64,
+    pub retired_slot: u64,
+}
+
+/// x25519 public keys a bridge encrypts its requests with
+#[account]
+#[derive(InitSpace)]
+pub struct ClientKeyRegistry {
+    pub bridge: Pubkey,
+    /// Id assigned to the next registered key
+    pub next_key_id: u32,
+    #[max_len(MAX_CLIENT_KEYS)]
+    pub keys: Vec<ClientKey>,
+    pub bump: u8,
+}
+
+impl ClientKeyRegistry {
+    /// Check `key_id` is active and every encrypted input uses its public key
+    fn require_active(&self, key_id: u32, inputs: &[&EncryptedInput]) -> Result<()> {
+        let key = self
+            .keys
+            .iter()
+            .find(|k| k.key_id == key_id)
+            .ok_or(ErrorCode::UnknownClientKey)?;
+        require!(key.status == ClientKeyStatus::Active, ErrorCode::ClientKeyRetired);
+        require!(
+            inputs.iter().all(|input| input.pub_key == key.pub_key),
+            ErrorCode::ClientKeyMismatch
+        );
+        Ok(())
+    }
+}
+
+/// Per-computation request record, keyed by computation offset
+#[account]
+#[derive(InitSpace)]
+pub struct ComputationRequest {
+    pub requester: Pubkey,
+    pub kind: CircuitKind,
+    pub computation_offset: u64,
+    /// SHA-256 commitment to the submitted encrypted inputs
+    pub input_commitment: [u8; 32],
+    /// Hash of the compiled circuit the computation was queued against
+    pub circuit_hash: [u8; 32],
+    /// Requester's client key the result is encrypted to
+    pub client_key_id: u32,
+    pub status: RequestStatus,
+    pub requested_slot: u64,
+    pub completed_slot: u64,
+    pub bump: u8,
+}
+
+impl ComputationRequest {
+    fn open(
+        &mut self,
+        requester: Pubkey,
+        kind: CircuitKind,
+        computation_offset: u64,
+        input_commitment: [u8; 32],
+        circuit_hash: [u8; 32],
+        client_key_id: u32,
+        bump: u8,
+    ) -> Result<()> {
+        self.requester = requester;
+        self.kind = kind;
+        self.computation_offset = computation_offset;
+        self.input_commitment = input_commitment;
+        self.circuit_hash = circuit_hash;
+        self.client_key_id = client_key_id;
+        self.status = RequestStatus::Pending;
+        self.requested_slot = Clock::get()?.slot;
+        self.completed_slot = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    fn finish(&mut self, status: RequestStatus, slot: u64) -> Result<()> {
+        require!(
+            self.status == RequestStatus::Pending,
+            ErrorCode::RequestNotPending
+        );
+        self.status = status;
+        self.completed_slot = slot;
+        Ok(())
+    }
+}
+
+/// Circuit currently registered for a comp def, keyed by circuit kind
+#[account]
+#[derive(InitSpace)]
+pub struct CircuitRegistration {
+    pub kind: CircuitKind,
+    pub comp_def_offset: u32,
+    /// SHA-256 of the compiled circuit (`build/<name>.arcis`)
+    pub circuit_hash: [u8; 32],
+    #[max_len(MAX_CIRCUIT_VERSION_LEN)]
+    pub version: String,
+    pub registered_slot: u64,
+    pub bump: u8,
+}
+
+/// Human-readable version of a circuit, keyed by circuit hash
+#[account]
+#[derive(InitSpace)]
+pub struct CircuitVersion {
+    pub kind: CircuitKind,
+    pub circuit_hash: [u8; 32],
+    #[max_len(MAX_CIRCUIT_VERSION_LEN)]
+    pub version: String,
+    pub registered_slot: u64,
+    pub bump: u8,
+}
+
+/// Result of the `circuit_version` query
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CircuitVersionInfo {
+    pub kind: CircuitKind,
+    pub version: String,
+    pub registered_slot: u64,
+}
+
+/// Off-chain circuit source of a comp def: Arx nodes fetch the compiled
+/// circuit from `source` and refuse to run it unless it hashes to `hash`
+fn off_chain_circuit(source: String, hash: [u8; 32]) -> CircuitSource {
+    CircuitSource::OffChain(OffChainCircuitSource { source, hash })
+}
+
+/// Circuit hash a comp def enforces, read from its off-chain circuit source
+fn comp_def_circuit_hash(comp_def: &AccountInfo) -> Result<[u8; 32]> {
+    let data = comp_def.try_borrow_data()?;
+    let account = ComputationDefinitionAccount::try_deserialize(&mut &data[..])?;
+    match account.circuit_source {
+        CircuitSource::OffChain(source) => Ok(source.hash),
+        _ => err!(ErrorCode::CircuitHashNotAttested),
+    }
+}
+
+/// Record `circuit_hash` as the circuit of `kind`. It must be the hash the
+/// comp def enforces, so a registration cannot attest a circuit the cluster
+/// does not run.
+#[allow(clippy::too_many_arguments)]
+fn register_circuit(
+    registration: &mut CircuitRegistration,
+    version_record: &mut CircuitVersion,
+    kind: CircuitKind,
+    comp_def_offset: u32,
+    comp_def: &AccountInfo,
+    circuit_hash: [u8; 32],
+    version: String,
+    (registration_bump, version_bump): (u8, u8),
+) -> Result<()> {
+    require!(
+        !version.is_empty() && version.len() <= MAX_CIRCUIT_VERSION_LEN,
+        ErrorCode::InvalidCircuitVersion
+    );
+    require!(
+        comp_def_circuit_hash(comp_def)? == circuit_hash,
+        ErrorCode::CircuitHashMismatch
+    );
+    let slot = Clock::get()?.slot;
+
+    registration.kind = kind;
+    registration.comp_def_offset = comp_def_offset;
+    registration.circuit_hash = circuit_hash;
+    registration.version = version.clone();
+    registration.registered_slot = slot;
+    registration.bump = registration_bump;
+
+    version_record.kind = kind;
+    version_record.circuit_hash = circuit_hash;
+    version_record.version = version.clone();
+    version_record.registered_slot = slot;
+    version_record.bump = version_bump;
+
+    emit!(CircuitRegisteredEvent {
+        kind,
+        comp_def_offset,
+        circuit_hash,
+        version,
+    });
+    Ok(())
+}
+
+// ========== Policies ==========
+
+/// Thresholds of `confidential_strategy_plan`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct StrategyPolicy {
+    pub stealth_risk: u16,
+    pub ghost_risk: u16,
+    pub low_win_rate: u16,
+    pub medium_volatility: u16,
+    pub high_volatility: u16,
+    pub fast_window_sec: u32,
+    pub medium_window_sec: u32,
+    pub slow_window_sec: u32,
+    pub min_slices: u8,
+    pub participation_pct: u8,
+    pub moderate_appetite: u8,
+    pub aggressive_appetite: u8,
+    pub aggressive_win_rate: u16,
+    pub take_profit_bps: u16,
+    pub stop_loss_bps: u16,
+    pub exit_window_pct: u8,
+    pub history_weight_trades: u16,
+}
+
+impl StrategyPolicy {
+    fn validate(&self) -> Result<()> {
+        require!(
+            self.low_win_rate <= 10_000
+                && self.aggressive_win_rate <= 10_000
+                // Each tier's threshold at or below the next one's
+                && self.stealth_risk <= self.ghost_risk
+                && self.medium_volatility <= self.high_volatility
+                && self.moderate_appetite <= self.aggressive_appetite
+                // Faster windows for higher volatility
+                && self.fast_window_sec <= self.medium_window_sec
+                && self.medium_window_sec <= self.slow_window_sec
+                && self.slow_window_sec <= MAX_POLICY_WINDOW_SEC
+                && (1..=MAX_STRATEGY_SLICES).contains(&self.min_slices)
+                && self.participation_pct <= 100
+                && self.stop_loss_bps <= 10_000
+                && self.exit_window_pct <= 100
+                && self.history_weight_trades >= 1,
+            ErrorCode::InvalidPolicy
+        );
+        Ok(())
+    }
+
+    fn push_args(&self, args: &mut Vec<Argument>) {
+        args.extend([
+            Argument::PlaintextU16(self.stealth_risk),
+            Argument::PlaintextU16(self.ghost_risk),
+            Argument::PlaintextU16(self.low_win_rate),
+            Argument::PlaintextU16(self.medium_volatility),
+            Argument::PlaintextU16(self.high_volatility),
+            Argument::PlaintextU32(self.fast_window_sec),
+            Argument::PlaintextU32(self.medium_window_sec),
+            Argument::PlaintextU32(self.slow_window_sec),
+            Argument::PlaintextU8(self.min_slices),
+            Argument::PlaintextU8(self.participation_pct),
+            Argument::PlaintextU8(self.moderate_appetite),
+            Argument::PlaintextU8(self.aggressive_appetite),
+            Argument::PlaintextU16(self.aggressive_win_rate),
+            Argument::PlaintextU16(self.take_profit_bps),
+            Argument::PlaintextU16(self.stop_loss_bps),
+            Argument::PlaintextU8(self.exit_window_pct),
+            Argument::PlaintextU16(self.history_weight_trades),
+        ]);
+    }
+}
+
+/// Thresholds of `confidential_risk_score`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct RiskPolicy {
+    pub high_exposure: u8,
+    pub over_exposure: u8,
+    pub low_diversification: u8,
+    pub high_volatility: u16,
+    pub very_high_volatility: u16,
+    pub high_liquidity_risk: u8,
+    pub poor_drawdown: u16,
+    pub good_sharpe: i16,
+    pub good_consistency: u8,
+    pub caution_risk: u8,
+    pub avoid_risk: u8,
+}
+
+impl RiskPolicy {
+    fn validate(&self) -> Result<()> {
+        require!(
+            self.poor_drawdown <= 10_000
+                // Each tier's threshold at or below the next one's
+                && self.high_exposure <= self.over_exposure
+                && self.high_volatility <= self.very_high_volatility
+                && self.caution_risk <= self.avoid_risk,
+            ErrorCode::InvalidPolicy
+        );
+        Ok(())
+    }
+
+    fn push_args(&self, args: &mut Vec<Argument>) {
+        args.extend([
+            Argument::PlaintextU8(self.high_exposure),
+            Argument::PlaintextU8(self.over_exposure),
+            Argument::PlaintextU8(self.low_diversification),
+            Argument::PlaintextU16(self.high_volatility),
+            Argument::PlaintextU16(self.very_high_volatility),
+            Argument::PlaintextU8(self.high_liquidity_risk),
+            Argument::PlaintextU16(self.poor_drawdown),
+            Argument::PlaintextI16(self.good_sharpe),
+            Argument::PlaintextU8(self.good_consistency),
+            Argument::PlaintextU8(self.caution_risk),
+            Argument::PlaintextU8(self.avoid_risk),
+        ]);
+    }
+}
+
+/// Thresholds of `confidential_curve_eval`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct CurvePolicy {
+    pub plenty_liquidity_multiple: u8,
+    pub liquidity_use_pct: u8,
+    pub momentum_bps: u16,
+    pub pressure_ratio: u8,
+    pub high_urgency: u8,
+    pub urgent: u8,
+    pub high_urgency_max_timing_sec: u32,
+    pub urgent_max_timing_sec: u32,
+    pub confident_liquidity_multiple: u8,
+    pub stable_bps: u16,
+    pub moderate_bps: u16,
+}
+
+impl CurvePolicy {
+    fn validate(&self) -> Result<()> {
+        require!(
+            self.liquidity_use_pct <= 100
+                // Each tier's threshold at or below the next one's
+                && self.high_urgency <= self.urgent
+                && self.stable_bps <= self.moderate_bps
+                // More urgent executions get the shorter timing cap
+                && self.urgent_max_timing_sec <= self.high_urgency_max_timing_sec,
+            ErrorCode::InvalidPolicy
+        );
+        Ok(())
+    }
+
+    fn push_args(&self, args: &mut Vec<Argument>) {
+        args.extend([
+            Argument::PlaintextU8(self.plenty_liquidity_multiple),
+            Argument::PlaintextU8(self.liquidity_use_pct),
+            Argument::PlaintextU16(self.momentum_bps),
+            Argument::PlaintextU8(self.pressure_ratio),
+            Argument::PlaintextU8(self.high_urgency),
+            Argument::PlaintextU8(self.urgent),
+            Argument::PlaintextU32(self.high_urgency_max_timing_sec),
+            Argument::PlaintextU32(self.urgent_max_timing_sec),
+            Argument::PlaintextU8(self.confident_liquidity_multiple),
+            Argument::PlaintextU16(self.stable_bps),
+            Argument::PlaintextU16(self.moderate_bps),
+        ]);
+    }
+}
+
+/// Thresholds of `evalys_gmpc_strategy` and `evalys_gmpc_strategy_quorum`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct GmpcPolicy {
+    pub low_risk_size_pct: u8,
+    pub normal_risk_size_pct: u8,
+    pub high_volatility: u16,
+    pub high_volatility_size_pct: u8,
+    pub normal_slices: u8,
+    pub stealth_slices: u8,
+    pub max_privacy_slices: u8,
+    pub calm_window_sec: u32,
+    pub volatile_window_sec: u32,
+    pub low_risk_volatility: u16,
+    pub high_risk_volatility: u16,
+}
+
+impl GmpcPolicy {
+    fn validate(&self) -> Result<()> {
+        require!(
+            [self.low_risk_size_pct, self.normal_risk_size_pct, self.high_volatility_size_pct]
+                .iter()
+                .all(|&pct| pct <= 100)
+                && [self.high_volatility, self.low_risk_volatility, self.high_risk_volatility]
+                    .iter()
+                    .all(|&bps| bps <= 10_000)
+                && [self.normal_slices, self.stealth_slices, self.max_privacy_slices]
+                    .iter()
+                    .all(|slices| (1..=MAX_GMPC_POLICY_SLICES).contains(slices))
+                && self.calm_window_sec <= MAX_POLICY_WINDOW_SEC
+                && self.volatile_window_sec <= MAX_POLICY_WINDOW_SEC,
+            ErrorCode::InvalidPolicy
+        );
+        Ok(())
+    }
+
+    fn push_args(&self, args: &mut Vec<Argument>) {
+        args.extend([
+            Argument::PlaintextU8(self.low_risk_size_pct),
+            Argument::PlaintextU8(self.normal_risk_size_pct),
+            Argument::PlaintextU16(self.high_volatility),
+            Argument::PlaintextU8(self.high_volatility_size_pct),
+            Argument::PlaintextU8(self.normal_slices),
+            Argument::PlaintextU8(self.stealth_slices),
+            Argument::PlaintextU8(self.max_privacy_slices),
+            Argument::PlaintextU32(self.calm_window_sec),
+            Argument::PlaintextU32(self.volatile_window_sec),
+            Argument::PlaintextU16(self.low_risk_volatility),
+            Argument::PlaintextU16(self.high_risk_volatility),
+        ]);
+    }
+}
+
+/// Policy of `confidential_strategy_plan`
+#[account]
+#[derive(InitSpace)]
+pub struct StrategyPolicyAccount {
+    pub policy: StrategyPolicy,
+    pub updated_slot: u64,
+    pub bump: u8,
+}
+
+/// Policy of `confidential_risk_score`
+#[account]
+#[derive(InitSpace)]
+pub struct RiskPolicyAccount {
+    pub policy: RiskPolicy,
+    pub updated_slot: u64,
+    pub bump: u8,
+}
+
+/// Policy of `confidential_curve_eval`
+#[account]
+#[derive(InitSpace)]
+pub struct CurvePolicyAccount {
+    pub policy: CurvePolicy,
+    pub updated_slot: u64,
+    pub bump: u8,
+}
+
+/// Policy shared by `evalys_gmpc_strategy` and its quorum variant
+#[account]
+#[derive(InitSpace)]
+pub struct GmpcPolicyAccount {
+    pub policy: GmpcPolicy,
+    pub updated_slot: u64,
+    pub bump: u8,
+}
+
+/// Emit the update of the policy of `kind` and return the current slot
+fn policy_updated(kind: CircuitKind) -> Result<u64> {
+    let updated_slot = Clock::get()?.slot;
+    emit!(PolicyUpdatedEvent { kind, updated_slot });
+    Ok(updated_slot)
+}
+
+// ========== Audit Log ==========
+
+/// Fixed-size ring buffer of request and callback records
+#[account(zero_copy)]
+#[repr(C)]
+pub struct AuditLog {
+    /// Total entries ever appended; the next write goes to `head % AUDIT_LOG_CAPACITY`
+    pub head: u64,
+    pub entries: [AuditEntry; AUDIT_LOG_CAPACITY],
+}
+
+#[zero_copy]
+#[repr(C)]
+pub struct AuditEntry {
+    pub requester: Pubkey,
+    /// SHA-256 of the output nonce and ciphertexts; zero for request entries
+    pub result_hash: [u8; 32],
+    pub computation_offset: u64,
+    pub slot: u64,
+    /// `CircuitKind` discriminant
+    pub kind: u8,
+    /// `RequestStatus` discriminant
+    pub status: u8,
+    pub _padding: [u8; 6],
+}
+
+impl AuditLog {
+    pub fn append(&mut self, entry: AuditEntry) {
+        let index = (self.head % AUDIT_LOG_CAPACITY as u64) as usize;
+        self.entries[index] = entry;
+        self.head += 1;
+    }
+}
+
+/// Hash of an encrypted result as recorded in the audit log
+pub fn result_hash<const N: usize>(ciphertexts: &[[u8; 32]; N], nonce: u128) -> [u8; 32] {
+    let nonce = nonce.to_le_bytes();
+    let mut parts: Vec<&[u8]> = Vec::with_capacity(N + 1);
+    parts.push(&nonce);
+    parts.extend(ciphertexts.iter().map(|c| c.as_slice()));
+    hashv(&parts).to_bytes()
+}
+
+fn append_audit(
+    audit_log: &AccountLoader<AuditLog>,
+    request: &ComputationRequest,
+    result_hash: [u8; 32],
+) -> Result<()> {
+    audit_log.load_mut()?.append(AuditEntry {
+        requester: request.requester,
+        result_hash,
+        computation_offset: request.computation_offset,
+        slot: Clock::get()?.slot,
+        kind: request.kind as u8,
+        status: request.status as u8,
+        _padding: [0; 6],
+    });
+    Ok(())
+}
+
+fn complete_computation(
+    request: &mut ComputationRequest,
+    audit_log: &AccountLoader<AuditLog>,
+    result_hash: [u8; 32],
+) -> Result<()> {
+    request.finish(RequestStatus::Completed, Clock::get()?.slot)?;
+    append_audit(audit_log, request, result_hash)
+}
+
+/// Record an aborted computation instead of reverting, so the failure stays auditable
+fn fail_computation(
+    request: &mut ComputationRequest,
+    audit_log: &AccountLoader<AuditLog>,
+) -> Result<()> {
+    request.finish(RequestStatus::Failed, Clock::get()?.slot)?;
+    append_audit(audit_log, request, [0; 32])?;
+    emit!(ComputationFailedEvent {
+        computation_offset: request.computation_offset,
+        kind: request.kind,
+    });
+    Ok(())
+}
+
+/// Mark the local quorum leg of `computation_offset` failed and end the
+/// quorum: without both digests it can never become `Final`
+fn fail_quorum(quorum: &mut QuorumRequest, computation_offset: u64) -> Result<()> {
+    let leg = &mut quorum.leg;
+    require!(
+        leg.queued && !leg.completed && !leg.failed && leg.computation_offset == computation_offset,
+        ErrorCode::InvalidQuorumLeg
+    );
+    leg.failed = true;
+    if quorum.status == QuorumStatus::Open {
+        quorum.status = QuorumStatus::Failed;
+        emit!(QuorumFailedEvent {
+            quorum_offset: quorum.quorum_offset,
+            computation_offset,
+        });
+    }
+    Ok(())
+}
+
+/// The peer deployment's side of `quorum`, or `None` if the peer has not
+/// opened it yet. `account` must be the address recorded at open time and be
+/// owned by the peer program; its record must belong to the same requester.
+fn peer_quorum(quorum: &QuorumRequest, account: &AccountInfo) -> Result<Option<QuorumRequest>> {
+    require_keys_eq!(account.key(), quorum.peer_request, ErrorCode::QuorumPeerMismatch);
+    if account.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*account.owner, quorum.peer, ErrorCode::QuorumPeerMismatch);
+    let peer = QuorumRequest::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    require!(
+        peer.requester == quorum.requester && peer.quorum_offset == quorum.quorum_offset,
+        ErrorCode::QuorumPeerMismatch
+    );
+    Ok(Some(peer))
+}
+
+/// Settle the local side of a quorum against the peer's: `Failed` once the
+/// peer's leg aborted, `Final` or `Mismatch` once both legs reported digests.
+/// Leaves the quorum open while the peer's leg is still running.
+fn settle_quorum(quorum: &mut QuorumRequest, peer: &QuorumRequest) {
+    if quorum.status != QuorumStatus::Open {
+        return;
+    }
+    if peer.leg.failed {
+        quorum.status = QuorumStatus::Failed;
+        emit!(QuorumFailedEvent {
+            quorum_offset: quorum.quorum_offset,
+            computation_offset: peer.leg.computation_offset,
+        });
+        return;
+    }
+    if !(quorum.leg.completed && peer.leg.completed) {
+        return;
+    }
+    let computation_offsets = [quorum.leg.computation_offset, peer.leg.computation_offset];
+    if quorum.leg.digest == peer.leg.digest {
+        quorum.status = QuorumStatus::Final;
+        emit!(QuorumFinalizedEvent {
+            quorum_offset: quorum.quorum_offset,
+            computation_offsets,
+            digest: quorum.leg.digest,
+        });
+    } else {
+        quorum.status = QuorumStatus::Mismatch;
+        emit!(QuorumMismatchEvent {
+            quorum_offset: quorum.quorum_offset,
+            computation_offsets,
+            digests: [quorum.leg.digest, peer.leg.digest],
+        });
+    }
 }
 
 // ========== Account Structures ==========
 
 #[derive(Accounts)]
+#[instruction(circuit_hash: [u8; 32])]
 pub struct InitStrategyCompDef<'info> {
     // Account structure auto-generated by Arcium macros
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitRegistration::INIT_SPACE,
+        seeds = [CIRCUIT_SEED, &[CircuitKind::StrategyPlan as u8]],
+        bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitVersion::INIT_SPACE,
+        seeds = [CIRCUIT_VERSION_SEED, circuit_hash.as_ref()],
+        bump,
+    )]
+    pub circuit_version: Account<'info, CircuitVersion>,
 }
 
 #[derive(Accounts)]
+#[instruction(computation_offset: u64)]
 pub struct RequestStrategyPlan<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    #[account(
+        seeds = [CLIENT_KEYS_SEED, payer.key().as_ref()],
+        bump = client_keys.bump,
+    )]
+    pub client_keys: Account<'info, ClientKeyRegistry>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ComputationRequest::INIT_SPACE,
+        seeds = [COMPUTATION_REQUEST_SEED, &computation_offset.to_le_bytes()],
+        bump,
+    )]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        seeds = [CIRCUIT_SEED, &[CircuitKind::StrategyPlan as u8]],
+        bump = circuit_registration.bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        seeds = [POLICY_SEED, &[CircuitKind::StrategyPlan as u8]],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, StrategyPolicyAccount>,
     // Other required accounts auto-generated
 }
 
 #[derive(Accounts)]
 pub struct StrategyPlanCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
     // Other required accounts
 }
 
 #[derive(Accounts)]
-pub struct InitRiskCompDef<'info> {}
+#[instruction(circuit_hash: [u8; 32])]
+pub struct InitRiskCompDef<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitRegistration::INIT_SPACE,
+        seeds = [CIRCUIT_SEED, &[CircuitKind::RiskScore as u8]],
+        bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitVersion::INIT_SPACE,
+        seeds = [CIRCUIT_VERSION_SEED, circuit_hash.as_ref()],
+        bump,
+    )]
+    pub circuit_version: Account<'info, CircuitVersion>,
+}
 
 #[derive(Accounts)]
+#[instruction(computation_offset: u64)]
 pub struct RequestRiskScore<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    #[account(
+        seeds = [CLIENT_KEYS_SEED, payer.key().as_ref()],
+        bump = client_keys.bump,
+    )]
+    pub client_keys: Account<'info, ClientKeyRegistry>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ComputationRequest::INIT_SPACE,
+        seeds = [COMPUTATION_REQUEST_SEED, &computation_offset.to_le_bytes()],
+        bump,
+    )]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        seeds = [CIRCUIT_SEED, &[CircuitKind::RiskScore as u8]],
+        bump = circuit_registration.bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        seeds = [POLICY_SEED, &[CircuitKind::RiskScore as u8]],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, RiskPolicyAccount>,
 }
 
 #[derive(Accounts)]
 pub struct RiskScoreCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
 }
 
 #[derive(Accounts)]
-pub struct InitCurveCompDef<'info> {}
+#[instruction(circuit_hash: [u8; 32])]
+pub struct InitCurveCompDef<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitRegistration::INIT_SPACE,
+        seeds = [CIRCUIT_SEED, &[CircuitKind::CurveEval as u8]],
+        bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitVersion::INIT_SPACE,
+        seeds = [CIRCUIT_VERSION_SEED, circuit_hash.as_ref()],
+        bump,
+    )]
+    pub circuit_version: Account<'info, CircuitVersion>,
+}
 
 #[derive(Accounts)]
+#[instruction(computation_offset: u64)]
 pub struct RequestCurveEval<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    #[account(
+        seeds = [CLIENT_KEYS_SEED, payer.key().as_ref()],
+        bump = client_keys.bump,
+    )]
+    pub client_keys: Account<'info, ClientKeyRegistry>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ComputationRequest::INIT_SPACE,
+        seeds = [COMPUTATION_REQUEST_SEED, &computation_offset.to_le_bytes()],
+        bump,
+    )]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        seeds = [CIRCUIT_SEED, &[CircuitKind::CurveEval as u8]],
+        bump = circuit_registration.bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        seeds = [POLICY_SEED, &[CircuitKind::CurveEval as u8]],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, CurvePolicyAccount>,
 }
 
 #[derive(Accounts)]
 pub struct CurveEvalCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
 }
 
 #[derive(Accounts)]
-pub struct InitGmpcCompDef<'info> {}
+#[instruction(circuit_hash: [u8; 32])]
+pub struct InitGmpcCompDef<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitRegistration::INIT_SPACE,
+        seeds = [CIRCUIT_SEED, &[CircuitKind::GmpcStrategy as u8]],
+        bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitVersion::INIT_SPACE,
+        seeds = [CIRCUIT_VERSION_SEED, circuit_hash.as_ref()],
+        bump,
+    )]
+    pub circuit_version: Account<'info, CircuitVersion>,
+}
 
 #[derive(Accounts)]
+#[instruction(computation_offset: u64)]
 pub struct RequestGmpcStrategy<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    #[account(
+        seeds = [CLIENT_KEYS_SEED, payer.key().as_ref()],
+        bump = client_keys.bump,
+    )]
+    pub client_keys: Account<'info, ClientKeyRegistry>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ComputationRequest::INIT_SPACE,
+        seeds = [COMPUTATION_REQUEST_SEED, &computation_offset.to_le_bytes()],
+        bump,
+    )]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        seeds = [CIRCUIT_SEED, &[CircuitKind::GmpcStrategy as u8]],
+        bump = circuit_registration.bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        seeds = [POLICY_SEED, &[CircuitKind::GmpcStrategy as u8]],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, GmpcPolicyAccount>,
 }
 
 #[derive(Accounts)]
 pub struct GmpcStrategyCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
 }
 
 #[derive(Accounts)]
-pub struct InitMultiUserCompDef<'info> {}
+#[instruction(circuit_hash: [u8; 32])]
+pub struct InitMultiUserCompDef<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitRegistration::INIT_SPACE,
+        seeds = [CIRCUIT_SEED, &[CircuitKind::MultiUserAnalytics as u8]],
+        bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitVersion::INIT_SPACE,
+        seeds = [CIRCUIT_VERSION_SEED, circuit_hash.as_ref()],
+        bump,
+    )]
+    pub circuit_version: Account<'info, CircuitVersion>,
+}
 
 #[derive(Accounts)]
+#[instruction(computation_offset: u64)]
 pub struct RequestMultiUserAnalytics<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    #[account(
+        seeds = [CLIENT_KEYS_SEED, payer.key().as_ref()],
+        bump = client_keys.bump,
+    )]
+    pub client_keys: Account<'info, ClientKeyRegistry>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ComputationRequest::INIT_SPACE,
+        seeds = [COMPUTATION_REQUEST_SEED, &computation_offset.to_le_bytes()],
+        bump,
+    )]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        seeds = [CIRCUIT_SEED, &[CircuitKind::MultiUserAnalytics as u8]],
+        bump = circuit_registration.bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
 }
 
 #[derive(Accounts)]
 pub struct MultiUserAnalyticsCallback<'info> {
     pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+}
+
+#[derive(Accounts)]
+#[instruction(kind: CircuitKind, circuit_hash: [u8; 32])]
+pub struct UpgradeCircuit<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        mut,
+        seeds = [CIRCUIT_SEED, &[kind as u8]],
+        bump = circuit_registration.bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    /// CHECK: Arcium comp def of the registered offset; its circuit source is
+    /// read by the handler
+    #[account(address = derive_comp_def_pda!(circuit_registration.comp_def_offset))]
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + CircuitVersion::INIT_SPACE,
+        seeds = [CIRCUIT_VERSION_SEED, circuit_hash.as_ref()],
+        bump,
+    )]
+    pub circuit_version: Account<'info, CircuitVersion>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MxeConfig::INIT_SPACE,
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::EvalysArciumGmpcMxe>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ ErrorCode::NotUpgradeAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAuditLog<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<AuditLog>(),
+        seeds = [AUDIT_LOG_SEED],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitClientKeyRegistry<'info> {
+    #[account(mut)]
+    pub bridge: Signer<'info>,
+    #[account(
+        init,
+        payer = bridge,
+        space = 8 + ClientKeyRegistry::INIT_SPACE,
+        seeds = [CLIENT_KEYS_SEED, bridge.key().as_ref()],
+        bump,
+    )]
+    pub client_keys: Account<'info, ClientKeyRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageClientKeys<'info> {
+    pub bridge: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CLIENT_KEYS_SEED, bridge.key().as_ref()],
+        bump = client_keys.bump,
+        has_one = bridge,
+    )]
+    pub client_keys: Account<'info, ClientKeyRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetStrategyPolicy<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + StrategyPolicyAccount::INIT_SPACE,
+        seeds = [POLICY_SEED, &[CircuitKind::StrategyPlan as u8]],
+        bump,
+    )]
+    pub policy: Account<'info, StrategyPolicyAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRiskPolicy<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RiskPolicyAccount::INIT_SPACE,
+        seeds = [POLICY_SEED, &[CircuitKind::RiskScore as u8]],
+        bump,
+    )]
+    pub policy: Account<'info, RiskPolicyAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCurvePolicy<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + CurvePolicyAccount::INIT_SPACE,
+        seeds = [POLICY_SEED, &[CircuitKind::CurveEval as u8]],
+        bump,
+    )]
+    pub policy: Account<'info, CurvePolicyAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGmpcPolicy<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + GmpcPolicyAccount::INIT_SPACE,
+        seeds = [POLICY_SEED, &[CircuitKind::GmpcStrategy as u8]],
+        bump,
+    )]
+    pub policy: Account<'info, GmpcPolicyAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(circuit_hash: [u8; 32])]
+pub struct InitGmpcQuorumCompDef<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitRegistration::INIT_SPACE,
+        seeds = [CIRCUIT_SEED, &[CircuitKind::GmpcStrategyQuorum as u8]],
+        bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CircuitVersion::INIT_SPACE,
+        seeds = [CIRCUIT_VERSION_SEED, circuit_hash.as_ref()],
+        bump,
+    )]
+    pub circuit_version: Account<'info, CircuitVersion>,
+}
+
+#[derive(Accounts)]
+#[instruction(quorum_offset: u64)]
+pub struct OpenGmpcQuorum<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, MxeConfig>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + QuorumRequest::INIT_SPACE,
+        seeds = [QUORUM_REQUEST_SEED, &quorum_offset.to_le_bytes()],
+        bump,
+    )]
+    pub quorum_request: Account<'info, QuorumRequest>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, quorum_offset: u64)]
+pub struct RequestGmpcStrategyQuorum<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [CLIENT_KEYS_SEED, payer.key().as_ref()],
+        bump = client_keys.bump,
+    )]
+    pub client_keys: Account<'info, ClientKeyRegistry>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ComputationRequest::INIT_SPACE,
+        seeds = [COMPUTATION_REQUEST_SEED, &computation_offset.to_le_bytes()],
+        bump,
+    )]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(
+        mut,
+        seeds = [QUORUM_REQUEST_SEED, &quorum_offset.to_le_bytes()],
+        bump = quorum_request.bump,
+        constraint = quorum_request.requester == payer.key() @ ErrorCode::QuorumRequesterMismatch,
+    )]
+    pub quorum_request: Account<'info, QuorumRequest>,
+    #[account(
+        seeds = [CIRCUIT_SEED, &[CircuitKind::GmpcStrategyQuorum as u8]],
+        bump = circuit_registration.bump,
+    )]
+    pub circuit_registration: Account<'info, CircuitRegistration>,
+    /// Shared with `evalys_gmpc_strategy`
+    #[account(
+        seeds = [POLICY_SEED, &[CircuitKind::GmpcStrategy as u8]],
+        bump = policy.bump,
+    )]
+    pub policy: Account<'info, GmpcPolicyAccount>,
+    // Other required accounts auto-generated
+}
+
+#[derive(Accounts)]
+pub struct GmpcStrategyQuorumCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(mut)]
+    pub computation_request: Account<'info, ComputationRequest>,
+    #[account(mut, seeds = [AUDIT_LOG_SEED], bump)]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+    #[account(mut)]
+    pub quorum_request: Account<'info, QuorumRequest>,
+    /// CHECK: owned by the peer program; `peer_quorum` checks the address,
+    /// owner and record
+    pub peer_quorum_request: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(quorum_offset: u64)]
+pub struct SettleGmpcQuorum<'info> {
+    #[account(
+        mut,
+        seeds = [QUORUM_REQUEST_SEED, &quorum_offset.to_le_bytes()],
+        bump = quorum_request.bump,
+    )]
+    pub quorum_request: Account<'info, QuorumRequest>,
+    /// CHECK: owned by the peer program; `peer_quorum` checks the address,
+    /// owner and record
+    pub peer_quorum_request: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(circuit_hash: [u8; 32])]
+pub struct CircuitVersionQuery<'info> {
+    #[account(
+        seeds = [CIRCUIT_VERSION_SEED, circuit_hash.as_ref()],
+        bump = circuit_version.bump,
+    )]
+    pub circuit_version: Account<'info, CircuitVersion>,
 }
 
 // ========== Events ==========
 
+#[event]
+pub struct CircuitRegisteredEvent {
+    pub kind: CircuitKind,
+    pub comp_def_offset: u32,
+    pub circuit_hash: [u8; 32],
+    pub version: String,
+}
+
+/// Ciphertext counts per encrypted output (one per scalar field, including
+/// the two `InputCommitment` words; arrays count one per element, and the
+/// strategy plan packs its per-slice arrays into 9 u128 words)
+pub const STRATEGY_PLAN_CIPHERTEXTS: usize = 21;
+pub const RISK_ASSESSMENT_CIPHERTEXTS: usize = 6;
+pub const EXECUTION_RECOMMENDATION_CIPHERTEXTS: usize = 7;
+pub const PLAN_OUTPUT_CIPHERTEXTS: usize = 8;
+pub const AGGREGATED_METRICS_CIPHERTEXTS: usize = 11;
+
 #[event]
 pub struct StrategyPlanEvent {
     pub plan_id: Pubkey,
-    pub encrypted_plan: [u8; 32],
+    pub requester: Pubkey,
+    pub computation_offset: u64,
+    pub circuit_hash: [u8; 32],
+    pub client_key_id: u32,
+    pub encrypted_plan: [[u8; 32]; STRATEGY_PLAN_CIPHERTEXTS],
     pub nonce: [u8; 16],
 }
 
 #[event]
 pub struct RiskScoreEvent {
     pub assessment_id: Pubkey,
-    pub encrypted_assessment: [u8; 32],
+    pub requester: Pubkey,
+    pub computation_offset: u64,
+    pub circuit_hash: [u8; 32],
+    pub client_key_id: u32,
+    pub encrypted_assessment: [[u8; 32]; RISK_ASSESSMENT_CIPHERTEXTS],
     pub nonce: [u8; 16],
 }
 
 #[event]
 pub struct CurveEvalEvent {
     pub recommendation_id: Pubkey,
-    pub encrypted_recommendation: [u8; 32],
+    pub requester: Pubkey,
+    pub computation_offset: u64,
+    pub circuit_hash: [u8; 32],
+    pub client_key_id: u32,
+    pub encrypted_recommendation: [[u8; 32]; EXECUTION_RECOMMENDATION_CIPHERTEXTS],
     pub nonce: [u8; 16],
 }
 
 #[event]
 pub struct GmpcStrategyEvent {
     pub plan_id: Pubkey,
-    pub encrypted_plan: [u8; 32],
+    pub requester: Pubkey,
+    pub computation_offset: u64,
+    pub circuit_hash: [u8; 32],
+    pub client_key_id: u32,
+    pub encrypted_plan: [[u8; 32]; PLAN_OUTPUT_CIPHERTEXTS],
     pub nonce: [u8; 16],
 }
 
 #[event]
 pub struct MultiUserAnalyticsEvent {
     pub analytics_id: Pubkey,
-    pub encrypted_metrics: [u8; 32],
+    pub requester: Pubkey,
+    pub computation_offset: u64,
+    pub circuit_hash: [u8; 32],
+    pub client_key_id: u32,
+    pub encrypted_metrics: [[u8; 32]; AGGREGATED_METRICS_CIPHERTEXTS],
     pub nonce: [u8; 16],
 }
 
+#[event]
+pub struct ClientKeyRotatedEvent {
+    pub bridge: Pubkey,
+    pub key_id: u32,
+    pub pub_key: [u8; 32],
+    pub retired_key_ids: Vec<u32>,
+}
+
+#[event]
+pub struct PolicyUpdatedEvent {
+    pub kind: CircuitKind,
+    pub updated_slot: u64,
+}
+
+#[event]
+pub struct ComputationFailedEvent {
+    pub computation_offset: u64,
+    pub kind: CircuitKind,
+}
+
+#[event]
+pub struct QuorumOpenedEvent {
+    pub quorum_offset: u64,
+    pub requester: Pubkey,
+    /// Program id of the deployment running the other leg
+    pub peer: Pubkey,
+}
+
+#[event]
+pub struct QuorumFinalizedEvent {
+    pub quorum_offset: u64,
+    pub computation_offsets: [u64; 2],
+    pub digest: u128,
+}
+
+#[event]
+pub struct QuorumMismatchEvent {
+    pub quorum_offset: u64,
+    pub computation_offsets: [u64; 2],
+    pub digests: [u128; 2],
+}
+
+#[event]
+pub struct QuorumFailedEvent {
+    pub quorum_offset: u64,
+    /// Leg that aborted
+    pub computation_offset: u64,
+}
+
 // ========== Error Codes ==========
 
 #[error_code]
 pub enum ErrorCode {
     #[msg("Computation was aborted")]
     AbortedComputation,
+    #[msg("Computation request is not pending")]
+    RequestNotPending,
+    #[msg("Profile count must be between 1 and MAX_ANALYTICS_PROFILES")]
+    InvalidProfileCount,
+    #[msg("Ciphertext count does not match the circuit input")]
+    InvalidCiphertextCount,
+    #[msg("Circuit version must be 1 to MAX_CIRCUIT_VERSION_LEN bytes")]
+    InvalidCircuitVersion,
+    #[msg("Quorum peer must be another deployment")]
+    InvalidQuorumPeer,
+    #[msg("Quorum request is not open")]
+    QuorumNotOpen,
+    #[msg("Quorum leg does not match the queued computation")]
+    InvalidQuorumLeg,
+    #[msg("Quorum leg was already queued")]
+    QuorumLegAlreadyQueued,
+    #[msg("Peer quorum record is not the peer deployment's record of this quorum")]
+    QuorumPeerMismatch,
+    #[msg("Only the quorum requester can queue its legs")]
+    QuorumRequesterMismatch,
+    #[msg("Client key id is not registered for this bridge")]
+    UnknownClientKey,
+    #[msg("Client key is retired and cannot be used for new requests")]
+    ClientKeyRetired,
+    #[msg("An encrypted input was not encrypted with the given client key")]
+    ClientKeyMismatch,
+    #[msg("Client key registry is full; remove a retired key first")]
+    ClientKeyRegistryFull,
+    #[msg("Client key is already registered")]
+    DuplicateClientKey,
+    #[msg("Only retired client keys can be removed")]
+    ClientKeyActive,
+    #[msg("Policy value is outside the range its circuit accepts or out of order with its tier")]
+    InvalidPolicy,
+    #[msg("Circuit hash differs from the one the comp def enforces")]
+    CircuitHashMismatch,
+    #[msg("Comp def has no off-chain circuit source with a hash")]
+    CircuitHashNotAttested,
+    #[msg("Only the program's upgrade authority can initialize the config")]
+    NotUpgradeAuthority,
 }
-
@@ -0,0 +1,103 @@
+/**
+ * Known-answer vectors for the Rust client cipher, from Arcium's reference client
+ *
+ * `evalys-mxe-client` reimplements the `Enc<Shared, T>` cipher (x25519, the
+ * Rescue-Prime key hash and the Rescue CTR cipher). Its round counts and
+ * constants are only trustworthy if it agrees with `@arcium-hq/client`, so
+ * this script records what the reference client computes for fixed keys,
+ * nonces and plaintexts. The KATs in `crates/evalys-mxe-client/src/cipher.rs`
+ * check the Rust side against the file:
+ *
+ *   npm install
+ *   npx ts-node scripts/arcium-cipher-kats.ts
+ *   cd crates && cargo test -p evalys-mxe-client --lib -- --ignored
+ *
+ * Rerun it when the pinned `@arcium-hq/client` version changes.
+ */
+
+import { RescueCipher, RescuePrimeHash, x25519 } from "@arcium-hq/client";
+import * as fs from "fs";
+import * as path from "path";
+
+const OUT = path.join(
+  __dirname,
+  "../crates/evalys-mxe-client/tests/vectors/arcium-cipher.json"
+);
+
+const hex = (bytes: Uint8Array): string => Buffer.from(bytes).toString("hex");
+
+/** 32-byte little-endian encoding of a field element */
+const fieldHex = (value: bigint): string => {
+  const bytes = new Uint8Array(32);
+  for (let i = 0; i < 32; i++) bytes[i] = Number((value >> BigInt(8 * i)) & 0xffn);
+  return hex(bytes);
+};
+
+/** 16-byte little-endian nonce */
+const nonceBytes = (nonce: bigint): Uint8Array => {
+  const bytes = new Uint8Array(16);
+  for (let i = 0; i < 16; i++) bytes[i] = Number((nonce >> BigInt(8 * i)) & 0xffn);
+  return bytes;
+};
+
+const filled = (byte: number): Uint8Array => new Uint8Array(32).fill(byte);
+
+interface CipherCase {
+  client_secret: string;
+  mxe_secret: string;
+  mxe_public_key: string;
+  shared_secret: string;
+  nonce: string;
+  plaintext: string[];
+  ciphertexts: string[];
+}
+
+interface HashCase {
+  input: string[];
+  output: string[];
+}
+
+function cipherCase(clientByte: number, mxeByte: number, nonce: bigint, plaintext: bigint[]): CipherCase {
+  const clientSecret = filled(clientByte);
+  const mxeSecret = filled(mxeByte);
+  const mxePublicKey = x25519.getPublicKey(mxeSecret);
+  const sharedSecret = x25519.getSharedSecret(clientSecret, mxePublicKey);
+  const cipher = new RescueCipher(sharedSecret);
+  const ciphertexts: number[][] = cipher.encrypt(plaintext, nonceBytes(nonce));
+  return {
+    client_secret: hex(clientSecret),
+    mxe_secret: hex(mxeSecret),
+    mxe_public_key: hex(mxePublicKey),
+    shared_secret: hex(sharedSecret),
+    nonce: nonce.toString(),
+    plaintext: plaintext.map((value) => value.toString()),
+    ciphertexts: ciphertexts.map((ct) => hex(Uint8Array.from(ct))),
+  };
+}
+
+function hashCase(input: bigint[]): HashCase {
+  const output: bigint[] = new RescuePrimeHash().digest(input);
+  return {
+    input: input.map((value) => value.toString()),
+    output: output.map(fieldHex),
+  };
+}
+
+const P = (1n << 255n) - 19n;
+
+const vectors = {
+  generator: "@arcium-hq/client " + require("@arcium-hq/client/package.json").version,
+  cipher: [
+    // One block, one partial block and a multi-block input with edge values
+    cipherCase(2, 1, 0n, [0n, 1n, 2n, 3n, 4n]),
+    cipherCase(7, 1, 1n, [42n]),
+    cipherCase(9, 3, (1n << 128n) - 1n, [
+      0n, 1n, P - 1n, 1n << 64n, (1n << 128n) - 1n, 255n, 65535n, 123456789n, P - 2n, 5n, 6n,
+    ]),
+  ],
+  hash: [hashCase([0n]), hashCase([1n, 2n, 3n, 4n, 5n]), hashCase([P - 1n, 7n, 11n, 13n, 17n, 19n])],
+};
+
+fs.mkdirSync(path.dirname(OUT), { recursive: true });
+fs.writeFileSync(OUT, JSON.stringify(vectors, null, 2) + "\n");
+console.log(`wrote ${OUT}`);
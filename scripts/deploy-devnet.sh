@@ -7,10 +7,16 @@
 # - Keypair file at ~/.config/solana/id.json (or specify with --keypair-path)
 # - Sufficient SOL in keypair for deployment fees
 #
+# Builds, deploys, initializes every comp def and writes the deployment
+# manifest (evalys-mxe.json) with `evalys-mxe deploy`. Options other than the
+# ones below are passed through to it, e.g. --arcium-program, --fee-pool,
+# --clock-account, --version, --circuit-url, --quorum-peer-program and
+# --quorum-peer-cluster-offset.
+#
 # Usage:
-#   ./scripts/deploy-devnet.sh
-#   ./scripts/deploy-devnet.sh --keypair-path /path/to/keypair.json
-#   ./scripts/deploy-devnet.sh --rpc-url https://custom-rpc-url.com
+#   ./scripts/deploy-devnet.sh --version v0.1.0 --circuit-url https://example.com/circuits
+#   ./scripts/deploy-devnet.sh --keypair-path /path/to/keypair.json --version v0.1.0 --circuit-url <url>
+#   ./scripts/deploy-devnet.sh --rpc-url https://custom-rpc-url.com --version v0.1.0 --circuit-url <url>
 
 set -e
 
@@ -18,6 +24,8 @@ set -e
 CLUSTER_OFFSET="1078779259"
 KEYPAIR_PATH="${HOME}/.config/solana/id.json"
 RPC_URL="https://api.devnet.solana.com"
+NETWORK="devnet"
+DEPLOY_ARGS=()
 
 # Parse command line arguments
 while [[ $# -gt 0 ]]; do
@@ -34,10 +42,13 @@ while [[ $# -gt 0 ]]; do
             RPC_URL="$2"
             shift 2
             ;;
+        --network)
+            NETWORK="$2"
+            shift 2
+            ;;
         *)
-            echo "Unknown option: $1"
-            echo "Usage: $0 [--cluster-offset OFFSET] [--keypair-path PATH] [--rpc-url URL]"
-            exit 1
+            DEPLOY_ARGS+=("$1")
+            shift
             ;;
     esac
 done
@@ -72,31 +83,24 @@ echo "  Keypair: $KEYPAIR_PATH"
 echo "  RPC URL: $RPC_URL"
 echo ""
 
-# Build the program first
-echo "Building MXE program..."
-arcium build || {
-    echo "Error: Build failed. Please check your code and dependencies."
-    exit 1
-}
-
-echo ""
-echo "Deploying to Arcium network..."
-arcium deploy \
+cargo run --release --quiet --manifest-path crates/Cargo.toml --bin evalys-mxe -- \
+    deploy \
+    --network "$NETWORK" \
     --cluster-offset "$CLUSTER_OFFSET" \
-    --keypair-path "$KEYPAIR_PATH" \
-    --rpc-url "$RPC_URL"
+    --keypair "$KEYPAIR_PATH" \
+    --rpc-url "$RPC_URL" \
+    "${DEPLOY_ARGS[@]}"
 
 if [ $? -eq 0 ]; then
     echo ""
     echo "✅ Deployment successful!"
     echo ""
     echo "Next steps:"
-    echo "  1. Note the deployed program ID (shown above)"
-    echo "  2. Update ARCIUM_MXE_PROGRAM_ID in bridge service .env file"
-    echo "  3. Test the deployment with: anchor test"
+    echo "  1. Point the SDK and bridge service at evalys-mxe.json"
+    echo "  2. Once the cluster has generated the MXE key, record it with:"
+    echo "     evalys-mxe deploy --skip-build --mxe-x25519-pubkey <hex> --version <version>"
 else
     echo ""
     echo "❌ Deployment failed. Check the error messages above."
     exit 1
 fi
-